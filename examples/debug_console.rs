@@ -0,0 +1,32 @@
+extern crate cursive;
+#[macro_use]
+extern crate log;
+
+use cursive::views::Dialog;
+use cursive::Cursive;
+
+fn main() {
+    // Initialize the cursive logger.
+    cursive::logger::init();
+
+    // Use some logging macros from the `log` crate.
+    error!("Something serious probably happened!");
+    warn!("Or did it?");
+    debug!("Logger initialized.");
+    info!("Starting!");
+
+    let mut siv = Cursive::default();
+    siv.add_layer(Dialog::text(
+        "Press ~ to open the console.\n\
+         Press l to generate logs.\n\
+         Press q to quit.",
+    ));
+    siv.add_global_callback('q', Cursive::quit);
+    siv.add_global_callback('~', Cursive::toggle_debug_console);
+
+    siv.add_global_callback('l', |_| trace!("Wooo"));
+
+    error!("Woops");
+
+    siv.run();
+}
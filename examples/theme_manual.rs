@@ -24,7 +24,10 @@ fn main() {
                 theme.shadow = !theme.shadow;
                 theme.borders = match theme.borders {
                     BorderStyle::Simple => BorderStyle::Outset,
-                    BorderStyle::Outset => BorderStyle::None,
+                    BorderStyle::Outset => BorderStyle::Rounded,
+                    BorderStyle::Rounded => BorderStyle::Double,
+                    BorderStyle::Double => BorderStyle::Ascii,
+                    BorderStyle::Ascii => BorderStyle::None,
                     BorderStyle::None => BorderStyle::Simple,
                 };
 
@@ -1,5 +1,7 @@
 //! Tools to control view alignment.
 
+use vec::Vec2;
+
 /// Specifies the alignment along both horizontal and vertical directions.
 #[derive(Debug)]
 pub struct Align {
@@ -39,6 +41,33 @@ impl Align {
     pub fn center() -> Self {
         Align::new(HAlign::Center, VAlign::Center)
     }
+
+    /// Returns the offset required to position a view of size `content`.
+    ///
+    /// On each axis, if `content` is larger than `container`, the offset
+    /// is 0 and the view is cropped at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::align::Align;
+    /// # use cursive::vec::Vec2;
+    /// let align = Align::center();
+    /// assert_eq!(align.compute_offset((2, 8), (6, 4)), Vec2::new(2, 0));
+    /// ```
+    pub fn compute_offset<C, S>(&self, content: C, container: S) -> Vec2
+    where
+        C: Into<Vec2>,
+        S: Into<Vec2>,
+    {
+        let content = content.into();
+        let container = container.into();
+
+        Vec2::new(
+            self.h.get_offset(content.x, container.x),
+            self.v.get_offset(content.y, container.y),
+        )
+    }
 }
 
 /// Horizontal alignment
@@ -69,15 +98,15 @@ impl HAlign {
     /// When drawing a view with size `content` when the available size is
     /// `container`, printing at the resulting offset will align the view as
     /// desired.
+    ///
+    /// Returns 0 if `content` is larger than `container`.
     pub fn get_offset(&self, content: usize, container: usize) -> usize {
-        if container < content {
-            0
-        } else {
-            match *self {
-                HAlign::Left => 0,
-                HAlign::Center => (container - content) / 2,
-                HAlign::Right => (container - content),
-            }
+        let extra = container.saturating_sub(content);
+
+        match *self {
+            HAlign::Left => 0,
+            HAlign::Center => extra / 2,
+            HAlign::Right => extra,
         }
     }
 }
@@ -88,15 +117,34 @@ impl VAlign {
     /// When drawing a view with size `content` when the available size is
     /// `container`, printing at the resulting offset will align the view as
     /// desired.
+    ///
+    /// Returns 0 if `content` is larger than `container`.
     pub fn get_offset(&self, content: usize, container: usize) -> usize {
-        if container < content {
-            0
-        } else {
-            match *self {
-                VAlign::Top => 0,
-                VAlign::Center => (container - content) / 2,
-                VAlign::Bottom => (container - content),
-            }
+        let extra = container.saturating_sub(content);
+
+        match *self {
+            VAlign::Top => 0,
+            VAlign::Center => extra / 2,
+            VAlign::Bottom => extra,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_larger_than_container() {
+        for align in &[HAlign::Left, HAlign::Center, HAlign::Right] {
+            assert_eq!(align.get_offset(10, 3), 0);
+            assert_eq!(align.get_offset(7, 0), 0);
+        }
+
+        assert_eq!(HAlign::Center.get_offset(3, 10), 3);
+        assert_eq!(VAlign::Bottom.get_offset(3, 10), 7);
+
+        let align = Align::new(HAlign::Right, VAlign::Center);
+        assert_eq!(align.compute_offset((4, 9), (10, 3)), Vec2::new(6, 0));
+    }
+}
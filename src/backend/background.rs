@@ -0,0 +1,175 @@
+//! Detect the background color of the terminal.
+//!
+//! Terminals supporting it answer an `OSC 11` query with their background
+//! color. A "device attributes" query is sent right after: every terminal
+//! answers it, so we don't wait for a reply that will never come.
+//!
+//! When the query fails, the `COLORFGBG` variable set by some terminals is
+//! used instead.
+
+use std::env;
+
+use theme::Color;
+
+/// Asks for the background color, then for the device attributes.
+pub const QUERY: &[u8] = b"\x1B]11;?\x07\x1B[c";
+
+/// Detects the background color of the terminal, if possible.
+///
+/// This should be called before the backend starts reading input.
+pub fn detect() -> Option<Color> {
+    query_tty().or_else(from_env)
+}
+
+/// Parses an answer to the `OSC 11` query.
+///
+/// It looks like `ESC ] 11 ; rgb:RRRR/GGGG/BBBB`, followed by `BEL` or
+/// `ESC \`. Each channel can have 1 to 4 hexadecimal digits.
+pub fn parse_response(bytes: &[u8]) -> Option<Color> {
+    let text = String::from_utf8_lossy(bytes);
+    let start = text.find("\x1B]11;rgb:")? + "\x1B]11;rgb:".len();
+    let text = &text[start..];
+    let end = text.find(&['\x07', '\x1B'][..])?;
+
+    let channels: Vec<u8> = text[..end]
+        .split('/')
+        .map(parse_channel)
+        .collect::<Option<_>>()?;
+
+    match channels[..] {
+        [r, g, b] => Some(Color::Rgb(r, g, b)),
+        _ => None,
+    }
+}
+
+/// Parses a single color channel, and scales it to 8 bits.
+fn parse_channel(hex: &str) -> Option<u8> {
+    if hex.is_empty() || hex.len() > 4 {
+        return None;
+    }
+
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1 << (4 * hex.len())) - 1;
+
+    Some((value * 255 / max) as u8)
+}
+
+/// Reads the background color from the `COLORFGBG` variable.
+///
+/// It looks like `15;0`, where the last field is the background color
+/// index.
+pub fn from_env() -> Option<Color> {
+    let value = env::var("COLORFGBG").ok()?;
+    let background = value.rsplit(';').next()?.parse::<u8>().ok()?;
+
+    Some(Color::from_256colors(background))
+}
+
+/// Sends the query to the controlling terminal and reads the answer.
+#[cfg(unix)]
+fn query_tty() -> Option<Color> {
+    use libc;
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+
+    // The answer should not be echoed, nor wait for a newline.
+    let mut original = unsafe { ::std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return None;
+    }
+    let mut raw = original;
+    unsafe {
+        libc::cfmakeraw(&mut raw);
+        libc::tcsetattr(fd, libc::TCSANOW, &raw);
+    }
+
+    let mut answer = Vec::new();
+    if tty.write_all(QUERY).and_then(|_| tty.flush()).is_ok() {
+        let mut buffer = [0u8; 64];
+        loop {
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // Give up if the terminal is silent for 100ms.
+            if unsafe { libc::poll(&mut pollfd, 1, 100) } <= 0 {
+                break;
+            }
+            match tty.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => answer.extend_from_slice(&buffer[..n]),
+            }
+            // The device attributes end with `c`: nothing else will come.
+            if ends_with_device_attributes(&answer) {
+                break;
+            }
+        }
+    }
+
+    unsafe {
+        libc::tcsetattr(fd, libc::TCSANOW, &original);
+    }
+
+    parse_response(&answer)
+}
+
+#[cfg(not(unix))]
+fn query_tty() -> Option<Color> {
+    None
+}
+
+/// Returns `true` if `bytes` ends with an answer to the `CSI c` query.
+fn ends_with_device_attributes(bytes: &[u8]) -> bool {
+    let start = match bytes.windows(3).rposition(|w| w == b"\x1B[?") {
+        Some(start) => start,
+        None => return false,
+    };
+
+    bytes.last() == Some(&b'c')
+        && bytes[start + 3..bytes.len() - 1]
+            .iter()
+            .all(|&b| b.is_ascii_digit() || b == b';')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response() {
+        assert_eq!(
+            parse_response(b"\x1B]11;rgb:ffff/8080/0000\x07\x1B[?62;c"),
+            Some(Color::Rgb(255, 128, 0))
+        );
+        assert_eq!(
+            parse_response(b"\x1B]11;rgb:1e/1e/1e\x1B\\"),
+            Some(Color::Rgb(0x1e, 0x1e, 0x1e))
+        );
+        assert_eq!(
+            parse_response(b"\x1B]11;rgb:f/0/f\x07"),
+            Some(Color::Rgb(255, 0, 255))
+        );
+        // Only the device attributes: no background support.
+        assert_eq!(parse_response(b"\x1B[?1;2c"), None);
+        assert_eq!(parse_response(b"\x1B]11;rgb:ff/ff\x07"), None);
+    }
+
+    #[test]
+    fn device_attributes() {
+        assert!(ends_with_device_attributes(
+            b"\x1B]11;rgb:0/0/0\x07\x1B[?62;22c"
+        ));
+        assert!(!ends_with_device_attributes(
+            b"\x1B]11;rgb:0/0/0\x07\x1B[?62"
+        ));
+    }
+}
@@ -299,6 +299,17 @@ impl backend::Backend for Backend {
         true
     }
 
+    fn capabilities(&self) -> backend::Capabilities {
+        backend::Capabilities {
+            colors: backend::ColorDepth::TrueColor,
+            unicode: true,
+            mouse: true,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: None,
+        }
+    }
+
     fn screen_size(&self) -> Vec2 {
         let Size { width, height } = terminal::state::size();
         (width, height).into()
@@ -312,6 +323,12 @@ impl backend::Backend for Backend {
         terminal::clear(None);
     }
 
+    fn set_title(&mut self, title: &str) {
+        terminal::set(
+            terminal::config::Window::empty().title(title.to_string()),
+        );
+    }
+
     fn refresh(&mut self) {
         terminal::refresh();
     }
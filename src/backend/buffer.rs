@@ -0,0 +1,325 @@
+//! Off-screen buffer to draw views into.
+//!
+//! A [`PrintBuffer`] records everything printed on it, to be drawn later,
+//! possibly several times, with [`Printer::print_buffer`].
+//!
+//! This lets a view cache an expensive drawing, and only render it again
+//! when its content changes.
+//!
+//! [`PrintBuffer`]: struct.PrintBuffer.html
+//! [`Printer::print_buffer`]: ../../struct.Printer.html#method.print_buffer
+
+use std::cell::{Cell, RefCell};
+
+use enumset::EnumSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+use backend;
+use theme::{Color, ColorPair, Effect, Theme};
+use utils::width::StrWidth;
+use vec::Vec2;
+use Printer;
+
+/// A single cell in the buffer.
+#[derive(Clone, PartialEq)]
+struct BufferCell {
+    // Empty for the second half of a double-width character.
+    text: String,
+    colors: ColorPair,
+    effects: EnumSet<Effect>,
+}
+
+/// Grid of cells, drawn into with a `Printer`.
+///
+/// Cells that were never printed on are transparent: they are skipped when
+/// the buffer is drawn.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::backend::buffer::PrintBuffer;
+/// # use cursive::theme;
+/// let theme = theme::load_default();
+/// let buffer = PrintBuffer::new((10, 1));
+/// buffer.printer(&theme).print((0, 0), "Expensive");
+///
+/// // Later, in `View::draw`:
+/// // printer.print_buffer((0, 0), &buffer);
+/// ```
+pub struct PrintBuffer {
+    size: Vec2,
+    cells: RefCell<Vec<Option<BufferCell>>>,
+    current_colors: Cell<ColorPair>,
+    current_effects: Cell<EnumSet<Effect>>,
+}
+
+impl PrintBuffer {
+    /// Creates a new transparent buffer of the given size.
+    pub fn new<S: Into<Vec2>>(size: S) -> Self {
+        let size = size.into();
+        PrintBuffer {
+            size,
+            cells: RefCell::new(vec![None; size.x * size.y]),
+            current_colors: Cell::new(ColorPair::from_256colors(0, 0)),
+            current_effects: Cell::new(EnumSet::new()),
+        }
+    }
+
+    /// Returns the size of this buffer.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// Changes the size of this buffer.
+    ///
+    /// The content is discarded.
+    pub fn resize<S: Into<Vec2>>(&mut self, size: S) {
+        *self = PrintBuffer::new(size);
+    }
+
+    /// Makes every cell transparent again.
+    pub fn reset(&self) {
+        for cell in self.cells.borrow_mut().iter_mut() {
+            *cell = None;
+        }
+    }
+
+    /// Returns a printer drawing on this buffer.
+    pub fn printer<'a>(&'a self, theme: &'a Theme) -> Printer<'a, 'a> {
+        Printer::new(self.size, theme, self)
+    }
+
+    /// Returns the text of the cells between columns `start` and `end`
+    /// (excluded) on row `y`.
+    ///
+    /// Transparent cells count as spaces.
+    pub fn text(&self, y: usize, start: usize, end: usize) -> String {
+        if y >= self.size.y {
+            return String::new();
+        }
+
+        let cells = self.cells.borrow();
+        let end = end.min(self.size.x);
+        (start.min(end)..end)
+            .map(|x| {
+                cells[y * self.size.x + x]
+                    .as_ref()
+                    .map_or(" ", |cell| &cell.text[..])
+            })
+            .collect()
+    }
+
+    /// Runs `f` on each run of cells with the same style.
+    ///
+    /// `f` is given the start of the run, its text, colors and effects.
+    pub(crate) fn for_each_run<F>(&self, mut f: F)
+    where
+        F: FnMut(Vec2, &str, ColorPair, EnumSet<Effect>),
+    {
+        let cells = self.cells.borrow();
+        for y in 0..self.size.y {
+            let row = &cells[y * self.size.x..(y + 1) * self.size.x];
+
+            let mut run: Option<(usize, String, &BufferCell)> = None;
+            for (x, cell) in row.iter().enumerate() {
+                let same_style = match (&run, cell) {
+                    (&Some((_, _, first)), &Some(ref cell)) => {
+                        first.colors == cell.colors
+                            && first.effects == cell.effects
+                    }
+                    _ => false,
+                };
+
+                if same_style {
+                    if let (Some((_, ref mut text, _)), Some(cell)) =
+                        (run.as_mut(), cell.as_ref())
+                    {
+                        text.push_str(&cell.text);
+                    }
+                    continue;
+                }
+
+                if let Some((start, text, first)) = run.take() {
+                    f((start, y).into(), &text, first.colors, first.effects);
+                }
+                run = cell.as_ref().map(|cell| (x, cell.text.clone(), cell));
+            }
+
+            if let Some((start, text, first)) = run {
+                f((start, y).into(), &text, first.colors, first.effects);
+            }
+        }
+    }
+
+    fn set_cell(
+        &self, cells: &mut [Option<BufferCell>], pos: Vec2, text: String,
+    ) {
+        let i = pos.y * self.size.x + pos.x;
+
+        // Don't leave half of a double-width character behind.
+        let old_width = cells[i].as_ref().map_or(1, |cell| cell.text.width());
+        if old_width == 0 && pos.x > 0 {
+            if let Some(ref mut cell) = cells[i - 1] {
+                cell.text = " ".to_string();
+            }
+        } else if old_width > 1 && pos.x + 1 < self.size.x {
+            if let Some(ref mut cell) = cells[i + 1] {
+                cell.text = " ".to_string();
+            }
+        }
+
+        cells[i] = Some(BufferCell {
+            text,
+            colors: self.current_colors.get(),
+            effects: self.current_effects.get(),
+        });
+    }
+}
+
+impl backend::Backend for PrintBuffer {
+    fn finish(&mut self) {}
+
+    fn refresh(&mut self) {}
+
+    fn has_colors(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> backend::Capabilities {
+        backend::Capabilities {
+            colors: backend::ColorDepth::TrueColor,
+            unicode: true,
+            mouse: false,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: None,
+        }
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        self.size
+    }
+
+    fn print_at(&self, pos: Vec2, text: &str) {
+        if pos.y >= self.size.y {
+            return;
+        }
+
+        let mut cells = self.cells.borrow_mut();
+        let mut x = pos.x;
+        for grapheme in text.graphemes(true) {
+            let width = grapheme.width();
+            if x + width > self.size.x {
+                break;
+            }
+
+            self.set_cell(&mut cells, (x, pos.y).into(), grapheme.into());
+            for i in 1..width {
+                self.set_cell(&mut cells, (x + i, pos.y).into(), "".into());
+            }
+            x += width;
+        }
+    }
+
+    fn clear(&self, color: Color) {
+        let blank = BufferCell {
+            text: " ".to_string(),
+            colors: ColorPair {
+                front: color,
+                back: color,
+            },
+            effects: EnumSet::new(),
+        };
+
+        for cell in self.cells.borrow_mut().iter_mut() {
+            *cell = Some(blank.clone());
+        }
+    }
+
+    fn set_color(&self, colors: ColorPair) -> ColorPair {
+        self.current_colors.replace(colors)
+    }
+
+    fn set_effect(&self, effect: Effect) {
+        let mut effects = self.current_effects.get();
+        effects.insert(effect);
+        self.current_effects.set(effects);
+    }
+
+    fn unset_effect(&self, effect: Effect) {
+        let mut effects = self.current_effects.get();
+        effects.remove(effect);
+        self.current_effects.set(effects);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::{self, BaseColor, ColorStyle};
+
+    fn row(buffer: &PrintBuffer, y: usize) -> String {
+        let cells = buffer.cells.borrow();
+        cells[y * buffer.size.x..(y + 1) * buffer.size.x]
+            .iter()
+            .map(|cell| cell.as_ref().map_or("_", |cell| &cell.text[..]))
+            .collect()
+    }
+
+    #[test]
+    fn print_and_blit() {
+        let theme = theme::load_default();
+        let red = ColorStyle::new(
+            Color::Dark(BaseColor::Red),
+            Color::Dark(BaseColor::Black),
+        );
+
+        let source = PrintBuffer::new((6, 2));
+        {
+            let printer = source.printer(&theme);
+            printer.print((0, 0), "ab");
+            printer.with_color(red, |printer| {
+                printer.print((2, 0), "日c");
+            });
+            printer.print((1, 1), "toolong");
+        }
+        assert_eq!(row(&source, 0), "ab日c_");
+        assert_eq!(row(&source, 1), "_toolo");
+        assert_eq!(source.text(1, 0, 3), " to");
+        assert_eq!(source.text(0, 1, 10), "b日c ");
+
+        let mut runs = Vec::new();
+        source.for_each_run(|pos, text, _, _| runs.push((pos, text.into())));
+        assert_eq!(
+            runs,
+            vec![
+                (Vec2::new(0, 0), "ab".to_string()),
+                (Vec2::new(2, 0), "日c".to_string()),
+                (Vec2::new(1, 1), "toolo".to_string()),
+            ]
+        );
+
+        let target = PrintBuffer::new((8, 3));
+        target.printer(&theme).print((0, 0), "xxxxxxxx");
+        target.printer(&theme).print_buffer((1, 0), &source);
+        assert_eq!(row(&target, 0), "xab日cxx");
+        assert_eq!(row(&target, 1), "__toolo_");
+
+        let cells = target.cells.borrow();
+        let cell = cells[3].as_ref().unwrap();
+        assert_eq!(cell.colors.front, Color::Dark(BaseColor::Red));
+    }
+
+    #[test]
+    fn overwrite_wide() {
+        let theme = theme::load_default();
+        let buffer = PrintBuffer::new((4, 1));
+        let printer = buffer.printer(&theme);
+
+        printer.print((0, 0), "日本");
+        printer.print((1, 0), "a");
+        assert_eq!(row(&buffer, 0), " a本");
+        printer.print((2, 0), "b");
+        assert_eq!(row(&buffer, 0), " ab ");
+    }
+}
@@ -6,9 +6,16 @@
 extern crate term_size;
 
 use std::collections::HashMap;
+use std::time::Instant;
 
+#[cfg(unix)]
+use libc;
+
+use backend::kitty;
+use backend::{PASTE_MAX_LEN, PASTE_TIMEOUT};
 use event::{Event, Key};
 use theme::{BaseColor, Color, ColorPair};
+use utils::width::StrWidth;
 use vec::Vec2;
 
 #[cfg(feature = "ncurses-backend")]
@@ -26,6 +33,108 @@ fn terminal_size() -> Vec2 {
     term_size::dimensions().unwrap_or((0, 0)).into()
 }
 
+/// Signals listened to: SIGWINCH (window resize), SIGINT (Ctrl-C), SIGTERM
+/// and SIGHUP (shutdown), SIGTSTP (Ctrl-Z) and SIGCONT (resume).
+///
+/// They are registered before curses starts, so it doesn't install its own
+/// SIGTSTP handler.
+#[cfg(unix)]
+const SIGNALS: &[libc::c_int] = &[
+    libc::SIGWINCH,
+    libc::SIGINT,
+    libc::SIGTERM,
+    libc::SIGHUP,
+    libc::SIGTSTP,
+    libc::SIGCONT,
+];
+
+/// Sequence sent by the terminal before some pasted text (after ESC).
+const PASTE_START: &[u8] = b"[200~";
+
+/// Sequence sent by the terminal after some pasted text.
+const PASTE_END: &[u8] = b"\x1B[201~";
+
+/// Reads the rest of a control sequence, right after `ESC [`.
+///
+/// `next` returns the next byte, or `None` if nothing came in time.
+///
+/// Curses already decodes the sequences it knows: this is for the other
+/// ones. They are returned as `Event::Unknown` if they are not known keys.
+fn parse_csi<F>(mut next: F) -> Event
+where
+    F: FnMut() -> Option<u8>,
+{
+    let mut bytes = b"\x1B[".to_vec();
+    loop {
+        match next() {
+            Some(byte) => {
+                bytes.push(byte);
+                // Final byte of the sequence.
+                if (0x40..=0x7E).contains(&byte) {
+                    break;
+                }
+            }
+            None => return Event::Unknown(bytes),
+        }
+    }
+
+    let (code, params) = bytes[2..].split_last().unwrap();
+    let params = String::from_utf8_lossy(params);
+    kitty::parse_csi(&params, *code as char)
+        .unwrap_or_else(|| Event::Unknown(bytes.clone()))
+}
+
+/// Reads a bracketed paste, right after `ESC` and `PASTE_START`.
+///
+/// `read` appends the next input to the buffer, and returns `false` if
+/// nothing came in time.
+///
+/// Reading stops at `PASTE_END`, after `PASTE_TIMEOUT` without input, or
+/// after `PASTE_MAX_LEN` bytes: what was read so far is still returned.
+fn read_paste<F>(mut read: F) -> Event
+where
+    F: FnMut(&mut Vec<u8>) -> bool,
+{
+    let mut bytes = Vec::new();
+    let mut last_input = Instant::now();
+    while !bytes.ends_with(PASTE_END) {
+        if bytes.len() >= PASTE_MAX_LEN {
+            warn!("Paste too long, truncated to {} bytes", bytes.len());
+            break;
+        }
+        if read(&mut bytes) {
+            last_input = Instant::now();
+        } else if last_input.elapsed() > PASTE_TIMEOUT {
+            warn!("Paste interrupted after {} bytes", bytes.len());
+            break;
+        }
+    }
+
+    paste_event(bytes)
+}
+
+/// Makes a paste event from the raw bytes read, maybe ending with
+/// `PASTE_END`.
+///
+/// An empty, unterminated paste is returned as `Event::Unknown`.
+fn paste_event(mut bytes: Vec<u8>) -> Event {
+    if bytes.ends_with(PASTE_END) {
+        let len = bytes.len() - PASTE_END.len();
+        bytes.truncate(len);
+    } else if bytes.is_empty() {
+        let mut start = vec![0x1B];
+        start.extend_from_slice(PASTE_START);
+        return Event::Unknown(start);
+    }
+
+    // Terminals send line breaks as carriage returns.
+    let text = String::from_utf8_lossy(&bytes)
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+
+    Event::Paste(text)
+}
+
 fn split_i32(code: i32) -> Vec<u8> {
     (0..4).map(|i| ((code >> (8 * i)) & 0xFF) as u8).collect()
 }
@@ -74,6 +183,120 @@ where
     }
 }
 
+/// Joins consecutive prints on the same row into a single run of text.
+///
+/// The backend must flush the pending run before changing the style, so
+/// that a run always uses a single style.
+struct RowBuffer {
+    // Where the pending text starts.
+    start: Vec2,
+    // Column right after the pending text.
+    end: usize,
+    text: String,
+}
+
+impl RowBuffer {
+    fn new() -> Self {
+        RowBuffer {
+            start: Vec2::zero(),
+            end: 0,
+            text: String::new(),
+        }
+    }
+
+    /// Adds some text printed at `pos`.
+    ///
+    /// If it doesn't directly follow the pending text, returns the pending
+    /// run so it can be printed first.
+    fn push(&mut self, pos: Vec2, text: &str) -> Option<(Vec2, String)> {
+        let previous = if pos == Vec2::new(self.end, self.start.y) {
+            None
+        } else {
+            let previous = self.take();
+            self.start = pos;
+            self.end = pos.x;
+            previous
+        };
+
+        self.text.push_str(text);
+        self.end += text.width();
+        previous
+    }
+
+    /// Takes the pending run, if any.
+    fn take(&mut self) -> Option<(Vec2, String)> {
+        if self.text.is_empty() {
+            return None;
+        }
+
+        let text = ::std::mem::take(&mut self.text);
+        Some((self.start, text))
+    }
+}
+
+/// Keeps track of the color pairs defined with `init_pair`.
+///
+/// Each (front, back) combination gets its own pair, so pairs are only
+/// initialized once. When all pairs are used, the least recently used one
+/// is redefined.
+struct PairCache {
+    // Maps (front, back) colors to a pair and the time it was last used.
+    pairs: HashMap<(i16, i16), (i16, usize)>,
+    // Incremented on each use.
+    clock: usize,
+}
+
+impl PairCache {
+    fn new() -> Self {
+        PairCache {
+            pairs: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Returns the pair to use for these colors.
+    ///
+    /// `color_pairs` is the number of pairs supported by the terminal.
+    /// `init_pair` is only called when a pair needs to be (re)defined.
+    fn get_or_create<F>(
+        &mut self, colors: (i16, i16), color_pairs: i32, init_pair: F,
+    ) -> i16
+    where
+        F: FnOnce(i16, i16, i16),
+    {
+        self.clock += 1;
+        let clock = self.clock;
+
+        if let Some(&mut (pair, ref mut last_used)) =
+            self.pairs.get_mut(&colors)
+        {
+            // We got it!
+            *last_used = clock;
+            return pair;
+        }
+
+        // Pair 0 is reserved for the terminal default colors.
+        let capacity = (color_pairs - 1).clamp(1, i32::from(i16::MAX));
+        let pair = if (self.pairs.len() as i32) < capacity {
+            // We still have plenty of space for everyone.
+            self.pairs.len() as i16 + 1
+        } else {
+            // The world is too small for both of us.
+            let (&oldest, &(pair, _)) = self
+                .pairs
+                .iter()
+                .min_by_key(|&(_, &(_, last_used))| last_used)
+                .unwrap();
+            self.pairs.remove(&oldest);
+            pair
+        };
+
+        self.pairs.insert(colors, (pair, clock));
+        init_pair(pair, colors.0, colors.1);
+        pair
+    }
+}
+
 fn find_closest_pair(pair: ColorPair, max_colors: i16) -> (i16, i16) {
     (
         find_closest(pair.front, max_colors),
@@ -134,3 +357,92 @@ fn find_closest(color: Color, max_colors: i16) -> i16 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste() {
+        let mut input = b"a\rb\x1B[201~c".iter();
+        let event = read_paste(|bytes| {
+            bytes.extend(input.next());
+            true
+        });
+        assert_eq!(event, Event::Paste("a\nb".to_string()));
+        assert_eq!(input.next(), Some(&b'c'));
+
+        // The end never comes.
+        let mut input = b"ab".iter();
+        let event = read_paste(|bytes| match input.next() {
+            Some(&byte) => {
+                bytes.push(byte);
+                true
+            }
+            None => false,
+        });
+        assert_eq!(event, Event::Paste("ab".to_string()));
+
+        let event = read_paste(|bytes| {
+            bytes.push(b'a');
+            true
+        });
+        assert_eq!(event, Event::Paste("a".repeat(PASTE_MAX_LEN)));
+
+        assert_eq!(
+            read_paste(|_| false),
+            Event::Unknown(b"\x1B[200~".to_vec())
+        );
+    }
+
+    #[test]
+    fn row_buffer() {
+        let mut buffer = RowBuffer::new();
+
+        assert_eq!(buffer.push(Vec2::new(2, 1), "ab"), None);
+        assert_eq!(buffer.push(Vec2::new(4, 1), "日本"), None);
+        // Wide characters use two columns each.
+        assert_eq!(buffer.push(Vec2::new(8, 1), "c"), None);
+
+        assert_eq!(
+            buffer.push(Vec2::new(0, 2), "d"),
+            Some((Vec2::new(2, 1), "ab日本c".to_string()))
+        );
+        assert_eq!(
+            buffer.push(Vec2::new(2, 2), "e"),
+            Some((Vec2::new(0, 2), "d".to_string()))
+        );
+        assert_eq!(buffer.take(), Some((Vec2::new(2, 2), "e".to_string())));
+        assert_eq!(buffer.take(), None);
+    }
+
+    #[test]
+    fn pair_cache() {
+        let mut cache = PairCache::new();
+        let mut inits = Vec::new();
+
+        {
+            let mut get = |colors| {
+                cache.get_or_create(colors, 4, |pair, front, back| {
+                    inits.push((pair, front, back))
+                })
+            };
+
+            assert_eq!(get((1, 0)), 1);
+            assert_eq!(get((2, 0)), 2);
+            // Known colors don't define a new pair.
+            assert_eq!(get((1, 0)), 1);
+            assert_eq!(get((3, 0)), 3);
+
+            // We're full: (2, 0) was used the longest time ago.
+            assert_eq!(get((4, 0)), 2);
+            assert_eq!(get((1, 0)), 1);
+            assert_eq!(get((2, 0)), 3);
+        }
+
+        assert_eq!(
+            inits,
+            vec![(1, 1, 0), (2, 2, 0), (3, 3, 0), (2, 4, 0), (3, 2, 0)]
+        );
+    }
+}
@@ -16,27 +16,34 @@ use libc;
 use signal_hook::iterator::Signals;
 
 use backend;
+use clipboard;
 use event::{Event, Key, MouseButton, MouseEvent};
 use theme::{Color, ColorPair, Effect};
 use utf8;
 use vec::Vec2;
 
-use self::super::split_i32;
+use self::super::{split_i32, PairCache, RowBuffer};
 use self::ncurses::mmask_t;
 
 /// Backend using ncurses.
 pub struct Backend {
     current_style: Cell<ColorPair>,
 
-    // Maps (front, back) ncurses colors to ncurses pairs
-    pairs: RefCell<HashMap<(i16, i16), i16>>,
+    pairs: RefCell<PairCache>,
+
+    // Text printed with the current style, not yet sent to ncurses.
+    output: RefCell<RowBuffer>,
 
     // This is set by the SIGWINCH-triggered thread.
     // When TRUE, we should tell ncurses about the new terminal size.
     needs_resize: Arc<AtomicBool>,
 
-    // The signal hook to receive SIGWINCH (window resize)
+    // The signal hook to receive SIGWINCH (window resize), SIGINT (Ctrl-C),
+    // SIGTERM and SIGHUP (shutdown), SIGTSTP (Ctrl-Z) and SIGCONT.
     signals: Option<Signals>,
+
+    // Background color of the terminal, detected at startup.
+    background: Option<Color>,
 }
 
 struct InputParser {
@@ -65,9 +72,32 @@ impl InputParser {
             return None;
         }
 
+        if ch == 27 {
+            if let Some(event) = self.parse_paste() {
+                return Some(event);
+            }
+            if let Some(event) = self.parse_csi() {
+                return Some(event);
+            }
+        }
+
         // Is it a UTF-8 starting point?
         let event = if 32 <= ch && ch <= 255 && ch != 127 {
-            utf8::read_char(ch as u8, || Some(ncurses::getch() as u8))
+            utf8::read_char(ch as u8, || {
+                // The rest of the character may not be there yet, even if
+                // we're only peeking.
+                ncurses::timeout(25);
+                match ncurses::getch() {
+                    -1 => None,
+                    byte @ 0..=255 => Some(byte as u8),
+                    key => {
+                        // A key code interrupted the character: keep it
+                        // for the next event.
+                        ncurses::ungetch(key);
+                        None
+                    }
+                }
+            })
                 .map(Event::Char)
                 .unwrap_or_else(|e| {
                     warn!("Error reading input: {}", e);
@@ -80,6 +110,56 @@ impl InputParser {
         Some(event)
     }
 
+    /// Reads a bracketed paste, right after an ESC was read.
+    ///
+    /// If the ESC does not start a paste, the input is left untouched.
+    fn parse_paste(&mut self) -> Option<Event> {
+        // The rest of the sequence should already be there.
+        ncurses::timeout(25);
+
+        let mut read = Vec::new();
+        for &expected in super::PASTE_START {
+            let ch = ncurses::getch();
+            read.push(ch);
+            if ch != i32::from(expected) {
+                // Not a paste after all, put everything back.
+                for &ch in read.iter().rev().filter(|&&ch| ch != -1) {
+                    ncurses::ungetch(ch);
+                }
+                return None;
+            }
+        }
+
+        Some(super::read_paste(|bytes| match ncurses::getch() {
+            -1 => false,
+            ch => {
+                if (0..=255).contains(&ch) {
+                    bytes.push(ch as u8);
+                }
+                true
+            }
+        }))
+    }
+
+    /// Reads a control sequence unknown to ncurses, right after an ESC.
+    ///
+    /// If the ESC does not start a control sequence, the input is left
+    /// untouched.
+    fn parse_csi(&mut self) -> Option<Event> {
+        let ch = ncurses::getch();
+        if ch != i32::from(b'[') {
+            if ch != -1 {
+                ncurses::ungetch(ch);
+            }
+            return None;
+        }
+
+        Some(super::parse_csi(|| match ncurses::getch() {
+            ch @ 0..=255 => Some(ch as u8),
+            _ => None,
+        }))
+    }
+
     fn parse_ncurses_char(&mut self, ch: i32) -> Event {
         // eprintln!("Found {:?}", ncurses::keyname(ch));
         if ch == ncurses::KEY_MOUSE {
@@ -194,8 +274,7 @@ fn find_closest_pair(pair: ColorPair) -> (i16, i16) {
 /// Since this is not going to be used often, we can afford to re-open the
 /// file every time.
 fn write_to_tty(bytes: &[u8]) -> io::Result<()> {
-    let mut tty_output =
-        File::create("/dev/tty").expect("cursive can only run with a tty");
+    let mut tty_output = File::create("/dev/tty")?;
     tty_output.write_all(bytes)?;
     // tty_output will be flushed automatically at the end of the function.
     Ok(())
@@ -204,7 +283,7 @@ fn write_to_tty(bytes: &[u8]) -> io::Result<()> {
 impl Backend {
     /// Creates a new ncurses-based backend.
     pub fn init() -> Box<backend::Backend> {
-        let signals = Some(Signals::new(&[libc::SIGWINCH]).unwrap());
+        let signals = Some(Signals::new(super::SIGNALS).unwrap());
 
         // Change the locale.
         // For some reasons it's mandatory to get some UTF-8 support.
@@ -215,6 +294,9 @@ impl Backend {
         // Default delay is way too long. 25 is imperceptible yet works fine.
         ::std::env::set_var("ESCDELAY", "25");
 
+        // Ask before ncurses starts reading the terminal.
+        let background = backend::background::detect();
+
         let tty_path = CString::new("/dev/tty").unwrap();
         let mode = CString::new("r+").unwrap();
         let tty = unsafe { libc::fopen(tty_path.as_ptr(), mode.as_ptr()) };
@@ -241,50 +323,37 @@ impl Backend {
         // This asks the terminal to provide us with mouse drag events
         // (Mouse move when a button is pressed).
         // Replacing 1002 with 1003 would give us ANY mouse move.
-        write_to_tty(b"\x1B[?1002h").unwrap();
+        // 2004 enables bracketed paste.
+        write_to_tty(b"\x1B[?1002h\x1B[?2004h")
+            .expect("cursive can only run with a tty");
 
         let c = Backend {
             current_style: Cell::new(ColorPair::from_256colors(0, 0)),
-            pairs: RefCell::new(HashMap::new()),
+            pairs: RefCell::new(PairCache::new()),
+            output: RefCell::new(RowBuffer::new()),
             needs_resize: Arc::new(AtomicBool::new(false)),
             signals,
+            background,
         };
 
         Box::new(c)
     }
 
-    /// Save a new color pair.
-    fn insert_color(
-        &self, pairs: &mut HashMap<(i16, i16), i16>, (front, back): (i16, i16),
-    ) -> i16 {
-        let n = 1 + pairs.len() as i16;
-
-        let target = if ncurses::COLOR_PAIRS() > i32::from(n) {
-            // We still have plenty of space for everyone.
-            n
-        } else {
-            // The world is too small for both of us.
-            let target = n - 1;
-            // Remove the mapping to n-1
-            pairs.retain(|_, &mut v| v != target);
-            target
-        };
-        pairs.insert((front, back), target);
-        ncurses::init_pair(target, front, back);
-        target
-    }
-
     /// Checks the pair in the cache, or re-define a color if needed.
     fn get_or_create(&self, pair: ColorPair) -> i16 {
-        let mut pairs = self.pairs.borrow_mut();
+        self.pairs.borrow_mut().get_or_create(
+            find_closest_pair(pair),
+            ncurses::COLOR_PAIRS(),
+            |pair, front, back| {
+                ncurses::init_pair(pair, front, back);
+            },
+        )
+    }
 
-        // Find if we have this color in stock
-        let (front, back) = find_closest_pair(pair);
-        if pairs.contains_key(&(front, back)) {
-            // We got it!
-            pairs[&(front, back)]
-        } else {
-            self.insert_color(&mut *pairs, (front, back))
+    /// Prints the pending text, if any.
+    fn flush_output(&self) {
+        if let Some((pos, text)) = self.output.borrow_mut().take() {
+            print_wide(pos, &text);
         }
     }
 
@@ -320,6 +389,21 @@ impl backend::Backend for Backend {
         ncurses::has_colors()
     }
 
+    fn capabilities(&self) -> backend::Capabilities {
+        backend::Capabilities {
+            colors: if ncurses::has_colors() {
+                backend::ColorDepth::from_count(ncurses::COLORS() as u32)
+            } else {
+                backend::ColorDepth::Monochrome
+            },
+            unicode: backend::Capabilities::detect_unicode(),
+            mouse: true,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: self.background,
+        }
+    }
+
     fn start_input_thread(
         &mut self, event_sink: Sender<Option<Event>>,
         input_request: Receiver<backend::InputRequest>,
@@ -327,7 +411,7 @@ impl backend::Backend for Backend {
         let running = Arc::new(AtomicBool::new(true));
 
         backend::resize::start_resize_thread(
-            self.signals.take().unwrap(),
+            self.signals.clone().unwrap(),
             event_sink.clone(),
             input_request.clone(),
             Arc::clone(&running),
@@ -361,14 +445,63 @@ impl backend::Backend for Backend {
     }
 
     fn finish(&mut self) {
-        write_to_tty(b"\x1B[?1002l").unwrap();
+        if let Some(signals) = self.signals.take() {
+            backend::resize::close_signals(&signals, super::SIGNALS);
+        }
+        write_to_tty(b"\x1B[?2004l\x1B[?1002l").unwrap();
         ncurses::endwin();
     }
 
+    fn suspend(&mut self) -> io::Result<()> {
+        write_to_tty(b"\x1B[?2004l\x1B[?1002l")?;
+        ncurses::def_prog_mode();
+        ncurses::endwin();
+        Ok(())
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        ncurses::reset_prog_mode();
+        write_to_tty(b"\x1B[?1002h\x1B[?2004h")?;
+        ncurses::refresh();
+        Ok(())
+    }
+
+    fn is_process_terminal(&self) -> bool {
+        true
+    }
+
+    fn set_clipboard(&mut self, content: &str) {
+        write_to_tty(clipboard::osc52(content).as_bytes()).unwrap();
+    }
+
+    fn set_title(&mut self, title: &str) {
+        write_to_tty(backend::title_sequence(title).as_bytes()).unwrap();
+    }
+
+    fn beep(&mut self) {
+        ncurses::beep();
+    }
+
+    fn set_cursor(&mut self, pos: Option<Vec2>) {
+        self.flush_output();
+        match pos {
+            Some(pos) => {
+                ncurses::curs_set(ncurses::CURSOR_VISIBILITY::CURSOR_VISIBLE);
+                ncurses::mv(pos.y as i32, pos.x as i32);
+            }
+            None => {
+                ncurses::curs_set(
+                    ncurses::CURSOR_VISIBILITY::CURSOR_INVISIBLE,
+                );
+            }
+        }
+    }
+
     fn set_color(&self, colors: ColorPair) -> ColorPair {
         // eprintln!("Color used: {:?}", colors);
         let current = self.current_style.get();
         if current != colors {
+            self.flush_output();
             self.set_colors(colors);
         }
 
@@ -376,6 +509,7 @@ impl backend::Backend for Backend {
     }
 
     fn set_effect(&self, effect: Effect) {
+        self.flush_output();
         let style = match effect {
             Effect::Reverse => ncurses::A_REVERSE(),
             Effect::Simple => ncurses::A_NORMAL(),
@@ -387,6 +521,7 @@ impl backend::Backend for Backend {
     }
 
     fn unset_effect(&self, effect: Effect) {
+        self.flush_output();
         let style = match effect {
             Effect::Reverse => ncurses::A_REVERSE(),
             Effect::Simple => ncurses::A_NORMAL(),
@@ -398,6 +533,7 @@ impl backend::Backend for Backend {
     }
 
     fn clear(&self, color: Color) {
+        self.flush_output();
         if self.needs_resize.swap(false, Ordering::Relaxed) {
             on_resize();
         }
@@ -412,14 +548,43 @@ impl backend::Backend for Backend {
     }
 
     fn refresh(&mut self) {
+        self.flush_output();
         ncurses::refresh();
     }
 
     fn print_at(&self, pos: Vec2, text: &str) {
-        ncurses::mvaddstr(pos.y as i32, pos.x as i32, text);
+        // Consecutive prints are sent to ncurses in a single call.
+        if let Some((pos, text)) = self.output.borrow_mut().push(pos, text) {
+            print_wide(pos, &text);
+        }
     }
 }
 
+/// Prints some text using wide characters.
+///
+/// This way multi-byte characters are never split.
+fn print_wide(pos: Vec2, text: &str) {
+    let text: Vec<libc::wchar_t> =
+        text.chars().map(|c| c as libc::wchar_t).collect();
+    unsafe {
+        mvaddnwstr(
+            pos.y as libc::c_int,
+            pos.x as libc::c_int,
+            text.as_ptr(),
+            text.len() as libc::c_int,
+        );
+    }
+}
+
+// Not exposed by the ncurses crate, but part of the ncursesw library it
+// links to with the `wide` feature.
+extern "C" {
+    fn mvaddnwstr(
+        y: libc::c_int, x: libc::c_int, wstr: *const libc::wchar_t,
+        n: libc::c_int,
+    ) -> libc::c_int;
+}
+
 /// Returns the Key enum corresponding to the given ncurses event.
 fn get_mouse_button(bare_event: i32) -> MouseButton {
     match bare_event {
@@ -3,31 +3,33 @@ extern crate pancurses;
 
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::io::{stdout, Write};
+use std::io::{self, stdout, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 
 use crossbeam_channel::{Receiver, Sender};
 
-#[cfg(unix)]
-use libc;
 #[cfg(unix)]
 use signal_hook::iterator::Signals;
 
 use backend;
+use clipboard;
 use event::{Event, Key, MouseButton, MouseEvent};
 use theme::{Color, ColorPair, Effect};
 use vec::Vec2;
 
 use self::pancurses::mmask_t;
-use super::split_i32;
+use super::{split_i32, PairCache, RowBuffer};
 
 /// Backend using pancurses.
 pub struct Backend {
     // Used
     current_style: Cell<ColorPair>,
-    pairs: RefCell<HashMap<(i16, i16), i32>>,
+    pairs: RefCell<PairCache>,
+
+    // Text printed with the current style, not yet sent to pancurses.
+    output: RefCell<RowBuffer>,
 
     // pancurses needs a handle to the current window.
     window: Arc<pancurses::Window>,
@@ -36,9 +38,13 @@ pub struct Backend {
     // When TRUE, we should tell ncurses about the new terminal size.
     needs_resize: Arc<AtomicBool>,
 
-    // The signal hook to receive SIGWINCH (window resize)
+    // The signal hook to receive SIGWINCH (window resize), SIGINT (Ctrl-C),
+    // SIGTERM and SIGHUP (shutdown), SIGTSTP (Ctrl-Z) and SIGCONT.
     #[cfg(unix)]
     signals: Option<Signals>,
+
+    // Background color of the terminal, detected at startup.
+    background: Option<Color>,
 }
 
 struct InputParser {
@@ -64,6 +70,62 @@ impl InputParser {
         }
     }
 
+    /// Reads a bracketed paste, right after an ESC was read.
+    ///
+    /// If the ESC does not start a paste, the input is left untouched.
+    fn parse_paste(&mut self) -> Option<Event> {
+        // The rest of the sequence should already be there.
+        self.window.timeout(25);
+
+        let mut read = Vec::new();
+        for &expected in super::PASTE_START {
+            let input = self.window.getch();
+            let matches =
+                input == Some(pancurses::Input::Character(expected as char));
+            read.extend(input);
+            if !matches {
+                // Not a paste after all, put everything back.
+                for input in read.iter().rev() {
+                    self.window.ungetch(input);
+                }
+                return None;
+            }
+        }
+
+        Some(super::read_paste(|bytes| match self.window.getch() {
+            Some(pancurses::Input::Character(c)) => {
+                let mut buffer = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                true
+            }
+            Some(_) => true,
+            None => false,
+        }))
+    }
+
+    /// Reads a control sequence unknown to pancurses, right after an ESC.
+    ///
+    /// If the ESC does not start a control sequence, the input is left
+    /// untouched.
+    fn parse_csi(&mut self) -> Option<Event> {
+        match self.window.getch() {
+            Some(pancurses::Input::Character('[')) => (),
+            None => return None,
+            Some(input) => {
+                self.window.ungetch(&input);
+                return None;
+            }
+        }
+
+        let window = &self.window;
+        Some(super::parse_csi(|| match window.getch() {
+            Some(pancurses::Input::Character(c)) if (c as u32) < 256 => {
+                Some(c as u8)
+            }
+            _ => None,
+        }))
+    }
+
     fn parse_next(&mut self) -> Option<Event> {
         if let Some(event) = self.input_buffer.take() {
             return Some(event);
@@ -79,7 +141,10 @@ impl InputParser {
                     Event::Key(Key::Backspace)
                 }
                 pancurses::Input::Character('\u{9}') => Event::Key(Key::Tab),
-                pancurses::Input::Character('\u{1b}') => Event::Key(Key::Esc),
+                pancurses::Input::Character('\u{1b}') => self
+                    .parse_paste()
+                    .or_else(|| self.parse_csi())
+                    .unwrap_or(Event::Key(Key::Esc)),
                 pancurses::Input::Character(c) if (c as u32) <= 26 => {
                     Event::CtrlChar((b'a' - 1 + c as u8) as char)
                 }
@@ -294,10 +359,13 @@ impl Backend {
         // We need to create this now, before ncurses initialization
         // Otherwise ncurses starts its own signal handling and it's a mess.
         #[cfg(unix)]
-        let signals = Some(Signals::new(&[libc::SIGWINCH]).unwrap());
+        let signals = Some(Signals::new(super::SIGNALS).unwrap());
 
         ::std::env::set_var("ESCDELAY", "25");
 
+        // Ask before pancurses starts reading the terminal.
+        let background = backend::background::detect();
+
         let window = pancurses::initscr();
         window.keypad(true);
         pancurses::noecho();
@@ -314,54 +382,39 @@ impl Backend {
         // This asks the terminal to provide us with mouse drag events
         // (Mouse move when a button is pressed).
         // Replacing 1002 with 1003 would give us ANY mouse move.
-        print!("\x1B[?1002h");
+        // 2004 enables bracketed paste.
+        print!("\x1B[?1002h\x1B[?2004h");
         stdout().flush().expect("could not flush stdout");
 
         let c = Backend {
             current_style: Cell::new(ColorPair::from_256colors(0, 0)),
-            pairs: RefCell::new(HashMap::new()),
+            pairs: RefCell::new(PairCache::new()),
+            output: RefCell::new(RowBuffer::new()),
             window: Arc::new(window),
             needs_resize: Arc::new(AtomicBool::new(false)),
             #[cfg(unix)]
             signals,
+            background,
         };
 
         Box::new(c)
     }
 
-    /// Save a new color pair.
-    fn insert_color(
-        &self, pairs: &mut HashMap<(i16, i16), i32>, (front, back): (i16, i16),
-    ) -> i32 {
-        let n = 1 + pairs.len() as i32;
-
-        // TODO: when COLORS_PAIRS is available...
-        let target = if 256 > n {
-            // We still have plenty of space for everyone.
-            n
-        } else {
-            // The world is too small for both of us.
-            let target = n - 1;
-            // Remove the mapping to n-1
-            pairs.retain(|_, &mut v| v != target);
-            target
-        };
-        pairs.insert((front, back), target);
-        pancurses::init_pair(target as i16, front, back);
-        target
+    /// Checks the pair in the cache, or re-define a color if needed.
+    fn get_or_create(&self, pair: ColorPair) -> i16 {
+        self.pairs.borrow_mut().get_or_create(
+            find_closest_pair(pair),
+            pancurses::COLOR_PAIRS(),
+            |pair, front, back| {
+                pancurses::init_pair(pair, front, back);
+            },
+        )
     }
 
-    /// Checks the pair in the cache, or re-define a color if needed.
-    fn get_or_create(&self, pair: ColorPair) -> i32 {
-        let mut pairs = self.pairs.borrow_mut();
-        let pair = find_closest_pair(pair);
-
-        // Find if we have this color in stock
-        if pairs.contains_key(&pair) {
-            // We got it!
-            pairs[&pair]
-        } else {
-            self.insert_color(&mut *pairs, pair)
+    /// Prints the pending text, if any.
+    fn flush_output(&self) {
+        if let Some((pos, text)) = self.output.borrow_mut().take() {
+            self.window.mvaddstr(pos.y as i32, pos.x as i32, &text);
         }
     }
 
@@ -396,16 +449,85 @@ impl backend::Backend for Backend {
         pancurses::has_colors()
     }
 
+    fn capabilities(&self) -> backend::Capabilities {
+        backend::Capabilities {
+            colors: if pancurses::has_colors() {
+                backend::ColorDepth::from_count(pancurses::COLORS() as u32)
+            } else {
+                backend::ColorDepth::Monochrome
+            },
+            unicode: backend::Capabilities::detect_unicode(),
+            mouse: true,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: self.background,
+        }
+    }
+
     fn finish(&mut self) {
-        print!("\x1B[?1002l");
+        #[cfg(unix)]
+        {
+            if let Some(signals) = self.signals.take() {
+                backend::resize::close_signals(&signals, super::SIGNALS);
+            }
+        }
+        print!("\x1B[?2004l\x1B[?1002l");
         stdout().flush().expect("could not flush stdout");
         pancurses::endwin();
     }
 
+    fn suspend(&mut self) -> io::Result<()> {
+        print!("\x1B[?2004l\x1B[?1002l");
+        stdout().flush()?;
+        pancurses::def_prog_mode();
+        pancurses::endwin();
+        Ok(())
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        pancurses::reset_prog_mode();
+        print!("\x1B[?1002h\x1B[?2004h");
+        stdout().flush()?;
+        self.window.refresh();
+        Ok(())
+    }
+
+    fn is_process_terminal(&self) -> bool {
+        true
+    }
+
+    fn set_clipboard(&mut self, content: &str) {
+        print!("{}", clipboard::osc52(content));
+        stdout().flush().expect("could not flush stdout");
+    }
+
+    fn set_title(&mut self, title: &str) {
+        print!("{}", backend::title_sequence(title));
+        stdout().flush().expect("could not flush stdout");
+    }
+
+    fn beep(&mut self) {
+        pancurses::beep();
+    }
+
+    fn set_cursor(&mut self, pos: Option<Vec2>) {
+        self.flush_output();
+        match pos {
+            Some(pos) => {
+                pancurses::curs_set(1);
+                self.window.mv(pos.y as i32, pos.x as i32);
+            }
+            None => {
+                pancurses::curs_set(0);
+            }
+        }
+    }
+
     fn set_color(&self, colors: ColorPair) -> ColorPair {
         let current = self.current_style.get();
 
         if current != colors {
+            self.flush_output();
             self.set_colors(colors);
         }
 
@@ -413,6 +535,7 @@ impl backend::Backend for Backend {
     }
 
     fn set_effect(&self, effect: Effect) {
+        self.flush_output();
         let style = match effect {
             Effect::Simple => pancurses::Attribute::Normal,
             Effect::Reverse => pancurses::Attribute::Reverse,
@@ -424,6 +547,7 @@ impl backend::Backend for Backend {
     }
 
     fn unset_effect(&self, effect: Effect) {
+        self.flush_output();
         let style = match effect {
             Effect::Simple => pancurses::Attribute::Normal,
             Effect::Reverse => pancurses::Attribute::Reverse,
@@ -435,6 +559,7 @@ impl backend::Backend for Backend {
     }
 
     fn clear(&self, color: Color) {
+        self.flush_output();
         if self.needs_resize.swap(false, Ordering::Relaxed) {
             on_resize();
         }
@@ -448,11 +573,15 @@ impl backend::Backend for Backend {
     }
 
     fn refresh(&mut self) {
+        self.flush_output();
         self.window.refresh();
     }
 
     fn print_at(&self, pos: Vec2, text: &str) {
-        self.window.mvaddstr(pos.y as i32, pos.x as i32, text);
+        // Consecutive prints are sent to pancurses in a single call.
+        if let Some((pos, text)) = self.output.borrow_mut().push(pos, text) {
+            self.window.mvaddstr(pos.y as i32, pos.x as i32, &text);
+        }
     }
 
     fn start_input_thread(
@@ -464,7 +593,7 @@ impl backend::Backend for Backend {
         #[cfg(unix)]
         {
             backend::resize::start_resize_thread(
-                self.signals.take().unwrap(),
+                self.signals.clone().unwrap(),
                 event_sink.clone(),
                 input_request.clone(),
                 Arc::clone(&running),
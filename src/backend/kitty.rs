@@ -0,0 +1,177 @@
+//! Keyboard input with the kitty keyboard protocol.
+//!
+//! Legacy terminal input is ambiguous: `Esc` also starts escape sequences,
+//! so it can be mistaken for an `Alt` combination, and `Ctrl-I` sends the
+//! same byte as `Tab`. Terminals implementing the [kitty keyboard protocol]
+//! can instead send these keys as distinct escape sequences, and can report
+//! key releases.
+//!
+//! The protocol uses progressive enhancement: backends send [`ENABLE`] on
+//! startup, and terminals without support simply ignore it, and keep
+//! sending legacy input. [`parse_csi`] understands both.
+//!
+//! The termion and web backends use this module. Key releases are only
+//! reported by the web backend: the termion input parser would choke on
+//! some of them.
+//!
+//! [kitty keyboard protocol]: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+//! [`ENABLE`]: constant.ENABLE.html
+//! [`parse_csi`]: fn.parse_csi.html
+
+use event::{Event, Key};
+
+/// Asks the terminal to send unambiguous escape sequences.
+pub const ENABLE: &str = "\x1B[>1u";
+
+/// Restores the keyboard mode in use before `ENABLE`.
+pub const DISABLE: &str = "\x1B[<u";
+
+/// Returns the sequence enabling or disabling key release reports.
+///
+/// Releases are sent as `Event::Released`. This only has an effect after
+/// `ENABLE`.
+pub fn report_releases(enabled: bool) -> &'static str {
+    if enabled {
+        "\x1B[=3;1u"
+    } else {
+        "\x1B[=1;1u"
+    }
+}
+
+/// Parses the key sent in a control sequence: `ESC [ params code`.
+///
+/// This handles legacy sequences with xterm modifiers, like `ESC [1;5A`
+/// for `Ctrl-Up`, as well as the sequences of the kitty protocol.
+///
+/// Returns `None` if the sequence is not a known key.
+pub fn parse_csi(params: &str, code: char) -> Option<Event> {
+    // Fields are separated by `;`, and sub-fields by `:`.
+    let mut fields = params.split(';');
+    let number = fields
+        .next()
+        .and_then(|field| field.split(':').next())
+        .and_then(|number| number.parse().ok())
+        .unwrap_or(1);
+    let mut modifiers = fields.next().unwrap_or("").split(':');
+    let bits = modifiers
+        .next()
+        .and_then(|bits| bits.parse::<u32>().ok())
+        .unwrap_or(1)
+        .saturating_sub(1);
+    // Event types are 1 for a press, 2 for a repeat and 3 for a release.
+    let release = modifiers.next() == Some("3");
+
+    let event = match code {
+        'u' => parse_key_code(number, bits)?,
+        'Z' => Event::Shift(Key::Tab),
+        code => with_modifiers(legacy_key(code, number)?, bits),
+    };
+
+    Some(if release {
+        Event::Released(Box::new(event))
+    } else {
+        event
+    })
+}
+
+/// Returns the key sent with the given final byte and number.
+fn legacy_key(code: char, number: u32) -> Option<Key> {
+    Some(match (code, number) {
+        ('A', _) => Key::Up,
+        ('B', _) => Key::Down,
+        ('C', _) => Key::Right,
+        ('D', _) => Key::Left,
+        ('H', _) => Key::Home,
+        ('F', _) => Key::End,
+        ('P', _) => Key::F1,
+        ('Q', _) => Key::F2,
+        ('R', _) => Key::F3,
+        ('S', _) => Key::F4,
+        ('~', 1) | ('~', 7) => Key::Home,
+        ('~', 2) => Key::Ins,
+        ('~', 3) => Key::Del,
+        ('~', 4) | ('~', 8) => Key::End,
+        ('~', 5) => Key::PageUp,
+        ('~', 6) => Key::PageDown,
+        ('~', n @ 11..=15) => Key::from_f(n as u8 - 10),
+        ('~', n @ 17..=21) => Key::from_f(n as u8 - 11),
+        ('~', n @ 23..=24) => Key::from_f(n as u8 - 12),
+        _ => return None,
+    })
+}
+
+/// Parses a kitty key code, with modifier bits.
+fn parse_key_code(number: u32, bits: u32) -> Option<Event> {
+    let key = match number {
+        9 => Key::Tab,
+        13 => Key::Enter,
+        27 => Key::Esc,
+        8 | 127 => Key::Backspace,
+        number => {
+            let c = ::std::char::from_u32(number)?;
+            // Other functional keys use the private use area.
+            if c.is_control() || ('\u{E000}'..='\u{F8FF}').contains(&c) {
+                return None;
+            }
+            let upper = c.to_uppercase().next().unwrap_or(c);
+
+            // Shift, then Alt, then Ctrl. Other modifiers are ignored.
+            return match bits & 0b111 {
+                0 => Some(Event::Char(c)),
+                1 => Some(Event::Char(upper)),
+                2 => Some(Event::AltChar(c)),
+                3 => Some(Event::AltChar(upper)),
+                4 => Some(Event::CtrlChar(c)),
+                _ => None,
+            };
+        }
+    };
+
+    Some(with_modifiers(key, bits))
+}
+
+/// Applies modifier bits: 1 for Shift, 2 for Alt and 4 for Ctrl.
+fn with_modifiers(key: Key, bits: u32) -> Event {
+    match bits & 0b111 {
+        1 => Event::Shift(key),
+        2 => Event::Alt(key),
+        3 => Event::AltShift(key),
+        4 => Event::Ctrl(key),
+        5 => Event::CtrlShift(key),
+        6 => Event::CtrlAlt(key),
+        _ => Event::Key(key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy() {
+        assert_eq!(parse_csi("", 'A'), Some(Event::Key(Key::Up)));
+        assert_eq!(parse_csi("1;5", 'C'), Some(Event::Ctrl(Key::Right)));
+        assert_eq!(parse_csi("24", '~'), Some(Event::Key(Key::F12)));
+        assert_eq!(parse_csi("3;2", '~'), Some(Event::Shift(Key::Del)));
+        assert_eq!(parse_csi("", 'Z'), Some(Event::Shift(Key::Tab)));
+        assert_eq!(parse_csi("42", '~'), None);
+    }
+
+    #[test]
+    fn kitty() {
+        assert_eq!(parse_csi("27", 'u'), Some(Event::Key(Key::Esc)));
+        // `Ctrl-I` is not `Tab` anymore.
+        assert_eq!(parse_csi("105;5", 'u'), Some(Event::CtrlChar('i')));
+        assert_eq!(parse_csi("9;5", 'u'), Some(Event::Ctrl(Key::Tab)));
+        assert_eq!(parse_csi("97;4", 'u'), Some(Event::AltChar('A')));
+        assert_eq!(parse_csi("13;3", 'u'), Some(Event::Alt(Key::Enter)));
+        assert_eq!(parse_csi("57358", 'u'), None);
+
+        // Releases, and repeats.
+        let release = |event| Some(Event::Released(Box::new(event)));
+        assert_eq!(parse_csi("97;1:3", 'u'), release(Event::Char('a')));
+        assert_eq!(parse_csi("1;5:3", 'A'), release(Event::Ctrl(Key::Up)));
+        assert_eq!(parse_csi("3;1:3", '~'), release(Event::Key(Key::Del)));
+        assert_eq!(parse_csi("1;1:2", 'B'), Some(Event::Key(Key::Down)));
+    }
+}
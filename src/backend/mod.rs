@@ -7,6 +7,10 @@
 //! using some common libraries. Each of those included backends needs a
 //! corresonding feature to be enabled.
 
+use std::env;
+use std::io;
+use std::time::Duration;
+
 use crossbeam_channel::{Receiver, Sender};
 
 use event::Event;
@@ -16,11 +20,24 @@ use vec::Vec2;
 #[cfg(unix)]
 mod resize;
 
+mod background;
+
+pub mod buffer;
 pub mod dummy;
+pub mod kitty;
 
 pub mod blt;
 pub mod curses;
 pub mod termion;
+pub mod web;
+
+/// Longest time to wait for the rest of a bracketed paste.
+#[allow(dead_code)]
+pub(crate) const PASTE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Largest bracketed paste read, in bytes.
+#[allow(dead_code)]
+pub(crate) const PASTE_MAX_LEN: usize = 1 << 20;
 
 /// A request for input, sent to the backend.
 pub enum InputRequest {
@@ -30,6 +47,102 @@ pub enum InputRequest {
     Block,
 }
 
+/// Number of colors a backend can display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors at all, only effects like bold or reverse.
+    Monochrome,
+    /// The 8 or 16 base terminal colors.
+    Basic,
+    /// A 256-colors palette.
+    Palette256,
+    /// Any RGB color.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Returns the color depth matching the given number of colors.
+    pub fn from_count(count: u32) -> Self {
+        if count < 8 {
+            ColorDepth::Monochrome
+        } else if count < 256 {
+            ColorDepth::Basic
+        } else if count < 1 << 24 {
+            ColorDepth::Palette256
+        } else {
+            ColorDepth::TrueColor
+        }
+    }
+
+    /// Guesses the color depth from the environment.
+    ///
+    /// Looks at `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+
+        let term = env::var("TERM").unwrap_or_default();
+        if term.is_empty() || term == "dumb" {
+            ColorDepth::Monochrome
+        } else if term.contains("256") {
+            ColorDepth::Palette256
+        } else {
+            ColorDepth::Basic
+        }
+    }
+
+    /// Returns `true` if this depth has any color.
+    pub fn has_colors(self) -> bool {
+        self != ColorDepth::Monochrome
+    }
+}
+
+/// Features supported by a backend and the terminal behind it.
+///
+/// Use [`Cursive::capabilities`] to adapt the theme or layout to the
+/// terminal, for instance to use ASCII borders when unicode is missing.
+///
+/// [`Cursive::capabilities`]: ../struct.Cursive.html#method.capabilities
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Number of colors that can be displayed.
+    pub colors: ColorDepth,
+
+    /// Whether non-ASCII characters can be displayed.
+    pub unicode: bool,
+
+    /// Whether mouse events are reported.
+    pub mouse: bool,
+
+    /// Current size of the screen, in characters.
+    pub screen_size: Vec2,
+
+    /// Largest possible screen size, if the backend has one.
+    pub max_screen_size: Option<Vec2>,
+
+    /// Background color of the terminal, if it could be detected.
+    pub background: Option<theme::Color>,
+}
+
+impl Capabilities {
+    /// Returns `true` if the locale indicates a UTF-8 terminal.
+    ///
+    /// Looks at `LC_ALL`, `LC_CTYPE` and `LANG`, in this order.
+    pub fn detect_unicode() -> bool {
+        ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| {
+                let value = value.to_lowercase();
+                value.contains("utf-8") || value.contains("utf8")
+            })
+            .unwrap_or(false)
+    }
+}
+
 /// Trait defining the required methods to be a backend.
 pub trait Backend {
     // TODO: take `self` by value?
@@ -61,6 +174,76 @@ pub trait Backend {
         let _ = input_request;
     }
 
+    /// Temporarily gives the terminal back to the user.
+    ///
+    /// This should restore the terminal to the state it was in before the
+    /// backend started, until `resume()` is called.
+    ///
+    /// Default implementation does nothing.
+    fn suspend(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Takes control of the terminal again after a call to `suspend()`.
+    ///
+    /// Default implementation does nothing.
+    fn resume(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns `true` if this backend runs in the terminal controlling the
+    /// process.
+    ///
+    /// Only then does `Ctrl-Z` stop the process by default.
+    ///
+    /// Default implementation returns `false`.
+    fn is_process_terminal(&self) -> bool {
+        false
+    }
+
+    /// Sends `content` to the system clipboard, if possible.
+    ///
+    /// Default implementation does nothing.
+    fn set_clipboard(&mut self, content: &str) {
+        // Little trick to avoid unused variables.
+        let _ = content;
+    }
+
+    /// Sets the title of the terminal window.
+    ///
+    /// Default implementation does nothing.
+    fn set_title(&mut self, title: &str) {
+        // Little trick to avoid unused variables.
+        let _ = title;
+    }
+
+    /// Asks the terminal to report key releases, as `Event::Released`.
+    ///
+    /// Default implementation does nothing.
+    fn set_key_release_events(&mut self, enabled: bool) {
+        // Little trick to avoid unused variables.
+        let _ = enabled;
+    }
+
+    /// Rings the terminal bell.
+    ///
+    /// Depending on the terminal configuration, this may be audible or
+    /// visual.
+    ///
+    /// Default implementation does nothing.
+    fn beep(&mut self) {}
+
+    /// Shows the terminal cursor at the given position.
+    ///
+    /// Hides it if `pos` is `None`. This is called after each frame is
+    /// drawn, before `refresh()`.
+    ///
+    /// Default implementation does nothing.
+    fn set_cursor(&mut self, pos: Option<Vec2>) {
+        // Little trick to avoid unused variables.
+        let _ = pos;
+    }
+
     /// Refresh the screen.
     fn refresh(&mut self);
 
@@ -70,6 +253,25 @@ pub trait Backend {
     /// Returns the screen size.
     fn screen_size(&self) -> Vec2;
 
+    /// Returns the features supported by this backend.
+    ///
+    /// Default implementation relies on `has_colors()`, `screen_size()` and
+    /// the locale, and reports no mouse support.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            colors: if self.has_colors() {
+                ColorDepth::Basic
+            } else {
+                ColorDepth::Monochrome
+            },
+            unicode: Capabilities::detect_unicode(),
+            mouse: false,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: None,
+        }
+    }
+
     /// Main method used for printing
     fn print_at(&self, pos: Vec2, text: &str);
 
@@ -86,4 +288,58 @@ pub trait Backend {
 
     /// Disables the given effect.
     fn unset_effect(&self, effect: theme::Effect);
+
+    /// Starts a hyperlink to the given URL, or ends it if `url` is `None`.
+    ///
+    /// Text printed while a link is active should be clickable.
+    ///
+    /// Default implementation does nothing.
+    fn set_link(&self, url: Option<&str>) {
+        // Little trick to avoid unused variables.
+        let _ = url;
+    }
+
+    /// Prints an escape sequence drawing graphics at the given position.
+    ///
+    /// `data` is a complete sequence, like a sixel or kitty graphics image.
+    ///
+    /// Returns `false` if this backend cannot output such sequences, in
+    /// which case nothing is printed. Default implementation returns
+    /// `false`.
+    fn print_graphics(&self, pos: Vec2, data: &str) -> bool {
+        // Little trick to avoid unused variables.
+        let _ = pos;
+        let _ = data;
+        false
+    }
+}
+
+/// Returns the OSC sequence setting the window title to `title`.
+///
+/// Control characters are removed first, so the title cannot end the
+/// sequence early and inject other ones.
+#[allow(dead_code)]
+pub(crate) fn title_sequence(title: &str) -> String {
+    let title: String = title.chars().filter(|c| !c.is_control()).collect();
+    format!("\x1B]0;{}\x07", title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitized_title() {
+        assert_eq!(title_sequence("a\x07b\x1B]c"), "\x1B]0;ab]c\x07");
+    }
+
+    #[test]
+    fn color_depth_from_count() {
+        assert_eq!(ColorDepth::from_count(0), ColorDepth::Monochrome);
+        assert_eq!(ColorDepth::from_count(8), ColorDepth::Basic);
+        assert_eq!(ColorDepth::from_count(16), ColorDepth::Basic);
+        assert_eq!(ColorDepth::from_count(256), ColorDepth::Palette256);
+        assert_eq!(ColorDepth::from_count(1 << 24), ColorDepth::TrueColor);
+        assert!(ColorDepth::Basic < ColorDepth::Palette256);
+    }
 }
@@ -3,6 +3,8 @@ use std::sync::Arc;
 use std::thread;
 
 use crossbeam_channel::{Receiver, Sender};
+use libc;
+use signal_hook::cleanup;
 use signal_hook::iterator::Signals;
 
 use backend::InputRequest;
@@ -14,6 +16,11 @@ use event::Event;
 /// when detected, it wil set `needs_resize` to true and send an event to
 /// `resize_sender`. It will also consume an event from `resize_requests`
 /// afterward, to keep the balance in the force.
+///
+/// If `signals` also listens to SIGINT, it is reported as a `Ctrl-C` key,
+/// and SIGTSTP as a `Ctrl-Z` key. SIGTERM and SIGHUP are reported as
+/// `Event::Shutdown`. SIGCONT is reported like a resize, since the terminal
+/// may have changed while the process was stopped.
 #[cfg(unix)]
 pub fn start_resize_thread(
     signals: Signals, resize_sender: Sender<Option<Event>>,
@@ -22,18 +29,34 @@ pub fn start_resize_thread(
 ) {
     thread::spawn(move || {
         // This thread will listen to SIGWINCH events and report them.
-        while resize_running.load(Ordering::Relaxed) {
-            // We know it will only contain SIGWINCH signals, so no need to check.
-            if signals.wait().count() > 0 {
-                // Tell ncurses about the new terminal size.
-                // Well, do the actual resizing later on, in the main thread.
-                // Ncurses isn't really thread-safe so calling resize_term() can crash
-                // other calls like clear() or refresh().
-                if let Some(ref needs_resize) = needs_resize {
-                    needs_resize.store(true, Ordering::Relaxed);
-                }
+        while resize_running.load(Ordering::Relaxed) && !signals.is_closed()
+        {
+            for signal in signals.wait() {
+                let event = if signal == libc::SIGINT {
+                    // Curses backends don't use raw mode, so Ctrl-C is a
+                    // signal rather than a key.
+                    Event::CtrlChar('c')
+                } else if signal == libc::SIGTSTP {
+                    // Cursive restores the terminal before stopping.
+                    Event::CtrlChar('z')
+                } else if signal == libc::SIGTERM || signal == libc::SIGHUP {
+                    Event::Shutdown
+                } else {
+                    // Tell ncurses about the new terminal size.
+                    // Well, do the actual resizing later on, in the main
+                    // thread. Ncurses isn't really thread-safe so calling
+                    // resize_term() can crash other calls like clear() or
+                    // refresh().
+                    if let Some(ref needs_resize) = needs_resize {
+                        needs_resize.store(true, Ordering::Relaxed);
+                    }
+                    Event::WindowResize
+                };
 
-                resize_sender.send(Some(Event::WindowResize)).unwrap();
+                if resize_sender.send(Some(event)).is_err() {
+                    // Cursive is gone.
+                    return;
+                }
                 // We've sent the message.
                 // This means Cursive was listening, and will now soon be sending a new request.
                 // This means the input thread accepted a request, but hasn't sent a message yet.
@@ -47,9 +70,25 @@ pub fn start_resize_thread(
                     // So instead, keep sending `None`
 
                     // Repeat until we receive a blocking call
-                    resize_sender.send(None).unwrap();
+                    if resize_sender.send(None).is_err() {
+                        return;
+                    }
                 }
             }
         }
     });
 }
+
+/// Stops the thread listening to `signals`, and restores the default
+/// behaviour of each signal in `list`.
+///
+/// Otherwise Ctrl-C would be ignored once Cursive is gone.
+#[cfg(unix)]
+pub fn close_signals(signals: &Signals, list: &[libc::c_int]) {
+    signals.close();
+    for &signal in list {
+        if let Err(err) = cleanup::cleanup_signal(signal) {
+            warn!("Could not restore signal {}: {}", signal, err);
+        }
+    }
+}
@@ -1,6 +1,12 @@
 //! Backend using the pure-rust termion library.
 //!
+//! Besides the process terminal, this backend can run over any pair of
+//! input and output streams, for instance a network connection. See
+//! [`Backend::init_with_streams`].
+//!
 //! Requires the `termion-backend` feature.
+//!
+//! [`Backend::init_with_streams`]: struct.Backend.html#method.init_with_streams
 #![cfg(feature = "termion")]
 
 extern crate termion;
@@ -21,48 +27,163 @@ use libc;
 use signal_hook::iterator::Signals;
 
 use backend;
+use backend::kitty;
+use backend::{PASTE_MAX_LEN, PASTE_TIMEOUT};
+use clipboard;
 use event::{Event, Key, MouseButton, MouseEvent};
 use theme;
 use vec::Vec2;
 
-use std::cell::Cell;
-use std::io::{Stdout, Write};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io::{self, Read, Stdout, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+
+// Same sequences as the ones used by `MouseTerminal`.
+const MOUSE_ENABLE: &str = "\x1B[?1000h\x1B[?1002h\x1B[?1015h\x1B[?1006h";
+const MOUSE_DISABLE: &str = "\x1B[?1006l\x1B[?1015l\x1B[?1002l\x1B[?1000l";
+
+const PASTE_ENABLE: &str = "\x1B[?2004h";
+const PASTE_DISABLE: &str = "\x1B[?2004l";
+
+/// Signals listened to: SIGWINCH (window resize), SIGTERM and SIGHUP
+/// (shutdown), SIGTSTP and SIGCONT (suspend and resume).
+///
+/// In raw mode, `Ctrl-Z` is a key rather than SIGTSTP, but the signal can
+/// still be sent by other processes.
+#[cfg(unix)]
+const SIGNALS: &[libc::c_int] = &[
+    libc::SIGWINCH,
+    libc::SIGTERM,
+    libc::SIGHUP,
+    libc::SIGTSTP,
+    libc::SIGCONT,
+];
 
 /// Backend using termion
 pub struct Backend {
-    terminal: AlternateScreen<MouseTerminal<RawTerminal<Stdout>>>,
+    terminal: RefCell<Terminal>,
     current_style: Cell<theme::ColorPair>,
+
+    // Size set by the application, when not using the process terminal.
+    size: Option<Arc<Mutex<Vec2>>>,
+
+    // Input stream, until the input thread starts.
+    reader: Option<Box<Read + Send>>,
+    input: (Sender<Input>, Receiver<Input>),
+
+    // The signal hook, once the input thread started on the process
+    // terminal.
+    #[cfg(unix)]
+    signals: Option<Signals>,
+
+    // Background color of the terminal, detected at startup.
+    background: Option<theme::Color>,
+
+    // Where to show the cursor once the frame is drawn.
+    cursor: Option<Vec2>,
+    // The cursor is visible, and should be hidden before drawing.
+    cursor_shown: Cell<bool>,
+}
+
+/// Where the output goes.
+enum Terminal {
+    /// The process terminal, in raw mode.
+    Tty(AlternateScreen<MouseTerminal<RawTerminal<Stdout>>>),
+    /// A stream given by the application.
+    Stream(Box<Write>),
+}
+
+impl Write for Terminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            Terminal::Tty(ref mut terminal) => terminal.write(buf),
+            Terminal::Stream(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            Terminal::Tty(ref mut terminal) => terminal.flush(),
+            Terminal::Stream(ref mut stream) => stream.flush(),
+        }
+    }
+}
+
+/// Handle to report the new size of a stream-based backend.
+///
+/// Returned by [`Backend::init_with_streams`]. It can be cloned and sent to
+/// other threads, for instance to the one handling the connection.
+///
+/// [`Backend::init_with_streams`]: struct.Backend.html#method.init_with_streams
+#[derive(Clone)]
+pub struct StreamResizer {
+    size: Arc<Mutex<Vec2>>,
+    input: Sender<Input>,
+}
+
+impl StreamResizer {
+    /// Sets the size of the remote terminal.
+    ///
+    /// The UI will receive an `Event::WindowResize` and be laid out again.
+    pub fn resize(&self, size: Vec2) {
+        *self.size.lock().unwrap() = size;
+        // If the UI already stopped, nobody cares about the new size.
+        let _ = self.input.send(Input::Resize);
+    }
+
+    /// Returns the current size of the remote terminal.
+    pub fn size(&self) -> Vec2 {
+        *self.size.lock().unwrap()
+    }
+}
+
+/// Message sent to the input parser.
+enum Input {
+    Event(TEvent),
+    Resize,
+    /// The input stream was closed.
+    Closed,
 }
 
 struct InputParser {
     // Inner state required to parse input
     last_button: Option<MouseButton>,
 
+    // Text pasted so far, while inside a bracketed paste.
+    paste: Option<String>,
+    // When the last input of the paste was received.
+    paste_input: Instant,
+
     event_due: bool,
+    closed: bool,
     requests: Sender<()>,
-    input: Receiver<TEvent>,
+    input: Receiver<Input>,
 }
 
 impl InputParser {
     // Creates a non-blocking abstraction over the usual blocking input
-    fn new() -> Self {
-        let (input_sender, input_receiver) = crossbeam_channel::bounded(0);
+    fn new(
+        reader: Box<Read + Send>, input: (Sender<Input>, Receiver<Input>),
+    ) -> Self {
+        let (input_sender, input_receiver) = input;
         let (request_sender, request_receiver) = crossbeam_channel::bounded(0);
 
         // This thread will stop after an event when `InputParser` is dropped.
         thread::spawn(move || {
-            let stdin = ::std::io::stdin();
-            let stdin = stdin.lock();
-            let mut events = stdin.events();
+            let mut events = reader.events();
 
             for _ in request_receiver {
-                let event: Result<TEvent, ::std::io::Error> =
-                    events.next().unwrap();
+                let input = match events.next() {
+                    Some(Ok(event)) => Input::Event(event),
+                    _ => Input::Closed,
+                };
+                let closed = matches!(input, Input::Closed);
 
-                if input_sender.send(event.unwrap()).is_err() {
+                if input_sender.send(input).is_err() || closed {
                     return;
                 }
             }
@@ -70,9 +191,12 @@ impl InputParser {
 
         InputParser {
             last_button: None,
+            paste: None,
+            paste_input: Instant::now(),
             input: input_receiver,
             requests: request_sender,
             event_due: false,
+            closed: false,
         }
     }
 
@@ -80,7 +204,7 @@ impl InputParser {
     ///
     /// If we were already expecting input, this is a NO-OP.
     fn request(&mut self) {
-        if !self.event_due {
+        if !self.event_due && !self.closed {
             self.requests.send(()).unwrap();
             self.event_due = true;
         }
@@ -89,31 +213,118 @@ impl InputParser {
     fn peek(&mut self) -> Option<Event> {
         self.request();
 
-        let timeout = ::std::time::Duration::from_millis(10);
+        let timeout = Duration::from_millis(10);
 
         let input = select! {
             recv(self.input) -> input => {
                 input
             }
-            recv(crossbeam_channel::after(timeout)) -> _ => return None,
+            recv(crossbeam_channel::after(timeout)) -> _ => {
+                return self.check_paste_timeout();
+            }
         };
 
-        // We got what we came for.
-        self.event_due = false;
-        Some(self.map_key(input.unwrap()))
+        self.receive(input.unwrap())
     }
 
     fn next_event(&mut self) -> Event {
-        self.request();
+        loop {
+            if self.closed {
+                return Event::Shutdown;
+            }
+            self.request();
+
+            // Don't wait forever for the end of a paste.
+            let input = if self.paste.is_none() {
+                self.input.recv().unwrap()
+            } else if let Ok(input) = self.input.recv_timeout(PASTE_TIMEOUT) {
+                input
+            } else {
+                match self.check_paste_timeout() {
+                    Some(event) => return event,
+                    None => continue,
+                }
+            };
+            if let Some(event) = self.receive(input) {
+                return event;
+            }
+        }
+    }
+
+    /// Ends the current paste if no input came for `PASTE_TIMEOUT`.
+    ///
+    /// Returns what was pasted so far, if anything.
+    fn check_paste_timeout(&mut self) -> Option<Event> {
+        if self.paste_input.elapsed() < PASTE_TIMEOUT {
+            return None;
+        }
+        let paste = self.paste.take()?;
+        warn!("Paste interrupted after {} bytes", paste.len());
+        if paste.is_empty() {
+            None
+        } else {
+            Some(Event::Paste(paste))
+        }
+    }
 
-        let input = self.input.recv().unwrap();
-        self.event_due = false;
-        self.map_key(input)
+    fn receive(&mut self, input: Input) -> Option<Event> {
+        match input {
+            // Resizes don't answer our request: keep waiting for input.
+            Input::Resize => Some(Event::WindowResize),
+            Input::Closed => {
+                self.event_due = false;
+                self.closed = true;
+                Some(Event::Shutdown)
+            }
+            Input::Event(event) => {
+                // We got what we came for.
+                self.event_due = false;
+                self.parse_event(event)
+            }
+        }
+    }
+
+    /// Parses an event, unless it is part of a bracketed paste.
+    ///
+    /// Returns the entire pasted text at the end of a paste, or once it
+    /// reaches `PASTE_MAX_LEN` bytes.
+    fn parse_event(&mut self, event: TEvent) -> Option<Event> {
+        if self.paste.is_some() {
+            self.paste_input = Instant::now();
+        }
+        match event {
+            TEvent::Unsupported(ref bytes) if bytes == b"\x1B[200~" => {
+                self.paste = Some(String::new());
+                self.paste_input = Instant::now();
+                None
+            }
+            TEvent::Unsupported(ref bytes) if bytes == b"\x1B[201~" => {
+                self.paste.take().map(Event::Paste)
+            }
+            TEvent::Key(TKey::Char(c)) if self.paste.is_some() => {
+                let mut len = 0;
+                if let Some(ref mut paste) = self.paste {
+                    paste.push(c);
+                    len = paste.len();
+                }
+                if len >= PASTE_MAX_LEN {
+                    warn!("Paste too long, truncated to {} bytes", len);
+                    return self.paste.take().map(Event::Paste);
+                }
+                None
+            }
+            // Ignore anything else sent during a paste.
+            _ if self.paste.is_some() => None,
+            event => Some(self.map_key(event)),
+        }
     }
 
     fn map_key(&mut self, event: TEvent) -> Event {
         match event {
-            TEvent::Unsupported(bytes) => Event::Unknown(bytes),
+            // Termion doesn't know the keys of the kitty protocol.
+            TEvent::Unsupported(bytes) => {
+                parse_csi(&bytes).unwrap_or(Event::Unknown(bytes))
+            }
             TEvent::Key(TKey::Esc) => Event::Key(Key::Esc),
             TEvent::Key(TKey::Backspace) => Event::Key(Key::Backspace),
             TEvent::Key(TKey::Left) => Event::Key(Key::Left),
@@ -131,7 +342,6 @@ impl InputParser {
             TEvent::Key(TKey::Char('\n')) => Event::Key(Key::Enter),
             TEvent::Key(TKey::Char('\t')) => Event::Key(Key::Tab),
             TEvent::Key(TKey::Char(c)) => Event::Char(c),
-            TEvent::Key(TKey::Ctrl('c')) => Event::Exit,
             TEvent::Key(TKey::Ctrl(c)) => Event::CtrlChar(c),
             TEvent::Key(TKey::Alt(c)) => Event::AltChar(c),
             TEvent::Mouse(TMouseEvent::Press(btn, x, y)) => {
@@ -186,29 +396,38 @@ impl InputParser {
     }
 }
 
+/// Parses an unsupported control sequence, with `kitty::parse_csi`.
+fn parse_csi(bytes: &[u8]) -> Option<Event> {
+    let sequence = ::std::str::from_utf8(bytes.strip_prefix(b"\x1B[")?).ok()?;
+    let code = sequence.chars().last()?;
+    kitty::parse_csi(&sequence[..sequence.len() - code.len_utf8()], code)
+}
+
 trait Effectable {
-    fn on(&self);
-    fn off(&self);
+    fn on(&self, out: &mut Write) -> io::Result<()>;
+    fn off(&self, out: &mut Write) -> io::Result<()>;
 }
 
 impl Effectable for theme::Effect {
-    fn on(&self) {
+    fn on(&self, out: &mut Write) -> io::Result<()> {
         match *self {
-            theme::Effect::Simple => (),
-            theme::Effect::Reverse => print!("{}", tstyle::Invert),
-            theme::Effect::Bold => print!("{}", tstyle::Bold),
-            theme::Effect::Italic => print!("{}", tstyle::Italic),
-            theme::Effect::Underline => print!("{}", tstyle::Underline),
+            theme::Effect::Simple => Ok(()),
+            theme::Effect::Reverse => write!(out, "{}", tstyle::Invert),
+            theme::Effect::Bold => write!(out, "{}", tstyle::Bold),
+            theme::Effect::Italic => write!(out, "{}", tstyle::Italic),
+            theme::Effect::Underline => write!(out, "{}", tstyle::Underline),
         }
     }
 
-    fn off(&self) {
+    fn off(&self, out: &mut Write) -> io::Result<()> {
         match *self {
-            theme::Effect::Simple => (),
-            theme::Effect::Reverse => print!("{}", tstyle::NoInvert),
-            theme::Effect::Bold => print!("{}", tstyle::NoBold),
-            theme::Effect::Italic => print!("{}", tstyle::NoItalic),
-            theme::Effect::Underline => print!("{}", tstyle::NoUnderline),
+            theme::Effect::Simple => Ok(()),
+            theme::Effect::Reverse => write!(out, "{}", tstyle::NoInvert),
+            theme::Effect::Bold => write!(out, "{}", tstyle::NoBold),
+            theme::Effect::Italic => write!(out, "{}", tstyle::NoItalic),
+            theme::Effect::Underline => {
+                write!(out, "{}", tstyle::NoUnderline)
+            }
         }
     }
 }
@@ -216,36 +435,233 @@ impl Effectable for theme::Effect {
 impl Backend {
     /// Creates a new termion-based backend.
     pub fn init() -> Box<backend::Backend> {
-        print!("{}", termion::cursor::Hide);
+        // Ask before anything else reads the terminal.
+        let background = backend::background::detect();
 
         // TODO: lock stdout
         let terminal = AlternateScreen::from(MouseTerminal::from(
             ::std::io::stdout().into_raw_mode().unwrap(),
         ));
 
-        let c = Backend {
-            terminal: terminal,
-            current_style: Cell::new(theme::ColorPair::from_256colors(0, 0)),
-        };
+        let mut c = Backend::with_terminal(
+            Terminal::Tty(terminal),
+            Box::new(::std::io::stdin()),
+            None,
+        );
+        c.background = background;
+        c.write(format_args!(
+            "{}{}{}",
+            termion::cursor::Hide,
+            PASTE_ENABLE,
+            kitty::ENABLE
+        ));
 
         Box::new(c)
     }
 
+    /// Creates a new backend running over the given streams.
+    ///
+    /// `input` should send what a terminal would, and `output` receives
+    /// the escape sequences drawing the UI. This lets a single process
+    /// serve separate UIs, for instance over SSH or telnet connections.
+    ///
+    /// Unlike the process terminal, the size of a remote terminal cannot
+    /// be queried: it starts at `size`, and the returned `StreamResizer`
+    /// reports any change. Setting the remote terminal in raw mode, if
+    /// needed, is also left to the application.
+    ///
+    /// When `input` reaches its end, an `Event::Shutdown` is sent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::termion::Backend;
+    /// # use std::net::TcpListener;
+    /// # use std::thread;
+    /// let listener = TcpListener::bind("127.0.0.1:2323").unwrap();
+    /// for stream in listener.incoming() {
+    ///     let stream = stream.unwrap();
+    ///     let input = stream.try_clone().unwrap();
+    ///     thread::spawn(move || {
+    ///         let (backend, _resizer) =
+    ///             Backend::init_with_streams(input, stream, (80, 24));
+    ///         let mut siv = Cursive::new(move || backend);
+    ///         siv.add_global_callback('q', |s| s.quit());
+    ///         siv.run();
+    ///     });
+    /// }
+    /// ```
+    pub fn init_with_streams<R, W, S>(
+        input: R, output: W, size: S,
+    ) -> (Box<backend::Backend>, StreamResizer)
+    where
+        R: Read + Send + 'static,
+        W: Write + 'static,
+        S: Into<Vec2>,
+    {
+        let size = Arc::new(Mutex::new(size.into()));
+
+        let c = Backend::with_terminal(
+            Terminal::Stream(Box::new(output)),
+            Box::new(input),
+            Some(Arc::clone(&size)),
+        );
+        c.write(format_args!(
+            "{}{}{}{}{}",
+            termion::screen::ToAlternateScreen,
+            MOUSE_ENABLE,
+            PASTE_ENABLE,
+            kitty::ENABLE,
+            termion::cursor::Hide
+        ));
+
+        let resizer = StreamResizer {
+            size,
+            input: c.input.0.clone(),
+        };
+
+        (Box::new(c), resizer)
+    }
+
+    fn with_terminal(
+        terminal: Terminal, reader: Box<Read + Send>,
+        size: Option<Arc<Mutex<Vec2>>>,
+    ) -> Self {
+        Backend {
+            terminal: RefCell::new(terminal),
+            current_style: Cell::new(theme::ColorPair::from_256colors(0, 0)),
+            size,
+            reader: Some(reader),
+            input: crossbeam_channel::unbounded(),
+            #[cfg(unix)]
+            signals: None,
+            background: None,
+            cursor: None,
+            cursor_shown: Cell::new(false),
+        }
+    }
+
+    /// Hides the cursor if it is visible, before drawing over it.
+    fn hide_cursor(&self) {
+        if self.cursor_shown.replace(false) {
+            self.write(format_args!("{}", termion::cursor::Hide));
+        }
+    }
+
+    /// Writes to the terminal.
+    ///
+    /// Errors are ignored: a closed stream is reported by the input side.
+    fn write(&self, args: fmt::Arguments) {
+        let _ = self.terminal.borrow_mut().write_fmt(args);
+    }
+
+    fn flush(&self) {
+        let _ = self.terminal.borrow_mut().flush();
+    }
+
     fn apply_colors(&self, colors: theme::ColorPair) {
-        with_color(&colors.front, |c| print!("{}", tcolor::Fg(c)));
-        with_color(&colors.back, |c| print!("{}", tcolor::Bg(c)));
+        with_color(&colors.front, |c| {
+            self.write(format_args!("{}", tcolor::Fg(c)))
+        });
+        with_color(&colors.back, |c| {
+            self.write(format_args!("{}", tcolor::Bg(c)))
+        });
     }
 }
 
 impl backend::Backend for Backend {
     fn finish(&mut self) {
-        print!("{}{}", termion::cursor::Show, termion::cursor::Goto(1, 1));
-        print!(
+        #[cfg(unix)]
+        {
+            if let Some(signals) = self.signals.take() {
+                backend::resize::close_signals(&signals, SIGNALS);
+            }
+        }
+
+        self.write(format_args!(
+            "{}{}{}{}",
+            kitty::DISABLE,
+            PASTE_DISABLE,
+            termion::cursor::Show,
+            termion::cursor::Goto(1, 1)
+        ));
+        self.write(format_args!(
             "{}[49m{}[39m{}",
             27 as char,
             27 as char,
             termion::clear::All
-        );
+        ));
+
+        // The process terminal restores itself when dropped.
+        if let Terminal::Stream(_) = *self.terminal.get_mut() {
+            self.write(format_args!(
+                "{}{}",
+                MOUSE_DISABLE,
+                termion::screen::ToMainScreen
+            ));
+        }
+        self.flush();
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        self.write(format_args!(
+            "{}{}{}{}{}",
+            kitty::DISABLE,
+            PASTE_DISABLE,
+            MOUSE_DISABLE,
+            termion::screen::ToMainScreen,
+            termion::cursor::Show
+        ));
+        self.flush();
+        if let Terminal::Tty(ref terminal) = *self.terminal.get_mut() {
+            terminal.suspend_raw_mode()?;
+        }
+        Ok(())
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        if let Terminal::Tty(ref terminal) = *self.terminal.get_mut() {
+            terminal.activate_raw_mode()?;
+        }
+        self.write(format_args!(
+            "{}{}{}{}{}",
+            termion::screen::ToAlternateScreen,
+            MOUSE_ENABLE,
+            PASTE_ENABLE,
+            kitty::ENABLE,
+            termion::cursor::Hide
+        ));
+        self.cursor_shown.set(false);
+        self.flush();
+        Ok(())
+    }
+
+    fn is_process_terminal(&self) -> bool {
+        match *self.terminal.borrow() {
+            Terminal::Tty(_) => true,
+            Terminal::Stream(_) => false,
+        }
+    }
+
+    fn set_clipboard(&mut self, content: &str) {
+        self.write(format_args!("{}", clipboard::osc52(content)));
+        self.flush();
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.write(format_args!("{}", backend::title_sequence(title)));
+        self.flush();
+    }
+
+    fn beep(&mut self) {
+        self.write(format_args!("\x07"));
+        self.flush();
+    }
+
+    fn set_cursor(&mut self, pos: Option<Vec2>) {
+        // The cursor is only shown once the frame is complete.
+        self.cursor = pos;
     }
 
     fn set_color(&self, color: theme::ColorPair) -> theme::ColorPair {
@@ -260,11 +676,21 @@ impl backend::Backend for Backend {
     }
 
     fn set_effect(&self, effect: theme::Effect) {
-        effect.on();
+        let _ = effect.on(&mut *self.terminal.borrow_mut());
     }
 
     fn unset_effect(&self, effect: theme::Effect) {
-        effect.off();
+        let _ = effect.off(&mut *self.terminal.borrow_mut());
+    }
+
+    fn set_link(&self, url: Option<&str>) {
+        // OSC 8 hyperlink. An empty URL closes the link.
+        self.write(format_args!("\x1B]8;;{}\x1B\\", url.unwrap_or("")));
+    }
+
+    fn print_graphics(&self, pos: Vec2, data: &str) -> bool {
+        self.print_at(pos, data);
+        true
     }
 
     fn has_colors(&self) -> bool {
@@ -272,7 +698,22 @@ impl backend::Backend for Backend {
         true
     }
 
+    fn capabilities(&self) -> backend::Capabilities {
+        backend::Capabilities {
+            colors: backend::ColorDepth::detect(),
+            unicode: backend::Capabilities::detect_unicode(),
+            mouse: true,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: self.background,
+        }
+    }
+
     fn screen_size(&self) -> Vec2 {
+        if let Some(ref size) = self.size {
+            return *size.lock().unwrap();
+        }
+
         let (x, y) = termion::terminal_size().unwrap_or((1, 1));
         (x, y).into()
     }
@@ -282,19 +723,32 @@ impl backend::Backend for Backend {
             front: color,
             back: color,
         });
-        print!("{}", termion::clear::All);
+        self.hide_cursor();
+        self.write(format_args!("{}", termion::clear::All));
     }
 
     fn refresh(&mut self) {
-        self.terminal.flush().unwrap();
+        match self.cursor {
+            Some(pos) => {
+                self.write(format_args!(
+                    "{}{}",
+                    termion::cursor::Goto(1 + pos.x as u16, 1 + pos.y as u16),
+                    termion::cursor::Show
+                ));
+                self.cursor_shown.set(true);
+            }
+            None => self.hide_cursor(),
+        }
+        self.flush();
     }
 
     fn print_at(&self, pos: Vec2, text: &str) {
-        print!(
+        self.hide_cursor();
+        self.write(format_args!(
             "{}{}",
             termion::cursor::Goto(1 + pos.x as u16, 1 + pos.y as u16),
             text
-        );
+        ));
     }
 
     fn start_input_thread(
@@ -303,18 +757,24 @@ impl backend::Backend for Backend {
     ) {
         let running = Arc::new(AtomicBool::new(true));
 
+        // Signals only concern the process terminal.
         #[cfg(unix)]
         {
-            backend::resize::start_resize_thread(
-                Signals::new(&[libc::SIGWINCH]).unwrap(),
-                event_sink.clone(),
-                input_request.clone(),
-                Arc::clone(&running),
-                None,
-            );
+            if self.size.is_none() {
+                let signals = Signals::new(SIGNALS).unwrap();
+                self.signals = Some(signals.clone());
+                backend::resize::start_resize_thread(
+                    signals,
+                    event_sink.clone(),
+                    input_request.clone(),
+                    Arc::clone(&running),
+                    None,
+                );
+            }
         }
 
-        let mut parser = InputParser::new();
+        let reader = self.reader.take().expect("input thread already started");
+        let mut parser = InputParser::new(reader, self.input.clone());
         thread::spawn(move || {
             for req in input_request {
                 match req {
@@ -365,3 +825,105 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linear::SharedWriter;
+    use std::rc::Rc;
+    use Cursive;
+
+    #[test]
+    fn streams() {
+        let output = SharedWriter::default();
+        let input = io::Cursor::new(b"ab".to_vec());
+        let (backend, resizer) =
+            Backend::init_with_streams(input, output.clone(), (40, 10));
+        // Remote clients can't stop the process with Ctrl-Z.
+        assert!(!backend.is_process_terminal());
+
+        let mut siv = Cursive::new(move || backend);
+        assert_eq!(siv.screen_size(), Vec2::new(40, 10));
+        resizer.resize((30, 8).into());
+        assert_eq!(siv.screen_size(), Vec2::new(30, 8));
+
+        let pressed = Rc::new(Cell::new(false));
+        let flag = Rc::clone(&pressed);
+        siv.add_global_callback('a', move |_| flag.set(true));
+        // The end of the input stops the event loop.
+        siv.run();
+        assert!(pressed.get());
+
+        // The remote terminal is restored when the root is dropped.
+        drop(siv);
+
+        let output = output.contents();
+        assert!(output.starts_with("\x1B[?1049h"));
+        assert!(output.contains(MOUSE_DISABLE));
+    }
+
+    #[test]
+    fn cursor_hidden_while_drawing() {
+        let output = SharedWriter::default();
+        let input = io::Cursor::new(Vec::new());
+        let (mut backend, _resizer) =
+            Backend::init_with_streams(input, output.clone(), (40, 10));
+        let show = termion::cursor::Show.to_string();
+        let hide = termion::cursor::Hide.to_string();
+
+        backend.print_at(Vec2::new(0, 0), "first");
+        backend.set_cursor(Some(Vec2::new(2, 0)));
+        assert!(!output.contents().contains(&show));
+        backend.refresh();
+        assert!(output.contents().ends_with(&show));
+
+        // The next frame hides the cursor before drawing.
+        let start = output.len();
+        backend.print_at(Vec2::new(0, 0), "second");
+        backend.set_cursor(None);
+        backend.refresh();
+        let frame = output.contents()[start..].to_string();
+        assert!(frame.starts_with(&hide));
+        assert!(!frame.contains(&show));
+    }
+
+    fn parser() -> InputParser {
+        let reader = Box::new(io::Cursor::new(Vec::new()));
+        InputParser::new(reader, crossbeam_channel::unbounded())
+    }
+
+    fn start_paste(parser: &mut InputParser) {
+        let start = TEvent::Unsupported(b"\x1B[200~".to_vec());
+        assert_eq!(parser.parse_event(start), None);
+    }
+
+    #[test]
+    fn paste_bounds() {
+        let mut parser = parser();
+        start_paste(&mut parser);
+        for _ in 1..PASTE_MAX_LEN {
+            let event = parser.parse_event(TEvent::Key(TKey::Char('a')));
+            assert_eq!(event, None);
+        }
+        let event = parser.parse_event(TEvent::Key(TKey::Char('a')));
+        assert_eq!(event, Some(Event::Paste("a".repeat(PASTE_MAX_LEN))));
+
+        // The rest is read as regular input.
+        let event = parser.parse_event(TEvent::Key(TKey::Char('b')));
+        assert_eq!(event, Some(Event::Char('b')));
+    }
+
+    #[test]
+    fn paste_timeout() {
+        let mut parser = parser();
+        start_paste(&mut parser);
+        parser.parse_event(TEvent::Key(TKey::Char('a')));
+        assert_eq!(parser.check_paste_timeout(), None);
+
+        parser.paste_input = Instant::now() - PASTE_TIMEOUT * 2;
+        let event = parser.check_paste_timeout();
+        assert_eq!(event, Some(Event::Paste("a".to_string())));
+        let event = parser.parse_event(TEvent::Key(TKey::Char('b')));
+        assert_eq!(event, Some(Event::Char('b')));
+    }
+}
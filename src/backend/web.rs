@@ -0,0 +1,627 @@
+//! Backend for browser terminals, like xterm.js.
+//!
+//! Browsers don't let the UI block while waiting for input, so the usual
+//! [`Cursive::run`] event loop cannot be used. Instead, a [`Terminal`] is
+//! driven by the page: it is fed the data typed in the terminal widget,
+//! and sends back the escape sequences to display.
+//!
+//! This backend doesn't depend on any terminal library, and doesn't start
+//! any thread, so it can be compiled to WebAssembly. The binding to the
+//! terminal widget, for instance with `wasm-bindgen`, is left to the
+//! application.
+//!
+//! Requires the `web-backend` feature.
+//!
+//! [`Cursive::run`]: ../../struct.Cursive.html#method.run
+//! [`Terminal`]: struct.Terminal.html
+#![cfg(feature = "web-backend")]
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Write;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+use backend;
+use backend::kitty;
+use backend::PASTE_MAX_LEN;
+use clipboard;
+use event::{Event, Key, MouseButton, MouseEvent};
+use theme;
+use vec::Vec2;
+use Cursive;
+
+const MOUSE_ENABLE: &str = "\x1B[?1000h\x1B[?1002h\x1B[?1006h";
+const MOUSE_DISABLE: &str = "\x1B[?1006l\x1B[?1002l\x1B[?1000l";
+
+const PASTE_ENABLE: &str = "\x1B[?2004h";
+const PASTE_DISABLE: &str = "\x1B[?2004l";
+const PASTE_END: &str = "\x1B[201~";
+
+/// Backend writing escape sequences to a browser terminal.
+///
+/// Output is buffered, and handed to the application on each refresh.
+pub struct Backend {
+    output: RefCell<String>,
+    writer: Box<FnMut(&str)>,
+    size: Rc<Cell<Vec2>>,
+    current_style: Cell<theme::ColorPair>,
+}
+
+impl Backend {
+    /// Creates a new backend.
+    ///
+    /// `writer` receives the escape sequences to display, and should
+    /// forward them to the terminal widget (`term.write()` with xterm.js).
+    ///
+    /// The screen size is read from `size`.
+    pub fn init<F>(size: Rc<Cell<Vec2>>, writer: F) -> Box<backend::Backend>
+    where
+        F: 'static + FnMut(&str),
+    {
+        let c = Backend {
+            output: RefCell::new(String::new()),
+            writer: Box::new(writer),
+            size,
+            current_style: Cell::new(theme::ColorPair::from_256colors(0, 0)),
+        };
+        c.write(format_args!(
+            "\x1B[?1049h{}{}{}\x1B[?25l",
+            MOUSE_ENABLE,
+            PASTE_ENABLE,
+            kitty::ENABLE
+        ));
+
+        Box::new(c)
+    }
+
+    fn write(&self, args: ::std::fmt::Arguments) {
+        let _ = self.output.borrow_mut().write_fmt(args);
+    }
+
+    fn apply_colors(&self, colors: theme::ColorPair) {
+        self.write(format_args!(
+            "\x1B[{};{}m",
+            color_code(colors.front, false),
+            color_code(colors.back, true)
+        ));
+    }
+}
+
+/// Returns the SGR parameters selecting the given color.
+fn color_code(color: theme::Color, background: bool) -> String {
+    let offset = if background { 10 } else { 0 };
+    match color {
+        theme::Color::TerminalDefault => format!("{}", 39 + offset),
+        theme::Color::Dark(base) => format!("{}", 30 + offset + base as u8),
+        theme::Color::Light(base) => format!("{}", 90 + offset + base as u8),
+        theme::Color::Rgb(r, g, b) => {
+            format!("{};2;{};{};{}", 38 + offset, r, g, b)
+        }
+        theme::Color::RgbLowRes(r, g, b) => {
+            format!("{};5;{}", 38 + offset, 16 + 36 * r + 6 * g + b)
+        }
+    }
+}
+
+fn effect_code(effect: theme::Effect, on: bool) -> Option<u8> {
+    let code = match effect {
+        theme::Effect::Simple => return None,
+        theme::Effect::Reverse => 7,
+        theme::Effect::Bold => 1,
+        theme::Effect::Italic => 3,
+        theme::Effect::Underline => 4,
+    };
+    match (on, code) {
+        (true, code) => Some(code),
+        // Bold is turned off along with faint text.
+        (false, 1) => Some(22),
+        (false, code) => Some(20 + code),
+    }
+}
+
+impl backend::Backend for Backend {
+    fn finish(&mut self) {
+        self.write(format_args!(
+            "\x1B[0m\x1B[2J{}{}{}\x1B[?25h\x1B[?1049l",
+            kitty::DISABLE,
+            PASTE_DISABLE,
+            MOUSE_DISABLE
+        ));
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let output = self.output.replace(String::new());
+        if !output.is_empty() {
+            (self.writer)(&output);
+        }
+    }
+
+    fn has_colors(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> backend::Capabilities {
+        backend::Capabilities {
+            colors: backend::ColorDepth::TrueColor,
+            unicode: true,
+            mouse: true,
+            screen_size: self.screen_size(),
+            max_screen_size: None,
+            background: None,
+        }
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        self.size.get()
+    }
+
+    fn print_at(&self, pos: Vec2, text: &str) {
+        self.write(format_args!("\x1B[{};{}H{}", pos.y + 1, pos.x + 1, text));
+    }
+
+    fn clear(&self, color: theme::Color) {
+        self.apply_colors(theme::ColorPair {
+            front: color,
+            back: color,
+        });
+        self.write(format_args!("\x1B[2J"));
+    }
+
+    fn set_color(&self, color: theme::ColorPair) -> theme::ColorPair {
+        let current_style = self.current_style.get();
+
+        if current_style != color {
+            self.apply_colors(color);
+            self.current_style.set(color);
+        }
+
+        current_style
+    }
+
+    fn set_effect(&self, effect: theme::Effect) {
+        if let Some(code) = effect_code(effect, true) {
+            self.write(format_args!("\x1B[{}m", code));
+        }
+    }
+
+    fn unset_effect(&self, effect: theme::Effect) {
+        if let Some(code) = effect_code(effect, false) {
+            self.write(format_args!("\x1B[{}m", code));
+        }
+    }
+
+    fn set_cursor(&mut self, pos: Option<Vec2>) {
+        match pos {
+            Some(pos) => self.write(format_args!(
+                "\x1B[{};{}H\x1B[?25h",
+                pos.y + 1,
+                pos.x + 1
+            )),
+            None => self.write(format_args!("\x1B[?25l")),
+        }
+    }
+
+    fn set_link(&self, url: Option<&str>) {
+        self.write(format_args!("\x1B]8;;{}\x1B\\", url.unwrap_or("")));
+    }
+
+    fn set_clipboard(&mut self, content: &str) {
+        self.write(format_args!("{}", clipboard::osc52(content)));
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.write(format_args!("{}", backend::title_sequence(title)));
+    }
+
+    fn beep(&mut self) {
+        self.write(format_args!("\x07"));
+    }
+
+    fn set_key_release_events(&mut self, enabled: bool) {
+        self.write(format_args!("{}", kitty::report_releases(enabled)));
+    }
+}
+
+/// Cursive root running in a browser terminal.
+///
+/// Instead of running an event loop, the page calls [`input`] with the data
+/// typed in the terminal widget, [`resize`] when it changes size, and
+/// [`step`] regularly (for instance from a timer) to run callbacks sent
+/// through [`Cursive::cb_sink`]. The screen is redrawn after each call if
+/// needed.
+///
+/// Derefs to the wrapped `Cursive` root, to add views and callbacks.
+///
+/// `wasm32-unknown-unknown` has no clock: give one to
+/// [`clock::set_clock`], for instance reading `Date.now()`, so type-ahead
+/// searches and multiple clicks can be detected.
+///
+/// # Examples
+///
+/// With `wasm-bindgen`, and an xterm.js `Terminal` given by the page:
+///
+/// ```rust,ignore
+/// #[wasm_bindgen]
+/// pub struct App(cursive::backend::web::Terminal);
+///
+/// #[wasm_bindgen]
+/// impl App {
+///     pub fn new(term: XtermTerminal) -> App {
+///         let size = (term.cols(), term.rows());
+///         let mut siv = Terminal::new(size, move |data| term.write(data));
+///         siv.add_layer(Dialog::info("Hello from the browser!"));
+///         App(siv)
+///     }
+///
+///     // Called from `term.onData`.
+///     pub fn on_data(&mut self, data: &str) {
+///         self.0.input(data);
+///     }
+/// }
+/// ```
+///
+/// [`input`]: #method.input
+/// [`resize`]: #method.resize
+/// [`step`]: #method.step
+/// [`Cursive::cb_sink`]: ../../struct.Cursive.html#method.cb_sink
+/// [`clock::set_clock`]: ../../utils/clock/fn.set_clock.html
+pub struct Terminal {
+    siv: Cursive,
+    size: Rc<Cell<Vec2>>,
+    parser: InputParser,
+}
+
+impl Terminal {
+    /// Creates a new Cursive root for a terminal of the given size.
+    ///
+    /// `writer` receives the escape sequences to display.
+    pub fn new<S, F>(size: S, writer: F) -> Self
+    where
+        S: Into<Vec2>,
+        F: 'static + FnMut(&str),
+    {
+        let size = Rc::new(Cell::new(size.into()));
+        let backend_size = Rc::clone(&size);
+        let mut siv =
+            Cursive::new(move || Backend::init(backend_size, writer));
+        siv.redraw_if_needed();
+
+        Terminal {
+            siv,
+            size,
+            parser: InputParser::default(),
+        }
+    }
+
+    /// Processes data typed in the terminal widget.
+    pub fn input(&mut self, data: &str) {
+        for event in self.parser.parse(data) {
+            self.siv.handle_event(event);
+        }
+        self.step();
+    }
+
+    /// Updates the size of the terminal widget.
+    pub fn resize<S: Into<Vec2>>(&mut self, size: S) {
+        self.size.set(size.into());
+        self.siv.handle_event(Event::WindowResize);
+        self.step();
+    }
+
+    /// Runs pending callbacks, and redraws the screen if needed.
+    pub fn step(&mut self) {
+        self.siv.process_callbacks();
+        self.siv.redraw_if_needed();
+    }
+
+    /// Returns the wrapped `Cursive` root.
+    pub fn into_inner(self) -> Cursive {
+        self.siv
+    }
+}
+
+impl Deref for Terminal {
+    type Target = Cursive;
+
+    fn deref(&self) -> &Cursive {
+        &self.siv
+    }
+}
+
+impl DerefMut for Terminal {
+    fn deref_mut(&mut self) -> &mut Cursive {
+        &mut self.siv
+    }
+}
+
+/// Parses the data sent by a terminal widget.
+///
+/// Each chunk is expected to hold complete sequences, like the ones given
+/// by the `onData` event of xterm.js. Only pasted text may be split across
+/// chunks.
+#[derive(Default)]
+struct InputParser {
+    last_button: Option<MouseButton>,
+    // Text pasted so far, while inside a bracketed paste.
+    paste: Option<String>,
+}
+
+impl InputParser {
+    fn parse(&mut self, data: &str) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut rest = data;
+        if self.paste.is_some() {
+            rest = self.read_paste(rest, &mut events);
+        }
+
+        while let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+
+            if c != '\x1B' {
+                events.push(parse_char(c));
+                continue;
+            }
+
+            if let Some(paste) = rest.strip_prefix("[200~") {
+                self.paste = Some(String::new());
+                rest = self.read_paste(paste, &mut events);
+            } else if let Some(seq) = rest.strip_prefix('[') {
+                // Control sequence: parameters, then a final byte.
+                match seq.find(|c: char| ('@'..='~').contains(&c)) {
+                    Some(end) => {
+                        let code = seq[end..].chars().next().unwrap();
+                        events.push(self.parse_csi(&seq[..end], code));
+                        rest = &seq[end + code.len_utf8()..];
+                    }
+                    None => {
+                        let mut bytes = vec![0x1B, b'['];
+                        bytes.extend(seq.bytes());
+                        events.push(Event::Unknown(bytes));
+                        rest = "";
+                    }
+                }
+            } else if let Some(seq) = rest.strip_prefix('O') {
+                match seq.chars().next() {
+                    Some(code) => {
+                        events.push(parse_ss3(code));
+                        rest = &seq[code.len_utf8()..];
+                    }
+                    None => {
+                        events.push(Event::AltChar('O'));
+                        rest = seq;
+                    }
+                }
+            } else if let Some(next) = rest.chars().next() {
+                // Alt sends an escape before the key.
+                events.push(match parse_char(next) {
+                    Event::Char(c) => Event::AltChar(c),
+                    Event::Key(key) => Event::Alt(key),
+                    event => event,
+                });
+                rest = &rest[next.len_utf8()..];
+            } else {
+                events.push(Event::Key(Key::Esc));
+            }
+        }
+
+        events
+    }
+
+    /// Reads pasted text, up to the end of the paste.
+    ///
+    /// Returns the data following the paste, which is empty if the paste
+    /// continues in the next chunk.
+    fn read_paste<'a>(
+        &mut self, data: &'a str, events: &mut Vec<Event>,
+    ) -> &'a str {
+        let (text, rest) = match data.find(PASTE_END) {
+            Some(end) => (&data[..end], Some(&data[end + PASTE_END.len()..])),
+            None => (data, None),
+        };
+        let mut paste = self.paste.take().unwrap_or_default();
+        paste.push_str(text);
+
+        let rest = match rest {
+            Some(rest) => rest,
+            None if paste.len() < PASTE_MAX_LEN => {
+                self.paste = Some(paste);
+                return "";
+            }
+            None => {
+                warn!("Paste too long, truncated to {} bytes", paste.len());
+                ""
+            }
+        };
+
+        // Terminals send line breaks as carriage returns.
+        let text = paste.replace("\r\n", "\n").replace('\r', "\n");
+        events.push(Event::Paste(text));
+        rest
+    }
+
+    /// Parses a control sequence: `ESC [ params code`.
+    fn parse_csi(&mut self, params: &str, code: char) -> Event {
+        if let Some(params) = params.strip_prefix('<') {
+            return self.parse_mouse(params, code);
+        }
+
+        kitty::parse_csi(params, code).unwrap_or_else(|| {
+            let sequence = format!("\x1B[{}{}", params, code);
+            Event::Unknown(sequence.into_bytes())
+        })
+    }
+
+    /// Parses an SGR mouse report: `ESC [ < button ; x ; y (M|m)`.
+    fn parse_mouse(&mut self, params: &str, code: char) -> Event {
+        let params: Vec<usize> =
+            params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        if params.len() != 3 {
+            return Event::Unknown(Vec::new());
+        }
+
+        // Ignore modifier keys.
+        let button = params[0] & !0b1_1100;
+        let position = Vec2::new(
+            params[1].saturating_sub(1),
+            params[2].saturating_sub(1),
+        );
+
+        let event = match (button, code) {
+            (64, _) => MouseEvent::WheelUp,
+            (65, _) => MouseEvent::WheelDown,
+            (_, 'm') => match self.last_button.take() {
+                Some(button) => MouseEvent::Release(button),
+                None => return Event::Unknown(Vec::new()),
+            },
+            (b, _) if b & 32 != 0 => match self.last_button {
+                Some(button) => MouseEvent::Hold(button),
+                None => return Event::Unknown(Vec::new()),
+            },
+            (b, _) => {
+                let button = match b {
+                    0 => MouseButton::Left,
+                    1 => MouseButton::Middle,
+                    2 => MouseButton::Right,
+                    _ => MouseButton::Other,
+                };
+                self.last_button = Some(button);
+                MouseEvent::Press(button)
+            }
+        };
+
+        Event::Mouse {
+            event,
+            position,
+            offset: Vec2::zero(),
+        }
+    }
+}
+
+/// Parses a single character, outside of any escape sequence.
+fn parse_char(c: char) -> Event {
+    match c {
+        '\r' | '\n' => Event::Key(Key::Enter),
+        '\t' => Event::Key(Key::Tab),
+        '\x7F' | '\x08' => Event::Key(Key::Backspace),
+        '\x1B' => Event::Key(Key::Esc),
+        '\x01'..='\x1A' => Event::CtrlChar((c as u8 - 1 + b'a') as char),
+        c => Event::Char(c),
+    }
+}
+
+/// Parses `ESC O code`, sent for F1 to F4 and in application cursor mode.
+fn parse_ss3(code: char) -> Event {
+    Event::Key(match code {
+        'A' => Key::Up,
+        'B' => Key::Down,
+        'C' => Key::Right,
+        'D' => Key::Left,
+        'H' => Key::Home,
+        'F' => Key::End,
+        'P' => Key::F1,
+        'Q' => Key::F2,
+        'R' => Key::F3,
+        'S' => Key::F4,
+        code => return Event::Unknown(vec![0x1B, b'O', code as u8]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::TextView;
+
+    fn parse(data: &str) -> Vec<Event> {
+        InputParser::default().parse(data)
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(
+            parse("a\r\x7F\x01"),
+            vec![
+                Event::Char('a'),
+                Event::Key(Key::Enter),
+                Event::Key(Key::Backspace),
+                Event::CtrlChar('a'),
+            ]
+        );
+        assert_eq!(parse("\x1B"), vec![Event::Key(Key::Esc)]);
+        assert_eq!(parse("\x1Bx"), vec![Event::AltChar('x')]);
+        assert_eq!(
+            parse("\x1B[A\x1BOB"),
+            vec![Event::Key(Key::Up), Event::Key(Key::Down),]
+        );
+        assert_eq!(parse("\x1B[1;5C"), vec![Event::Ctrl(Key::Right)]);
+        assert_eq!(
+            parse("\x1B[3~\x1B[24~"),
+            vec![Event::Key(Key::Del), Event::Key(Key::F12),]
+        );
+        assert_eq!(parse("\x1B[Z"), vec![Event::Shift(Key::Tab)]);
+
+        // With the kitty keyboard protocol.
+        assert_eq!(
+            parse("\x1B[27u\x1B[105;5u\t"),
+            vec![
+                Event::Key(Key::Esc),
+                Event::CtrlChar('i'),
+                Event::Key(Key::Tab),
+            ]
+        );
+    }
+
+    #[test]
+    fn paste_and_mouse() {
+        assert_eq!(
+            parse("\x1B[200~a\rb\x1B[201~c"),
+            vec![Event::Paste("a\nb".to_string()), Event::Char('c')]
+        );
+
+        // A paste split across chunks.
+        let mut parser = InputParser::default();
+        assert_eq!(parser.parse("\x1B[200~a\r"), vec![]);
+        assert_eq!(
+            parser.parse("\nb\x1B[201~c"),
+            vec![Event::Paste("a\nb".to_string()), Event::Char('c')]
+        );
+
+        let events = parse("\x1B[<0;3;2M\x1B[<32;4;2M\x1B[<0;4;2m");
+        let mouse = |event, x| Event::Mouse {
+            event,
+            position: Vec2::new(x, 1),
+            offset: Vec2::zero(),
+        };
+        assert_eq!(
+            events,
+            vec![
+                mouse(MouseEvent::Press(MouseButton::Left), 2),
+                mouse(MouseEvent::Hold(MouseButton::Left), 3),
+                mouse(MouseEvent::Release(MouseButton::Left), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn terminal() {
+        let output = Rc::new(RefCell::new(String::new()));
+        let sink = Rc::clone(&output);
+        let mut siv = Terminal::new((20, 5), move |data: &str| {
+            sink.borrow_mut().push_str(data)
+        });
+        assert!(output.borrow().contains(MOUSE_ENABLE));
+
+        siv.add_global_callback('q', |s| s.quit());
+        siv.input("q");
+        assert!(!siv.is_running());
+
+        siv.resize((30, 6));
+        assert_eq!(siv.screen_size(), Vec2::new(30, 6));
+
+        let cb = |s: &mut Cursive| s.add_layer(TextView::new("Hello"));
+        siv.cb_sink().send(Box::new(cb)).unwrap();
+        output.borrow_mut().clear();
+        siv.step();
+        assert!(output.borrow().contains("Hello"));
+    }
+}
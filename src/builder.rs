@@ -0,0 +1,484 @@
+//! Build view trees from configuration files.
+//!
+//! A [`Context`] creates views from a TOML description, so layouts can be
+//! changed without recompiling, or shared between applications.
+//!
+//! Each view is a table with a `type`, and fields depending on that type.
+//! Callbacks are referenced by name, and must be registered on the context
+//! with [`Context::register_callback`].
+//!
+//! ```toml
+//! type = "Dialog"
+//! title = "Welcome"
+//!
+//! [content]
+//! type = "LinearLayout"
+//! orientation = "vertical"
+//!
+//! [[content.children]]
+//! type = "TextView"
+//! content = "What is your name?"
+//!
+//! [[content.children]]
+//! type = "EditView"
+//! id = "name"
+//! width = 20
+//! on_submit = "greet"
+//!
+//! [[buttons]]
+//! label = "Quit"
+//! callback = "quit"
+//! ```
+//!
+//! # Views
+//!
+//! The following types are available by default:
+//!
+//! * `Button`: `label`, and an optional `callback`.
+//! * `Checkbox`: `checked`, false by default, and an optional `label`.
+//! * `Dialog`: optional `title`, `content` and `buttons`, a list of tables
+//!   with a `label` and a `callback`.
+//! * `DummyView`.
+//! * `EditView`: optional `content`, `secret` and `on_submit` callback.
+//! * `LinearLayout`: `orientation`, `"vertical"` by default, and
+//!   `children`.
+//! * `Panel`: optional `title`, and `content`.
+//! * `ScrollView`: `content`.
+//! * `TextView`: `content`, empty by default.
+//!
+//! Other types can be added with [`Context::register_view`].
+//!
+//! Any view can also have an `id`, to find it with
+//! [`Cursive::find_id`], and a fixed `width` or `height`.
+//!
+//! [`Context`]: struct.Context.html
+//! [`Context::register_callback`]: struct.Context.html#method.register_callback
+//! [`Context::register_view`]: struct.Context.html#method.register_view
+//! [`Cursive::find_id`]: ../struct.Cursive.html#method.find_id
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use toml;
+use toml::value::Table;
+
+use direction::Orientation;
+use event::Callback;
+use view::SizeConstraint::{self, Fixed};
+use view::View;
+use views::{
+    BoxView, Button, Checkbox, Dialog, DummyView, EditView, IdView,
+    LinearLayout, Panel, ScrollView, TextView, ViewBox,
+};
+use Cursive;
+
+/// Function creating a view from its description.
+type Builder = Fn(&Table, &Context) -> Result<Box<View>, Error>;
+
+/// Possible error returned when building views.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occured when reading the file.
+    Io(io::Error),
+    /// An error occured when parsing the toml content.
+    Parse(toml::de::Error),
+    /// No view type was registered with this name.
+    UnknownType(String),
+    /// No callback was registered with this name.
+    UnknownCallback(String),
+    /// A field is missing or has the wrong type.
+    InvalidField(String),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// Registry of view types and callbacks, used to build views.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::builder::Context;
+/// let mut context = Context::new();
+/// context.register_callback("quit", Cursive::quit);
+///
+/// let view = context
+///     .load_toml(
+///         r#"
+///         type = "Button"
+///         label = "Quit"
+///         callback = "quit"
+///         "#,
+///     )
+///     .unwrap();
+///
+/// let mut siv = Cursive::dummy();
+/// siv.add_layer(view);
+/// ```
+pub struct Context {
+    builders: HashMap<String, Box<Builder>>,
+    callbacks: HashMap<String, Callback>,
+}
+
+impl Context {
+    /// Creates a new context, knowing the default view types.
+    pub fn new() -> Self {
+        let mut context = Context {
+            builders: HashMap::new(),
+            callbacks: HashMap::new(),
+        };
+
+        context.register_view("Button", build_button);
+        context.register_view("Checkbox", build_checkbox);
+        context.register_view("Dialog", build_dialog);
+        context.register_view("DummyView", |_, _| Ok(DummyView));
+        context.register_view("EditView", build_edit_view);
+        context.register_view("LinearLayout", build_linear_layout);
+        context.register_view("Panel", |config, context| {
+            let content = context.build_field(config, "content")?;
+            let mut panel = Panel::new(content);
+            if let Some(title) = get_str(config, "title")? {
+                panel.set_title(title);
+            }
+            Ok(panel)
+        });
+        context.register_view("ScrollView", |config, context| {
+            let content = context.build_field(config, "content")?;
+            Ok(ScrollView::new(content))
+        });
+        context.register_view("TextView", |config, _| {
+            let content = get_str(config, "content")?.unwrap_or("");
+            Ok(TextView::new(content))
+        });
+
+        context
+    }
+
+    /// Adds a view type, or replaces an existing one.
+    ///
+    /// `builder` is given the table describing the view, and this context
+    /// to build any child.
+    pub fn register_view<V, F>(&mut self, name: &str, builder: F)
+    where
+        V: View,
+        F: 'static + Fn(&Table, &Context) -> Result<V, Error>,
+    {
+        let builder = move |config: &Table, context: &Context| {
+            let view = builder(config, context)?;
+
+            // Wrap the view itself, so `find_id` can return it.
+            Ok(match get_str(config, "id")? {
+                Some(id) => Box::new(IdView::new(id, view)) as Box<View>,
+                None => Box::new(view),
+            })
+        };
+        self.builders.insert(name.to_string(), Box::new(builder));
+    }
+
+    /// Adds a callback that views can reference by name.
+    pub fn register_callback<F>(&mut self, name: &str, callback: F)
+    where
+        F: 'static + Fn(&mut Cursive),
+    {
+        self.callbacks
+            .insert(name.to_string(), Callback::from_fn(callback));
+    }
+
+    /// Returns the callback registered under `name`.
+    pub fn callback(&self, name: &str) -> Result<Callback, Error> {
+        self.callbacks
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownCallback(name.to_string()))
+    }
+
+    /// Builds a view from its description.
+    pub fn build(&self, config: &toml::Value) -> Result<Box<View>, Error> {
+        let config = config
+            .as_table()
+            .ok_or_else(|| Error::InvalidField("type".to_string()))?;
+        let kind = get_str(config, "type")?
+            .ok_or_else(|| Error::InvalidField("type".to_string()))?;
+        let builder = self
+            .builders
+            .get(kind)
+            .ok_or_else(|| Error::UnknownType(kind.to_string()))?;
+
+        let mut view = builder(config, self)?;
+
+        let width = get_size(config, "width")?;
+        let height = get_size(config, "height")?;
+        if width.is_some() || height.is_some() {
+            let width = width.map_or(SizeConstraint::Free, Fixed);
+            let height = height.map_or(SizeConstraint::Free, Fixed);
+            view = Box::new(BoxView::new(width, height, ViewBox::new(view)));
+        }
+
+        Ok(view)
+    }
+
+    /// Builds the view described in the given field of `config`.
+    ///
+    /// Returns an error if the field is missing.
+    pub fn build_field(
+        &self, config: &Table, field: &str,
+    ) -> Result<ViewBox, Error> {
+        let child = config
+            .get(field)
+            .ok_or_else(|| Error::InvalidField(field.to_string()))?;
+        self.build(child).map(ViewBox::new)
+    }
+
+    /// Builds a view from a TOML string.
+    pub fn load_toml(&self, content: &str) -> Result<Box<View>, Error> {
+        let config: toml::Value = toml::de::from_str(content)?;
+        self.build(&config)
+    }
+
+    /// Builds a view from a TOML file.
+    pub fn load_file<P: AsRef<Path>>(
+        &self, filename: P,
+    ) -> Result<Box<View>, Error> {
+        let content = fs::read_to_string(filename)?;
+        self.load_toml(&content)
+    }
+
+    /// Returns the callback named in the given field of `config`, if any.
+    fn callback_field(
+        &self, config: &Table, field: &str,
+    ) -> Result<Option<Callback>, Error> {
+        match get_str(config, field)? {
+            Some(name) => self.callback(name).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context::new()
+    }
+}
+
+/// Returns the string in the given field, if any.
+///
+/// Returns an error if the field is not a string.
+pub fn get_str<'a>(
+    config: &'a Table, field: &str,
+) -> Result<Option<&'a str>, Error> {
+    match config.get(field) {
+        None => Ok(None),
+        Some(value) => value
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| Error::InvalidField(field.to_string())),
+    }
+}
+
+/// Returns the boolean in the given field, if any.
+///
+/// Returns an error if the field is not a boolean.
+pub fn get_bool(config: &Table, field: &str) -> Result<Option<bool>, Error> {
+    match config.get(field) {
+        None => Ok(None),
+        Some(value) => value
+            .as_bool()
+            .map(Some)
+            .ok_or_else(|| Error::InvalidField(field.to_string())),
+    }
+}
+
+fn get_size(config: &Table, field: &str) -> Result<Option<usize>, Error> {
+    match config.get(field) {
+        None => Ok(None),
+        Some(value) => value
+            .as_integer()
+            .filter(|&size| size >= 0)
+            .map(|size| Some(size as usize))
+            .ok_or_else(|| Error::InvalidField(field.to_string())),
+    }
+}
+
+fn build_button(config: &Table, context: &Context) -> Result<Button, Error> {
+    let label = get_str(config, "label")?
+        .ok_or_else(|| Error::InvalidField("label".to_string()))?;
+    let callback = context.callback_field(config, "callback")?;
+    Ok(Button::new(label, move |s| {
+        if let Some(ref callback) = callback {
+            callback(s);
+        }
+    }))
+}
+
+fn build_checkbox(config: &Table, _: &Context) -> Result<Checkbox, Error> {
+    let mut checkbox = Checkbox::new();
+    checkbox.set_checked(get_bool(config, "checked")?.unwrap_or(false));
+    if let Some(label) = get_str(config, "label")? {
+        checkbox.set_label(label);
+    }
+    Ok(checkbox)
+}
+
+fn build_dialog(config: &Table, context: &Context) -> Result<Dialog, Error> {
+    let mut dialog = match config.get("content") {
+        Some(_) => Dialog::around(context.build_field(config, "content")?),
+        None => Dialog::new(),
+    };
+
+    if let Some(title) = get_str(config, "title")? {
+        dialog.set_title(title);
+    }
+
+    let buttons = match config.get("buttons") {
+        None => &[][..],
+        Some(toml::Value::Array(buttons)) => &buttons[..],
+        Some(_) => return Err(Error::InvalidField("buttons".to_string())),
+    };
+    for button in buttons {
+        let button = button
+            .as_table()
+            .ok_or_else(|| Error::InvalidField("buttons".to_string()))?;
+        let label = get_str(button, "label")?
+            .ok_or_else(|| Error::InvalidField("label".to_string()))?;
+        let callback = context.callback_field(button, "callback")?;
+        dialog.add_button(label, move |s| {
+            if let Some(ref callback) = callback {
+                callback(s);
+            }
+        });
+    }
+
+    Ok(dialog)
+}
+
+fn build_edit_view(
+    config: &Table, context: &Context,
+) -> Result<EditView, Error> {
+    let mut edit = EditView::new();
+    if let Some(content) = get_str(config, "content")? {
+        edit.set_content(content);
+    }
+    if get_bool(config, "secret")?.unwrap_or(false) {
+        edit.set_secret(true);
+    }
+    if let Some(callback) = context.callback_field(config, "on_submit")? {
+        edit.set_on_submit(move |s, _| callback(s));
+    }
+    Ok(edit)
+}
+
+fn build_linear_layout(
+    config: &Table, context: &Context,
+) -> Result<LinearLayout, Error> {
+    let orientation = match get_str(config, "orientation")? {
+        None | Some("vertical") => Orientation::Vertical,
+        Some("horizontal") => Orientation::Horizontal,
+        Some(_) => return Err(Error::InvalidField("orientation".to_string())),
+    };
+
+    let mut layout = LinearLayout::new(orientation);
+    match config.get("children") {
+        None => (),
+        Some(toml::Value::Array(children)) => {
+            for child in children {
+                layout.add_child(context.build(child)?);
+            }
+        }
+        Some(_) => return Err(Error::InvalidField("children".to_string())),
+    }
+    Ok(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn build() {
+        let pressed = Rc::new(Cell::new(false));
+        let mut context = Context::new();
+        {
+            let pressed = Rc::clone(&pressed);
+            context.register_callback("press", move |_| pressed.set(true));
+        }
+
+        let view = context
+            .load_toml(
+                r#"
+                type = "Dialog"
+                title = "Hello"
+
+                [content]
+                type = "LinearLayout"
+
+                [[content.children]]
+                type = "TextView"
+                content = "Name:"
+
+                [[content.children]]
+                type = "EditView"
+                id = "name"
+                content = "Bob"
+                width = 10
+
+                [[buttons]]
+                label = "Ok"
+                callback = "press"
+                "#,
+            )
+            .unwrap();
+
+        let mut siv = Cursive::dummy();
+        siv.add_layer(view);
+        let content =
+            siv.call_on_id("name", |v: &mut EditView| v.get_content());
+        assert_eq!(content.unwrap().as_str(), "Bob");
+
+        let button = context
+            .load_toml(
+                "type = \"Button\"\nlabel = \"Ok\"\ncallback = \"press\"",
+            )
+            .unwrap();
+        siv.add_layer(button);
+        siv.on_event(Event::Key(Key::Enter));
+        assert!(pressed.get());
+    }
+
+    #[test]
+    fn errors() {
+        let context = Context::new();
+        let error = |content| context.load_toml(content).err().unwrap();
+
+        match error("type = \"Nope\"") {
+            Error::UnknownType(ref kind) if kind == "Nope" => (),
+            e => panic!("{:?}", e),
+        }
+        match error("type = \"Button\"\nlabel = \"a\"\ncallback = \"b\"") {
+            Error::UnknownCallback(ref name) if name == "b" => (),
+            e => panic!("{:?}", e),
+        }
+        match error("type = \"TextView\"\ncontent = 3") {
+            Error::InvalidField(ref field) if field == "content" => (),
+            e => panic!("{:?}", e),
+        }
+        match error("type = ") {
+            Error::Parse(_) => (),
+            e => panic!("{:?}", e),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Clipboard shared by text inputs.
+//!
+//! [`EditView`] and [`TextArea`] copy their content to this clipboard when
+//! receiving their copy key, and insert the clipboard content when receiving
+//! their paste key. These keys are set per view with [`ClipboardKeys`].
+//!
+//! The clipboard is kept in memory. When set through
+//! [`Cursive::set_clipboard`], its content is also sent to the terminal
+//! using the OSC 52 escape sequence, which most terminal emulators forward
+//! to the system clipboard.
+//!
+//! [`EditView`]: ../views/struct.EditView.html
+//! [`TextArea`]: ../views/struct.TextArea.html
+//! [`ClipboardKeys`]: struct.ClipboardKeys.html
+//! [`Cursive::set_clipboard`]: ../struct.Cursive.html#method.set_clipboard
+
+use event::Event;
+use std::sync::Mutex;
+use utils::base64;
+
+lazy_static! {
+    static ref CLIPBOARD: Mutex<String> = Mutex::new(String::new());
+}
+
+/// Returns the current content of the clipboard.
+pub fn get() -> String {
+    CLIPBOARD.lock().unwrap().clone()
+}
+
+/// Sets the content of the in-memory clipboard.
+///
+/// Unlike [`Cursive::set_clipboard`], this does not forward the content to
+/// the terminal.
+///
+/// [`Cursive::set_clipboard`]: ../struct.Cursive.html#method.set_clipboard
+pub fn set<S: Into<String>>(content: S) {
+    *CLIPBOARD.lock().unwrap() = content.into();
+}
+
+/// Keys copying and pasting in a text input.
+///
+/// Note that `Ctrl-C` quits the application by default, so you may want to
+/// use another copy binding, or change this with [`Cursive::set_ctrl_c`].
+///
+/// [`Cursive::set_ctrl_c`]: ../struct.Cursive.html#method.set_ctrl_c
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClipboardKeys {
+    /// Key copying content to the clipboard. Defaults to `Ctrl+C`.
+    pub copy: Option<Event>,
+    /// Key pasting the clipboard content. Defaults to `Ctrl+V`.
+    pub paste: Option<Event>,
+}
+
+impl Default for ClipboardKeys {
+    fn default() -> Self {
+        ClipboardKeys {
+            copy: Some(Event::CtrlChar('c')),
+            paste: Some(Event::CtrlChar('v')),
+        }
+    }
+}
+
+impl ClipboardKeys {
+    /// Returns keys without any clipboard binding.
+    pub fn none() -> Self {
+        ClipboardKeys {
+            copy: None,
+            paste: None,
+        }
+    }
+
+    /// Returns `true` if `event` copies content to the clipboard.
+    pub fn is_copy(&self, event: &Event) -> bool {
+        self.copy.as_ref() == Some(event)
+    }
+
+    /// Returns `true` if `event` pastes the clipboard content.
+    pub fn is_paste(&self, event: &Event) -> bool {
+        self.paste.as_ref() == Some(event)
+    }
+}
+
+/// Returns the OSC 52 sequence setting the system clipboard to `content`.
+pub(crate) fn osc52(content: &str) -> String {
+    format!("\x1B]52;c;{}\x07", base64::encode(content.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        let keys = ClipboardKeys::default();
+        assert!(keys.is_copy(&Event::CtrlChar('c')));
+        assert!(!keys.is_paste(&Event::CtrlChar('c')));
+        assert!(!ClipboardKeys::none().is_paste(&Event::CtrlChar('v')));
+    }
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hi"), "\x1B]52;c;aGk=\x07");
+    }
+}
@@ -1,19 +1,46 @@
-use std::any::Any;
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::io;
+use std::mem;
 use std::path::Path;
-use std::time::Duration;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::{self, Receiver, Sender};
+#[cfg(unix)]
+use libc;
 
+use align::HAlign;
 use backend;
+use backend::buffer::PrintBuffer;
+use clipboard;
 use direction;
-use event::{Callback, Event, EventResult};
+use event::{
+    Callback, Event, EventResult, Key, MouseButton, MouseEvent, Priority,
+};
+use linear;
+use panic_hook;
 use printer::Printer;
+#[cfg(feature = "profiling")]
+use profiling::{self, FrameStats};
+use rect::Rect;
+use replay;
 use theme;
+use transition::{self, Animation, Target, Transition, TransitionStyle};
+use utils::clock;
 use vec::Vec2;
-use view::{self, Finder, IntoBoxedView, Position, View};
+use view::{self, Finder, IntoBoxedView, Position, Scrollable, View};
 use views::{self, LayerPosition};
 
+/// Id of the layer used by the view inspector.
+static INSPECTOR_ID: &str = "_cursive_inspector";
+
+/// Id of the view used by the debug console.
+static DEBUG_VIEW_ID: &str = "_cursive_debug_view";
+
 /// Central part of the cursive library.
 ///
 /// It initializes ncurses on creation and cleans up on drop.
@@ -23,20 +50,100 @@ use views::{self, LayerPosition};
 /// It uses a list of screen, with one screen active at a time.
 pub struct Cursive {
     theme: theme::Theme,
+
+    // Themes to pick from, depending on `theme_mode`.
+    dark_theme: Option<theme::Theme>,
+    light_theme: Option<theme::Theme>,
+    theme_mode: theme::ThemeMode,
+
+    // Replace colors with effects.
+    monochrome: bool,
+
+    // Log how each event is dispatched.
+    trace_events: bool,
+
     screens: Vec<views::StackView>,
     global_callbacks: HashMap<Event, Vec<Callback>>,
+    // Global callbacks running before the views.
+    priority_callbacks: HashMap<Event, Vec<Callback>>,
+
+    // Events to use for input sequences the backend doesn't know.
+    sequences: HashMap<Vec<u8>, Event>,
+    // Menubar of the active screen.
     menubar: views::Menubar,
+    // Menubar shared by screens without their own, while the active screen
+    // has its own.
+    shared_menubar: Option<views::Menubar>,
+    // Menubars of inactive screens having their own.
+    screen_menubars: HashMap<ScreenId, views::Menubar>,
+    // Titles shown by `ScreenTabs` views.
+    screen_titles: HashMap<ScreenId, String>,
+    // Screens listed by `ScreenTabs` views, kept up to date.
+    screen_list: Rc<RefCell<views::ScreenList>>,
 
     // Last layer sizes of the stack view.
     // If it changed, clear the screen.
     last_sizes: Vec<Vec2>,
 
+    // Screen size when the "too small" message was last drawn.
+    too_small_size: Option<Vec2>,
+
     fps: u32,
 
+    // Something happened since the last frame.
+    needs_redraw: bool,
+    last_frame: Option<Instant>,
+    frame_budget: FrameBudget,
+
     active_screen: ScreenId,
 
     running: bool,
 
+    // Called before quitting; returns `false` to keep running.
+    on_quit: Option<OnQuit>,
+
+    ctrl_c: CtrlC,
+
+    on_resize: Option<OnResize>,
+
+    // Receives every event instead of the views, when set.
+    input_grab: Option<InputGrab>,
+
+    // Below this size, views are replaced with a warning.
+    min_screen_size: Vec2,
+
+    // Drop redundant events from batches.
+    coalesce_events: bool,
+
+    // Pop the top layer on ignored `Esc` presses.
+    esc_closes_layer: bool,
+
+    // Last mouse press, to detect double and triple clicks.
+    last_press: Option<LastPress>,
+    multi_press_interval: Duration,
+
+    // Animation used when screens and layers change, if any.
+    transition: Option<Transition>,
+    animation: Option<Animation>,
+
+    // Area currently highlighted by the view inspector.
+    inspector_highlight: Option<Rect>,
+
+    // Where to save events received from the backend, if anywhere.
+    recorder: Option<replay::Recorder>,
+
+    // Plain text output following the focus, for screen readers.
+    linear_output: Option<linear::LinearOutput>,
+
+    // Synthetic events to process before any real input.
+    injected_events: VecDeque<Event>,
+
+    // Timings of the last frame, and whether to show them on screen.
+    #[cfg(feature = "profiling")]
+    frame_stats: FrameStats,
+    #[cfg(feature = "profiling")]
+    profiling_overlay: bool,
+
     backend: Box<backend::Backend>,
 
     cb_source: Receiver<Box<CbFunc>>,
@@ -49,6 +156,14 @@ pub struct Cursive {
     expecting_event: bool,
 }
 
+/// A mouse press, remembered to count quick successive presses.
+struct LastPress {
+    time: Option<Duration>,
+    button: MouseButton,
+    position: Vec2,
+    count: usize,
+}
+
 /// Describes one of the possible interruptions we should handle.
 enum Interruption {
     /// An input event was received
@@ -59,6 +174,83 @@ enum Interruption {
     Timeout,
 }
 
+/// What happens when the user presses `Ctrl-C`.
+///
+/// Set with [`Cursive::set_ctrl_c`].
+///
+/// [`Cursive::set_ctrl_c`]: struct.Cursive.html#method.set_ctrl_c
+#[derive(Clone)]
+pub enum CtrlC {
+    /// Quits the application, as if [`Cursive::quit`] was called.
+    ///
+    /// This is the default.
+    ///
+    /// [`Cursive::quit`]: struct.Cursive.html#method.quit
+    Quit,
+
+    /// Sends `Event::CtrlChar('c')` to the views, like any other key.
+    Event,
+
+    /// Runs the given callback.
+    Callback(Callback),
+}
+
+/// Drawing statistics, returned by [`Cursive::frame_budget`].
+///
+/// [`Cursive::frame_budget`]: struct.Cursive.html#method.frame_budget
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameBudget {
+    /// Time allowed between two frames, set by `Cursive::set_fps`.
+    ///
+    /// `None` if the frame rate is not limited.
+    pub budget: Option<Duration>,
+
+    /// Number of frames drawn.
+    pub frames: u64,
+
+    /// Time spent drawing the last frame.
+    pub last_frame: Duration,
+
+    /// Longest time spent drawing a frame.
+    pub max_frame: Duration,
+
+    /// Number of frames which took longer than the budget.
+    pub over_budget: u64,
+
+    /// Number of redraws postponed to respect the frame rate.
+    pub deferred: u64,
+}
+
+/// Navigable state of the application, returned by [`Cursive::dump`].
+///
+/// With the `serde` feature, it can be saved to a file, so the user can
+/// resume where they left off.
+///
+/// [`Cursive::dump`]: struct.Cursive.html#method.dump
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// Id of the active screen.
+    pub active_screen: ScreenId,
+
+    /// For each screen, the id of each layer, from back to front.
+    ///
+    /// This is the first id found in the layer, if any.
+    pub layers: Vec<Vec<Option<String>>>,
+
+    /// State of each view with an id.
+    pub views: HashMap<String, view::ViewState>,
+}
+
+/// Callback called before quitting.
+type OnQuit = Box<FnMut(&mut Cursive) -> bool>;
+
+/// Callback called when the terminal is resized.
+type OnResize = Rc<Fn(&mut Cursive, Vec2)>;
+
+/// Callback receiving all events while the input is grabbed.
+type InputGrab = Rc<RefCell<FnMut(&mut Cursive, Event)>>;
+
 /// Identifies a screen in the cursive root.
 pub type ScreenId = usize;
 
@@ -133,15 +325,59 @@ impl Cursive {
         let mut backend = backend_init();
         backend.start_input_thread(event_sink, input_source);
 
+        // See https://no-color.org
+        let no_color =
+            env::var_os("NO_COLOR").map_or(false, |v| !v.is_empty());
+        let monochrome =
+            no_color || !backend.capabilities().colors.has_colors();
+        panic_hook::acquire();
+
         Cursive {
             fps: 0,
+            needs_redraw: true,
+            last_frame: None,
+            frame_budget: FrameBudget::default(),
             theme,
+            dark_theme: None,
+            light_theme: None,
+            theme_mode: theme::ThemeMode::Auto,
+            monochrome,
+            trace_events: false,
             screens: vec![views::StackView::new()],
             last_sizes: Vec::new(),
+            too_small_size: None,
             global_callbacks: HashMap::new(),
+            priority_callbacks: HashMap::new(),
+            sequences: HashMap::new(),
             menubar: views::Menubar::new(),
+            shared_menubar: None,
+            screen_menubars: HashMap::new(),
+            screen_titles: HashMap::new(),
+            screen_list: Rc::new(RefCell::new(views::ScreenList {
+                titles: vec![String::new()],
+                active: 0,
+            })),
             active_screen: 0,
             running: true,
+            on_quit: None,
+            ctrl_c: CtrlC::Quit,
+            on_resize: None,
+            input_grab: None,
+            min_screen_size: Vec2::zero(),
+            coalesce_events: true,
+            esc_closes_layer: false,
+            last_press: None,
+            multi_press_interval: Duration::from_millis(500),
+            transition: None,
+            animation: None,
+            inspector_highlight: None,
+            recorder: None,
+            linear_output: None,
+            injected_events: VecDeque::new(),
+            #[cfg(feature = "profiling")]
+            frame_stats: FrameStats::default(),
+            #[cfg(feature = "profiling")]
+            profiling_overlay: false,
             cb_source,
             cb_sink,
             event_source,
@@ -190,9 +426,6 @@ impl Cursive {
     /// Callbacks will be executed in the order
     /// of arrival on the next event cycle.
     ///
-    /// Note that you currently need to call [`set_fps`] to force cursive to
-    /// regularly check for messages.
-    ///
     /// # Examples
     ///
     /// ```rust
@@ -206,8 +439,6 @@ impl Cursive {
     /// siv.cb_sink().send(Box::new(|s: &mut Cursive| s.quit())).unwrap();
     /// # }
     /// ```
-    ///
-    /// [`set_fps`]: #method.set_fps
     pub fn cb_sink(&self) -> &Sender<Box<CbFunc>> {
         &self.cb_sink
     }
@@ -229,6 +460,9 @@ impl Cursive {
     ///
     /// This allows to add menu items to the menubar.
     ///
+    /// If the active screen has its own menubar, this returns it. See
+    /// [`set_screen_menubar`].
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -275,10 +509,90 @@ impl Cursive {
     /// siv.add_global_callback(event::Key::Esc, |s| s.select_menubar());
     /// # }
     /// ```
+    ///
+    /// [`set_screen_menubar`]: #method.set_screen_menubar
     pub fn menubar(&mut self) -> &mut views::Menubar {
         &mut self.menubar
     }
 
+    /// Gives its own menubar to the given screen.
+    ///
+    /// It replaces the shared menubar while this screen is active. Other
+    /// screens keep using the shared menubar.
+    ///
+    /// # Panics
+    ///
+    /// If no screen with this ID exists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Menubar;
+    /// let mut siv = Cursive::dummy();
+    /// siv.menubar().add_leaf("Quit", |s| s.quit());
+    ///
+    /// let editor = siv.add_screen();
+    /// let mut menubar = Menubar::new();
+    /// menubar
+    ///     .add_leaf("Save", |_| ())
+    ///     .add_leaf("Close", |s| s.set_screen(0));
+    /// siv.set_screen_menubar(editor, menubar);
+    ///
+    /// siv.set_screen(editor);
+    /// assert_eq!(siv.menubar().len(), 2);
+    /// ```
+    pub fn set_screen_menubar(
+        &mut self, screen_id: ScreenId, menubar: views::Menubar,
+    ) {
+        self.check_screen_id(screen_id);
+        if screen_id != self.active_screen {
+            self.screen_menubars.insert(screen_id, menubar);
+        } else if self.shared_menubar.is_some() {
+            self.menubar = menubar;
+        } else {
+            let shared = mem::replace(&mut self.menubar, menubar);
+            self.shared_menubar = Some(shared);
+        }
+        self.clear();
+    }
+
+    /// Makes the given screen use the shared menubar again.
+    ///
+    /// Returns the menubar it had, if any.
+    ///
+    /// # Panics
+    ///
+    /// If no screen with this ID exists.
+    pub fn remove_screen_menubar(
+        &mut self, screen_id: ScreenId,
+    ) -> Option<views::Menubar> {
+        self.check_screen_id(screen_id);
+        if screen_id != self.active_screen {
+            return self.screen_menubars.remove(&screen_id);
+        }
+
+        let shared = self.shared_menubar.take()?;
+        self.clear();
+        Some(mem::replace(&mut self.menubar, shared))
+    }
+
+    /// Puts the menubar of `screen_id` in place, before it becomes active.
+    fn swap_menubar(&mut self, screen_id: ScreenId) {
+        if screen_id == self.active_screen {
+            return;
+        }
+
+        if let Some(shared) = self.shared_menubar.take() {
+            let own = mem::replace(&mut self.menubar, shared);
+            self.screen_menubars.insert(self.active_screen, own);
+        }
+        if let Some(own) = self.screen_menubars.remove(&screen_id) {
+            let shared = mem::replace(&mut self.menubar, own);
+            self.shared_menubar = Some(shared);
+        }
+    }
+
     /// Returns the currently used theme.
     pub fn current_theme(&self) -> &theme::Theme {
         &self.theme
@@ -290,12 +604,197 @@ impl Cursive {
         self.clear();
     }
 
+    /// Registers the theme to use on dark backgrounds.
+    ///
+    /// It will be used right away if it matches the current theme mode.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::theme::{self, Color, PaletteColor};
+    /// # let mut siv = Cursive::dummy();
+    /// let mut dark = theme::load_default();
+    /// dark.palette[PaletteColor::Background] = Color::Rgb(0, 0, 0);
+    /// siv.set_dark_theme(dark);
+    /// siv.set_light_theme(theme::load_default());
+    /// ```
+    pub fn set_dark_theme(&mut self, theme: theme::Theme) {
+        self.dark_theme = Some(theme);
+        self.apply_theme_mode();
+    }
+
+    /// Registers the theme to use on light backgrounds.
+    ///
+    /// It will be used right away if it matches the current theme mode.
+    pub fn set_light_theme(&mut self, theme: theme::Theme) {
+        self.light_theme = Some(theme);
+        self.apply_theme_mode();
+    }
+
+    /// Chooses between the dark and light themes.
+    ///
+    /// By default, the theme matching the terminal background is used.
+    pub fn set_theme_mode(&mut self, mode: theme::ThemeMode) {
+        self.theme_mode = mode;
+        self.apply_theme_mode();
+    }
+
+    /// Returns the current theme mode.
+    pub fn theme_mode(&self) -> theme::ThemeMode {
+        self.theme_mode
+    }
+
+    /// Uses the registered theme matching the theme mode, if any.
+    fn apply_theme_mode(&mut self) {
+        let dark = match self.theme_mode {
+            theme::ThemeMode::Dark => true,
+            theme::ThemeMode::Light => false,
+            theme::ThemeMode::Auto => self
+                .capabilities()
+                .background
+                .map_or(true, theme::Color::is_dark),
+        };
+
+        let (preferred, other) = if dark {
+            (&self.dark_theme, &self.light_theme)
+        } else {
+            (&self.light_theme, &self.dark_theme)
+        };
+
+        if let Some(theme) = preferred.as_ref().or_else(|| other.as_ref()) {
+            let theme = theme.clone();
+            self.set_theme(theme);
+        }
+    }
+
     /// Clears the screen.
     ///
     /// Users rarely have to call this directly.
     pub fn clear(&self) {
-        self.backend
-            .clear(self.theme.palette[theme::PaletteColor::Background]);
+        if self.monochrome {
+            self.backend.clear(theme::Color::TerminalDefault);
+        } else {
+            self.backend
+                .clear(self.theme.palette[theme::PaletteColor::Background]);
+        }
+    }
+
+    /// Draws without colors, using only effects like reverse or bold.
+    ///
+    /// This keeps the UI usable on monochrome terminals. It is enabled by
+    /// default when the terminal has no colors, or when the `NO_COLOR`
+    /// environment variable is set.
+    ///
+    /// See [`ColorStyle::monochrome_effects`].
+    ///
+    /// [`ColorStyle::monochrome_effects`]: theme/struct.ColorStyle.html#method.monochrome_effects
+    pub fn set_monochrome(&mut self, monochrome: bool) {
+        self.monochrome = monochrome;
+        self.clear();
+    }
+
+    /// Returns `true` if colors are replaced by effects.
+    pub fn is_monochrome(&self) -> bool {
+        self.monochrome
+    }
+
+    /// Logs how each event is dispatched.
+    ///
+    /// For every event, debug records tell whether the views consumed it,
+    /// and how many callbacks ran when they ignored it. Records also give
+    /// the focus path when the event was sent: the view which handled it
+    /// is on this path, but isn't identified, nor are the callbacks.
+    /// Show these records with the [debug console].
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Key;
+    /// let mut siv = Cursive::dummy();
+    /// cursive::logger::init();
+    ///
+    /// siv.set_event_tracing(true);
+    /// siv.add_global_callback(Key::F2, Cursive::toggle_debug_console);
+    /// ```
+    ///
+    /// [debug console]: #method.toggle_debug_console
+    pub fn set_event_tracing(&mut self, enabled: bool) {
+        self.trace_events = enabled;
+    }
+
+    /// Returns `true` if the dispatch of events is logged.
+    pub fn is_event_tracing(&self) -> bool {
+        self.trace_events
+    }
+
+    /// Logs a step in the dispatch of an event, if tracing is enabled.
+    fn trace_event<F>(&self, message: F)
+    where
+        F: FnOnce() -> String,
+    {
+        if self.trace_events {
+            debug!("[event] {}", message());
+        }
+    }
+
+    /// Returns the names of the focused views, from the screen down.
+    fn focus_path(&self) -> String {
+        self.inspect()
+            .into_iter()
+            .filter(|node| node.focused)
+            .map(|node| node.name)
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+
+    /// Sets the content of the clipboard.
+    ///
+    /// The content is kept in memory, and is also sent to the terminal's
+    /// clipboard when the backend supports it.
+    ///
+    /// See the [`clipboard`] module for more details.
+    ///
+    /// [`clipboard`]: clipboard/index.html
+    pub fn set_clipboard<S: Into<String>>(&mut self, content: S) {
+        let content = content.into();
+        self.backend.set_clipboard(&content);
+        clipboard::set(content);
+    }
+
+    /// Returns the current content of the clipboard.
+    pub fn get_clipboard(&self) -> String {
+        clipboard::get()
+    }
+
+    /// Sets the title of the terminal window.
+    ///
+    /// Not all backends or terminals support this.
+    pub fn set_window_title<S: AsRef<str>>(&mut self, title: S) {
+        self.backend.set_title(title.as_ref());
+    }
+
+    /// Rings the terminal bell.
+    ///
+    /// Depending on the terminal, this may be a sound or a visual flash.
+    pub fn beep(&mut self) {
+        self.backend.beep();
+    }
+
+    /// Enables or disables `Event::Released` events, sent when keys are
+    /// released.
+    ///
+    /// Only the web backend supports this, in terminals implementing the
+    /// kitty keyboard protocol. See the [`backend::kitty`] module.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`backend::kitty`]: backend/kitty/index.html
+    pub fn set_key_release_events(&mut self, enabled: bool) {
+        self.backend.set_key_release_events(enabled);
     }
 
     /// Loads a theme from the given file.
@@ -318,15 +817,100 @@ impl Cursive {
     ///
     /// Regularly redraws everything, even when no input is given.
     ///
-    /// You currently need this to regularly check
-    /// for events sent using [`cb_sink`].
+    /// This also caps the frame rate: input is still processed as soon as
+    /// it is received, but the screen is redrawn at most `fps` times per
+    /// second.
     ///
     /// Between 0 and 1000. Call with `fps = 0` to disable (default value).
-    ///
-    /// [`cb_sink`]: #method.cb_sink
     pub fn set_fps(&mut self, fps: u32) {
         // self.backend.set_refresh_rate(fps)
         self.fps = fps;
+        self.frame_budget.budget = self.frame_interval();
+    }
+
+    /// Returns statistics about the time spent drawing.
+    pub fn frame_budget(&self) -> FrameBudget {
+        self.frame_budget
+    }
+
+    /// Returns the time spent on the last frame, and on each
+    /// [`ProfiledView`].
+    ///
+    /// Requires the `profiling` feature.
+    ///
+    /// [`ProfiledView`]: views/struct.ProfiledView.html
+    #[cfg(feature = "profiling")]
+    pub fn last_frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
+    }
+
+    /// Shows the timings of the last frame in the top-right corner.
+    ///
+    /// Requires the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    pub fn set_profiling_overlay(&mut self, enabled: bool) {
+        self.profiling_overlay = enabled;
+        self.clear();
+    }
+
+    /// Returns the time between two frames, if the frame rate is limited.
+    fn frame_interval(&self) -> Option<Duration> {
+        if self.fps > 0 {
+            Some(Duration::from_millis(1000 / u64::from(self.fps)))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the time between two frames, including while animating.
+    fn tick_interval(&self) -> Option<Duration> {
+        let fps = u64::from(transition::DEFAULT_FPS);
+        self.frame_interval().or_else(|| {
+            self.animation
+                .as_ref()
+                .map(|_| Duration::from_millis(1000 / fps))
+        })
+    }
+
+    /// Animates screen and layer changes with the given transition.
+    ///
+    /// Animations advance by one step on each frame. If no frame rate was
+    /// set with [`set_fps`], the screen is refreshed 30 times per second
+    /// while animating.
+    ///
+    /// See the [`transition`] module for more details.
+    ///
+    /// [`set_fps`]: #method.set_fps
+    /// [`transition`]: transition/index.html
+    pub fn set_transition(&mut self, transition: Transition) {
+        self.transition = Some(transition);
+    }
+
+    /// Disables animations.
+    pub fn clear_transition(&mut self) {
+        self.transition = None;
+        if self.animation.take().is_some() {
+            self.invalidate();
+        }
+    }
+
+    /// Returns `true` if a transition is currently animated.
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    fn start_animation(&mut self, target: Target) {
+        if let Some(transition) = self.transition {
+            self.animation = Some(Animation::new(transition, target));
+        }
+    }
+
+    /// Clears the screen, and redraws every background on the next frame.
+    fn invalidate(&self) {
+        self.clear();
+        for screen in &self.screens {
+            screen.invalidate_background();
+        }
     }
 
     /// Returns a reference to the currently active screen.
@@ -350,6 +934,7 @@ impl Cursive {
     pub fn add_screen(&mut self) -> ScreenId {
         let res = self.screens.len();
         self.screens.push(views::StackView::new());
+        self.update_screen_tabs();
         res
     }
 
@@ -361,16 +946,169 @@ impl Cursive {
     }
 
     /// Sets the active screen. Panics if no such screen exist.
+    ///
+    /// The callbacks and the menubar of this screen, if it has its own,
+    /// become active.
     pub fn set_screen(&mut self, screen_id: ScreenId) {
+        self.check_screen_id(screen_id);
+        if screen_id != self.active_screen {
+            self.start_animation(Target::Screen(self.active_screen));
+            self.swap_menubar(screen_id);
+        }
+        self.active_screen = screen_id;
+        self.update_screen_tabs();
+    }
+
+    /// Activates the next screen, going back to the first after the last.
+    pub fn next_screen(&mut self) {
+        let id = (self.active_screen + 1) % self.screens.len();
+        self.set_screen(id);
+    }
+
+    /// Activates the previous screen, going to the last before the first.
+    pub fn prev_screen(&mut self) {
+        let count = self.screens.len();
+        let id = (self.active_screen + count - 1) % count;
+        self.set_screen(id);
+    }
+
+    /// Returns the number of screens.
+    pub fn screen_count(&self) -> usize {
+        self.screens.len()
+    }
+
+    /// Sets the title of the given screen.
+    ///
+    /// Titles are shown by [`ScreenTabs`] views.
+    ///
+    /// # Panics
+    ///
+    /// If no screen with this ID exists.
+    ///
+    /// [`ScreenTabs`]: views/struct.ScreenTabs.html
+    pub fn set_screen_title<S: Into<String>>(
+        &mut self, screen_id: ScreenId, title: S,
+    ) {
+        self.check_screen_id(screen_id);
+        self.screen_titles.insert(screen_id, title.into());
+        self.update_screen_tabs();
+    }
+
+    /// Returns the title of the given screen, if it has one.
+    pub fn screen_title(&self, screen_id: ScreenId) -> Option<&str> {
+        self.screen_titles.get(&screen_id).map(String::as_str)
+    }
+
+    /// Returns a view listing the screens, like terminal tabs.
+    ///
+    /// It is kept up to date as screens are added, activated or renamed.
+    pub fn screen_tabs(&self) -> views::ScreenTabs {
+        views::ScreenTabs::with_screens(Rc::clone(&self.screen_list))
+    }
+
+    /// Updates the list of screens shown by `ScreenTabs` views.
+    fn update_screen_tabs(&mut self) {
+        let titles = (0..self.screens.len())
+            .map(|id| self.screen_title(id).unwrap_or("").to_string())
+            .collect();
+        *self.screen_list.borrow_mut() = views::ScreenList {
+            titles,
+            active: self.active_screen,
+        };
+    }
+
+    /// Panics if no screen with this ID exists.
+    fn check_screen_id(&self, screen_id: ScreenId) {
         if screen_id >= self.screens.len() {
             panic!(
-                "Tried to set an invalid screen ID: {}, but only {} \
+                "Tried to use an invalid screen ID: {}, but only {} \
                  screens present.",
                 screen_id,
                 self.screens.len()
             );
         }
-        self.active_screen = screen_id;
+    }
+
+    /// Saves the navigable state of the application.
+    ///
+    /// This includes the active screen, the order of layers, and the state
+    /// of views with an id: content of text inputs, checkboxes, selection,
+    /// scroll offsets, ...
+    ///
+    /// Use [`restore`] to apply it again, for instance on a new run.
+    ///
+    /// [`restore`]: #method.restore
+    pub fn dump(&mut self) -> Snapshot {
+        let selector = view::Selector::Type(TypeId::of::<view::IdState>());
+        let mut snapshot = Snapshot {
+            active_screen: self.active_screen,
+            ..Snapshot::default()
+        };
+
+        for screen in &mut self.screens {
+            let mut layers = Vec::new();
+            let mut i = 0;
+            while let Some(layer) = screen.get_mut(LayerPosition::FromBack(i))
+            {
+                let mut layer_id = None;
+                let views = &mut snapshot.views;
+                layer.call_on_any(
+                    &selector,
+                    Box::new(|any| {
+                        if let Some(slot) = any.downcast_mut::<view::IdState>()
+                        {
+                            if layer_id.is_none() {
+                                layer_id = Some(slot.id.clone());
+                            }
+                            if let Some(state) = slot.state.take() {
+                                views.insert(slot.id.clone(), state);
+                            }
+                        }
+                    }),
+                );
+                layers.push(layer_id);
+                i += 1;
+            }
+            snapshot.layers.push(layers);
+        }
+
+        snapshot
+    }
+
+    /// Restores a state saved with [`dump`].
+    ///
+    /// Layers and views are found by id: the ones missing from the current
+    /// view tree are ignored.
+    ///
+    /// [`dump`]: #method.dump
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        if snapshot.active_screen < self.screens.len() {
+            self.swap_menubar(snapshot.active_screen);
+            self.active_screen = snapshot.active_screen;
+        }
+
+        let selector = view::Selector::Type(TypeId::of::<view::IdState>());
+        for (screen, layers) in self.screens.iter_mut().zip(&snapshot.layers)
+        {
+            // Bring each layer to the front, from back to front.
+            for id in layers.iter().filter_map(Option::as_ref) {
+                if let Some(position) = screen.find_layer_from_id(id) {
+                    screen.move_to_front(position);
+                }
+            }
+        }
+
+        for screen in &mut self.screens {
+            screen.call_on_any(
+                &selector,
+                Box::new(|any| {
+                    if let Some(slot) = any.downcast_mut::<view::IdState>() {
+                        slot.restore = snapshot.views.get(&slot.id).cloned();
+                    }
+                }),
+            );
+        }
+        self.clear();
     }
 
     /// Tries to find the view pointed to by the given selector.
@@ -415,14 +1153,36 @@ impl Cursive {
         self.screen_mut().call_on(sel, callback)
     }
 
-    /// Tries to find the view identified by the given id.
-    ///
-    /// Convenient method to use `call_on` with a `view::Selector::Id`.
+    /// Runs a callback on every view of type `V`, in the active screen.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # extern crate cursive;
+    /// # use cursive::Cursive;
+    /// # use cursive::views::{Button, Dialog};
+    /// # let mut siv = Cursive::dummy();
+    /// siv.add_layer(Dialog::text("Working...").button("Cancel", |s| {
+    ///     s.quit()
+    /// }));
+    ///
+    /// siv.call_on_all(|button: &mut Button| button.disable());
+    /// ```
+    pub fn call_on_all<V, F>(&mut self, callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V),
+    {
+        self.screen_mut().call_on_all(callback)
+    }
+
+    /// Tries to find the view identified by the given id.
+    ///
+    /// Convenient method to use `call_on` with a `view::Selector::Id`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate cursive;
     /// # use cursive::{Cursive, views};
     /// # use cursive::traits::*;
     /// # fn main() {
@@ -529,13 +1289,46 @@ impl Cursive {
     where
         F: FnMut(&mut Cursive) + 'static,
     {
-        self.global_callbacks
+        let priority = Priority::AfterViews;
+        self.add_global_callback_with_priority(event, priority, cb);
+    }
+
+    /// Adds a global callback, running before or after the views.
+    ///
+    /// With `Priority::BeforeViews`, the callback runs as soon as the event
+    /// is received, and the views never see it: the shortcut works even
+    /// when a text input has the focus.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::{Event, Priority};
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_global_callback_with_priority(
+    ///     Event::CtrlChar('q'),
+    ///     Priority::BeforeViews,
+    ///     |s| s.quit(),
+    /// );
+    /// ```
+    pub fn add_global_callback_with_priority<F, E>(
+        &mut self, event: E, priority: Priority, cb: F,
+    ) where
+        E: Into<Event>,
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        let callbacks = match priority {
+            Priority::BeforeViews => &mut self.priority_callbacks,
+            Priority::AfterViews => &mut self.global_callbacks,
+        };
+        callbacks
             .entry(event.into())
             .or_insert_with(Vec::new)
             .push(Callback::from_fn_mut(cb));
     }
 
-    /// Removes any callback tied to the given event.
+    /// Removes any callback tied to the given event, whatever its priority.
     ///
     /// # Examples
     ///
@@ -555,6 +1348,157 @@ impl Cursive {
     {
         let event = event.into();
         self.global_callbacks.remove(&event);
+        self.priority_callbacks.remove(&event);
+    }
+
+    /// Sends every input event to `cb`, until [`release_input`] is called.
+    ///
+    /// Views, menubar and global callbacks don't see any input meanwhile.
+    /// This is useful to capture a key to assign to an action, or for
+    /// simple games. The screen is still drawn as usual, and
+    /// `Event::Refresh`, `Event::WindowResize` and `Event::Exit`, which
+    /// don't come from the user, are dispatched as usual too.
+    ///
+    /// `Ctrl-C` and `Event::Exit` keep their usual meaning, so the
+    /// application can always be quit.
+    ///
+    /// Calling this again replaces the previous callback.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Dialog;
+    /// # let mut siv = Cursive::dummy();
+    /// siv.add_layer(Dialog::text("Press the key to assign"));
+    /// siv.grab_input(|s, event| {
+    ///     s.release_input();
+    ///     s.pop_layer();
+    ///     s.add_layer(Dialog::info(format!("Assigned {:?}", event)));
+    /// });
+    /// ```
+    ///
+    /// [`release_input`]: #method.release_input
+    pub fn grab_input<F>(&mut self, cb: F)
+    where
+        F: FnMut(&mut Cursive, Event) + 'static,
+    {
+        self.input_grab = Some(Rc::new(RefCell::new(cb)));
+    }
+
+    /// Stops sending every event to the callback set by [`grab_input`].
+    ///
+    /// [`grab_input`]: #method.grab_input
+    pub fn release_input(&mut self) {
+        self.input_grab = None;
+    }
+
+    /// Returns `true` if the input is grabbed by a callback.
+    pub fn is_input_grabbed(&self) -> bool {
+        self.input_grab.is_some()
+    }
+
+    /// Translates an input sequence unknown to the backend.
+    ///
+    /// Backends send the input they cannot decode as `Event::Unknown`, with
+    /// the raw bytes they read when possible. From now on, `sequence` will
+    /// be processed as `event` instead, so exotic keys can still be used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::{Event, Key};
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// // Some terminals send this for `Meta-Up`.
+    /// siv.add_sequence("\x1B[1;9A", Event::Alt(Key::Up));
+    /// ```
+    pub fn add_sequence<S>(&mut self, sequence: S, event: Event)
+    where
+        S: Into<Vec<u8>>,
+    {
+        self.sequences.insert(sequence.into(), event);
+    }
+
+    /// Stops translating `sequence`, added with [`add_sequence`].
+    ///
+    /// [`add_sequence`]: #method.add_sequence
+    pub fn remove_sequence(&mut self, sequence: &[u8]) {
+        self.sequences.remove(sequence);
+    }
+
+    /// Adds a callback active while the current top layer is on top.
+    ///
+    /// It replaces global callbacks for the same event, and is removed
+    /// along with the layer. This lets dialogs add shortcuts without
+    /// leaking them to the rest of the application.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Dialog;
+    /// # let mut siv = Cursive::dummy();
+    /// siv.add_global_callback('q', |s| s.quit());
+    ///
+    /// siv.add_layer(Dialog::info("Press q to close"));
+    /// siv.add_layer_callback('q', |s| {
+    ///     s.pop_layer();
+    /// });
+    /// ```
+    pub fn add_layer_callback<F, E: Into<Event>>(&mut self, event: E, cb: F)
+    where
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        self.screen_mut().add_layer_callback(
+            LayerPosition::FromFront(0),
+            event,
+            cb,
+        );
+    }
+
+    /// Adds a callback active while the current screen is active.
+    ///
+    /// It replaces global callbacks for the same event, but not callbacks
+    /// of the top layer.
+    pub fn add_screen_callback<F, E: Into<Event>>(&mut self, event: E, cb: F)
+    where
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        self.screen_mut().add_callback(event, cb);
+    }
+
+    /// Adds a callback active while the given screen is active.
+    ///
+    /// Like [`add_screen_callback`], for a screen which may not be active
+    /// yet. This lets each screen bring its own key bindings.
+    ///
+    /// # Panics
+    ///
+    /// If no screen with this ID exists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// let mut siv = Cursive::dummy();
+    /// let editor = siv.add_screen();
+    ///
+    /// siv.add_global_callback('q', |s| s.quit());
+    /// // In the editor, `q` goes back to the first screen.
+    /// siv.add_screen_callback_for(editor, 'q', |s| s.set_screen(0));
+    /// ```
+    ///
+    /// [`add_screen_callback`]: #method.add_screen_callback
+    pub fn add_screen_callback_for<F, E>(
+        &mut self, screen_id: ScreenId, event: E, cb: F,
+    ) where
+        E: Into<Event>,
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        self.check_screen_id(screen_id);
+        self.screens[screen_id].add_callback(event, cb);
     }
 
     /// Add a layer to the current screen.
@@ -575,6 +1519,7 @@ impl Cursive {
         T: IntoBoxedView,
     {
         self.screen_mut().add_layer(view);
+        self.start_animation(Target::LayerAdded);
     }
 
     /// Adds a new full-screen layer to the current screen.
@@ -585,11 +1530,41 @@ impl Cursive {
         T: IntoBoxedView,
     {
         self.screen_mut().add_fullscreen_layer(view);
+        self.start_animation(Target::LayerAdded);
+    }
+
+    /// Adds a new layer to the current screen, at the given position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate cursive;
+    /// # use cursive::{Cursive, views};
+    /// # use cursive::view::Position;
+    /// # fn main() {
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_layer_at(
+    ///     Position::absolute((2, 1)),
+    ///     views::TextView::new("Hello world!"),
+    /// );
+    /// # }
+    /// ```
+    pub fn add_layer_at<T>(&mut self, position: Position, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        self.screen_mut().add_layer_at(position, view);
+        self.start_animation(Target::LayerAdded);
     }
 
     /// Convenient method to remove a layer from the current screen.
     pub fn pop_layer(&mut self) -> Option<Box<View>> {
-        self.screen_mut().pop_layer()
+        let layer = self.screen_mut().pop_layer();
+        if layer.is_some() {
+            self.start_animation(Target::LayerRemoved);
+        }
+        layer
     }
 
     /// Convenient stub forwarding layer repositioning.
@@ -600,6 +1575,10 @@ impl Cursive {
     }
 
     fn peek(&mut self) -> Option<Interruption> {
+        if let Some(event) = self.injected_events.pop_front() {
+            return Some(Interruption::Event(event));
+        }
+
         // First, try a callback
         select! {
             // Skip to input if nothing is ready
@@ -624,21 +1603,12 @@ impl Cursive {
     /// Wait until something happens.
     ///
     /// If `peek` is `true`, return `None` immediately if nothing is ready.
-    fn poll(&mut self) -> Option<Interruption> {
-        if !self.expecting_event {
-            self.input_trigger
-                .send(backend::InputRequest::Block)
-                .unwrap();
-            self.backend.prepare_input(backend::InputRequest::Block);
-            self.expecting_event = true;
+    fn poll(&mut self, timeout: Duration) -> Option<Interruption> {
+        if let Some(event) = self.injected_events.pop_front() {
+            return Some(Interruption::Event(event));
         }
 
-        let timeout = if self.fps > 0 {
-            Duration::from_millis(1000 / self.fps as u64)
-        } else {
-            // Defaults to 1 refresh per hour.
-            Duration::from_secs(3600)
-        };
+        self.request_input();
 
         select! {
             recv(self.event_source) -> event => {
@@ -658,10 +1628,40 @@ impl Cursive {
 
     // Handles a key event when it was ignored by the current view
     fn on_ignored_event(&mut self, event: Event) {
+        if let Some(cb_list) = self.screen().callbacks(&event) {
+            self.trace_event(|| {
+                format!("{} screen callback(s) for {:?}", cb_list.len(), event)
+            });
+            for cb in cb_list {
+                cb(self);
+            }
+            return;
+        }
+
         let cb_list = match self.global_callbacks.get(&event) {
-            None => return,
+            None if event == Event::Key(Key::Esc) => {
+                self.trace_event(|| "Esc closes the top layer".to_string());
+                return self.close_top_layer();
+            }
+            #[cfg(unix)]
+            None if event == Event::CtrlChar('z')
+                && self.backend.is_process_terminal() =>
+            {
+                self.trace_event(|| "Ctrl-Z suspends the process".to_string());
+                if let Err(e) = self.suspend_process() {
+                    warn!("Could not suspend the process: {}", e);
+                }
+                return;
+            }
+            None => {
+                self.trace_event(|| format!("no callback for {:?}", event));
+                return;
+            }
             Some(cb_list) => cb_list.clone(),
         };
+        self.trace_event(|| {
+            format!("{} global callback(s) for {:?}", cb_list.len(), event)
+        });
         // Not from a view, so no viewpath here
         for cb in cb_list {
             cb(self);
@@ -670,16 +1670,76 @@ impl Cursive {
 
     /// Processes an event.
     ///
+    /// * Unknown input sequences are translated, see [`add_sequence`].
+    /// * `Event::Shutdown` only triggers global callbacks, then quits.
+    /// * If the input is grabbed, the event is only given to the grabbing
+    ///   callback.
+    /// * Global callbacks with `Priority::BeforeViews` run first. If there
+    ///   are any, the views never see the event.
     /// * If the menubar is active, it will be handled the event.
     /// * The view tree will be handled the event.
-    /// * If ignored, global_callbacks will be checked for this event.
+    /// * If ignored, callbacks of the top layer, then of the screen, then
+    ///   global callbacks will be checked for this event.
+    /// * If there are none, `Esc` closes the top layer, and `Ctrl-Z`
+    ///   suspends the process if the backend runs in the process terminal
+    ///   (see [`suspend_process`]).
+    ///
+    /// [`suspend_process`]: #method.suspend_process
+    /// [`add_sequence`]: #method.add_sequence
     pub fn on_event(&mut self, event: Event) {
+        let event = match event {
+            Event::Unknown(bytes) => match self.sequences.get(&bytes) {
+                Some(event) => event.clone(),
+                None => Event::Unknown(bytes),
+            },
+            event => event,
+        };
+        self.trace_event(|| format!("received {:?}", event));
+
         if event == Event::Exit {
             self.quit();
         }
 
+        if event == Event::Shutdown {
+            // The terminal may be gone already, so don't wait for views.
+            self.on_ignored_event(event);
+            return self.force_quit();
+        }
+
+        if event == Event::CtrlChar('c') {
+            match self.ctrl_c {
+                CtrlC::Quit => {
+                    self.trace_event(|| "Ctrl-C quits".to_string());
+                    return self.quit();
+                }
+                CtrlC::Callback(ref cb) => {
+                    self.trace_event(|| "Ctrl-C callback".to_string());
+                    let cb = cb.clone();
+                    return cb(self);
+                }
+                CtrlC::Event => (),
+            }
+        }
+
         if event == Event::WindowResize {
             self.clear();
+            if let Some(cb) = self.on_resize.clone() {
+                let size = self.screen_size();
+                cb(self, size);
+            }
+        }
+
+        let is_input = !matches!(
+            event,
+            Event::Refresh | Event::WindowResize | Event::Exit
+        );
+        if let Some(grab) = self.input_grab.clone().filter(|_| is_input) {
+            self.trace_event(|| "sent to the input grab".to_string());
+            // Events sent from the callback itself are dropped.
+            if let Ok(mut cb) = grab.try_borrow_mut() {
+                (&mut *cb)(self, event);
+            }
+            return;
         }
 
         if let Event::Mouse {
@@ -695,16 +1755,48 @@ impl Cursive {
             }
         }
 
+        let multi_press = self.count_presses(&event);
+
         // Event dispatch order:
+        // * Global callbacks with `Priority::BeforeViews`
         // * Focused element:
         //     * Menubar (if active)
         //     * Current screen (top layer)
         // * Global callbacks
-        if self.menubar.receive_events() {
-            self.menubar.on_event(event).process(self);
+        if let Some(cb_list) = self.priority_callbacks.get(&event).cloned() {
+            self.trace_event(|| {
+                let count = cb_list.len();
+                format!("{} priority callback(s) for {:?}", count, event)
+            });
+            for cb in cb_list {
+                cb(self);
+            }
+        } else if self.menubar.receive_events() {
+            self.trace_event(|| "sent to the menubar".to_string());
+            match self.menubar.on_event(event.clone()) {
+                EventResult::Ignored => self.on_ignored_event(event),
+                other => other.process(self),
+            }
         } else {
             let offset = if self.menubar.autohide { 0 } else { 1 };
-            match self.screen_mut().on_event(event.relativized((0, offset))) {
+            // The event follows the focus, as it was before the event.
+            // Which view on this path handled it isn't known.
+            let path = if self.trace_events {
+                self.focus_path()
+            } else {
+                String::new()
+            };
+            let result =
+                self.screen_mut().on_event(event.relativized((0, offset)));
+            self.trace_event(|| {
+                let outcome = match result {
+                    EventResult::Ignored => "ignored",
+                    EventResult::Consumed(None) => "consumed",
+                    EventResult::Consumed(Some(_)) => "consumed, callback",
+                };
+                format!("{} (focus: {})", outcome, path)
+            });
+            match result {
                 // If the event was ignored,
                 // it is our turn to play with it.
                 EventResult::Ignored => self.on_ignored_event(event),
@@ -712,99 +1804,534 @@ impl Cursive {
                 EventResult::Consumed(Some(cb)) => cb(self),
             }
         }
+
+        if let Some(event) = multi_press {
+            self.on_event(event);
+        }
     }
 
-    /// Returns the size of the screen, in characters.
-    pub fn screen_size(&self) -> Vec2 {
-        self.backend.screen_size()
+    /// Returns the `MultiPress` event to send after `event`, if any.
+    fn count_presses(&mut self, event: &Event) -> Option<Event> {
+        let (button, position, offset) = match *event {
+            Event::Mouse {
+                event: MouseEvent::Press(button),
+                position,
+                offset,
+            } => (button, position, offset),
+            _ => return None,
+        };
+
+        // Without a clock, presses are never counted.
+        let now = clock::now();
+        let count = match self.last_press {
+            Some(ref last)
+                if last.button == button
+                    && last.position == position
+                    && clock::within(
+                        last.time,
+                        now,
+                        self.multi_press_interval,
+                    ) =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        self.last_press = Some(LastPress {
+            time: now,
+            button,
+            position,
+            count,
+        });
+
+        if count < 2 {
+            return None;
+        }
+        Some(Event::Mouse {
+            event: MouseEvent::MultiPress(button, count),
+            position,
+            offset,
+        })
     }
 
-    fn layout(&mut self) {
-        let size = self.screen_size();
+    /// Sets the longest delay between presses forming a double click.
+    ///
+    /// A press with the same button, at the same position, within this
+    /// delay of the previous one is followed by a `MouseEvent::MultiPress`
+    /// event.
+    ///
+    /// Defaults to 500ms. Presses are only counted if the time is known:
+    /// see [`utils::clock`].
+    ///
+    /// [`utils::clock`]: utils/clock/index.html
+    pub fn set_multi_press_interval(&mut self, interval: Duration) {
+        self.multi_press_interval = interval;
+    }
+
+    /// Returns a snapshot of the view tree for the active screen.
+    ///
+    /// Nodes are listed depth-first, starting with the screen itself.
+    /// Areas are given in absolute screen coordinates, as of the last layout.
+    pub fn inspect(&self) -> Vec<view::ViewNode> {
         let offset = if self.menubar.autohide { 0 } else { 1 };
-        let size = size.saturating_sub((0, offset));
-        self.screen_mut().layout(size);
+        let size = self.screen_size().saturating_sub((0, offset));
+        view::inspect(self.screen(), Vec2::new(0, offset), size)
     }
 
-    fn draw(&mut self) {
-        let sizes = self.screen().layer_sizes();
-        if self.last_sizes != sizes {
+    /// Sets a key to toggle the view inspector.
+    ///
+    /// The inspector is a debugging tool listing the current view tree,
+    /// with the size and focus state of each view.
+    /// Selecting a view in the list highlights its boundaries on screen.
+    ///
+    /// Like any global callback, the key will only be caught if no view
+    /// consumes it first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Key;
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_inspector_key(Key::F12);
+    /// ```
+    pub fn set_inspector_key<E: Into<Event>>(&mut self, event: E) {
+        self.add_global_callback(event, Cursive::toggle_inspector);
+    }
+
+    /// Shows or hides the view inspector.
+    ///
+    /// See [`set_inspector_key`](#method.set_inspector_key).
+    pub fn toggle_inspector(&mut self) {
+        let layer = self.screen_mut().find_layer_from_id(INSPECTOR_ID);
+        if let Some(position) = layer {
+            self.screen_mut().remove_layer(position);
+            self.inspector_highlight = None;
             self.clear();
-            self.last_sizes = sizes;
+            return;
         }
 
-        let printer =
-            Printer::new(self.screen_size(), &self.theme, &*self.backend);
-
-        let selected = self.menubar.receive_events();
-
-        // Print the stackview background before the menubar
-        let offset = if self.menubar.autohide { 0 } else { 1 };
-        let id = self.active_screen;
-        let sv_printer = printer.offset((0, offset)).focused(!selected);
-
-        self.screens[id].draw_bg(&sv_printer);
+        let nodes = self.inspect();
+        self.inspector_highlight = nodes.first().map(|node| node.area);
 
-        // Draw the currently active screen
-        // If the menubar is active, nothing else can be.
-        // Draw the menubar?
-        if self.menubar.visible() {
-            let printer = printer.focused(self.menubar.receive_events());
-            self.menubar.draw(&printer);
+        let mut list = views::SelectView::new().on_select(
+            |s: &mut Cursive, area: &Rect| {
+                s.inspector_highlight = Some(*area);
+                s.clear();
+            },
+        );
+        for node in nodes {
+            let size = node.area.size();
+            let label = format!(
+                "{:indent$}{}{} {}x{} @ {},{}",
+                "",
+                if node.focused { "* " } else { "" },
+                node.name,
+                size.x,
+                size.y,
+                node.area.left(),
+                node.area.top(),
+                indent = 2 * node.depth
+            );
+            list.add_item(label, node.area);
         }
 
-        // finally draw stackview layers
-        // using variables from above
-        self.screens[id].draw_fg(&sv_printer);
+        self.add_layer(views::IdView::new(
+            INSPECTOR_ID,
+            views::Dialog::around(list.scrollable())
+                .title("Inspector")
+                .button("Close", Cursive::toggle_inspector),
+        ));
     }
 
-    /// Returns `true` until [`quit(&mut self)`] is called.
-    ///
-    /// [`quit(&mut self)`]: #method.quit
-    pub fn is_running(&self) -> bool {
-        self.running
+    /// Shows the debug console.
+    ///
+    /// It displays the log records collected by the cursive logger, if
+    /// [`logger::init()`] was called.
+    ///
+    /// [`logger::init()`]: logger/fn.init.html
+    pub fn show_debug_console(&mut self) {
+        self.add_layer(
+            views::Dialog::around(
+                views::ScrollView::new(views::IdView::new(
+                    DEBUG_VIEW_ID,
+                    views::DebugView::new(),
+                )).scroll_x(true)
+                .scroll_strategy(view::ScrollStrategy::StickToBottom),
+            ).title("Debug console"),
+        );
     }
 
-    /// Runs the event loop.
+    /// Shows the debug console, or hides it if it is already visible.
     ///
-    /// It will wait for user input (key presses)
-    /// and trigger callbacks accordingly.
-    ///
-    /// Calls [`step(&mut self)`] until [`quit(&mut self)`] is called.
+    /// # Examples
     ///
-    /// After this function returns, you can call
-    /// it again and it will start a new loop.
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Key;
+    /// let mut siv = Cursive::dummy();
+    /// cursive::logger::init();
     ///
-    /// [`step(&mut self)`]: #method.step
-    /// [`quit(&mut self)`]: #method.quit
-    pub fn run(&mut self) {
-        self.running = true;
-
-        // And the big event loop begins!
-        while self.running {
-            self.step();
+    /// siv.add_global_callback(Key::F2, Cursive::toggle_debug_console);
+    /// ```
+    pub fn toggle_debug_console(&mut self) {
+        let layer = self.screen_mut().find_layer_from_id(DEBUG_VIEW_ID);
+        if let Some(position) = layer {
+            self.screen_mut().remove_layer(position);
+        } else {
+            self.show_debug_console();
         }
     }
 
-    /// Performs a single step from the event loop.
+    /// Shows a capture of the current screen, to select and copy text.
     ///
-    /// Useful if you need tighter control on the event loop.
-    /// Otherwise, [`run(&mut self)`] might be more convenient.
+    /// The selection is put on the clipboard. See [`CopyMode`] for the
+    /// keys used to select text.
     ///
-    /// [`run(&mut self)`]: #method.run
-    pub fn step(&mut self) {
-        // Do we need to redraw everytime?
-        // Probably, actually.
-        // TODO: Do we need to re-layout everytime?
-        self.layout();
-
-        // TODO: Do we need to redraw every view every time?
-        // (Is this getting repetitive? :p)
-        self.draw();
-        self.backend.refresh();
-
-        if let Some(interruption) = self.poll() {
-            self.handle_interruption(interruption);
+    /// [`CopyMode`]: views/struct.CopyMode.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Key;
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_global_callback(Key::F7, Cursive::enter_copy_mode);
+    /// ```
+    pub fn enter_copy_mode(&mut self) {
+        self.layout();
+
+        let offset = if self.menubar.autohide { 0 } else { 1 };
+        let size = self.screen_size().saturating_sub((0, offset));
+        let buffer = PrintBuffer::new(size);
+        self.screen().draw(&buffer.printer(&self.theme));
+
+        self.screen_mut()
+            .add_fullscreen_layer(views::CopyMode::new(buffer));
+    }
+
+    /// Suspends cursive to run an external program.
+    ///
+    /// The terminal is restored to its original state before running `f`.
+    /// Once `f` returns, cursive takes control of the terminal again and
+    /// redraws everything.
+    ///
+    /// This is useful to spawn an interactive program, like `$EDITOR`.
+    ///
+    /// Returns an error if the terminal could not be restored, in which case
+    /// `f` is not run, or if it could not be taken back afterward.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// # let mut siv = Cursive::dummy();
+    /// use std::process::Command;
+    ///
+    /// siv.add_global_callback('e', |s| {
+    ///     let _ = s.run_external(|| Command::new("vi").status());
+    /// });
+    /// ```
+    pub fn run_external<F, R>(&mut self, f: F) -> io::Result<R>
+    where
+        F: FnOnce() -> R,
+    {
+        self.backend.suspend()?;
+        let result = f();
+        // Redraw everything, even if the terminal is in a bad state.
+        self.clear();
+        self.backend.resume()?;
+
+        Ok(result)
+    }
+
+    /// Stops the process, as if `Ctrl-Z` was pressed in a shell.
+    ///
+    /// The terminal is restored before the process is stopped, and cursive
+    /// takes control again when the process is continued (for example
+    /// with `fg`).
+    ///
+    /// This is what `Ctrl-Z` (or `SIGTSTP`, on backends listening to
+    /// signals) does when no callback uses it, if the backend runs in the
+    /// terminal controlling the process. Remote terminals, like the ones
+    /// served with the termion backend over streams, can't stop the process.
+    #[cfg(unix)]
+    pub fn suspend_process(&mut self) -> io::Result<()> {
+        self.run_external(|| unsafe {
+            libc::raise(libc::SIGSTOP);
+        })
+    }
+
+    /// Returns the size of the screen, in characters.
+    pub fn screen_size(&self) -> Vec2 {
+        self.backend.screen_size()
+    }
+
+    /// Returns the features supported by the backend and terminal.
+    ///
+    /// This can be used to adapt the theme or the layout to the terminal:
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::theme::BorderStyle;
+    /// # let mut siv = Cursive::dummy();
+    /// if !siv.capabilities().unicode {
+    ///     let mut theme = siv.current_theme().clone();
+    ///     theme.borders = BorderStyle::Ascii;
+    ///     siv.set_theme(theme);
+    /// }
+    /// ```
+    pub fn capabilities(&self) -> backend::Capabilities {
+        self.backend.capabilities()
+    }
+
+    fn layout(&mut self) {
+        if self.is_screen_too_small() {
+            return;
+        }
+
+        let size = self.screen_size();
+        let offset = if self.menubar.autohide { 0 } else { 1 };
+        let size = size.saturating_sub((0, offset));
+        self.screen_mut().layout(size);
+
+        // The previous screen is still visible while sliding away.
+        if let Some(Target::Screen(from)) =
+            self.animation.as_ref().map(|a| a.target)
+        {
+            self.screens[from].layout(size);
+        }
+    }
+
+    fn draw(&mut self) {
+        if self.is_screen_too_small() {
+            // Only clear when the size changes, to avoid flickering.
+            let size = self.screen_size();
+            if self.too_small_size != Some(size) {
+                self.clear();
+                self.too_small_size = Some(size);
+            }
+            {
+                let mut printer = Printer::new(
+                    self.screen_size(),
+                    &self.theme,
+                    &*self.backend,
+                );
+                printer.monochrome = self.monochrome;
+                self.draw_too_small(&printer);
+            }
+            self.backend.set_cursor(None);
+            return;
+        }
+
+        let sizes = self.screen().layer_sizes();
+        if self.too_small_size.take().is_some() || self.last_sizes != sizes {
+            self.clear();
+            self.last_sizes = sizes;
+        }
+        if self.animation.is_some() {
+            self.invalidate();
+        }
+
+        let cursor = {
+            let mut printer =
+                Printer::new(self.screen_size(), &self.theme, &*self.backend);
+            printer.monochrome = self.monochrome;
+            self.draw_with(&printer);
+            #[cfg(feature = "profiling")]
+            {
+                if self.profiling_overlay {
+                    draw_frame_stats(&printer, &self.frame_stats);
+                }
+            }
+            printer.cursor_position()
+        };
+
+        self.backend.set_cursor(cursor);
+        self.update_linear_output();
+    }
+
+    /// Writes the focused view to the linear output, if enabled.
+    fn update_linear_output(&mut self) {
+        if self.linear_output.is_none() {
+            return;
+        }
+
+        let focused = self.inspect().into_iter().filter(|n| n.focused).last();
+        let node = match focused {
+            Some(node) => node,
+            None => return,
+        };
+
+        // Rendering the screen again is only needed if the content may
+        // have changed, or if another view has the focus.
+        let unchanged = match self.linear_output {
+            Some(ref output) => output.is_focused(&node.name, node.area),
+            None => return,
+        };
+        if unchanged && !self.needs_redraw {
+            return;
+        }
+
+        let offset = if self.menubar.autohide { 0 } else { 1 };
+        let buffer = PrintBuffer::new(self.screen_size());
+        self.screen()
+            .draw(&buffer.printer(&self.theme).offset((0, offset)));
+
+        let area = node.area;
+        let content: Vec<String> = (area.top()..area.top() + area.height())
+            .map(|y| buffer.text(y, area.left(), area.left() + area.width()))
+            .collect();
+
+        let result = match self.linear_output {
+            Some(ref mut output) => output.update(&node.name, area, &content),
+            None => return,
+        };
+        if let Err(e) = result {
+            warn!("Error writing the linear output: {}", e);
+            self.linear_output = None;
+        }
+    }
+
+    /// Draws the message shown when the screen is too small.
+    fn draw_too_small(&self, printer: &Printer) {
+        let min = self.min_screen_size;
+        let text = format!("Terminal too small (need {}x{})", min.x, min.y);
+        let x = HAlign::Center.get_offset(text.len(), printer.size.x);
+        printer.print((x, printer.size.y / 2), &text);
+    }
+
+    fn draw_with(&self, printer: &Printer) {
+        let selected = self.menubar.receive_events();
+
+        // Print the stackview background before the menubar
+        let offset = if self.menubar.autohide { 0 } else { 1 };
+        let id = self.active_screen;
+        let sv_printer = printer.offset((0, offset)).focused(!selected);
+
+        let faded = self.animation.as_ref().map(|animation| {
+            transition::faded(&self.theme, animation.progress())
+        });
+        let mut screen_printer = sv_printer.clone();
+        let mut previous = None;
+        let mut top_shift = Vec2::zero();
+        let mut top_theme = None;
+        if let Some(ref animation) = self.animation {
+            let progress = animation.progress();
+            let size = sv_printer.size;
+            match (animation.transition.style, animation.target) {
+                (TransitionStyle::Slide, Target::Screen(from)) => {
+                    // The new screen pushes the previous one to the left.
+                    let shift = (size.x as f64 * progress).round() as usize;
+                    previous =
+                        Some((from, sv_printer.content_offset((shift, 0))));
+                    screen_printer = sv_printer
+                        .offset((size.x - shift, 0))
+                        .inner_size(size);
+                }
+                (TransitionStyle::Slide, Target::LayerAdded) => {
+                    let shift = (1.0 - progress) * size.y as f64;
+                    top_shift = Vec2::new(0, shift.round() as usize);
+                }
+                (TransitionStyle::Fade, Target::LayerAdded) => {
+                    top_theme = faded.as_ref();
+                }
+                _ => {
+                    if let Some(ref faded) = faded {
+                        screen_printer = screen_printer.theme(faded);
+                    }
+                }
+            }
+        }
+
+        if let Some((from, ref printer)) = previous {
+            self.screens[from].draw_bg(printer);
+        }
+        self.screens[id].draw_bg(&screen_printer);
+
+        // Draw the currently active screen
+        // If the menubar is active, nothing else can be.
+        // Draw the menubar?
+        if self.menubar.visible() {
+            let printer = printer.focused(self.menubar.receive_events());
+            self.menubar.draw(&printer);
+        }
+
+        // finally draw stackview layers
+        // using variables from above
+        if let Some((from, ref printer)) = previous {
+            self.screens[from].draw_fg(printer);
+        }
+        self.screens[id].draw_fg_transition(
+            &screen_printer,
+            top_shift,
+            top_theme,
+        );
+
+        // The inspector highlight goes on top of everything.
+        if let Some(area) = self.inspector_highlight {
+            draw_highlight(printer, area);
+        }
+    }
+
+    /// Returns `true` until [`quit(&mut self)`] is called.
+    ///
+    /// [`quit(&mut self)`]: #method.quit
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Runs the event loop.
+    ///
+    /// It will wait for user input (key presses)
+    /// and trigger callbacks accordingly.
+    ///
+    /// Calls [`step(&mut self)`] until [`quit(&mut self)`] is called.
+    ///
+    /// After this function returns, you can call
+    /// it again and it will start a new loop.
+    ///
+    /// [`step(&mut self)`]: #method.step
+    /// [`quit(&mut self)`]: #method.quit
+    pub fn run(&mut self) {
+        self.running = true;
+
+        // And the big event loop begins!
+        while self.running {
+            self.step();
+        }
+    }
+
+    /// Performs a single step from the event loop.
+    ///
+    /// Useful if you need tighter control on the event loop.
+    /// Otherwise, [`run(&mut self)`] might be more convenient.
+    ///
+    /// [`run(&mut self)`]: #method.run
+    pub fn step(&mut self) {
+        let interval = self.tick_interval();
+        let next_frame = match (self.last_frame, interval) {
+            (Some(last_frame), Some(interval)) => Some(last_frame + interval),
+            _ => None,
+        };
+
+        let now = Instant::now();
+        let timeout = if !self.needs_redraw {
+            // Wait for something to happen, or for the next regular refresh.
+            // Defaults to 1 refresh per hour.
+            interval.unwrap_or_else(|| Duration::from_secs(3600))
+        } else if next_frame.is_some_and(|next_frame| next_frame > now) {
+            // Too early for a new frame: keep processing input meanwhile.
+            self.frame_budget.deferred += 1;
+            next_frame.unwrap() - now
+        } else {
+            self.refresh();
+            interval.unwrap_or_else(|| Duration::from_secs(3600))
+        };
+
+        if let Some(interruption) = self.poll(timeout) {
+            self.handle_interruption(interruption);
             if !self.running {
                 return;
             }
@@ -812,7 +2339,28 @@ impl Cursive {
 
         // Don't block, but try to read any other pending event.
         // This lets us batch-process chunks of events, like big copy-paste or mouse drags.
+        self.handle_pending(Vec::new());
+    }
+
+    /// Handles `batch`, then every pending interruption, without blocking.
+    fn handle_pending(&mut self, mut batch: Vec<Interruption>) {
         while let Some(interruption) = self.peek() {
+            // Anything after a request to quit can wait.
+            let quitting = matches!(
+                interruption,
+                Interruption::Event(Event::Exit)
+                    | Interruption::Event(Event::Shutdown)
+            );
+            batch.push(interruption);
+            if quitting {
+                break;
+            }
+        }
+        if self.coalesce_events {
+            batch = coalesce(batch);
+        }
+
+        for interruption in batch {
             self.handle_interruption(interruption);
             if !self.running {
                 return;
@@ -820,9 +2368,221 @@ impl Cursive {
         }
     }
 
+    /// Enables or disables event coalescing.
+    ///
+    /// Events received while the previous ones are processed are handled
+    /// together, before the next redraw. With coalescing, redundant events
+    /// in such a batch are dropped: consecutive resize and refresh events,
+    /// and all but the last position of a mouse drag. Key presses are never
+    /// dropped.
+    ///
+    /// Enabled by default. Applications needing every event, like games,
+    /// can disable it.
+    pub fn set_event_coalescing(&mut self, enabled: bool) {
+        self.coalesce_events = enabled;
+    }
+
+    /// Makes `Esc` close the top layer of the active screen.
+    ///
+    /// The key is only used when ignored by the views, and when no global
+    /// callback is registered for it. The last layer of the screen is never
+    /// closed, nor layers marked with [`StackView::set_closable`].
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::{Dialog, LayerPosition, TextView};
+    /// # let mut siv = Cursive::dummy();
+    /// siv.set_global_esc_closes_layer(true);
+    ///
+    /// siv.add_layer(TextView::new("Main view"));
+    /// siv.add_layer(Dialog::info("Press Esc to close me"));
+    ///
+    /// // This one must be closed with its button.
+    /// siv.add_layer(Dialog::text("Really quit?").button("No", |s| {
+    ///     s.pop_layer();
+    /// }));
+    /// siv.screen_mut().set_closable(LayerPosition::FromFront(0), false);
+    /// ```
+    ///
+    /// [`StackView::set_closable`]: views/struct.StackView.html#method.set_closable
+    pub fn set_global_esc_closes_layer(&mut self, enabled: bool) {
+        self.esc_closes_layer = enabled;
+    }
+
+    /// Pops the top layer, if `Esc` is allowed to close it.
+    fn close_top_layer(&mut self) {
+        let screen = self.screen();
+        if self.esc_closes_layer
+            && screen.get(LayerPosition::FromBack(1)).is_some()
+            && screen.is_closable(LayerPosition::FromFront(0))
+        {
+            self.pop_layer();
+        }
+    }
+
+    /// Handles every pending interruption, without blocking.
+    ///
+    /// Used by drivers that don't own the event loop.
+    #[cfg(feature = "async")]
+    pub(crate) fn process_pending(&mut self) {
+        let mut batch = Vec::new();
+
+        // We may have asked for input earlier, and it may have arrived.
+        if self.expecting_event {
+            if let Ok(event) = self.event_source.try_recv() {
+                self.expecting_event = false;
+                batch.extend(event.map(Interruption::Event));
+            }
+        }
+        self.handle_pending(batch);
+    }
+
+    /// Returns the time left before the next regular frame, if any.
+    #[cfg(feature = "async")]
+    pub(crate) fn time_to_next_frame(&self) -> Option<Duration> {
+        let interval = self.tick_interval()?;
+        let next_frame = match self.last_frame {
+            Some(last_frame) => last_frame + interval,
+            None => return Some(Duration::from_secs(0)),
+        };
+        Some(next_frame.saturating_duration_since(Instant::now()))
+    }
+
+    /// Sends `Event::Refresh` if a regular frame is due.
+    #[cfg(feature = "async")]
+    pub(crate) fn refresh_if_due(&mut self) {
+        if self.time_to_next_frame() == Some(Duration::from_secs(0)) {
+            self.handle_interruption(Interruption::Event(Event::Refresh));
+        }
+    }
+
+    /// Redraws the screen if anything happened since the last frame.
+    #[cfg(feature = "async")]
+    pub(crate) fn refresh_if_needed(&mut self) {
+        if self.needs_redraw {
+            self.refresh();
+        }
+    }
+
+    /// Asks the backend for the next input, without waiting for it.
+    ///
+    /// The input will be sent to `event_source`.
+    pub(crate) fn request_input(&mut self) {
+        if !self.expecting_event {
+            self.input_trigger
+                .send(backend::InputRequest::Block)
+                .unwrap();
+            self.backend.prepare_input(backend::InputRequest::Block);
+            self.expecting_event = true;
+        }
+    }
+
+    /// Returns the channels receiving input and callbacks.
+    #[cfg(any(test, feature = "async"))]
+    pub(crate) fn receivers(
+        &self,
+    ) -> (Receiver<Option<Event>>, Receiver<Box<CbFunc>>) {
+        (self.event_source.clone(), self.cb_source.clone())
+    }
+
+    /// Starts the event loop, without running it.
+    #[cfg(feature = "async")]
+    pub(crate) fn start(&mut self) {
+        self.running = true;
+    }
+
+    /// Handles an event given by a driver that doesn't own the event loop.
+    #[cfg(feature = "web-backend")]
+    pub(crate) fn handle_event(&mut self, event: Event) {
+        self.handle_interruption(Interruption::Event(event));
+    }
+
+    /// Runs every pending callback, without blocking.
+    #[cfg(feature = "web-backend")]
+    pub(crate) fn process_callbacks(&mut self) {
+        while let Ok(cb) = self.cb_source.try_recv() {
+            self.handle_interruption(Interruption::Callback(cb));
+        }
+    }
+
+    /// Redraws the screen if anything happened since the last frame.
+    ///
+    /// Unlike `refresh_if_needed`, this doesn't measure time, which is not
+    /// available on every platform.
+    #[cfg(feature = "web-backend")]
+    pub(crate) fn redraw_if_needed(&mut self) {
+        if self.needs_redraw {
+            self.draw_frame();
+        }
+    }
+
+    /// Lays out and draws everything.
+    fn draw_frame(&mut self) {
+        // Only keep timings from this frame.
+        #[cfg(feature = "profiling")]
+        profiling::take_views();
+
+        // Time is not available on every platform, so only measure it when
+        // asked to.
+        #[cfg(feature = "profiling")]
+        let start = Instant::now();
+        // TODO: Do we need to re-layout everytime?
+        self.layout();
+        #[cfg(feature = "profiling")]
+        let layout = start.elapsed();
+        self.draw();
+        #[cfg(feature = "profiling")]
+        self.record_frame(layout, start.elapsed() - layout);
+        self.backend.refresh();
+        self.needs_redraw = false;
+
+        if let Some(mut animation) = self.animation.take() {
+            if animation.advance() {
+                self.animation = Some(animation);
+            } else {
+                // Draw the final state, without any leftover.
+                self.invalidate();
+            }
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Saves the timings of the frame just drawn.
+    #[cfg(feature = "profiling")]
+    fn record_frame(&mut self, layout: Duration, draw: Duration) {
+        self.frame_stats = FrameStats {
+            layout,
+            draw,
+            views: profiling::take_views(),
+        };
+    }
+
+    /// Lays out and draws everything, and updates the frame statistics.
+    fn refresh(&mut self) {
+        let start = Instant::now();
+        self.draw_frame();
+
+        let elapsed = start.elapsed();
+        let stats = &mut self.frame_budget;
+        stats.frames += 1;
+        stats.last_frame = elapsed;
+        stats.max_frame = stats.max_frame.max(elapsed);
+        if stats.budget.is_some_and(|budget| elapsed > budget) {
+            stats.over_budget += 1;
+        }
+
+        self.last_frame = Some(start);
+    }
+
     fn handle_interruption(&mut self, interruption: Interruption) {
+        self.needs_redraw = true;
         match interruption {
             Interruption::Event(event) => {
+                self.record_event(&event);
                 self.on_event(event);
             }
             Interruption::Callback(cb) => {
@@ -832,14 +2592,1044 @@ impl Cursive {
         }
     }
 
+    /// Queues events to process as if they came from the user.
+    ///
+    /// Queued events are handled by the event loop, in order, before any
+    /// real input. This lets tests or demos drive the application.
+    ///
+    /// Unlike [`on_event`], this doesn't process the events right away:
+    /// events queued from a callback wait until that callback returns.
+    ///
+    /// [`on_event`]: #method.on_event
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::{Event, Key};
+    /// # let mut siv = Cursive::dummy();
+    /// siv.add_global_callback('d', |s| {
+    ///     s.inject_events(vec![
+    ///         Event::Char('h'),
+    ///         Event::Char('i'),
+    ///         Event::Key(Key::Enter),
+    ///     ]);
+    /// });
+    /// ```
+    pub fn inject_events<I>(&mut self, events: I)
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        self.injected_events.extend(events);
+    }
+
+    /// Saves every event received from now on to the file at `path`.
+    ///
+    /// The file is overwritten. Events are written as they arrive, one per
+    /// line with a timestamp, and can be played again with
+    /// [`replay_events`]. This helps reproducing bugs, or automating demos.
+    ///
+    /// Events sent with [`on_event`] are not recorded.
+    ///
+    /// [`replay_events`]: #method.replay_events
+    /// [`on_event`]: #method.on_event
+    pub fn record_events<P: AsRef<Path>>(
+        &mut self, path: P,
+    ) -> io::Result<()> {
+        self.recorder = Some(replay::Recorder::create(path)?);
+        Ok(())
+    }
+
+    /// Stops saving events started by [`record_events`].
+    ///
+    /// [`record_events`]: #method.record_events
+    pub fn stop_recording_events(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Plays the events saved in the file at `path` by [`record_events`].
+    ///
+    /// The file is read right away, and the events are sent to the event
+    /// loop with their original delays, starting now. Real input is still
+    /// processed during the replay.
+    ///
+    /// Returns an error if the file cannot be read, or is not a valid
+    /// recording.
+    ///
+    /// [`record_events`]: #method.record_events
+    pub fn replay_events<P: AsRef<Path>>(
+        &self, path: P,
+    ) -> io::Result<()> {
+        let events = replay::read_events(path)?;
+        let sink = self.cb_sink.clone();
+        let start = Instant::now();
+
+        thread::spawn(move || {
+            for (delay, event) in events {
+                let elapsed = start.elapsed();
+                if delay > elapsed {
+                    thread::sleep(delay - elapsed);
+                }
+                let cb = move |s: &mut Cursive| s.on_event(event);
+                if sink.send(Box::new(cb)).is_err() {
+                    // The Cursive root is gone.
+                    return;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Also writes the focused view as plain lines to `output`.
+    ///
+    /// This is an experimental accessibility mode. Each time the focus
+    /// moves, a line naming the focused view is written, followed by its
+    /// content. Changes to the content of the focused view are written as
+    /// well. Screen reader users can then follow the UI from another
+    /// terminal, reading a file or a pipe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// # use std::fs::File;
+    /// # let mut siv = Cursive::dummy();
+    /// // Then run `tail -f /tmp/ui.txt` in another terminal.
+    /// siv.set_linear_output(File::create("/tmp/ui.txt").unwrap());
+    /// ```
+    pub fn set_linear_output<W: io::Write + 'static>(&mut self, output: W) {
+        self.linear_output = Some(linear::LinearOutput::new(Box::new(output)));
+        self.needs_redraw = true;
+    }
+
+    /// Stops the output started by [`set_linear_output`].
+    ///
+    /// [`set_linear_output`]: #method.set_linear_output
+    pub fn clear_linear_output(&mut self) {
+        self.linear_output = None;
+    }
+
+    /// Saves `event` if a recording is in progress.
+    fn record_event(&mut self, event: &Event) {
+        let result = match self.recorder {
+            Some(ref mut recorder) => recorder.record(event),
+            None => return,
+        };
+        if let Err(e) = result {
+            warn!("Error recording events: {}", e);
+            self.recorder = None;
+        }
+    }
+
     /// Stops the event loop.
+    ///
+    /// If a callback was set with [`set_on_quit`], it is called first and
+    /// can cancel quitting.
+    ///
+    /// [`set_on_quit`]: #method.set_on_quit
     pub fn quit(&mut self) {
+        // Calling `quit()` from the callback itself quits right away.
+        let mut on_quit = match self.on_quit.take() {
+            Some(on_quit) => on_quit,
+            None => return self.force_quit(),
+        };
+
+        let quit = on_quit(self);
+        // The callback may have set a new callback.
+        if self.on_quit.is_none() {
+            self.on_quit = Some(on_quit);
+        }
+        if quit {
+            self.force_quit();
+        }
+    }
+
+    /// Stops the event loop, without calling the quit callback.
+    pub fn force_quit(&mut self) {
         self.running = false;
     }
+
+    /// Sets a callback called when quitting is requested.
+    ///
+    /// This happens when [`quit`] is called, or when the backend asks to
+    /// exit (for instance on `Ctrl-C` with termion).
+    ///
+    /// If the callback returns `false`, the application keeps running. It can
+    /// for instance ask the user to save their changes, and later call
+    /// [`force_quit`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Dialog;
+    /// # let mut siv = Cursive::dummy();
+    /// siv.set_on_quit(|s| {
+    ///     s.add_layer(
+    ///         Dialog::text("Discard unsaved changes?")
+    ///             .button("Quit", |s| s.force_quit())
+    ///             .dismiss_button("Cancel"),
+    ///     );
+    ///     false
+    /// });
+    /// ```
+    ///
+    /// [`quit`]: #method.quit
+    /// [`force_quit`]: #method.force_quit
+    pub fn set_on_quit<F>(&mut self, cb: F)
+    where
+        F: FnMut(&mut Cursive) -> bool + 'static,
+    {
+        self.on_quit = Some(Box::new(cb));
+    }
+
+    /// Sets what happens when the user presses `Ctrl-C`.
+    ///
+    /// By default, `Ctrl-C` quits the application.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::{Cursive, CtrlC};
+    /// # let mut siv = Cursive::dummy();
+    /// // Let views use Ctrl-C, for instance to copy text.
+    /// siv.set_ctrl_c(CtrlC::Event);
+    /// ```
+    pub fn set_ctrl_c(&mut self, ctrl_c: CtrlC) {
+        self.ctrl_c = ctrl_c;
+    }
+
+    /// Runs the given callback when the user presses `Ctrl-C`.
+    ///
+    /// Shortcut for `set_ctrl_c(CtrlC::Callback(...))`.
+    pub fn set_on_ctrl_c<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive) + 'static,
+    {
+        self.set_ctrl_c(CtrlC::Callback(Callback::from_fn(cb)));
+    }
+
+    /// Sets a callback called when the terminal is resized.
+    ///
+    /// It receives the new size of the screen.
+    pub fn set_on_resize<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive, Vec2) + 'static,
+    {
+        self.on_resize = Some(Rc::new(cb));
+    }
+
+    /// Sets the smallest screen size the application supports.
+    ///
+    /// When the terminal is smaller, a "terminal too small" message is shown
+    /// instead of the views. Events are still processed.
+    ///
+    /// Defaults to `(0, 0)`.
+    pub fn set_min_screen_size<S: Into<Vec2>>(&mut self, size: S) {
+        self.min_screen_size = size.into();
+    }
+
+    /// Returns `true` if the screen is smaller than the minimum size.
+    ///
+    /// See [`set_min_screen_size`].
+    ///
+    /// [`set_min_screen_size`]: #method.set_min_screen_size
+    pub fn is_screen_too_small(&self) -> bool {
+        !self.screen_size().fits(self.min_screen_size)
+    }
+
+    /// Removes the callback set with [`set_on_quit`].
+    ///
+    /// [`set_on_quit`]: #method.set_on_quit
+    pub fn clear_on_quit(&mut self) {
+        self.on_quit = None;
+    }
+}
+
+/// Returns `true` if `previous` can be dropped when followed by `next`.
+fn is_superseded(previous: &Event, next: &Event) -> bool {
+    match (previous, next) {
+        (Event::WindowResize, Event::WindowResize)
+        | (Event::Refresh, Event::Refresh) => true,
+        (
+            Event::Mouse {
+                offset,
+                event: MouseEvent::Hold(button),
+                ..
+            },
+            Event::Mouse {
+                offset: next_offset,
+                event: MouseEvent::Hold(next_button),
+                ..
+            },
+        ) => offset == next_offset && button == next_button,
+        _ => false,
+    }
+}
+
+/// Removes redundant events from a batch of interruptions.
+fn coalesce(batch: Vec<Interruption>) -> Vec<Interruption> {
+    let mut result: Vec<Interruption> = Vec::with_capacity(batch.len());
+    for interruption in batch {
+        let superseded = match (result.last(), &interruption) {
+            (
+                Some(Interruption::Event(previous)),
+                Interruption::Event(next),
+            ) => is_superseded(previous, next),
+            _ => false,
+        };
+        if superseded {
+            result.pop();
+        }
+        result.push(interruption);
+    }
+    result
+}
+
+/// Draws the outline of `area`, as used by the view inspector.
+fn draw_highlight(printer: &Printer, area: Rect) {
+    let size = area.size();
+    printer.with_color(theme::ColorStyle::highlight(), |printer| {
+        printer.print_hline(area.top_left(), size.x, "═");
+        printer.print_hline(area.bottom_left(), size.x, "═");
+        printer.print_vline(area.top_left(), size.y, "║");
+        printer.print_vline(area.top_right(), size.y, "║");
+    });
+}
+
+/// Prints frame timings in the top-right corner, slowest views first.
+#[cfg(feature = "profiling")]
+fn draw_frame_stats(printer: &Printer, stats: &FrameStats) {
+    use utils::width::StrWidth;
+
+    fn ms(duration: Duration) -> String {
+        format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+    }
+
+    let mut views: Vec<_> = stats.views.iter().collect();
+    views.sort_by_key(|view| std::cmp::Reverse(view.total()));
+
+    let mut lines =
+        vec![format!("layout {} draw {}", ms(stats.layout), ms(stats.draw))];
+    lines.extend(views.iter().take(5).map(|view| {
+        format!("{} {}", view.name, ms(view.total()))
+    }));
+
+    printer.with_color(theme::ColorStyle::highlight(), |printer| {
+        for (y, line) in lines.iter().enumerate() {
+            let x = printer.size.x.saturating_sub(line.width());
+            printer.print((x, y), line);
+        }
+    });
 }
 
 impl Drop for Cursive {
     fn drop(&mut self) {
         self.backend.finish();
+        panic_hook::release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn quit_callback() {
+        let mut siv = Cursive::dummy();
+        let calls = Rc::new(Cell::new(0));
+
+        let counter = Rc::clone(&calls);
+        siv.set_on_quit(move |_| {
+            counter.set(counter.get() + 1);
+            false
+        });
+        siv.quit();
+        siv.on_event(Event::Exit);
+        assert!(siv.is_running());
+        assert_eq!(calls.get(), 2);
+
+        siv.set_on_quit(|s| {
+            s.quit();
+            false
+        });
+        siv.quit();
+        assert!(!siv.is_running());
+    }
+
+    #[test]
+    fn ctrl_c() {
+        let mut siv = Cursive::dummy();
+        siv.on_event(Event::CtrlChar('c'));
+        assert!(!siv.is_running());
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        siv.add_global_callback(Event::CtrlChar('c'), move |_| {
+            counter.set(counter.get() + 1);
+        });
+
+        siv.set_ctrl_c(CtrlC::Event);
+        siv.on_event(Event::CtrlChar('c'));
+        assert_eq!(calls.get(), 1);
+
+        let counter = Rc::clone(&calls);
+        siv.set_on_ctrl_c(move |_| counter.set(counter.get() + 10));
+        siv.on_event(Event::CtrlChar('c'));
+        assert_eq!(calls.get(), 11);
+    }
+
+    #[test]
+    fn shutdown() {
+        let mut siv = Cursive::dummy();
+        let saved = Rc::new(Cell::new(false));
+
+        let flag = Rc::clone(&saved);
+        siv.add_global_callback(Event::Shutdown, move |_| flag.set(true));
+        siv.set_on_quit(|_| false);
+        siv.on_event(Event::Shutdown);
+
+        assert!(saved.get());
+        assert!(!siv.is_running());
+    }
+
+    #[test]
+    fn ctrl_z_outside_process_terminal() {
+        // The dummy backend doesn't run in the process terminal: this would
+        // otherwise stop the test process.
+        let mut siv = Cursive::dummy();
+        siv.on_event(Event::CtrlChar('z'));
+        assert!(siv.is_running());
+    }
+
+    #[test]
+    fn resize() {
+        let mut siv = Cursive::dummy();
+        let size = Rc::new(Cell::new(Vec2::zero()));
+
+        let last = Rc::clone(&size);
+        siv.set_on_resize(move |_, size| last.set(size));
+        siv.on_event(Event::WindowResize);
+        assert_eq!(size.get(), Vec2::new(1, 1));
+
+        assert!(!siv.is_screen_too_small());
+        siv.set_min_screen_size((80, 24));
+        assert!(siv.is_screen_too_small());
+    }
+
+    // Backend with a settable size, counting how often it is cleared.
+    struct Clears {
+        size: Rc<Cell<Vec2>>,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl backend::Backend for Clears {
+        fn finish(&mut self) {}
+        fn refresh(&mut self) {}
+        fn has_colors(&self) -> bool {
+            false
+        }
+        fn screen_size(&self) -> Vec2 {
+            self.size.get()
+        }
+        fn print_at(&self, _: Vec2, _: &str) {}
+        fn clear(&self, _: theme::Color) {
+            self.count.set(self.count.get() + 1);
+        }
+        fn set_color(&self, colors: theme::ColorPair) -> theme::ColorPair {
+            colors
+        }
+        fn set_effect(&self, _: theme::Effect) {}
+        fn unset_effect(&self, _: theme::Effect) {}
+    }
+
+    #[test]
+    fn too_small_clears_on_resize() {
+        let size = Rc::new(Cell::new(Vec2::new(20, 5)));
+        let count = Rc::new(Cell::new(0));
+        let backend = Clears {
+            size: Rc::clone(&size),
+            count: Rc::clone(&count),
+        };
+        let mut siv = Cursive::new(move || Box::new(backend));
+        siv.set_min_screen_size((80, 24));
+
+        siv.draw_frame();
+        siv.draw_frame();
+        assert_eq!(count.get(), 1);
+
+        size.set(Vec2::new(30, 5));
+        siv.draw_frame();
+        siv.draw_frame();
+        assert_eq!(count.get(), 2);
+
+        // The message is cleared once the screen is large enough.
+        size.set(Vec2::new(80, 24));
+        siv.draw_frame();
+        assert_eq!(count.get(), 3);
+        siv.draw_frame();
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    fn coalescing() {
+        let drag = |x| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, 0),
+            event: MouseEvent::Hold(MouseButton::Left),
+        };
+        let batch = vec![
+            Event::WindowResize,
+            Event::WindowResize,
+            Event::Key(Key::Down),
+            Event::Key(Key::Down),
+            Event::Key(Key::Up),
+            Event::Char('a'),
+            Event::Char('a'),
+            drag(1),
+            drag(2),
+            Event::WindowResize,
+        ];
+
+        let events: Vec<Event> =
+            coalesce(batch.into_iter().map(Interruption::Event).collect())
+                .into_iter()
+                .map(|interruption| match interruption {
+                    Interruption::Event(event) => event,
+                    _ => unreachable!(),
+                })
+                .collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::WindowResize,
+                Event::Key(Key::Down),
+                Event::Key(Key::Down),
+                Event::Key(Key::Up),
+                Event::Char('a'),
+                Event::Char('a'),
+                drag(2),
+                Event::WindowResize,
+            ]
+        );
+    }
+
+    #[test]
+    fn frame_budget() {
+        let mut siv = Cursive::dummy();
+        siv.set_fps(50);
+        assert_eq!(
+            siv.frame_budget().budget,
+            Some(Duration::from_millis(20))
+        );
+
+        // The dummy backend sends `Event::Exit` right away.
+        siv.step();
+        assert!(!siv.is_running());
+        assert_eq!(siv.frame_budget().frames, 1);
+    }
+
+    #[test]
+    fn inject_events() {
+        use view::Identifiable;
+        use views::EditView;
+
+        let mut siv = Cursive::dummy();
+        siv.add_layer(EditView::new().with_id("edit"));
+        siv.inject_events(vec![Event::Char('h'), Event::Char('i')]);
+
+        // The dummy backend sends `Event::Exit` after the injected events.
+        siv.step();
+        assert!(!siv.is_running());
+        let content = siv.call_on_id("edit", |v: &mut EditView| {
+            v.get_content()
+        });
+        assert_eq!(content.unwrap().as_str(), "hi");
+    }
+
+    #[test]
+    fn record_and_replay() {
+        use std::fs;
+        use view::Identifiable;
+        use views::EditView;
+
+        let path = std::env::temp_dir()
+            .join(format!("cursive-events-{}", std::process::id()));
+
+        // The dummy backend sends `Event::Exit` right away.
+        let mut siv = Cursive::dummy();
+        siv.record_events(&path).unwrap();
+        siv.step();
+        let recording = fs::read_to_string(&path).unwrap();
+        assert!(recording.ends_with(" exit\n"), "{}", recording);
+
+        fs::write(&path, "0 char h\n# comment\n\n10 char i\n").unwrap();
+        let mut siv = Cursive::dummy();
+        siv.add_layer(EditView::new().with_id("edit"));
+        siv.replay_events(&path).unwrap();
+        for _ in 0..2 {
+            let cb = siv.cb_source.recv_timeout(Duration::from_secs(1));
+            cb.unwrap().call_box(&mut siv);
+        }
+        let content = siv.call_on_id("edit", |v: &mut EditView| {
+            v.get_content()
+        });
+        assert_eq!(content.unwrap().as_str(), "hi");
+
+        fs::write(&path, "0 char h\n10 nope\n").unwrap();
+        let error = siv.replay_events(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn frame_stats() {
+        use views::{LinearLayout, ProfiledView, TextView};
+
+        let mut siv = Cursive::dummy();
+        siv.add_layer(
+            LinearLayout::vertical()
+                .child(ProfiledView::new(TextView::new("a")).name("text"))
+                .child(ProfiledView::new(TextView::new("b")).name("text"))
+                .child(ProfiledView::new(TextView::new("c"))),
+        );
+        siv.set_profiling_overlay(true);
+        siv.refresh();
+
+        let names: Vec<_> = siv
+            .last_frame_stats()
+            .views
+            .iter()
+            .map(|view| view.name.clone())
+            .collect();
+        assert_eq!(names, vec!["text", TextView::new("").type_name()]);
+
+        // Views are only counted in the frame they are drawn in.
+        siv.pop_layer();
+        siv.refresh();
+        assert!(siv.last_frame_stats().views.is_empty());
+    }
+
+    #[test]
+    fn dump_and_restore() {
+        use view::Identifiable;
+        use views::{Checkbox, EditView, LinearLayout, TextView};
+
+        fn build(siv: &mut Cursive) {
+            siv.add_layer(TextView::new("Back").with_id("back"));
+            siv.add_layer(
+                LinearLayout::vertical()
+                    .child(EditView::new().with_id("name"))
+                    .child(Checkbox::new().with_id("check"))
+                    .with_id("form"),
+            );
+            siv.add_active_screen();
+            siv.add_layer(TextView::new("Other"));
+        }
+
+        let mut siv = Cursive::dummy();
+        build(&mut siv);
+        siv.set_screen(0);
+        siv.call_on_id("name", |v: &mut EditView| v.set_content("Bob"));
+        siv.call_on_id("check", |v: &mut Checkbox| v.check());
+        siv.screen_mut().move_to_back(LayerPosition::FromFront(0));
+
+        let snapshot = siv.dump();
+        assert_eq!(snapshot.active_screen, 0);
+        assert_eq!(
+            snapshot.layers,
+            vec![
+                vec![Some("form".to_string()), Some("back".to_string())],
+                vec![None],
+            ]
+        );
+        assert_eq!(
+            snapshot.views["name"],
+            view::ViewState::Text("Bob".to_string())
+        );
+
+        let mut siv = Cursive::dummy();
+        build(&mut siv);
+        siv.restore(&snapshot);
+        assert_eq!(siv.active_screen(), 0);
+        assert_eq!(
+            siv.screen_mut().find_layer_from_id("back"),
+            Some(LayerPosition::FromBack(1))
+        );
+        let name = siv.call_on_id("name", |v: &mut EditView| v.get_content());
+        assert_eq!(name.unwrap().as_str(), "Bob");
+        let checked =
+            siv.call_on_id("check", |v: &mut Checkbox| v.is_checked());
+        assert_eq!(checked, Some(true));
+    }
+
+    #[test]
+    fn transitions() {
+        let mut siv = Cursive::dummy();
+        siv.add_layer(views::TextView::new("Hello"));
+        assert!(!siv.is_animating());
+        assert_eq!(siv.tick_interval(), None);
+
+        siv.set_transition(Transition::slide().frames(2));
+        siv.add_layer(views::TextView::new("World"));
+        assert!(siv.is_animating());
+        assert_eq!(siv.tick_interval(), Some(Duration::from_millis(33)));
+
+        // One frame per refresh.
+        siv.refresh();
+        assert!(siv.is_animating());
+        siv.refresh();
+        assert!(!siv.is_animating());
+
+        let screen = siv.add_active_screen();
+        assert_eq!(siv.active_screen(), screen);
+        assert!(siv.is_animating());
+        siv.clear_transition();
+        assert!(!siv.is_animating());
+
+        siv.set_transition(Transition::fade());
+        siv.pop_layer();
+        assert!(!siv.is_animating());
+    }
+
+    #[test]
+    fn esc_closes_layer() {
+        let mut siv = Cursive::dummy();
+        let layers = |siv: &Cursive| siv.screen().layer_sizes().len();
+        siv.add_layer(views::TextView::new("Main"));
+        siv.add_layer(views::TextView::new("Popup"));
+        siv.add_layer(views::TextView::new("Popup"));
+
+        siv.on_event(Event::Key(Key::Esc));
+        assert_eq!(layers(&siv), 3);
+
+        siv.set_global_esc_closes_layer(true);
+        siv.on_event(Event::Key(Key::Esc));
+        assert_eq!(layers(&siv), 2);
+
+        siv.screen_mut().set_closable(LayerPosition::FromFront(0), false);
+        siv.on_event(Event::Key(Key::Esc));
+        assert_eq!(layers(&siv), 2);
+
+        // The last layer is never closed.
+        siv.screen_mut().set_closable(LayerPosition::FromFront(0), true);
+        siv.on_event(Event::Key(Key::Esc));
+        siv.on_event(Event::Key(Key::Esc));
+        assert_eq!(layers(&siv), 1);
+
+        // Global callbacks come first.
+        siv.add_layer(views::TextView::new("Popup"));
+        siv.add_global_callback(Key::Esc, |_| ());
+        siv.on_event(Event::Key(Key::Esc));
+        assert_eq!(layers(&siv), 2);
+    }
+
+    #[test]
+    fn scoped_callbacks() {
+        let mut siv = Cursive::dummy();
+        let calls = Rc::new(Cell::new(""));
+        let record = |name| {
+            let calls = Rc::clone(&calls);
+            move |_: &mut Cursive| calls.set(name)
+        };
+        siv.add_global_callback('a', record("global"));
+        siv.add_screen_callback('a', record("screen"));
+        siv.add_layer(views::TextView::new("Main"));
+        siv.add_layer(views::TextView::new("Popup"));
+        siv.add_layer_callback('a', record("layer"));
+
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), "layer");
+
+        siv.pop_layer();
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), "screen");
+
+        siv.add_active_screen();
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), "global");
+    }
+
+    #[test]
+    fn input_grab() {
+        let mut siv = Cursive::dummy();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let edit = views::EditView::new();
+        siv.add_layer(view::Identifiable::with_id(edit, "edit"));
+
+        let grabbed = Rc::clone(&events);
+        siv.grab_input(move |s, event| {
+            if event == Event::Key(Key::Enter) {
+                s.release_input();
+            }
+            grabbed.borrow_mut().push(event);
+        });
+        assert!(siv.is_input_grabbed());
+
+        siv.on_event(Event::Refresh);
+        siv.on_event(Event::Char('a'));
+        siv.on_event(Event::WindowResize);
+        siv.on_event(Event::Key(Key::Enter));
+        siv.on_event(Event::Char('b'));
+        assert!(!siv.is_input_grabbed());
+        assert_eq!(
+            *events.borrow(),
+            vec![Event::Char('a'), Event::Key(Key::Enter)]
+        );
+
+        let content = siv
+            .call_on_id("edit", |view: &mut views::EditView| {
+                view.get_content()
+            }).unwrap();
+        assert_eq!(&*content, "b");
+    }
+
+    #[test]
+    fn screen_menubars() {
+        let mut siv = Cursive::dummy();
+        let calls = Rc::new(Cell::new(""));
+        let record = |name| {
+            let calls = Rc::clone(&calls);
+            move |_: &mut Cursive| calls.set(name)
+        };
+        siv.menubar().add_leaf("Shared", |_| ());
+        let first = siv.add_screen();
+        let second = siv.add_screen();
+
+        let mut menubar = views::Menubar::new();
+        menubar.add_leaf("First", |_| ()).add_leaf("Own", |_| ());
+        siv.set_screen_menubar(first, menubar);
+        siv.add_global_callback('a', record("global"));
+        siv.add_screen_callback_for(first, 'a', record("first"));
+
+        siv.set_screen(first);
+        assert_eq!(siv.menubar().len(), 2);
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), "first");
+
+        siv.set_screen(second);
+        assert_eq!(siv.menubar().len(), 1);
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), "global");
+
+        siv.set_screen(first);
+        assert_eq!(siv.menubar().len(), 2);
+        assert_eq!(siv.remove_screen_menubar(first).map(|m| m.len()), Some(2));
+        assert_eq!(siv.menubar().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid screen ID")]
+    fn remove_missing_screen_menubar() {
+        let mut siv = Cursive::dummy();
+        siv.remove_screen_menubar(1);
+    }
+
+    #[test]
+    fn screen_tabs() {
+        let mut siv = Cursive::new(|| Box::new(PrintBuffer::new((20, 3))));
+        siv.set_screen_title(0, "A");
+        let tabs = siv.screen_tabs();
+        siv.add_fullscreen_layer(tabs);
+        let second = siv.add_active_screen();
+        let tabs = siv.screen_tabs();
+        siv.add_fullscreen_layer(tabs);
+        assert_eq!(siv.screen_count(), 2);
+        assert_eq!(siv.screen_title(second), None);
+
+        siv.next_screen();
+        assert_eq!(siv.active_screen(), 0);
+        siv.prev_screen();
+        assert_eq!(siv.active_screen(), second);
+
+        siv.draw_frame();
+        let buffer = PrintBuffer::new((20, 1));
+        siv.screen().draw(&buffer.printer(&siv.theme));
+        assert_eq!(buffer.text(0, 0, 8), " 1:A  2 ");
+    }
+
+    #[test]
+    fn screen_tabs_click() {
+        let mut siv = Cursive::new(|| Box::new(PrintBuffer::new((20, 3))));
+        let tabs = siv.screen_tabs();
+        siv.add_fullscreen_layer(
+            views::LinearLayout::vertical()
+                .child(views::TextView::new("Content"))
+                .child(tabs),
+        );
+        siv.add_screen();
+        siv.draw_frame();
+
+        // Tabs are ` 1  2 `: click on the second one.
+        let click = |event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(4, 1),
+            event,
+        };
+        siv.on_event(click(MouseEvent::Press(MouseButton::Left)));
+        siv.on_event(click(MouseEvent::Release(MouseButton::Left)));
+        assert_eq!(siv.active_screen(), 1);
+
+        // The tabs have the focus now.
+        siv.set_screen(0);
+        siv.on_event(Event::Key(Key::Right));
+        assert_eq!(siv.active_screen(), 1);
+    }
+
+    #[test]
+    fn priority_callbacks() {
+        let mut siv = Cursive::dummy();
+        let calls = Rc::new(Cell::new(0));
+        let edit = views::EditView::new();
+        siv.add_layer(view::Identifiable::with_id(edit, "edit"));
+
+        let counter = Rc::clone(&calls);
+        siv.add_global_callback('a', move |_| counter.set(counter.get() + 1));
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), 0);
+
+        let counter = Rc::clone(&calls);
+        siv.add_global_callback_with_priority(
+            'a',
+            Priority::BeforeViews,
+            move |_| counter.set(counter.get() + 10),
+        );
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), 10);
+
+        let content = siv
+            .call_on_id("edit", |view: &mut views::EditView| {
+                view.get_content()
+            }).unwrap();
+        assert_eq!(&*content, "a");
+
+        siv.clear_global_callbacks('a');
+        siv.on_event(Event::Char('a'));
+        assert_eq!(calls.get(), 10);
+    }
+
+    #[test]
+    fn multi_press() {
+        let mut siv = Cursive::dummy();
+        let press = |button, x| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, 0),
+            event: MouseEvent::Press(button),
+        };
+        let count = |event: Option<Event>| match event {
+            Some(Event::Mouse { event, .. }) => event.click_count(),
+            _ => 1,
+        };
+
+        let left = MouseButton::Left;
+        assert_eq!(count(siv.count_presses(&press(left, 0))), 1);
+        assert_eq!(count(siv.count_presses(&press(left, 0))), 2);
+        assert_eq!(count(siv.count_presses(&press(left, 0))), 3);
+
+        // Another place or button starts a new count.
+        assert_eq!(count(siv.count_presses(&press(left, 1))), 1);
+        let right = MouseButton::Right;
+        assert_eq!(count(siv.count_presses(&press(right, 1))), 1);
+
+        siv.set_multi_press_interval(Duration::from_millis(0));
+        thread::sleep(Duration::from_millis(1));
+        assert_eq!(count(siv.count_presses(&press(right, 1))), 1);
+    }
+
+    #[test]
+    fn sequences() {
+        let mut siv = Cursive::dummy();
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        siv.add_global_callback(Event::Alt(Key::Up), move |_| {
+            counter.set(counter.get() + 1)
+        });
+
+        let sequence = b"\x1B[1;9A";
+        siv.on_event(Event::Unknown(sequence.to_vec()));
+        assert_eq!(calls.get(), 0);
+
+        siv.add_sequence(&sequence[..], Event::Alt(Key::Up));
+        siv.on_event(Event::Unknown(sequence.to_vec()));
+        assert_eq!(calls.get(), 1);
+
+        siv.remove_sequence(sequence);
+        siv.on_event(Event::Unknown(sequence.to_vec()));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn theme_mode() {
+        let mut siv = Cursive::dummy();
+        let mut dark = theme::load_default();
+        dark.shadow = false;
+        let mut light = theme::load_default();
+        light.borders = theme::BorderStyle::None;
+
+        // The dummy backend doesn't know its background: dark is used.
+        siv.set_light_theme(light);
+        assert_eq!(siv.current_theme().borders, theme::BorderStyle::None);
+        siv.set_dark_theme(dark);
+        assert!(!siv.current_theme().shadow);
+
+        siv.set_theme_mode(theme::ThemeMode::Light);
+        assert!(siv.current_theme().shadow);
+        assert_eq!(siv.current_theme().borders, theme::BorderStyle::None);
+
+        siv.set_theme_mode(theme::ThemeMode::Auto);
+        assert!(!siv.current_theme().shadow);
+    }
+
+    #[test]
+    fn linear_output() {
+        let mut siv = Cursive::new(|| Box::new(PrintBuffer::new((20, 5))));
+        let written = ::linear::SharedWriter::default();
+        siv.set_linear_output(written.clone());
+        siv.add_fullscreen_layer(views::TextView::new("Hello"));
+        let edit = views::EditView::new().content("Joe");
+        siv.add_fullscreen_layer(view::Boxable::full_width(edit));
+        siv.draw_frame();
+
+        let text = written.contents();
+        assert!(text.starts_with("[focus] EditView\nJoe"), "{:?}", text);
+
+        // Nothing changed: nothing is written.
+        let len = written.len();
+        siv.draw_frame();
+        assert_eq!(written.len(), len);
+
+        siv.handle_interruption(Interruption::Event(Event::Char('y')));
+        siv.draw_frame();
+        let text = written.contents();
+        assert!(text[len..].starts_with("Joey_"), "{:?}", text);
+    }
+
+    #[test]
+    fn event_tracing() {
+        ::logger::init();
+
+        let mut siv = Cursive::new(|| Box::new(PrintBuffer::new((20, 5))));
+        siv.add_fullscreen_layer(views::EditView::new());
+        siv.draw_frame();
+        siv.set_event_tracing(true);
+        siv.on_event(Event::Char('a'));
+        siv.on_event(Event::Key(Key::F5));
+
+        let logs = ::logger::LOGS.lock().unwrap();
+        let messages: Vec<&str> =
+            logs.iter().map(|r| r.message.as_str()).collect();
+        // The focus path ends with the EditView.
+        let traced = |prefix: &str, suffix: &str| {
+            messages
+                .iter()
+                .any(|m| m.starts_with(prefix) && m.ends_with(suffix))
+        };
+        assert!(traced("[event] received Char('a')", ""), "{:?}", messages);
+        assert!(traced("[event] consumed, callback (focus: ", "EditView)"));
+        assert!(traced("[event] ignored (focus: ", "EditView)"));
+        assert!(traced("[event] no callback for Key(F5)", ""));
     }
 }
@@ -0,0 +1,297 @@
+//! Runs the event loop as a future.
+//!
+//! Requires the `async` feature.
+
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{self, Receiver, Select, Sender};
+
+use Cursive;
+
+/// Drives a `Cursive` root from an async runtime.
+///
+/// Instead of blocking the current thread like [`Cursive::run`], the event
+/// loop runs as a future: input and callbacks are processed whenever the
+/// task is polled, so the UI can live alongside other tasks.
+///
+/// A small watcher thread wakes the task when input or callbacks are ready;
+/// it never processes them itself. Callbacks sent through
+/// [`Cursive::cb_sink`], for instance from other tasks, are run on the next
+/// poll.
+///
+/// With [`Cursive::set_fps`], `Event::Refresh` is sent and the screen is
+/// redrawn at that rate. Otherwise, the screen is redrawn after each batch
+/// of interruptions.
+///
+/// Requires the `async` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let mut siv = CursiveAsync::new(Cursive::default());
+/// siv.add_global_callback('q', |s| s.quit());
+///
+/// siv.run().await;
+/// ```
+///
+/// [`Cursive::run`]: struct.Cursive.html#method.run
+/// [`Cursive::cb_sink`]: struct.Cursive.html#method.cb_sink
+/// [`Cursive::set_fps`]: struct.Cursive.html#method.set_fps
+pub struct CursiveAsync {
+    siv: Cursive,
+    watcher: WatcherHandle,
+}
+
+/// Stops the watcher thread when dropped.
+struct WatcherHandle(Arc<Watcher>);
+
+impl Deref for WatcherHandle {
+    type Target = Watcher;
+
+    fn deref(&self) -> &Watcher {
+        &self.0
+    }
+}
+
+impl Drop for WatcherHandle {
+    fn drop(&mut self) {
+        self.0.stop();
+    }
+}
+
+/// State shared with the watcher thread.
+struct Watcher {
+    state: Mutex<WatcherState>,
+    changed: Condvar,
+    /// Wakes the watcher thread up if it is waiting for input.
+    stop_sender: Sender<()>,
+}
+
+#[derive(Default)]
+struct WatcherState {
+    /// Waker of the task to notify, set when the task waits.
+    waker: Option<Waker>,
+    /// Wake the task after this time, even if nothing is ready.
+    timeout: Option<Duration>,
+    /// Set when the watcher is no longer needed.
+    stopped: bool,
+}
+
+/// What the watcher thread should wait for.
+enum Wait {
+    /// Input or callbacks, but no longer than the given time, if any.
+    Ready(Option<Duration>),
+    /// Nothing: the watcher is no longer needed.
+    Stop,
+}
+
+impl Watcher {
+    /// Creates a new watcher, and the receiver woken when it stops.
+    fn new() -> (Self, Receiver<()>) {
+        let (stop_sender, stop_receiver) = crossbeam_channel::bounded(1);
+        let watcher = Watcher {
+            state: Mutex::new(WatcherState::default()),
+            changed: Condvar::new(),
+            stop_sender,
+        };
+        (watcher, stop_receiver)
+    }
+
+    /// Waits until the task needs to be woken.
+    fn wait_armed(&self) -> Wait {
+        let mut state = self.state.lock().unwrap();
+        while state.waker.is_none() && !state.stopped {
+            state = self.changed.wait(state).unwrap();
+        }
+        if state.stopped {
+            Wait::Stop
+        } else {
+            Wait::Ready(state.timeout)
+        }
+    }
+
+    fn arm(&self, waker: &Waker, timeout: Option<Duration>) {
+        let mut state = self.state.lock().unwrap();
+        state.waker = Some(waker.clone());
+        state.timeout = timeout;
+        self.changed.notify_one();
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.state.lock().unwrap().waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.changed.notify_one();
+        // The thread may be waiting for input rather than for the state.
+        let _ = self.stop_sender.try_send(());
+    }
+}
+
+impl CursiveAsync {
+    /// Wraps the given `Cursive` root.
+    pub fn new(siv: Cursive) -> Self {
+        let (watcher, stop) = Watcher::new();
+        let watcher = Arc::new(watcher);
+        let (events, callbacks) = siv.receivers();
+
+        let thread_watcher = Arc::clone(&watcher);
+        thread::spawn(move || {
+            while let Wait::Ready(timeout) = thread_watcher.wait_armed() {
+                // Only wait for something to be ready: the task will
+                // receive it.
+                let mut select = Select::new();
+                select.recv(&events);
+                select.recv(&callbacks);
+                select.recv(&stop);
+                match timeout {
+                    Some(timeout) => {
+                        let _ = select.ready_timeout(timeout);
+                    }
+                    None => {
+                        select.ready();
+                    }
+                }
+
+                thread_watcher.wake();
+            }
+        });
+
+        CursiveAsync {
+            siv,
+            watcher: WatcherHandle(watcher),
+        }
+    }
+
+    /// Returns the wrapped `Cursive` root.
+    pub fn into_inner(self) -> Cursive {
+        self.watcher.stop();
+        self.siv
+    }
+
+    /// Runs the event loop until [`Cursive::quit`] is called.
+    ///
+    /// [`Cursive::quit`]: struct.Cursive.html#method.quit
+    pub fn run(&mut self) -> Run<'_> {
+        self.siv.start();
+        Run { driver: self }
+    }
+}
+
+impl Deref for CursiveAsync {
+    type Target = Cursive;
+
+    fn deref(&self) -> &Cursive {
+        &self.siv
+    }
+}
+
+impl DerefMut for CursiveAsync {
+    fn deref_mut(&mut self) -> &mut Cursive {
+        &mut self.siv
+    }
+}
+
+/// Future returned by [`CursiveAsync::run`].
+///
+/// [`CursiveAsync::run`]: struct.CursiveAsync.html#method.run
+pub struct Run<'a> {
+    driver: &'a mut CursiveAsync,
+}
+
+impl<'a> Future for Run<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let driver = &mut *self.get_mut().driver;
+        let siv = &mut driver.siv;
+
+        siv.refresh_if_due();
+        siv.process_pending();
+        if !siv.is_running() {
+            return Poll::Ready(());
+        }
+
+        siv.refresh_if_needed();
+        siv.request_input();
+        driver.watcher.arm(cx.waker(), siv.time_to_next_frame());
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::task::Wake;
+
+    /// Wakes the test thread through a channel.
+    struct ChannelWaker(Mutex<mpsc::Sender<()>>);
+
+    impl Wake for ChannelWaker {
+        fn wake(self: Arc<Self>) {
+            let _ = self.0.lock().unwrap().send(());
+        }
+    }
+
+    /// Minimal executor polling a single future.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let (sender, receiver) = mpsc::channel();
+        let waker = Waker::from(Arc::new(ChannelWaker(Mutex::new(sender))));
+        let mut context = Context::from_waker(&waker);
+
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+            receiver.recv().unwrap();
+        }
+    }
+
+    #[test]
+    fn run_until_exit() {
+        // The dummy backend sends `Event::Exit` when asked for input.
+        let mut siv = CursiveAsync::new(Cursive::dummy());
+        block_on(siv.run());
+        assert!(!siv.is_running());
+
+        // Callbacks are run too.
+        let sink = siv.cb_sink().clone();
+        sink.send(Box::new(|s: &mut Cursive| s.set_fps(30))).unwrap();
+        block_on(siv.run());
+        assert!(siv.frame_budget().budget.is_some());
+    }
+
+    #[test]
+    fn refresh_on_fps() {
+        use event::Event;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let count_refreshes = |fps| {
+            let refreshes = Arc::new(AtomicUsize::new(0));
+            let counter = Arc::clone(&refreshes);
+
+            let mut siv = CursiveAsync::new(Cursive::dummy());
+            siv.set_fps(fps);
+            siv.add_global_callback(Event::Refresh, move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+            block_on(siv.run());
+            refreshes.load(Ordering::SeqCst)
+        };
+
+        assert_eq!(count_refreshes(0), 0);
+        assert!(count_refreshes(30) > 0);
+    }
+}
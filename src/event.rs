@@ -12,11 +12,33 @@
 //! * If no view consumes the event, the
 //!   [global callback](../struct.Cursive.html#method.add_global_callback)
 //!   table is checked.
+//!
+//! # Mouse
+//!
+//! Mouse events follow the same path, with a few rules shared by all views:
+//!
+//! * Pressing a button focuses the view under the cursor, if it can take
+//!   focus.
+//! * Pressing again quickly at the same place is followed by a
+//!   `MouseEvent::MultiPress`, to react to double and triple clicks.
+//! * Releasing the button on a button, a checkbox or a menu item activates
+//!   it.
+//! * Wheel events go to the view under the cursor, without changing the
+//!   focus.
+//! * A click outside a layer can close it, with
+//!   [`StackView::set_dismiss_on_click_outside`].
+//!
+//! [`StackView::set_dismiss_on_click_outside`]: ../views/struct.StackView.html#method.set_dismiss_on_click_outside
 
 use std::any::Any;
 use std::cell::RefCell;
+use std::iter::FromIterator;
 use std::ops::Deref;
 use std::rc::Rc;
+#[cfg(feature = "serde")]
+use replay;
+#[cfg(feature = "serde")]
+use serde;
 use vec::Vec2;
 use Cursive;
 
@@ -145,6 +167,43 @@ impl Callback {
     pub fn dummy() -> Self {
         Callback::from_fn(|_| ())
     }
+
+    /// Returns a callback running `self`, then `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Callback;
+    /// let cb = Callback::from_fn(|s| s.set_fps(10))
+    ///     .then(Callback::from_fn(|s| s.quit()));
+    ///
+    /// let mut siv = Cursive::dummy();
+    /// cb(&mut siv);
+    /// assert!(!siv.is_running());
+    /// ```
+    pub fn then(self, other: Callback) -> Self {
+        Callback::from_fn(move |s| {
+            (self)(s);
+            (other)(s);
+        })
+    }
+}
+
+impl FromIterator<Callback> for Callback {
+    /// Collects callbacks into one, running them in sequence.
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Callback>,
+    {
+        let callbacks: Vec<Callback> = iter.into_iter().collect();
+
+        Callback::from_fn(move |s| {
+            for cb in &callbacks {
+                cb(s);
+            }
+        })
+    }
 }
 
 impl Deref for Callback {
@@ -226,7 +285,23 @@ impl EventResult {
         }
     }
 
+    /// Appends `f` to the callback of `self`, if the event was consumed.
+    ///
+    /// The callback already in `self`, if any, runs first. An ignored
+    /// event stays ignored.
+    pub fn and_cb<F>(self, f: F) -> Self
+    where
+        F: 'static + Fn(&mut Cursive),
+    {
+        match self {
+            EventResult::Ignored => EventResult::Ignored,
+            consumed => consumed.and(EventResult::with_cb(f)),
+        }
+    }
+
     /// Returns an event result that combines `self` and `other`.
+    ///
+    /// If both have a callback, the one from `self` runs first.
     pub fn and(self, other: Self) -> Self {
         match (self, other) {
             (EventResult::Ignored, result)
@@ -238,14 +313,29 @@ impl EventResult {
             (
                 EventResult::Consumed(Some(cb1)),
                 EventResult::Consumed(Some(cb2)),
-            ) => EventResult::with_cb(move |siv| {
-                (cb1)(siv);
-                (cb2)(siv);
-            }),
+            ) => EventResult::Consumed(Some(cb1.then(cb2))),
         }
     }
 }
 
+/// When a global callback runs, relative to the view tree.
+///
+/// Used with [`Cursive::add_global_callback_with_priority`].
+///
+/// [`Cursive::add_global_callback_with_priority`]: ../struct.Cursive.html#method.add_global_callback_with_priority
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum Priority {
+    /// Runs before the views see the event, which never reaches them.
+    ///
+    /// Use this for shortcuts that must always work, even when a text
+    /// input has the focus.
+    BeforeViews,
+    /// Runs only if the views ignored the event.
+    ///
+    /// This is the default for global callbacks.
+    AfterViews,
+}
+
 /// A non-character key on the keyboard
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
 pub enum Key {
@@ -367,6 +457,16 @@ pub enum MouseButton {
 pub enum MouseEvent {
     /// A button was pressed.
     Press(MouseButton),
+    /// A button was pressed again, quickly and at the same place.
+    ///
+    /// Sent right after the corresponding `Press`, with the number of
+    /// presses so far: `2` for a double click, `3` for a triple click...
+    ///
+    /// The interval between clicks is set with
+    /// [`Cursive::set_multi_press_interval`].
+    ///
+    /// [`Cursive::set_multi_press_interval`]: ../struct.Cursive.html#method.set_multi_press_interval
+    MultiPress(MouseButton, usize),
     /// A button was released.
     Release(MouseButton),
     /// A button is being held.
@@ -384,6 +484,7 @@ impl MouseEvent {
     pub fn button(self) -> Option<MouseButton> {
         match self {
             MouseEvent::Press(btn)
+            | MouseEvent::MultiPress(btn, _)
             | MouseEvent::Release(btn)
             | MouseEvent::Hold(btn) => Some(btn),
             _ => None,
@@ -392,15 +493,31 @@ impl MouseEvent {
 
     /// Returns `true` if `self` is an event that can grab focus.
     ///
-    /// This includes `Press`, `WheelUp` and `WheelDown`.
+    /// This is only `Press`: wheel events go to the view under the cursor
+    /// without changing the focus.
     pub fn grabs_focus(self) -> bool {
         match self {
-            MouseEvent::Press(_)
-            | MouseEvent::WheelUp
-            | MouseEvent::WheelDown => true,
+            MouseEvent::Press(_) => true,
             _ => false,
         }
     }
+
+    /// Returns the number of quick presses this event completes.
+    ///
+    /// This is `1` for `Press`, the count for `MultiPress`, and `0` for
+    /// other events.
+    pub fn click_count(self) -> usize {
+        match self {
+            MouseEvent::Press(_) => 1,
+            MouseEvent::MultiPress(_, count) => count,
+            _ => 0,
+        }
+    }
+
+    /// Returns `true` if `self` is `WheelUp` or `WheelDown`.
+    pub fn is_wheel(self) -> bool {
+        self == MouseEvent::WheelUp || self == MouseEvent::WheelDown
+    }
 }
 
 /// Represents an event as seen by the application.
@@ -445,8 +562,36 @@ pub enum Event {
         event: MouseEvent,
     },
 
+    /// The process was asked to terminate.
+    ///
+    /// Sent on `SIGTERM` and `SIGHUP` by backends supporting signals.
+    /// Global callbacks for this event are called, then the application
+    /// quits; this is the time to save any unsaved state.
+    Shutdown,
+
+    /// Some text was pasted.
+    ///
+    /// Backends supporting bracketed paste send the pasted text as a single
+    /// event, instead of one `Char` event per character.
+    Paste(String),
+
+    /// A key was released.
+    ///
+    /// Holds the event sent when the key was pressed. Only sent when
+    /// enabled with [`Cursive::set_key_release_events`], by terminals
+    /// supporting it.
+    ///
+    /// [`Cursive::set_key_release_events`]: ../struct.Cursive.html#method.set_key_release_events
+    Released(Box<Event>),
+
     // TODO: use a backend-dependent type for the unknown values?
     /// An unknown event was received.
+    ///
+    /// Holds the raw input, when the backend has it, like the bytes of an
+    /// escape sequence. Such sequences can be translated into other events
+    /// with [`Cursive::add_sequence`].
+    ///
+    /// [`Cursive::add_sequence`]: ../struct.Cursive.html#method.add_sequence
     Unknown(Vec<u8>),
 
     // Maybe add a `Custom(Rc<Any>)` ?
@@ -520,3 +665,96 @@ impl From<Key> for Event {
         Event::Key(k)
     }
 }
+
+/// Keys are serialized by name, like `"PageUp"` or `"F1"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Key {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&format_args!("{:?}", self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, Unexpected};
+
+        let name = String::deserialize(deserializer)?;
+        replay::parse_key(&name).ok_or_else(|| {
+            D::Error::invalid_value(Unexpected::Str(&name), &"a key name")
+        })
+    }
+}
+
+/// Events are serialized as strings, like `"ctrl-char x"` or
+/// `"alt-shift Left"`, in the format used by `Cursive::record_events`.
+///
+/// This makes them usable as keys in a key map.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Event {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&replay::format_event(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Event {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, Unexpected};
+
+        let text = String::deserialize(deserializer)?;
+        replay::parse_event(&text).ok_or_else(|| {
+            D::Error::invalid_value(Unexpected::Str(&text), &"an event")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a callback pushing `i` to `log`.
+    fn push(log: &Rc<RefCell<Vec<u8>>>, i: u8) -> Callback {
+        let log = Rc::clone(log);
+        Callback::from_fn(move |_| log.borrow_mut().push(i))
+    }
+
+    #[test]
+    fn composed_callbacks() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut siv = Cursive::dummy();
+
+        let cb: Callback = (1..4).map(|i| push(&log, i)).collect();
+        cb.then(push(&log, 4))(&mut siv);
+        assert_eq!(*log.borrow(), vec![1, 2, 3, 4]);
+
+        log.borrow_mut().clear();
+        let tail = push(&log, 6);
+        EventResult::Consumed(Some(push(&log, 5)))
+            .and_cb(move |s| tail(s))
+            .process(&mut siv);
+        assert_eq!(*log.borrow(), vec![5, 6]);
+
+        let tail = push(&log, 7);
+        let result = EventResult::Ignored.and_cb(move |s| tail(s));
+        assert!(!result.is_consumed());
+
+        let tail = push(&log, 8);
+        EventResult::Consumed(None)
+            .and_cb(move |s| tail(s))
+            .process(&mut siv);
+        assert_eq!(*log.borrow(), vec![5, 6, 8]);
+    }
+}
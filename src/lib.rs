@@ -55,10 +55,21 @@
 //! on drop. While it is alive, printing to the terminal will not work
 //! as expected, making debugging a bit harder.
 //!
-//! One solution is to redirect stderr to a file when running the application,
-//! and log to it instead of stdout.
+//! One solution is to use the [`logger`](./logger/index.html) module: log
+//! records are kept in memory, and can be seen from the application itself
+//! with [`Cursive::toggle_debug_console`].
+//!
+//! Another solution is to redirect stderr to a file when running the
+//! application, and log to it instead of stdout.
 //!
 //! Or you can use gdb as usual.
+//!
+//! Finally, calling [`panic_hook::install()`] makes sure panic messages are
+//! printed after the terminal has been restored, instead of being lost.
+//!
+//! [`panic_hook::install()`]: panic_hook/fn.install.html
+//!
+//! [`Cursive::toggle_debug_console`]: struct.Cursive.html#method.toggle_debug_console
 #![deny(missing_docs)]
 
 #[macro_use]
@@ -66,6 +77,8 @@ extern crate enum_map;
 #[macro_use]
 extern crate enumset;
 #[macro_use]
+extern crate lazy_static;
+#[macro_use]
 extern crate log;
 #[macro_use]
 extern crate crossbeam_channel;
@@ -79,6 +92,18 @@ extern crate maplit;
 #[cfg(unix)]
 extern crate signal_hook;
 
+#[cfg(feature = "bidi")]
+extern crate unicode_bidi;
+
+#[cfg(feature = "image")]
+extern crate image;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(all(unix, feature = "vt100"))]
+extern crate vt100;
+
 extern crate libc;
 extern crate num;
 extern crate owning_ref;
@@ -107,16 +132,28 @@ pub mod view;
 pub mod utils;
 
 pub mod align;
+pub mod builder;
+pub mod clipboard;
 pub mod direction;
+pub mod logger;
 pub mod menu;
+pub mod panic_hook;
+#[cfg(feature = "profiling")]
+pub mod profiling;
 pub mod rect;
+pub mod tasks;
 pub mod theme;
+pub mod transition;
 pub mod vec;
 pub mod views;
 
 // This probably doesn't need to be public?
 mod cursive;
+#[cfg(feature = "async")]
+mod cursive_async;
+mod linear;
 mod printer;
+mod replay;
 mod with;
 mod xy;
 
@@ -125,7 +162,11 @@ mod utf8;
 
 pub mod backend;
 
-pub use cursive::{CbFunc, Cursive, ScreenId};
+pub use cursive::{
+    CbFunc, CtrlC, Cursive, FrameBudget, ScreenId, Snapshot,
+};
+#[cfg(feature = "async")]
+pub use cursive_async::{CursiveAsync, Run};
 pub use printer::Printer;
 pub use vec::Vec2;
 pub use with::With;
@@ -0,0 +1,144 @@
+//! Linearized output for screen readers, used by `Cursive::set_linear_output`.
+//!
+//! The focused view is followed after each frame. When the focus moves, a
+//! line naming the newly focused view is written, followed by its content.
+//! When only the content changes, the new content is written again:
+//!
+//! ```text
+//! [focus] EditView
+//! John_____
+//! Johnny___
+//! [focus] Button
+//! <Ok>
+//! ```
+//!
+//! Trailing spaces and empty lines are skipped.
+
+use std::io::{self, Write};
+
+use rect::Rect;
+
+#[cfg(test)]
+use std::cell::RefCell;
+#[cfg(test)]
+use std::rc::Rc;
+
+/// Writes the focused view and its content as plain lines.
+pub struct LinearOutput {
+    writer: Box<Write>,
+
+    /// Name and area of the focused view, as of the last update.
+    focus: Option<(String, Rect)>,
+
+    /// Lines written for the focused view, as of the last update.
+    content: Vec<String>,
+}
+
+impl LinearOutput {
+    /// Creates a new output writing to `writer`.
+    pub fn new(writer: Box<Write>) -> Self {
+        LinearOutput {
+            writer,
+            focus: None,
+            content: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the last update was for this view.
+    pub fn is_focused(&self, name: &str, area: Rect) -> bool {
+        match self.focus {
+            Some((ref focus, focus_area)) => {
+                focus == name && focus_area == area
+            }
+            None => false,
+        }
+    }
+
+    /// Reports the currently focused view, and its content.
+    ///
+    /// Only writes something if the focus or the content changed.
+    pub fn update(
+        &mut self, name: &str, area: Rect, content: &[String],
+    ) -> io::Result<()> {
+        let content: Vec<String> = content
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let focus = Some((name.to_string(), area));
+        let moved = self.focus != focus;
+        if !moved && self.content == content {
+            return Ok(());
+        }
+
+        if moved {
+            writeln!(self.writer, "[focus] {}", name)?;
+        }
+        for line in &content {
+            writeln!(self.writer, "{}", line)?;
+        }
+        self.writer.flush()?;
+
+        self.focus = focus;
+        self.content = content;
+        Ok(())
+    }
+}
+
+/// Writer sharing what it receives, for tests.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct SharedWriter(Rc<RefCell<Vec<u8>>>);
+
+#[cfg(test)]
+impl SharedWriter {
+    /// Returns everything written so far.
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+
+    /// Returns the number of bytes written so far.
+    pub(crate) fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+}
+
+#[cfg(test)]
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_changes() {
+        let shared = SharedWriter::default();
+        let mut output = LinearOutput::new(Box::new(shared.clone()));
+        let area = Rect::from_size((0, 0), (10, 2));
+        let lines = |lines: &[&str]| -> Vec<String> {
+            lines.iter().map(|line| line.to_string()).collect()
+        };
+
+        let mut update = |name, content: &[&str]| {
+            output.update(name, area, &lines(content)).unwrap();
+        };
+        update("EditView", &["Jo  ", ""]);
+        update("EditView", &["Jo", " "]);
+        update("EditView", &["Joe"]);
+        update("Button", &["<Ok>"]);
+
+        assert_eq!(
+            shared.contents(),
+            "[focus] EditView\nJo\nJoe\n[focus] Button\n<Ok>\n"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Logging utilities.
+//!
+//! Printing to stdout or stderr while cursive is running corrupts the
+//! screen. Instead, this module provides a [`log`] implementation that keeps
+//! records in memory, where they can be displayed by a [`DebugView`].
+//!
+//! [`log`]: https://docs.rs/log
+//! [`DebugView`]: ../views/struct.DebugView.html
+
+use log;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Default number of records kept in memory.
+const DEFAULT_CAPACITY: usize = 1_000;
+
+/// Saves all log records in a global ring buffer.
+struct CursiveLogger;
+
+static LOGGER: CursiveLogger = CursiveLogger;
+
+/// A log record.
+pub struct Record {
+    /// Log level used for this record.
+    pub level: log::Level,
+    /// Time this message was logged.
+    pub time: SystemTime,
+    /// Message content.
+    pub message: String,
+}
+
+/// Bounded queue of log records.
+pub struct Logs {
+    records: VecDeque<Record>,
+    capacity: usize,
+}
+
+impl Logs {
+    fn new(capacity: usize) -> Self {
+        Logs {
+            records: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Adds a record, dropping the oldest one if we're at capacity.
+    pub fn push(&mut self, record: Record) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        while self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Returns the maximum number of records kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the maximum number of records kept.
+    ///
+    /// Drops the oldest records if there are too many.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.records.len() > capacity {
+            self.records.pop_front();
+        }
+    }
+
+    /// Removes all records.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Returns the number of records currently stored.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if no record is stored.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Iterates on the stored records, from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Record> {
+        self.records.iter()
+    }
+}
+
+lazy_static! {
+    /// Circular buffer for logs. Used by `DebugView`.
+    pub static ref LOGS: Mutex<Logs> =
+        Mutex::new(Logs::new(DEFAULT_CAPACITY));
+}
+
+impl log::Log for CursiveLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let record = Record {
+            level: record.level(),
+            time: SystemTime::now(),
+            message: format!("{}", record.args()),
+        };
+        LOGS.lock().unwrap().push(record);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Initializes the cursive logger.
+///
+/// Make sure this is the only logger you are using.
+///
+/// Use a [`DebugView`] to see the logs, or use
+/// [`Cursive::toggle_debug_console()`].
+///
+/// Calling this more than once, or after another logger was installed,
+/// has no effect.
+///
+/// [`DebugView`]: ../views/struct.DebugView.html
+/// [`Cursive::toggle_debug_console()`]: ../struct.Cursive.html#method.toggle_debug_console
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+}
+
+/// Sets the maximum number of records kept in memory.
+///
+/// Defaults to 1000.
+pub fn set_capacity(capacity: usize) {
+    LOGS.lock().unwrap().set_capacity(capacity);
+}
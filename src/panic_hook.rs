@@ -0,0 +1,90 @@
+//! Panic hook keeping panic messages readable.
+//!
+//! While cursive is running, the terminal is in raw mode and any message
+//! printed to stderr is either garbled or hidden when the screen is restored.
+//!
+//! The hook installed by [`install()`] holds back panic messages while a
+//! [`Cursive`] root is alive, and prints them once the terminal has been
+//! restored (when the `Cursive` root is dropped during unwinding).
+//!
+//! This requires panics to unwind: with `panic = "abort"`, the terminal
+//! cannot be restored.
+//!
+//! [`install()`]: fn.install.html
+//! [`Cursive`]: ../struct.Cursive.html
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Number of `Cursive` roots currently controlling the terminal.
+static ACTIVE_ROOTS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// Messages held back until the terminal is restored.
+    static ref PENDING: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+/// Installs the panic hook.
+///
+/// Any previously installed hook is still called for panics happening
+/// while no `Cursive` root is alive.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// cursive::panic_hook::install();
+///
+/// let mut siv = Cursive::dummy();
+/// siv.add_global_callback('p', |_| panic!("This will be readable."));
+/// ```
+pub fn install() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if ACTIVE_ROOTS.load(Ordering::SeqCst) == 0 {
+            previous(info);
+            return;
+        }
+
+        let thread = thread::current();
+        let mut message = format!(
+            "thread '{}' {}",
+            thread.name().unwrap_or("<unnamed>"),
+            info
+        );
+
+        let backtrace = Backtrace::capture();
+        if backtrace.status() == BacktraceStatus::Captured {
+            message.push_str(&format!("\nstack backtrace:\n{}", backtrace));
+        }
+
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.push(message);
+        }
+    }));
+}
+
+/// Marks the terminal as being controlled by a `Cursive` root.
+pub(crate) fn acquire() {
+    ACTIVE_ROOTS.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Marks the terminal as restored, and prints held-back messages.
+pub(crate) fn release() {
+    if ACTIVE_ROOTS.fetch_sub(1, Ordering::SeqCst) != 1 {
+        // Someone else is still using the terminal.
+        return;
+    }
+
+    let pending = match PENDING.lock() {
+        Ok(mut pending) => ::std::mem::take(&mut *pending),
+        Err(_) => return,
+    };
+
+    for message in pending {
+        eprintln!("{}", message);
+    }
+}
@@ -1,13 +1,20 @@
 //! Provide higher-level abstraction to draw things on backends.
 
+use backend::buffer::PrintBuffer;
 use backend::Backend;
 use direction::Orientation;
 use enumset::EnumSet;
+use rect::Rect;
+use std::cell::{Cell, RefCell};
 use std::cmp::min;
-use theme::{BorderStyle, ColorStyle, Effect, PaletteColor, Style, Theme};
+use std::rc::Rc;
+use theme::{
+    BorderChars, BorderStyle, Color, ColorPair, ColorStyle, Effect, Link,
+    PaletteColor, Style, Theme,
+};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 use utils::lines::simple::{prefix, suffix};
+use utils::width::StrWidth;
 use vec::Vec2;
 use with::With;
 
@@ -17,6 +24,16 @@ use with::With;
 ///
 /// The part of the content it will print is defined by `content_offset`
 /// and `size`.
+///
+/// Sub-printers are created with [`offset`], [`cropped`], [`shrinked`] or
+/// [`windowed`]. They can be composed in any order: a sub-printer never
+/// prints outside of its parent's area, so a view cannot draw outside of
+/// the area it was given, even if it still uses an outdated size.
+///
+/// [`offset`]: #method.offset
+/// [`cropped`]: #method.cropped
+/// [`shrinked`]: #method.shrinked
+/// [`windowed`]: #method.windowed
 #[derive(Clone)]
 pub struct Printer<'a, 'b> {
     /// Offset into the window this printer should start drawing at.
@@ -46,11 +63,29 @@ pub struct Printer<'a, 'b> {
     /// Whether the view to draw is currently enabled or not.
     pub enabled: bool,
 
+    /// Whether colors are replaced by effects.
+    ///
+    /// See [`ColorStyle::monochrome_effects`].
+    ///
+    /// [`ColorStyle::monochrome_effects`]: theme/struct.ColorStyle.html#method.monochrome_effects
+    pub monochrome: bool,
+
     /// Currently used theme
     pub theme: &'a Theme,
 
     /// Backend used to actually draw things
     backend: &'b Backend,
+
+    /// Where the terminal cursor should be shown, if anywhere.
+    ///
+    /// Shared by all printers derived from the same root.
+    cursor: Rc<Cell<Option<Vec2>>>,
+
+    /// Colors currently used, inherited by `ColorType::InheritParent`.
+    current_color: Cell<ColorPair>,
+
+    /// Link currently active, restored after a nested link.
+    current_link: RefCell<Option<Link>>,
 }
 
 impl<'a, 'b> Printer<'a, 'b> {
@@ -69,8 +104,15 @@ impl<'a, 'b> Printer<'a, 'b> {
             size,
             focused: true,
             enabled: true,
+            monochrome: false,
             theme,
             backend,
+            cursor: Rc::new(Cell::new(None)),
+            current_color: Cell::new(ColorPair {
+                front: theme.palette[PaletteColor::Primary],
+                back: theme.palette[PaletteColor::View],
+            }),
+            current_link: RefCell::new(None),
         }
     }
 
@@ -80,8 +122,40 @@ impl<'a, 'b> Printer<'a, 'b> {
     ///
     /// Users rarely need to call this directly.
     pub fn clear(&self) {
-        self.backend
-            .clear(self.theme.palette[PaletteColor::Background]);
+        if self.monochrome {
+            self.backend.clear(Color::TerminalDefault);
+        } else {
+            self.backend
+                .clear(self.theme.palette[PaletteColor::Background]);
+        }
+    }
+
+    /// Requests the terminal cursor to be shown at the given position.
+    ///
+    /// This only has an effect if this printer is focused, and if the
+    /// position is visible. If multiple views request the cursor, the last
+    /// one drawn wins.
+    ///
+    /// Text inputs use this to place the blinking cursor where text will
+    /// be inserted.
+    pub fn show_cursor<S: Into<Vec2>>(&self, pos: S) {
+        let pos = pos.into();
+
+        if !self.focused || !pos.fits(self.content_offset) {
+            return;
+        }
+
+        let pos = pos - self.content_offset;
+        if pos.x >= self.output_size.x || pos.y >= self.output_size.y {
+            return;
+        }
+
+        self.cursor.set(Some(pos + self.offset));
+    }
+
+    /// Returns the cursor position requested while drawing, if any.
+    pub(crate) fn cursor_position(&self) -> Option<Vec2> {
+        self.cursor.get()
     }
 
     /// Prints some styled text at the given position.
@@ -92,7 +166,7 @@ impl<'a, 'b> Printer<'a, 'b> {
     {
         let Vec2 { mut x, y } = start.into();
         for span in text.spans() {
-            self.with_style(*span.attr, |printer| {
+            self.with_style(span.attr.clone(), |printer| {
                 printer.print((x, y), span.content);
                 x += span.content.width();
             });
@@ -152,18 +226,73 @@ impl<'a, 'b> Printer<'a, 'b> {
         // What we did before should guarantee that this won't overflow.
         let start = start - self.content_offset;
 
+        // If a double-width character was cut in half on the left,
+        // its visible half will be replaced with a space.
+        let padding_left = skipped_width - hidden_part.x;
+
         // Do we have enough room for the entire line?
-        let room = self.output_size.x - start.x;
+        let room = self.output_size.x.saturating_sub(start.x);
 
         // Drop the end of the text if it's too long
         // We want the number of CHARACTERS, not bytes.
         // (Actually we want the "width" of the string, see unicode-width)
         let prefix_len = prefix(text.graphemes(true), room, "").length;
+        let cut_right = prefix_len < text.len();
         let text = &text[..prefix_len];
-        assert!(text.width() <= room);
+        let width = text.width();
+        assert!(width <= room);
 
         let start = start + self.offset;
+
+        // Never leave half a character on screen: the terminal would
+        // render it (or not) in its own way.
+        if padding_left > 0 {
+            let padding = " ".repeat(padding_left);
+            self.backend.print_at(start - (padding_left, 0), &padding);
+        }
         self.backend.print_at(start, text);
+        if cut_right && width < room {
+            // A double-width character didn't fit on the right.
+            let padding = " ".repeat(room - width);
+            self.backend.print_at(start + (width, 0), &padding);
+        }
+    }
+
+    /// Prints a graphics escape sequence covering the given area.
+    ///
+    /// `data` is a complete sequence, like a sixel or kitty graphics image,
+    /// covering `size` cells from `start`.
+    ///
+    /// Graphics cannot be cropped: nothing is printed unless the entire area
+    /// is visible. Returns `true` if the sequence was printed.
+    pub fn print_graphics<S: Into<Vec2>>(
+        &self, start: S, size: Vec2, data: &str,
+    ) -> bool {
+        let start = start.into();
+
+        if !start.fits(self.content_offset)
+            || !(start + size).fits_in(self.output_size + self.content_offset)
+        {
+            return false;
+        }
+
+        let start = start - self.content_offset + self.offset;
+        self.backend.print_graphics(start, data)
+    }
+
+    /// Draws the content of `buffer`, starting at `start`.
+    ///
+    /// Transparent cells are skipped.
+    pub fn print_buffer<S: Into<Vec2>>(&self, start: S, buffer: &PrintBuffer) {
+        let start = start.into();
+        buffer.for_each_run(|pos, text, colors, effects| {
+            let style = ColorStyle::new(colors.front, colors.back);
+            self.with_color(style, |printer| {
+                printer.with_effects(effects, |printer| {
+                    printer.print(start + pos, text);
+                });
+            });
+        });
     }
 
     /// Prints a vertical line using the given character.
@@ -238,7 +367,11 @@ impl<'a, 'b> Printer<'a, 'b> {
         let start = start - self.content_offset;
 
         // Don't write too much if we're close to the end
-        let width = min(width, (self.output_size.x - start.x) / c.width());
+        let char_width = c.width();
+        if char_width == 0 {
+            return;
+        }
+        let width = min(width, (self.output_size.x - start.x) / char_width);
 
         // Could we avoid allocating?
         let text: String = ::std::iter::repeat(c).take(width).collect();
@@ -263,13 +396,49 @@ impl<'a, 'b> Printer<'a, 'b> {
     ///     printer.print((0,0), "This text is highlighted!");
     /// });
     /// ```
+    ///
+    /// Parts of the style using `ColorType::InheritParent` keep the colors
+    /// of the enclosing `with_color` call:
+    ///
+    /// ```rust
+    /// # use cursive::Printer;
+    /// # use cursive::theme::{self, ColorStyle, PaletteColor};
+    /// # use cursive::backend;
+    /// # let b = backend::dummy::Backend::init();
+    /// # let t = theme::load_default();
+    /// # let printer = Printer::new((6,4), &t, &*b);
+    /// printer.with_color(ColorStyle::highlight(), |printer| {
+    ///     // Keeps the highlight background.
+    ///     printer.with_color(ColorStyle::front(PaletteColor::Primary), |p| {
+    ///         p.print((0,0), "Blended");
+    ///     });
+    /// });
+    /// ```
     pub fn with_color<F>(&self, c: ColorStyle, f: F)
     where
         F: FnOnce(&Printer),
     {
-        let old = self.backend.set_color(c.resolve(&self.theme.palette));
-        f(self);
-        self.backend.set_color(old);
+        let parent = self.current_color.get();
+        let color = c.resolve_with_parent(&self.theme.palette, parent);
+        self.current_color.set(color);
+        if self.monochrome {
+            let old = self.backend.set_color(ColorPair {
+                front: Color::TerminalDefault,
+                back: Color::TerminalDefault,
+            });
+            self.with_effects(c.monochrome_effects(), f);
+            self.backend.set_color(old);
+        } else {
+            let old = self.backend.set_color(color);
+            f(self);
+            self.backend.set_color(old);
+        }
+        self.current_color.set(parent);
+    }
+
+    /// Returns the colors currently used by this printer.
+    pub fn current_color(&self) -> ColorPair {
+        self.current_color.get()
     }
 
     /// Call the given closure with a styled printer,
@@ -283,6 +452,12 @@ impl<'a, 'b> Printer<'a, 'b> {
 
         let color = style.color;
         let effects = style.effects;
+        let link = style.link;
+
+        let f = |printer: &Printer| match link {
+            Some(link) => printer.with_link(link, f),
+            None => f(printer),
+        };
 
         if let Some(color) = color {
             self.with_color(color, |printer| {
@@ -293,6 +468,22 @@ impl<'a, 'b> Printer<'a, 'b> {
         }
     }
 
+    /// Call the given closure with a modified printer
+    /// that will turn printed text into a hyperlink.
+    ///
+    /// Not all backends support hyperlinks. Once `f` returns, the
+    /// previous link, if any, is active again.
+    pub fn with_link<F>(&self, link: Link, f: F)
+    where
+        F: FnOnce(&Printer),
+    {
+        self.backend.set_link(Some(link.url()));
+        let parent = self.current_link.replace(Some(link));
+        f(self);
+        self.backend.set_link(parent.as_ref().map(Link::url));
+        *self.current_link.borrow_mut() = parent;
+    }
+
     /// Call the given closure with a modified printer
     /// that will apply the given effect on prints.
     pub fn with_effect<F>(&self, effect: Effect, f: F)
@@ -368,21 +559,39 @@ impl<'a, 'b> Printer<'a, 'b> {
         }
         let size = size - (1, 1);
 
+        let chars = self.border_chars();
+
         self.with_high_border(invert, |s| {
-            s.print(start, "┌");
-            s.print(start + size.keep_y(), "└");
-            s.print_hline(start + (1, 0), size.x - 1, "─");
-            s.print_vline(start + (0, 1), size.y - 1, "│");
+            s.print(start, chars.top_left);
+            s.print(start + size.keep_y(), chars.bottom_left);
+            s.print_hline(start + (1, 0), size.x - 1, chars.horizontal);
+            s.print_vline(start + (0, 1), size.y - 1, chars.vertical);
         });
 
         self.with_low_border(invert, |s| {
-            s.print(start + size.keep_x(), "┐");
-            s.print(start + size, "┘");
-            s.print_hline(start + (1, 0) + size.keep_y(), size.x - 1, "─");
-            s.print_vline(start + (0, 1) + size.keep_x(), size.y - 1, "│");
+            s.print(start + size.keep_x(), chars.top_right);
+            s.print(start + size, chars.bottom_right);
+            s.print_hline(
+                start + (1, 0) + size.keep_y(),
+                size.x - 1,
+                chars.horizontal,
+            );
+            s.print_vline(
+                start + (0, 1) + size.keep_x(),
+                size.y - 1,
+                chars.vertical,
+            );
         });
     }
 
+    /// Returns the characters used to draw borders with the current theme.
+    ///
+    /// Views drawing their own lines should use them, to match the boxes
+    /// drawn by `print_box`.
+    pub fn border_chars(&self) -> BorderChars {
+        self.theme.borders.chars()
+    }
+
     /// Runs the given function using a color depending on the theme.
     ///
     /// * If the theme's borders is `None`, return without calling `f`.
@@ -444,20 +653,47 @@ impl<'a, 'b> Printer<'a, 'b> {
     }
 
     /// Prints a horizontal delimiter with side border `├` and `┤`.
+    ///
+    /// The delimiter joins the left and right sides of a box drawn with
+    /// `print_box`, using the characters of the current theme.
     pub fn print_hdelim<T>(&self, start: T, len: usize)
     where
         T: Into<Vec2>,
     {
         let start = start.into();
-        self.print(start, "├");
-        self.print_hline(start + (1, 0), len.saturating_sub(2), "─");
-        self.print(start + (len.saturating_sub(1), 0), "┤");
+        let chars = self.border_chars();
+        self.print(start, chars.left_tee);
+        self.print_hline(
+            start + (1, 0),
+            len.saturating_sub(2),
+            chars.horizontal,
+        );
+        self.print(start + (len.saturating_sub(1), 0), chars.right_tee);
+    }
+
+    /// Prints a vertical delimiter with ends `┬` and `┴`.
+    ///
+    /// The delimiter joins the top and bottom sides of a box drawn with
+    /// `print_box`, using the characters of the current theme.
+    pub fn print_vdelim<T>(&self, start: T, len: usize)
+    where
+        T: Into<Vec2>,
+    {
+        let start = start.into();
+        let chars = self.border_chars();
+        self.print(start, chars.top_tee);
+        self.print_vline(
+            start + (0, 1),
+            len.saturating_sub(2),
+            chars.vertical,
+        );
+        self.print(start + (0, len.saturating_sub(1)), chars.bottom_tee);
     }
 
     /// Returns a sub-printer with the given offset.
     ///
     /// It will print in an area slightly to the bottom/right.
-    pub fn offset<S>(&self, offset: S) -> Printer
+    pub fn offset<S>(&self, offset: S) -> Self
     where
         S: Into<Vec2>,
     {
@@ -502,17 +738,30 @@ impl<'a, 'b> Printer<'a, 'b> {
     /// The new printer size will be the minimum of `size` and its current size.
     ///
     /// Any size reduction happens at the bottom-right.
+    ///
+    /// `size` is measured from the start of the content, so with a content
+    /// offset, less of it remains visible.
     pub fn cropped<S>(&self, size: S) -> Self
     where
         S: Into<Vec2>,
     {
         self.clone().with(|s| {
             let size = size.into();
-            s.output_size = Vec2::min(s.output_size, size);
+            s.output_size = Vec2::min(
+                s.output_size,
+                size.saturating_sub(s.content_offset),
+            );
             s.size = Vec2::min(s.size, size);
         })
     }
 
+    /// Returns a new sub-printer restricted to the given area.
+    ///
+    /// This is `self.offset(area.top_left()).cropped(area.size())`.
+    pub fn windowed(&self, area: Rect) -> Self {
+        self.offset(area.top_left()).cropped(area.size())
+    }
+
     /// Returns a new sub-printer with a shrinked area.
     ///
     /// The printer size will be reduced by the given border from the bottom-right.
@@ -548,3 +797,232 @@ impl<'a, 'b> Printer<'a, 'b> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend;
+    use std::cell::RefCell;
+    use theme;
+
+    /// Backend recording every `print_at`, `set_effect` and `set_link`
+    /// call.
+    #[derive(Default)]
+    struct Recorder {
+        prints: RefCell<Vec<(Vec2, String)>>,
+        effects: RefCell<Vec<theme::Effect>>,
+        links: RefCell<Vec<Option<String>>>,
+    }
+
+    impl backend::Backend for Recorder {
+        fn finish(&mut self) {}
+        fn refresh(&mut self) {}
+        fn has_colors(&self) -> bool {
+            false
+        }
+        fn screen_size(&self) -> Vec2 {
+            Vec2::new(10, 1)
+        }
+        fn print_at(&self, pos: Vec2, text: &str) {
+            self.prints.borrow_mut().push((pos, text.to_string()));
+        }
+        fn clear(&self, _: theme::Color) {}
+        fn set_color(&self, colors: theme::ColorPair) -> theme::ColorPair {
+            colors
+        }
+        fn set_effect(&self, effect: theme::Effect) {
+            self.effects.borrow_mut().push(effect);
+        }
+        fn unset_effect(&self, _: theme::Effect) {}
+        fn set_link(&self, url: Option<&str>) {
+            self.links.borrow_mut().push(url.map(String::from));
+        }
+    }
+
+    fn prints(f: impl FnOnce(&Printer)) -> Vec<(Vec2, String)> {
+        let backend = Recorder::default();
+        let theme = Theme::default();
+        f(&Printer::new((10, 1), &theme, &backend));
+        backend.prints.into_inner()
+    }
+
+    #[test]
+    fn nested_links() {
+        let backend = Recorder::default();
+        let theme = Theme::default();
+        let printer = Printer::new((10, 1), &theme, &backend);
+
+        printer.with_link(Link::new("a"), |printer| {
+            printer.with_link(Link::new("b"), |_| ());
+        });
+
+        let url = |url: &str| Some(url.to_string());
+        assert_eq!(
+            backend.links.into_inner(),
+            vec![url("a"), url("b"), url("a"), None]
+        );
+    }
+
+    #[test]
+    fn wide_char_cut_left() {
+        let result = prints(|printer| {
+            let printer = printer.content_offset((1, 0));
+            printer.print((0, 0), "漢字");
+        });
+        // The right half of `漢` is replaced with a space.
+        assert_eq!(
+            result,
+            vec![
+                (Vec2::new(0, 0), " ".to_string()),
+                (Vec2::new(1, 0), "字".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_char_cut_right() {
+        let result = prints(|printer| {
+            let printer = printer.cropped((3, 1));
+            printer.print((0, 0), "漢字");
+        });
+        // The left half of `字` is replaced with a space.
+        assert_eq!(
+            result,
+            vec![
+                (Vec2::new(0, 0), "漢".to_string()),
+                (Vec2::new(2, 0), " ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn narrow_text_not_padded() {
+        let result = prints(|printer| {
+            let printer = printer.cropped((3, 1));
+            printer.print((0, 0), "abcd");
+        });
+        assert_eq!(result, vec![(Vec2::new(0, 0), "abc".to_string())]);
+    }
+
+    #[test]
+    fn inherit_parent_color() {
+        let backend = Recorder::default();
+        let theme = Theme::default();
+        let printer = Printer::new((10, 1), &theme, &backend);
+        let palette = &theme.palette;
+
+        printer.with_color(ColorStyle::highlight(), |printer| {
+            let front = ColorStyle::front(PaletteColor::Secondary);
+            printer.with_color(front, |printer| {
+                let color = printer.current_color();
+                assert_eq!(color.front, palette[PaletteColor::Secondary]);
+                assert_eq!(color.back, palette[PaletteColor::Highlight]);
+
+                // Derived printers keep the colors.
+                let printer = printer.offset((1, 0));
+                printer.with_color(ColorStyle::inherit_parent(), |p| {
+                    assert_eq!(p.current_color(), color);
+                });
+            });
+            let color = printer.current_color();
+            assert_eq!(color.back, palette[PaletteColor::Highlight]);
+            assert_eq!(color.front, palette[PaletteColor::View]);
+        });
+    }
+
+    #[test]
+    fn monochrome() {
+        let backend = Recorder::default();
+        let theme = Theme::default();
+        let mut printer = Printer::new((10, 1), &theme, &backend);
+        printer.monochrome = true;
+
+        printer.with_color(ColorStyle::highlight(), |printer| {
+            // The colors are still tracked for the views.
+            let color = printer.current_color();
+            assert_eq!(color.back, theme.palette[PaletteColor::Highlight]);
+        });
+        printer.with_color(ColorStyle::title_primary(), |_| ());
+        printer.with_color(ColorStyle::primary(), |_| ());
+        assert_eq!(
+            *backend.effects.borrow(),
+            vec![Effect::Reverse, Effect::Bold]
+        );
+    }
+
+    #[test]
+    fn box_with_delimiters() {
+        let mut theme = Theme::default();
+        let buffer = PrintBuffer::new((5, 4));
+        let text = |buffer: &PrintBuffer| -> Vec<String> {
+            (0..4).map(|y| buffer.text(y, 0, 5)).collect()
+        };
+
+        theme.borders = BorderStyle::Rounded;
+        let printer = buffer.printer(&theme);
+        printer.print_box((0, 0), (5, 4), false);
+        printer.print_hdelim((0, 2), 5);
+        printer.print_vdelim((2, 0), 3);
+        printer.print((2, 2), printer.border_chars().cross);
+        assert_eq!(
+            text(&buffer),
+            vec!["╭─┬─╮", "│ │ │", "├─┼─┤", "╰───╯"]
+        );
+
+        buffer.reset();
+        theme.borders = BorderStyle::Ascii;
+        let printer = buffer.printer(&theme);
+        printer.print_box((0, 0), (5, 4), false);
+        printer.print_hdelim((0, 2), 5);
+        assert_eq!(text(&buffer), vec!["+---+", "|   |", "+---+", "+---+"]);
+    }
+
+    #[test]
+    fn cropped_with_content_offset() {
+        let result = prints(|printer| {
+            // Only `c` is left before the crop.
+            let printer = printer.content_offset((2, 0)).cropped((3, 1));
+            printer.print((0, 0), "abcdef");
+        });
+        assert_eq!(result, vec![(Vec2::new(0, 0), "c".to_string())]);
+    }
+
+    #[test]
+    fn composition_stays_inside() {
+        let buffer = PrintBuffer::new((10, 4));
+        let theme = Theme::default();
+        let root = buffer.printer(&theme);
+
+        // However sub-printers are composed, nothing gets out of `window`.
+        let window = root.windowed(Rect::from_size((2, 1), (4, 2)));
+        let printers = vec![
+            window.clone(),
+            window.offset((1, 1)),
+            window.cropped((3, 1)).offset((1, 0)),
+            window.shrinked((1, 1)).content_offset((1, 1)),
+            window.content_offset((0, 1)).cropped((10, 10)),
+            window.inner_size((20, 20)).offset((3, 0)),
+        ];
+        for printer in &printers {
+            for y in 0..5 {
+                printer.print((0, y), "xxxxxxxxxx");
+                printer.print_hline((0, y), 10, "x");
+            }
+            printer.print_vline((1, 0), 10, "x");
+            printer.print_box((0, 0), (10, 10), false);
+        }
+
+        for y in 0..4 {
+            let inside = 1 <= y && y < 3;
+            assert_eq!(buffer.text(y, 0, 2).trim(), "");
+            assert_eq!(buffer.text(y, 6, 10).trim(), "");
+            assert_eq!(buffer.text(y, 2, 6).trim().is_empty(), !inside);
+        }
+    }
+
+    #[test]
+    fn zero_width_line() {
+        let result = prints(|printer| printer.print_hline((0, 0), 5, ""));
+        assert!(result.is_empty());
+    }
+}
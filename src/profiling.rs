@@ -0,0 +1,81 @@
+//! Timing statistics, to diagnose slow views.
+//!
+//! [`Cursive::last_frame_stats`] returns how long the last frame took to
+//! lay out and to draw. Views wrapped in a [`ProfiledView`] also report
+//! their own timings.
+//!
+//! Requires the `profiling` feature.
+//!
+//! [`Cursive::last_frame_stats`]: ../struct.Cursive.html#method.last_frame_stats
+//! [`ProfiledView`]: ../views/struct.ProfiledView.html
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// Timings of a single frame.
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    /// Time spent laying out the views.
+    pub layout: Duration,
+
+    /// Time spent drawing the views.
+    pub draw: Duration,
+
+    /// Timings reported by each `ProfiledView`, in the order they were
+    /// first laid out.
+    pub views: Vec<ViewStats>,
+}
+
+/// Timings of the views with a given name, during a single frame.
+///
+/// Several views with the same name are added up.
+#[derive(Clone, Debug, Default)]
+pub struct ViewStats {
+    /// Name of the view.
+    pub name: String,
+
+    /// Time spent in `required_size` and `layout`.
+    pub layout: Duration,
+
+    /// Time spent in `draw`.
+    pub draw: Duration,
+}
+
+impl ViewStats {
+    /// Returns the total time spent on these views.
+    pub fn total(&self) -> Duration {
+        self.layout + self.draw
+    }
+}
+
+thread_local! {
+    // Timings recorded since the beginning of the current frame.
+    static VIEWS: RefCell<Vec<ViewStats>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Adds some time to the stats of the given view.
+pub(crate) fn record<F>(name: &str, f: F)
+where
+    F: FnOnce(&mut ViewStats),
+{
+    VIEWS.with(|views| {
+        let mut views = views.borrow_mut();
+        match views.iter().position(|stats| stats.name == name) {
+            Some(i) => f(&mut views[i]),
+            None => {
+                let mut stats = ViewStats {
+                    name: name.to_string(),
+                    ..ViewStats::default()
+                };
+                f(&mut stats);
+                views.push(stats);
+            }
+        }
+    });
+}
+
+/// Returns the timings recorded since the last call.
+pub(crate) fn take_views() -> Vec<ViewStats> {
+    VIEWS.with(|views| views.replace(Vec::new()))
+}
@@ -0,0 +1,384 @@
+//! Recording and replay of events, used by `Cursive::record_events`.
+//!
+//! Events are stored one per line, after the number of milliseconds since
+//! the recording started:
+//!
+//! ```text
+//! 0 char h
+//! 120 ctrl-char x
+//! 350 key Enter
+//! 400 alt-shift Left
+//! 800 mouse press Left 12 4 0 0
+//! 950 paste first line\nsecond line
+//! ```
+//!
+//! Empty lines and lines starting with `#` are ignored.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use event::{Event, Key, MouseButton, MouseEvent};
+use vec::Vec2;
+
+/// Every key, to parse them back from their name.
+const KEYS: [Key; 29] = [
+    Key::Enter,
+    Key::Tab,
+    Key::Backspace,
+    Key::Esc,
+    Key::Left,
+    Key::Right,
+    Key::Up,
+    Key::Down,
+    Key::Ins,
+    Key::Del,
+    Key::Home,
+    Key::End,
+    Key::PageUp,
+    Key::PageDown,
+    Key::PauseBreak,
+    Key::NumpadCenter,
+    Key::F0,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+];
+
+const BUTTONS: [MouseButton; 6] = [
+    MouseButton::Left,
+    MouseButton::Middle,
+    MouseButton::Right,
+    MouseButton::Button4,
+    MouseButton::Button5,
+    MouseButton::Other,
+];
+
+/// Writes events to a file as they are received.
+pub struct Recorder {
+    file: File,
+    start: Instant,
+}
+
+impl Recorder {
+    /// Creates (or truncates) the file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Recorder {
+            file: File::create(path)?,
+            start: Instant::now(),
+        })
+    }
+
+    /// Appends an event to the file.
+    ///
+    /// Each event is written right away, so a recording is complete even
+    /// if the application crashes.
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        let millis = self.start.elapsed().as_millis();
+        writeln!(self.file, "{} {}", millis, format_event(event))
+    }
+}
+
+/// Reads the events recorded in the file at `path`.
+///
+/// Returns each event with its delay since the start of the recording.
+pub fn read_events<P: AsRef<Path>>(
+    path: P,
+) -> io::Result<Vec<(Duration, Event)>> {
+    let file = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+
+    for (i, line) in file.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let event = parse_line(&line).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid event on line {}: {}", i + 1, line),
+            )
+        })?;
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+fn parse_line(line: &str) -> Option<(Duration, Event)> {
+    let (millis, event) = split_word(line);
+    let millis = millis.parse().ok()?;
+    Some((Duration::from_millis(millis), parse_event(event)?))
+}
+
+/// Returns the text representation of an event.
+pub fn format_event(event: &Event) -> String {
+    match *event {
+        Event::WindowResize => "resize".to_string(),
+        Event::Refresh => "refresh".to_string(),
+        Event::Shutdown => "shutdown".to_string(),
+        Event::Exit => "exit".to_string(),
+        Event::Char(c) => format!("char {}", escape(&c.to_string())),
+        Event::CtrlChar(c) => format!("ctrl-char {}", escape(&c.to_string())),
+        Event::AltChar(c) => format!("alt-char {}", escape(&c.to_string())),
+        Event::Key(key) => format!("key {:?}", key),
+        Event::Shift(key) => format!("shift {:?}", key),
+        Event::Alt(key) => format!("alt {:?}", key),
+        Event::AltShift(key) => format!("alt-shift {:?}", key),
+        Event::Ctrl(key) => format!("ctrl {:?}", key),
+        Event::CtrlShift(key) => format!("ctrl-shift {:?}", key),
+        Event::CtrlAlt(key) => format!("ctrl-alt {:?}", key),
+        Event::Mouse {
+            offset,
+            position,
+            event,
+        } => {
+            let event = match event {
+                MouseEvent::Press(button) => format!("press {:?}", button),
+                MouseEvent::MultiPress(button, count) => {
+                    format!("multi-press {:?} {}", button, count)
+                }
+                MouseEvent::Release(button) => {
+                    format!("release {:?}", button)
+                }
+                MouseEvent::Hold(button) => format!("hold {:?}", button),
+                MouseEvent::WheelUp => "wheel-up".to_string(),
+                MouseEvent::WheelDown => "wheel-down".to_string(),
+            };
+            format!(
+                "mouse {} {} {} {} {}",
+                event, position.x, position.y, offset.x, offset.y
+            )
+        }
+        Event::Paste(ref text) => format!("paste {}", escape(text)),
+        Event::Released(ref event) => {
+            format!("released {}", format_event(event))
+        }
+        Event::Unknown(ref bytes) => {
+            let bytes: Vec<_> =
+                bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("unknown {}", bytes.join(" "))
+        }
+    }
+}
+
+/// Parses a key name, as written by `format_event`.
+pub fn parse_key(name: &str) -> Option<Key> {
+    KEYS.iter().cloned().find(|key| format!("{:?}", key) == name)
+}
+
+/// Parses an event written by `format_event`.
+pub fn parse_event(text: &str) -> Option<Event> {
+    let (kind, rest) = split_word(text);
+    let key = || parse_key(rest);
+    let single_char = || {
+        let text = unescape(rest)?;
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+
+    Some(match kind {
+        "resize" => Event::WindowResize,
+        "refresh" => Event::Refresh,
+        "shutdown" => Event::Shutdown,
+        "exit" => Event::Exit,
+        "char" => Event::Char(single_char()?),
+        "ctrl-char" => Event::CtrlChar(single_char()?),
+        "alt-char" => Event::AltChar(single_char()?),
+        "key" => Event::Key(key()?),
+        "shift" => Event::Shift(key()?),
+        "alt" => Event::Alt(key()?),
+        "alt-shift" => Event::AltShift(key()?),
+        "ctrl" => Event::Ctrl(key()?),
+        "ctrl-shift" => Event::CtrlShift(key()?),
+        "ctrl-alt" => Event::CtrlAlt(key()?),
+        "mouse" => parse_mouse(rest)?,
+        "paste" => Event::Paste(unescape(rest)?),
+        "released" => Event::Released(Box::new(parse_event(rest)?)),
+        "unknown" => Event::Unknown(
+            rest.split_whitespace()
+                .map(|b| u8::from_str_radix(b, 16).ok())
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+fn parse_mouse(text: &str) -> Option<Event> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let button = || {
+        let name = words.get(1)?;
+        BUTTONS
+            .iter()
+            .cloned()
+            .find(|b| format!("{:?}", b) == *name)
+    };
+
+    let event = match *words.first()? {
+        "press" => MouseEvent::Press(button()?),
+        "multi-press" => {
+            MouseEvent::MultiPress(button()?, words.get(2)?.parse().ok()?)
+        }
+        "release" => MouseEvent::Release(button()?),
+        "hold" => MouseEvent::Hold(button()?),
+        "wheel-up" => MouseEvent::WheelUp,
+        "wheel-down" => MouseEvent::WheelDown,
+        _ => return None,
+    };
+
+    // Skip the kind, the button and the count if any.
+    let skip = match event {
+        MouseEvent::MultiPress(..) => 3,
+        _ if event.button().is_some() => 2,
+        _ => 1,
+    };
+    let numbers = words[skip..]
+        .iter()
+        .map(|n| n.parse().ok())
+        .collect::<Option<Vec<usize>>>()?;
+    if numbers.len() != 4 {
+        return None;
+    }
+
+    Some(Event::Mouse {
+        position: Vec2::new(numbers[0], numbers[1]),
+        offset: Vec2::new(numbers[2], numbers[3]),
+        event,
+    })
+}
+
+/// Splits the first word from the rest of `text`.
+fn split_word(text: &str) -> (&str, &str) {
+    match text.find(' ') {
+        Some(i) => (&text[..i], &text[i + 1..]),
+        None => (text, ""),
+    }
+}
+
+/// Escapes backslashes and control characters, to keep text on one line.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => {
+                result.push_str(&format!("\\u{{{:x}}}", c as u32))
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Reverts `escape`.
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => result.push('\\'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            't' => result.push('\t'),
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let code: String =
+                    chars.by_ref().take_while(|&c| c != '}').collect();
+                let code = u32::from_str_radix(&code, 16).ok()?;
+                result.push(::std::char::from_u32(code)?);
+            }
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let events = vec![
+            Event::WindowResize,
+            Event::Char(' '),
+            Event::Char('\\'),
+            Event::CtrlChar('x'),
+            Event::AltChar('é'),
+            Event::Key(Key::PageUp),
+            Event::CtrlShift(Key::F10),
+            Event::Mouse {
+                offset: Vec2::new(1, 2),
+                position: Vec2::new(12, 4),
+                event: MouseEvent::Press(MouseButton::Left),
+            },
+            Event::Mouse {
+                offset: Vec2::new(1, 2),
+                position: Vec2::new(12, 4),
+                event: MouseEvent::MultiPress(MouseButton::Left, 2),
+            },
+            Event::Mouse {
+                offset: Vec2::zero(),
+                position: Vec2::new(3, 3),
+                event: MouseEvent::WheelDown,
+            },
+            Event::Paste("a\tb\nc\u{7}".to_string()),
+            Event::Released(Box::new(Event::CtrlChar('i'))),
+            Event::Unknown(vec![0x1b, 0x5b]),
+            Event::Exit,
+        ];
+
+        for event in events {
+            let text = format_event(&event);
+            assert!(!text.contains('\n'));
+            assert_eq!(parse_event(&text), Some(event), "{}", text);
+        }
+
+        assert_eq!(parse_event("key Nope"), None);
+        assert_eq!(parse_event("char ab"), None);
+        assert_eq!(parse_event("mouse press Left 1 2"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn key_map() {
+        use std::collections::HashMap;
+        use toml;
+
+        let mut keys = HashMap::new();
+        keys.insert(Event::CtrlChar('s'), "save".to_string());
+        keys.insert(Event::AltShift(Key::Left), "back".to_string());
+
+        let text = toml::to_string(&keys).unwrap();
+        assert!(text.contains("\"ctrl-char s\" = \"save\""), "{}", text);
+        let parsed: HashMap<Event, String> = toml::from_str(&text).unwrap();
+        assert_eq!(parsed, keys);
+
+        let key: HashMap<String, Key> = toml::from_str("a = \"F5\"").unwrap();
+        assert_eq!(key["a"], Key::F5);
+    }
+}
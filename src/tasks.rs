@@ -0,0 +1,352 @@
+//! Run work on a background thread, and get the result in the event loop.
+//!
+//! A [`Task`] runs a closure on a worker thread. The closure receives a
+//! [`TaskContext`] to report its progress, check for cancellation, or run
+//! code on the UI thread. Once it returns, its result is given to a callback
+//! running in the event loop, with a full access to the [`Cursive`] root.
+//!
+//! Panics in the closure are caught, and reported as a [`TaskError`]. They
+//! are logged with `warn!` rather than going through the panic hook, which
+//! would print over the UI. This relies on a hook installed on the first
+//! spawn, wrapping the one set at that time: setting another hook later
+//! disables it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use cursive::Cursive;
+//! # use cursive::tasks::Task;
+//! # use cursive::views::Dialog;
+//! let mut siv = Cursive::dummy();
+//! siv.set_fps(10);
+//!
+//! Task::new(|ctx| {
+//!     let mut lines = 0;
+//!     for i in 0..100 {
+//!         if ctx.is_cancelled() {
+//!             break;
+//!         }
+//!         lines += i;
+//!         ctx.set_progress(i + 1);
+//!     }
+//!     lines
+//! })
+//! .with_progress("Counting lines")
+//! .cancellable()
+//! .spawn(&mut siv, |s, result| match result {
+//!     Ok(lines) => s.add_layer(Dialog::info(format!("{} lines", lines))),
+//!     Err(err) => s.add_layer(Dialog::info(format!("{:?}", err))),
+//! });
+//! ```
+//!
+//! [`Task`]: struct.Task.html
+//! [`TaskContext`]: struct.TaskContext.html
+//! [`TaskError`]: enum.TaskError.html
+//! [`Cursive`]: ../struct.Cursive.html
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
+use std::thread;
+
+use crossbeam_channel::Sender;
+
+use utils::Counter;
+use views::{unique_id, Dialog, IdView};
+use {CbFunc, Cursive};
+
+/// Guards the installation of the task panic hook.
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    /// Set on task threads, so their panics skip the previous hook.
+    static IN_TASK: Cell<bool> = Cell::new(false);
+}
+
+/// Reason why a task didn't produce a result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskError {
+    /// The task was cancelled, and its result discarded.
+    Cancelled,
+    /// The task panicked, with the given message.
+    Panicked(String),
+}
+
+/// Given to the closure of a task, to communicate with the application.
+pub struct TaskContext {
+    counter: Counter,
+    cancelled: Arc<AtomicBool>,
+    cb_sink: Sender<Box<CbFunc>>,
+}
+
+impl TaskContext {
+    /// Sets the progress of the task, from 0 to 100.
+    ///
+    /// The progress layer closes itself once it reaches 100.
+    pub fn set_progress(&self, value: usize) {
+        self.counter.set(value);
+    }
+
+    /// Advances the progress of the task by `ticks`.
+    pub fn tick(&self, ticks: usize) {
+        self.counter.tick(ticks);
+    }
+
+    /// Returns `true` if the task was cancelled.
+    ///
+    /// The task should check this regularly, and return early when it is
+    /// set.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Runs `cb` in the event loop, for instance to show partial results.
+    pub fn post<F>(&self, cb: F)
+    where
+        F: FnOnce(&mut Cursive) + Send + 'static,
+    {
+        // Cursive may be gone already.
+        let _ = self.cb_sink.send(Box::new(cb));
+    }
+}
+
+/// Handle to a running task.
+#[derive(Clone)]
+pub struct TaskHandle {
+    counter: Counter,
+    cancelled: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    /// Asks the task to stop.
+    ///
+    /// The task only stops when it checks [`TaskContext::is_cancelled`].
+    /// Its result is then discarded.
+    ///
+    /// [`TaskContext::is_cancelled`]: struct.TaskContext.html#method.is_cancelled
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the task was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` once the task returned or panicked.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    /// Returns the last progress reported by the task.
+    pub fn progress(&self) -> usize {
+        self.counter.get()
+    }
+}
+
+/// Work to run on a background thread.
+///
+/// See the [module documentation](index.html).
+pub struct Task<F> {
+    work: F,
+    title: Option<String>,
+    cancellable: bool,
+}
+
+impl<F> Task<F> {
+    /// Creates a new task running `work`.
+    pub fn new<T>(work: F) -> Self
+    where
+        F: FnOnce(&TaskContext) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        Task {
+            work,
+            title: None,
+            cancellable: false,
+        }
+    }
+
+    /// Shows a progress dialog with the given title while the task runs.
+    ///
+    /// The dialog is closed when the task ends. Use [`Cursive::set_fps`] so
+    /// the progress is redrawn without user input.
+    ///
+    /// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+    pub fn with_progress<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Adds a `Cancel` button to the progress dialog.
+    ///
+    /// Has no effect without [`with_progress`].
+    ///
+    /// [`with_progress`]: #method.with_progress
+    pub fn cancellable(mut self) -> Self {
+        self.cancellable = true;
+        self
+    }
+
+    /// Starts the task on a new thread.
+    ///
+    /// `on_done` runs in the event loop once the task returns, with its
+    /// result. It also runs if the task panicked or was cancelled.
+    pub fn spawn<T, D>(self, siv: &mut Cursive, on_done: D) -> TaskHandle
+    where
+        F: FnOnce(&TaskContext) -> T + Send + 'static,
+        T: Send + 'static,
+        D: FnOnce(&mut Cursive, Result<T, TaskError>) + Send + 'static,
+    {
+        let handle = TaskHandle {
+            counter: Counter::new(0),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            finished: Arc::new(AtomicBool::new(false)),
+        };
+        let cb_sink = siv.cb_sink().clone();
+        let Task {
+            work,
+            title,
+            cancellable,
+        } = self;
+
+        let layer_id = title.map(|title| {
+            let id = unique_id("task");
+            let counter = handle.counter.clone();
+            let mut dialog = Dialog::progress(title, counter);
+            if cancellable {
+                let cancelled = Arc::clone(&handle.cancelled);
+                dialog = dialog.cancel_button("Cancel", cancelled);
+            }
+            siv.add_layer(IdView::new(id.clone(), dialog));
+            id
+        });
+
+        let context = TaskContext {
+            counter: handle.counter.clone(),
+            cancelled: Arc::clone(&handle.cancelled),
+            cb_sink,
+        };
+        let finished = Arc::clone(&handle.finished);
+
+        install_panic_hook();
+        thread::spawn(move || {
+            IN_TASK.with(|in_task| in_task.set(true));
+            let result =
+                panic::catch_unwind(AssertUnwindSafe(|| work(&context)))
+                    .map_err(|e| TaskError::Panicked(panic_message(&e)));
+            let result = match result {
+                Ok(_) if context.is_cancelled() => Err(TaskError::Cancelled),
+                result => result,
+            };
+            finished.store(true, Ordering::SeqCst);
+
+            context.post(move |s| {
+                // The progress layer may be closed already.
+                let layer = layer_id
+                    .and_then(|id| s.screen_mut().find_layer_from_id(&id));
+                if let Some(position) = layer {
+                    s.screen_mut().remove_layer(position);
+                }
+                on_done(s, result);
+            });
+        });
+
+        handle
+    }
+}
+
+/// Logs panics from task threads instead of forwarding them to the hook.
+///
+/// They are caught and reported to `on_done` anyway.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if IN_TASK.with(Cell::get) {
+                warn!("Task panicked: {}", info);
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Returns the message given to `panic!`, if possible.
+fn panic_message(payload: &Box<Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// Waits for the callback sent by the task, and runs it.
+    fn wait_done(siv: &mut Cursive) {
+        let (_, cb_source) = siv.receivers();
+        let cb = cb_source.recv_timeout(Duration::from_secs(5)).unwrap();
+        cb.call_box(siv);
+    }
+
+    #[test]
+    fn result() {
+        let mut siv = Cursive::dummy();
+        let (sender, receiver) = mpsc::channel();
+        let handle = Task::new(|ctx| {
+            ctx.set_progress(50);
+            42
+        }).with_progress("Test")
+        .spawn(&mut siv, move |_, result| sender.send(result).unwrap());
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+
+        wait_done(&mut siv);
+        assert_eq!(receiver.try_recv(), Ok(Ok(42)));
+        assert!(handle.is_finished());
+        assert_eq!(handle.progress(), 50);
+        // The progress layer is gone.
+        assert_eq!(siv.screen().layer_sizes().len(), 0);
+    }
+
+    #[test]
+    fn panic() {
+        let mut siv = Cursive::dummy();
+        let (sender, receiver) = mpsc::channel();
+        Task::new(|_| -> usize { panic!("Oops") })
+            .spawn(&mut siv, move |_, result| sender.send(result).unwrap());
+
+        wait_done(&mut siv);
+        assert_eq!(
+            receiver.try_recv(),
+            Ok(Err(TaskError::Panicked("Oops".to_string())))
+        );
+    }
+
+    #[test]
+    fn cancel() {
+        let mut siv = Cursive::dummy();
+        let (sender, receiver) = mpsc::channel();
+        let (start, started) = mpsc::channel();
+        let handle = Task::new(move |ctx| {
+            start.send(()).unwrap();
+            while !ctx.is_cancelled() {
+                thread::yield_now();
+            }
+        }).spawn(&mut siv, move |_, result| sender.send(result).unwrap());
+
+        started.recv().unwrap();
+        handle.cancel();
+        wait_done(&mut siv);
+        assert_eq!(receiver.try_recv(), Ok(Err(TaskError::Cancelled)));
+    }
+}
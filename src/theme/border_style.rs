@@ -9,18 +9,128 @@ pub enum BorderStyle {
     Simple,
     /// Outset borders with a simple 3d effect.
     Outset,
+    /// Simple borders with rounded corners.
+    Rounded,
+    /// Double-line borders.
+    Double,
+    /// Borders using only ASCII characters, for terminals without unicode.
+    Ascii,
     /// No borders.
     None,
 }
 
+impl BorderStyle {
+    /// Returns the characters used to draw lines with this style.
+    ///
+    /// `None` still returns simple characters, for separators drawn
+    /// inside views.
+    pub fn chars(self) -> BorderChars {
+        match self {
+            BorderStyle::Simple | BorderStyle::Outset | BorderStyle::None => {
+                BorderChars::SIMPLE
+            }
+            BorderStyle::Rounded => BorderChars::ROUNDED,
+            BorderStyle::Double => BorderChars::DOUBLE,
+            BorderStyle::Ascii => BorderChars::ASCII,
+        }
+    }
+}
+
 impl<S: Deref<Target = String>> From<S> for BorderStyle {
     fn from(s: S) -> Self {
-        if &*s == "simple" {
-            BorderStyle::Simple
-        } else if &*s == "outset" {
-            BorderStyle::Outset
-        } else {
-            BorderStyle::None
+        match &**s {
+            "simple" => BorderStyle::Simple,
+            "outset" => BorderStyle::Outset,
+            "rounded" => BorderStyle::Rounded,
+            "double" => BorderStyle::Double,
+            "ascii" => BorderStyle::Ascii,
+            _ => BorderStyle::None,
         }
     }
 }
+
+/// Characters used to draw lines, boxes and their junctions.
+///
+/// Tees are named after the side of the box they sit on: `left_tee` is
+/// `├`, joining the left border with a horizontal line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BorderChars {
+    /// Horizontal line.
+    pub horizontal: &'static str,
+    /// Vertical line.
+    pub vertical: &'static str,
+    /// Top-left corner.
+    pub top_left: &'static str,
+    /// Top-right corner.
+    pub top_right: &'static str,
+    /// Bottom-left corner.
+    pub bottom_left: &'static str,
+    /// Bottom-right corner.
+    pub bottom_right: &'static str,
+    /// Junction of the left border with a horizontal line.
+    pub left_tee: &'static str,
+    /// Junction of the right border with a horizontal line.
+    pub right_tee: &'static str,
+    /// Junction of the top border with a vertical line.
+    pub top_tee: &'static str,
+    /// Junction of the bottom border with a vertical line.
+    pub bottom_tee: &'static str,
+    /// Crossing of a horizontal and a vertical line.
+    pub cross: &'static str,
+}
+
+impl BorderChars {
+    /// Single lines: `┌─┐`.
+    pub const SIMPLE: BorderChars = BorderChars {
+        horizontal: "─",
+        vertical: "│",
+        top_left: "┌",
+        top_right: "┐",
+        bottom_left: "└",
+        bottom_right: "┘",
+        left_tee: "├",
+        right_tee: "┤",
+        top_tee: "┬",
+        bottom_tee: "┴",
+        cross: "┼",
+    };
+
+    /// Single lines with rounded corners: `╭─╮`.
+    pub const ROUNDED: BorderChars = BorderChars {
+        top_left: "╭",
+        top_right: "╮",
+        bottom_left: "╰",
+        bottom_right: "╯",
+        ..BorderChars::SIMPLE
+    };
+
+    /// Double lines: `╔═╗`.
+    pub const DOUBLE: BorderChars = BorderChars {
+        horizontal: "═",
+        vertical: "║",
+        top_left: "╔",
+        top_right: "╗",
+        bottom_left: "╚",
+        bottom_right: "╝",
+        left_tee: "╠",
+        right_tee: "╣",
+        top_tee: "╦",
+        bottom_tee: "╩",
+        cross: "╬",
+    };
+
+    /// ASCII characters only: `+-+`.
+    pub const ASCII: BorderChars = BorderChars {
+        horizontal: "-",
+        vertical: "|",
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        left_tee: "+",
+        right_tee: "+",
+        top_tee: "+",
+        bottom_tee: "+",
+        cross: "+",
+    };
+}
@@ -114,6 +114,26 @@ impl Color {
         }
     }
 
+    /// Returns `true` if this color is closer to black than to white.
+    ///
+    /// `TerminalDefault` is considered dark, like most terminals.
+    pub fn is_dark(self) -> bool {
+        let (r, g, b) = match self {
+            Color::TerminalDefault => return true,
+            Color::Dark(BaseColor::White) => return false,
+            Color::Dark(_) => return true,
+            Color::Light(BaseColor::Black) => return true,
+            Color::Light(_) => return false,
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::RgbLowRes(r, g, b) => (r * 51, g * 51, b * 51),
+        };
+
+        // Perceived brightness, with the usual luma coefficients.
+        let luma =
+            299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b);
+        luma < 128 * 1000
+    }
+
     /// Parse a string into a color.
     ///
     /// Examples:
@@ -151,7 +171,7 @@ impl Color {
             let (l, multiplier) = match value.len() {
                 6 => (2, 1),
                 3 => (1, 17),
-                _ => panic!("Cannot parse color: {}", value),
+                _ => return None,
             };
             let r = load_hex(&value[0..l]) * multiplier;
             let g = load_hex(&value[l..2 * l]) * multiplier;
@@ -209,4 +229,15 @@ mod tests {
             Color::from_256colors(i as u8);
         }
     }
+
+    #[test]
+    fn test_is_dark() {
+        use super::{BaseColor, Color};
+
+        assert!(Color::Rgb(0x1e, 0x1e, 0x1e).is_dark());
+        assert!(!Color::Rgb(0xfd, 0xf6, 0xe3).is_dark());
+        assert!(Color::Dark(BaseColor::Blue).is_dark());
+        assert!(!Color::Light(BaseColor::White).is_dark());
+        assert!(!Color::RgbLowRes(5, 5, 4).is_dark());
+    }
 }
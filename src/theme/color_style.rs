@@ -1,11 +1,14 @@
-use super::{Color, ColorPair, Palette, PaletteColor};
+use std::borrow::Cow;
+
+use super::{Color, ColorPair, Effect, Palette, PaletteColor};
+use enumset::EnumSet;
 
 /// Possible color style for a cell.
 ///
 /// Represents a color pair role to use when printing something.
 ///
 /// The current theme will assign each role a foreground and background color.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ColorStyle {
     /// Color used for the foreground (the text itself).
     pub front: ColorType,
@@ -66,6 +69,23 @@ impl ColorStyle {
         Self::new(PaletteColor::TitleSecondary, PaletteColor::View)
     }
 
+    /// Uses the given front color, keeping the parent background.
+    ///
+    /// This lets text blend in views nested in differently-colored parents.
+    pub fn front<F: Into<ColorType>>(front: F) -> Self {
+        Self::new(front, ColorType::InheritParent)
+    }
+
+    /// Uses the given background color, keeping the parent front color.
+    pub fn back<B: Into<ColorType>>(back: B) -> Self {
+        Self::new(ColorType::InheritParent, back)
+    }
+
+    /// Keeps both colors of the parent.
+    pub fn inherit_parent() -> Self {
+        Self::new(ColorType::InheritParent, ColorType::InheritParent)
+    }
+
     /// Alternate text with highlight background.
     pub fn highlight() -> Self {
         Self::new(PaletteColor::View, PaletteColor::Highlight)
@@ -76,11 +96,90 @@ impl ColorStyle {
         Self::new(PaletteColor::View, PaletteColor::HighlightInactive)
     }
 
+    /// Returns the effects standing for this style when colors are disabled.
+    ///
+    /// Highlights and inverted styles are reversed, inactive highlights are
+    /// underlined, and titles are bold.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::theme::{ColorStyle, Effect};
+    /// let effects = ColorStyle::highlight().monochrome_effects();
+    /// assert!(effects.contains(Effect::Reverse));
+    /// assert!(ColorStyle::primary().monochrome_effects().is_empty());
+    /// ```
+    pub fn monochrome_effects(&self) -> EnumSet<Effect> {
+        let mut effects = EnumSet::new();
+
+        match self.back {
+            ColorType::Palette(PaletteColor::HighlightInactive) => {
+                effects.insert(Effect::Underline);
+            }
+            ColorType::Palette(PaletteColor::Highlight)
+            | ColorType::Palette(PaletteColor::Primary)
+            | ColorType::Palette(PaletteColor::Secondary)
+            | ColorType::Palette(PaletteColor::Tertiary)
+            | ColorType::Palette(PaletteColor::TitlePrimary)
+            | ColorType::Palette(PaletteColor::TitleSecondary) => {
+                effects.insert(Effect::Reverse);
+            }
+            _ => (),
+        }
+
+        match self.front {
+            ColorType::Palette(PaletteColor::TitlePrimary)
+            | ColorType::Palette(PaletteColor::TitleSecondary)
+            | ColorType::Palette(PaletteColor::Highlight) => {
+                effects.insert(Effect::Bold);
+            }
+            _ => (),
+        }
+
+        effects
+    }
+
+    /// Returns `self` where inherited colors are taken from `parent`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::theme::{ColorStyle, ColorType, PaletteColor};
+    /// let style = ColorStyle::front(PaletteColor::Secondary)
+    ///     .merged_with(ColorStyle::highlight());
+    /// assert_eq!(style.back, ColorType::Palette(PaletteColor::Highlight));
+    /// ```
+    pub fn merged_with(self, parent: ColorStyle) -> Self {
+        Self {
+            front: self.front.merged_with(parent.front),
+            back: self.back.merged_with(parent.back),
+        }
+    }
+
     /// Return the color pair that this style represents.
+    ///
+    /// Inherited colors are taken from the primary and view colors, like
+    /// for views drawn at the root. See [`resolve_with_parent`] to inherit
+    /// from other colors.
+    ///
+    /// [`resolve_with_parent`]: #method.resolve_with_parent
     pub fn resolve(&self, palette: &Palette) -> ColorPair {
+        let parent = ColorPair {
+            front: palette[PaletteColor::Primary],
+            back: palette[PaletteColor::View],
+        };
+        self.resolve_with_parent(palette, parent)
+    }
+
+    /// Return the color pair that this style represents.
+    ///
+    /// Inherited colors are taken from `parent`.
+    pub fn resolve_with_parent(
+        &self, palette: &Palette, parent: ColorPair,
+    ) -> ColorPair {
         ColorPair {
-            front: self.front.resolve(palette),
-            back: self.back.resolve(palette),
+            front: self.front.resolve_with_parent(palette, parent.front),
+            back: self.back.resolve_with_parent(palette, parent.back),
         }
     }
 }
@@ -114,21 +213,53 @@ where
 }
 
 /// Either a color from the palette, or a direct color.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ColorType {
     /// Uses a color from the application palette.
     Palette(PaletteColor),
 
     /// Uses a direct color, independent of the current palette.
     Color(Color),
+
+    /// Keeps the color used by the parent view.
+    InheritParent,
+
+    /// Uses a custom color from the palette, by name.
+    ///
+    /// The palette color is used if the palette doesn't define this name,
+    /// for instance when the theme file doesn't.
+    Custom(Cow<'static, str>, PaletteColor),
 }
 
 impl ColorType {
     /// Given a palette, resolve `self` to a concrete color.
-    pub fn resolve(self, palette: &Palette) -> Color {
-        match self {
+    ///
+    /// `InheritParent` resolves to the terminal default color.
+    pub fn resolve(&self, palette: &Palette) -> Color {
+        self.resolve_with_parent(palette, Color::TerminalDefault)
+    }
+
+    /// Given a palette, resolve `self` to a concrete color.
+    ///
+    /// `InheritParent` resolves to `parent`.
+    pub fn resolve_with_parent(
+        &self, palette: &Palette, parent: Color,
+    ) -> Color {
+        match *self {
             ColorType::Color(color) => color,
             ColorType::Palette(color) => color.resolve(palette),
+            ColorType::InheritParent => parent,
+            ColorType::Custom(ref name, fallback) => {
+                palette.custom_or(name, fallback)
+            }
+        }
+    }
+
+    /// Returns `parent` if `self` is `InheritParent`, `self` otherwise.
+    pub fn merged_with(self, parent: ColorType) -> Self {
+        match self {
+            ColorType::InheritParent => parent,
+            color => color,
         }
     }
 }
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+/// Hyperlink target attached to some text.
+///
+/// Backends supporting it (like termion) make such text clickable in
+/// terminals understanding the OSC 8 escape sequence. Other terminals simply
+/// display the text.
+///
+/// The URL is shared, so cloning a `Link` is cheap.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::theme::Link;
+/// # use cursive::utils::markup::StyledString;
+/// let mut text = StyledString::plain("See ");
+/// text.append_styled("the docs", Link::new("https://docs.rs/cursive"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Link(Arc<str>);
+
+impl Link {
+    /// Returns a link to the given URL.
+    pub fn new<S: Into<String>>(url: S) -> Self {
+        Link(url.into().into())
+    }
+
+    /// Returns the URL this link points to.
+    pub fn url(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equality() {
+        let a = Link::new("https://example.com/a");
+        let b = Link::new("https://example.com/b");
+
+        assert_ne!(a, b);
+        assert_eq!(a, Link::new("https://example.com/a"));
+        assert_eq!(b.url(), "https://example.com/b");
+    }
+}
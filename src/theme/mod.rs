@@ -131,7 +131,8 @@
 //!
 //! # First come some various options
 //! shadow = false  # Don't draw shadows around stacked views
-//! borders = "simple"  # Alternatives are "none" and "outset"
+//! # Alternatives are "outset", "rounded", "double", "ascii" and "none"
+//! borders = "simple"
 //!
 //! # Here we define the color palette.
 //! [colors]
@@ -155,21 +156,38 @@
 //! 	# Lower precision values can use only 3 digits.
 //! 	highlight          = "#F00"
 //! 	highlight_inactive = "#5555FF"
+//!
+//! # Custom colors can be given any name, and are used by the application
+//! # with `ColorType::Custom`.
+//! [colors.custom]
+//! accent = "#00afff"
+//!
+//! # Scrollbars can be drawn with other characters and colors.
+//! [scrollbar]
+//! vertical_track = "│"
+//! thumb = "█"
+//! thumb_color = "light blue"
 //! ```
 mod border_style;
 mod color;
 mod color_pair;
 mod color_style;
 mod effect;
+mod link;
 mod palette;
+mod scrollbar;
+#[cfg(feature = "serde")]
+mod serialize;
 mod style;
 
-pub use self::border_style::BorderStyle;
+pub use self::border_style::{BorderChars, BorderStyle};
 pub use self::color::{BaseColor, Color};
 pub use self::color_pair::ColorPair;
 pub use self::color_style::{ColorStyle, ColorType};
 pub use self::effect::Effect;
+pub use self::link::Link;
 pub use self::palette::{Palette, PaletteColor};
+pub use self::scrollbar::ScrollbarStyle;
 pub use self::style::Style;
 use std::fs::File;
 use std::io;
@@ -186,6 +204,8 @@ pub struct Theme {
     pub borders: BorderStyle,
     /// What colors should be used through the application?
     pub palette: Palette,
+    /// How scrollbars should be drawn.
+    pub scrollbar: ScrollbarStyle,
 }
 
 impl Default for Theme {
@@ -194,6 +214,7 @@ impl Default for Theme {
             shadow: true,
             borders: BorderStyle::Simple,
             palette: Palette::default(),
+            scrollbar: ScrollbarStyle::default(),
         }
     }
 }
@@ -211,6 +232,35 @@ impl Theme {
         if let Some(&toml::Value::Table(ref table)) = table.get("colors") {
             palette::load_toml(&mut self.palette, table);
         }
+
+        if let Some(toml::Value::Table(table)) = table.get("scrollbar") {
+            self.scrollbar.load_toml(table);
+        }
+    }
+}
+
+/// Chooses between the dark and light themes of an application.
+///
+/// See [`Cursive::set_theme_mode`].
+///
+/// [`Cursive::set_theme_mode`]: ../struct.Cursive.html#method.set_theme_mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThemeMode {
+    /// Picks a theme matching the terminal background.
+    ///
+    /// If the background could not be detected, the dark theme is used.
+    ///
+    /// This is the default.
+    Auto,
+    /// Always uses the dark theme.
+    Dark,
+    /// Always uses the light theme.
+    Light,
+}
+
+impl Default for ThemeMode {
+    fn default() -> Self {
+        ThemeMode::Auto
     }
 }
 
@@ -261,3 +311,27 @@ pub fn load_toml(content: &str) -> Result<Theme, Error> {
 pub fn load_default() -> Theme {
     Theme::default()
 }
+
+/// Loads a high-contrast theme, for better accessibility.
+///
+/// It uses [`Palette::high_contrast`], without shadows.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::theme;
+/// # let mut siv = Cursive::dummy();
+/// siv.add_global_callback('h', |s| {
+///     s.set_theme(theme::load_high_contrast());
+/// });
+/// ```
+///
+/// [`Palette::high_contrast`]: struct.Palette.html#method.high_contrast
+pub fn load_high_contrast() -> Theme {
+    Theme {
+        shadow: false,
+        palette: Palette::high_contrast(),
+        ..Theme::default()
+    }
+}
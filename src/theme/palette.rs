@@ -62,6 +62,39 @@ impl IndexMut<PaletteColor> for Palette {
 }
 
 impl Palette {
+    /// Returns a high-contrast palette.
+    ///
+    /// Text is white, cyan or yellow on black, and highlights are black on
+    /// yellow or white. Every pair used by `ColorStyle` has a contrast ratio
+    /// above 7:1, as recommended by the WCAG (level AAA).
+    ///
+    /// Exact RGB values are used, so the terminal color scheme doesn't
+    /// affect them.
+    pub fn high_contrast() -> Palette {
+        use self::PaletteColor::*;
+
+        let black = Color::Rgb(0, 0, 0);
+        let white = Color::Rgb(255, 255, 255);
+        let yellow = Color::Rgb(255, 255, 0);
+        let cyan = Color::Rgb(0, 255, 255);
+
+        Palette {
+            basic: enum_map!{
+                Background => black,
+                Shadow => black,
+                View => black,
+                Primary => white,
+                Secondary => cyan,
+                Tertiary => white,
+                TitlePrimary => yellow,
+                TitleSecondary => cyan,
+                Highlight => yellow,
+                HighlightInactive => white,
+            },
+            custom: HashMap::new(),
+        }
+    }
+
     /// Returns a custom color from this palette.
     ///
     /// Returns `None` if the given key was not found.
@@ -75,6 +108,25 @@ impl Palette {
         })
     }
 
+    /// Returns a custom color from this palette, or `fallback` if missing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::theme::{BaseColor, Color, Palette, PaletteColor};
+    /// let mut palette = Palette::default();
+    /// palette.set_color("accent", Color::Light(BaseColor::Cyan));
+    ///
+    /// let accent = palette.custom_or("accent", PaletteColor::Highlight);
+    /// assert_eq!(accent, Color::Light(BaseColor::Cyan));
+    ///
+    /// let missing = palette.custom_or("missing", PaletteColor::Highlight);
+    /// assert_eq!(missing, palette[PaletteColor::Highlight]);
+    /// ```
+    pub fn custom_or(&self, key: &str, fallback: PaletteColor) -> Color {
+        self.custom(key).cloned().unwrap_or(self[fallback])
+    }
+
     /// Returns a new palette where the given namespace has been merged.
     ///
     /// All values in the namespace will override previous values.
@@ -129,6 +181,53 @@ impl Palette {
         self.custom
             .insert(key.to_string(), PaletteNode::Namespace(namespace));
     }
+
+    /// Sets a value read from a theme file.
+    pub(crate) fn load_node(&mut self, key: &str, node: PaletteNode) {
+        match node {
+            // User colors never replace the basic ones.
+            PaletteNode::Namespace(map) if key == "custom" => {
+                self.custom.extend(map)
+            }
+            PaletteNode::Color(color) => self.set_color(key, color),
+            PaletteNode::Namespace(map) => self.add_namespace(key, map),
+        }
+    }
+
+    /// Returns the custom colors and namespaces.
+    #[cfg(feature = "serde")]
+    pub(crate) fn custom_nodes(&self) -> &HashMap<String, PaletteNode> {
+        &self.custom
+    }
+
+    /// Replaces every color of the palette, including custom ones.
+    pub(crate) fn map_colors<F>(&mut self, f: F)
+    where
+        F: Fn(Color) -> Color,
+    {
+        for (_, color) in self.basic.iter_mut() {
+            *color = f(*color);
+        }
+        for node in self.custom.values_mut() {
+            node.map_colors(&f);
+        }
+    }
+}
+
+impl PaletteNode {
+    fn map_colors<F>(&mut self, f: &F)
+    where
+        F: Fn(Color) -> Color,
+    {
+        match *self {
+            PaletteNode::Color(ref mut color) => *color = f(*color),
+            PaletteNode::Namespace(ref mut namespace) => {
+                for node in namespace.values_mut() {
+                    node.map_colors(f);
+                }
+            }
+        }
+    }
 }
 
 /// Returns the default palette for a cursive application.
@@ -216,10 +315,7 @@ pub(crate) fn load_toml(palette: &mut Palette, table: &toml::value::Table) {
     // Problem: toml-rs doesn't do well with Enums...
 
     for (key, value) in iterate_toml(table) {
-        match value {
-            PaletteNode::Color(color) => palette.set_color(key, color),
-            PaletteNode::Namespace(map) => palette.add_namespace(key, map),
-        }
+        palette.load_node(key, value);
     }
 }
 
@@ -256,3 +352,84 @@ impl PaletteColor {
         palette[self]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::{self, BaseColor, ColorStyle, ColorType};
+
+    #[test]
+    fn custom_colors() {
+        let theme = theme::load_toml(
+            r##"
+            [colors]
+            highlight = "red"
+
+            [colors.custom]
+            accent = "#00afff"
+            primary = "green"
+            "##,
+        )
+        .unwrap();
+        let palette = &theme.palette;
+
+        assert_eq!(palette.custom("accent"), Some(&Color::Rgb(0, 175, 255)));
+        // Custom colors don't replace the basic ones.
+        let green = Color::Dark(BaseColor::Green);
+        assert_eq!(palette.custom("primary"), Some(&green));
+        let default = Palette::default();
+        assert_eq!(
+            palette[PaletteColor::Primary],
+            default[PaletteColor::Primary]
+        );
+        let red = Color::Dark(BaseColor::Red);
+        assert_eq!(palette[PaletteColor::Highlight], red);
+
+        let style = ColorStyle::new(
+            ColorType::Custom("accent".into(), PaletteColor::Primary),
+            ColorType::Custom("missing".into(), PaletteColor::View),
+        );
+        let pair = style.resolve(palette);
+        assert_eq!(pair.front, Color::Rgb(0, 175, 255));
+        assert_eq!(pair.back, palette[PaletteColor::View]);
+    }
+
+    /// Contrast ratio between two colors, as defined by the WCAG.
+    fn contrast(a: Color, b: Color) -> f64 {
+        let luminance = |color| match color {
+            Color::Rgb(r, g, b) => {
+                let channel = |c: u8| {
+                    let c = f64::from(c) / 255.0;
+                    if c <= 0.039_28 {
+                        c / 12.92
+                    } else {
+                        ((c + 0.055) / 1.055).powf(2.4)
+                    }
+                };
+                0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+            }
+            _ => panic!("expected an RGB color"),
+        };
+        let (a, b) = (luminance(a), luminance(b));
+
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    #[test]
+    fn high_contrast() {
+        let palette = Palette::high_contrast();
+
+        for style in &[
+            ColorStyle::primary(),
+            ColorStyle::secondary(),
+            ColorStyle::tertiary(),
+            ColorStyle::title_primary(),
+            ColorStyle::title_secondary(),
+            ColorStyle::highlight(),
+            ColorStyle::highlight_inactive(),
+        ] {
+            let pair = style.resolve(&palette);
+            assert!(contrast(pair.front, pair.back) >= 7.0, "{:?}", style);
+        }
+    }
+}
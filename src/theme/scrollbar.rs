@@ -0,0 +1,97 @@
+use super::{Color, ColorStyle, PaletteColor};
+use direction::Orientation;
+use toml;
+
+/// Specifies how scrollbars should be drawn.
+///
+/// Used by the `[scrollbar]` section of theme files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ScrollbarStyle {
+    /// Track of vertical scrollbars. Defaults to `"|"`.
+    pub vertical_track: String,
+    /// Track of horizontal scrollbars. Defaults to `"-"`.
+    pub horizontal_track: String,
+    /// Thumb, showing the visible part of the content. Defaults to `"▒"`.
+    pub thumb: String,
+    /// Thumb while it is dragged with the mouse. Defaults to `" "`.
+    pub grabbed_thumb: String,
+    /// Corner between two scrollbars. Defaults to `"╳"`.
+    pub corner: String,
+    /// Background of the thumb.
+    ///
+    /// Defaults to `Highlight`, or `HighlightInactive` out of focus.
+    pub thumb_color: Option<Color>,
+    /// Color of the track. Defaults to `Primary`.
+    pub track_color: Option<Color>,
+}
+
+impl Default for ScrollbarStyle {
+    fn default() -> Self {
+        ScrollbarStyle {
+            vertical_track: "|".to_string(),
+            horizontal_track: "-".to_string(),
+            thumb: "▒".to_string(),
+            grabbed_thumb: " ".to_string(),
+            corner: "╳".to_string(),
+            thumb_color: None,
+            track_color: None,
+        }
+    }
+}
+
+impl ScrollbarStyle {
+    /// Returns the track of a scrollbar with the given orientation.
+    pub fn track(&self, orientation: Orientation) -> &str {
+        match orientation {
+            Orientation::Vertical => &self.vertical_track,
+            Orientation::Horizontal => &self.horizontal_track,
+        }
+    }
+
+    /// Returns the color style of the thumb.
+    pub fn thumb_style(&self, focused: bool) -> ColorStyle {
+        match self.thumb_color {
+            Some(color) => ColorStyle::new(PaletteColor::View, color),
+            None if focused => ColorStyle::highlight(),
+            None => ColorStyle::highlight_inactive(),
+        }
+    }
+
+    /// Returns the color style of the track.
+    pub fn track_style(&self) -> ColorStyle {
+        match self.track_color {
+            Some(color) => ColorStyle::new(color, PaletteColor::View),
+            None => ColorStyle::primary(),
+        }
+    }
+
+    pub(crate) fn load_toml(&mut self, table: &toml::value::Table) {
+        let fields = [
+            ("vertical_track", &mut self.vertical_track),
+            ("horizontal_track", &mut self.horizontal_track),
+            ("thumb", &mut self.thumb),
+            ("grabbed_thumb", &mut self.grabbed_thumb),
+            ("corner", &mut self.corner),
+        ];
+        for (key, field) in fields {
+            if let Some(toml::Value::String(value)) = table.get(key) {
+                field.clone_from(value);
+            }
+        }
+
+        let colors = [
+            ("thumb_color", &mut self.thumb_color),
+            ("track_color", &mut self.track_color),
+        ];
+        for (key, field) in colors {
+            if let Some(toml::Value::String(value)) = table.get(key) {
+                *field = Color::parse(value);
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Serde support for themes, enabled by the `serde` feature.
+//!
+//! Themes use the same format as theme files: colors are strings like
+//! `"light red"` or `"#ff8800"`, so a serialized theme can also be loaded
+//! with `load_toml`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeStruct, Serializer};
+
+use super::palette::PaletteNode;
+use super::{BaseColor, BorderStyle, Color, Palette, PaletteColor, Theme};
+
+/// Names of the basic palette colors, as used in theme files.
+const PALETTE_COLORS: [(&str, PaletteColor); 10] = [
+    ("background", PaletteColor::Background),
+    ("shadow", PaletteColor::Shadow),
+    ("view", PaletteColor::View),
+    ("primary", PaletteColor::Primary),
+    ("secondary", PaletteColor::Secondary),
+    ("tertiary", PaletteColor::Tertiary),
+    ("title_primary", PaletteColor::TitlePrimary),
+    ("title_secondary", PaletteColor::TitleSecondary),
+    ("highlight", PaletteColor::Highlight),
+    ("highlight_inactive", PaletteColor::HighlightInactive),
+];
+
+/// Returns the name of a color, as parsed by `Color::parse`.
+fn color_name(color: Color) -> String {
+    fn base_name(base: BaseColor) -> &'static str {
+        match base {
+            BaseColor::Black => "black",
+            BaseColor::Red => "red",
+            BaseColor::Green => "green",
+            BaseColor::Yellow => "yellow",
+            BaseColor::Blue => "blue",
+            BaseColor::Magenta => "magenta",
+            BaseColor::Cyan => "cyan",
+            BaseColor::White => "white",
+        }
+    }
+
+    match color {
+        Color::TerminalDefault => "default".to_string(),
+        Color::Dark(base) => base_name(base).to_string(),
+        Color::Light(base) => format!("light {}", base_name(base)),
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::RgbLowRes(r, g, b) => format!("{}{}{}", r, g, b),
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(
+        &self, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color_name(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Color::parse(&name).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Str(&name), &"a color")
+        })
+    }
+}
+
+impl Serialize for BorderStyle {
+    fn serialize<S: Serializer>(
+        &self, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match *self {
+            BorderStyle::Simple => "simple",
+            BorderStyle::Outset => "outset",
+            BorderStyle::Rounded => "rounded",
+            BorderStyle::Double => "double",
+            BorderStyle::Ascii => "ascii",
+            BorderStyle::None => "none",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for BorderStyle {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(BorderStyle::from(&name))
+    }
+}
+
+impl Serialize for PaletteNode {
+    fn serialize<S: Serializer>(
+        &self, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match *self {
+            PaletteNode::Color(ref color) => color.serialize(serializer),
+            PaletteNode::Namespace(ref map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PaletteNode {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct NodeVisitor;
+
+        impl<'de> Visitor<'de> for NodeVisitor {
+            type Value = PaletteNode;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a color or a map of colors")
+            }
+
+            fn visit_str<E: de::Error>(
+                self, value: &str,
+            ) -> Result<PaletteNode, E> {
+                Color::parse(value).map(PaletteNode::Color).ok_or_else(|| {
+                    E::invalid_value(de::Unexpected::Str(value), &"a color")
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self, mut map: A,
+            ) -> Result<PaletteNode, A::Error> {
+                let mut namespace = HashMap::new();
+                while let Some((key, node)) = map.next_entry()? {
+                    namespace.insert(key, node);
+                }
+                Ok(PaletteNode::Namespace(namespace))
+            }
+        }
+
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+impl Serialize for Palette {
+    fn serialize<S: Serializer>(
+        &self, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let custom = self.custom_nodes();
+        let mut map =
+            serializer.serialize_map(Some(PALETTE_COLORS.len() + 1))?;
+        for &(name, color) in PALETTE_COLORS.iter() {
+            map.serialize_entry(name, &self[color])?;
+        }
+        if !custom.is_empty() {
+            map.serialize_entry("custom", custom)?;
+        }
+        map.end()
+    }
+}
+
+/// Missing colors keep their default value, like in theme files.
+impl<'de> Deserialize<'de> for Palette {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let nodes: HashMap<String, PaletteNode> =
+            HashMap::deserialize(deserializer)?;
+        let mut palette = Palette::default();
+        for (key, node) in nodes {
+            palette.load_node(&key, node);
+        }
+        Ok(palette)
+    }
+}
+
+impl Serialize for Theme {
+    fn serialize<S: Serializer>(
+        &self, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut theme = serializer.serialize_struct("Theme", 4)?;
+        theme.serialize_field("shadow", &self.shadow)?;
+        theme.serialize_field("borders", &self.borders)?;
+        theme.serialize_field("colors", &self.palette)?;
+        theme.serialize_field("scrollbar", &self.scrollbar)?;
+        theme.end()
+    }
+}
+
+/// Missing fields keep their default value, like in theme files.
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct ThemeVisitor;
+
+        impl<'de> Visitor<'de> for ThemeVisitor {
+            type Value = Theme;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a theme")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self, mut map: A,
+            ) -> Result<Theme, A::Error> {
+                let mut theme = Theme::default();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "shadow" => theme.shadow = map.next_value()?,
+                        "borders" => theme.borders = map.next_value()?,
+                        "colors" => theme.palette = map.next_value()?,
+                        "scrollbar" => theme.scrollbar = map.next_value()?,
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(theme)
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "Theme",
+            &["shadow", "borders", "colors", "scrollbar"],
+            ThemeVisitor,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme;
+    use toml;
+
+    #[test]
+    fn round_trip() {
+        let mut theme = Theme {
+            shadow: false,
+            borders: BorderStyle::Outset,
+            ..Theme::default()
+        };
+        theme.palette[PaletteColor::View] = Color::Rgb(0x12, 0xab, 0xff);
+        theme.palette[PaletteColor::Shadow] = Color::RgbLowRes(1, 2, 3);
+        theme
+            .palette
+            .set_color("accent", Color::Light(BaseColor::Cyan));
+
+        theme.scrollbar.thumb = "█".to_string();
+        theme.scrollbar.thumb_color = Some(Color::Dark(BaseColor::Blue));
+
+        let text = toml::to_string(&theme).unwrap();
+        assert!(text.contains("view = \"#12abff\""), "{}", text);
+
+        let parsed: Theme = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.shadow, theme.shadow);
+        assert_eq!(parsed.borders, theme.borders);
+        assert_eq!(parsed.palette, theme.palette);
+        assert_eq!(parsed.scrollbar, theme.scrollbar);
+
+        // The same text can be loaded as a theme file.
+        let loaded = theme::load_toml(&text).unwrap();
+        assert_eq!(loaded.palette, theme.palette);
+        assert_eq!(loaded.scrollbar, theme.scrollbar);
+    }
+
+    #[test]
+    fn defaults() {
+        let theme: Theme = toml::from_str("[colors]\nview = \"red\"").unwrap();
+        assert!(theme.shadow);
+        assert_eq!(
+            theme.palette[PaletteColor::View],
+            Color::Dark(BaseColor::Red)
+        );
+        assert_eq!(
+            theme.palette[PaletteColor::Primary],
+            Palette::default()[PaletteColor::Primary]
+        );
+
+        assert!(toml::from_str::<Theme>("[colors]\nview = \"nope\"").is_err());
+    }
+}
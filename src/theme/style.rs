@@ -1,10 +1,10 @@
-use super::{Color, ColorStyle, ColorType, Effect, PaletteColor};
+use super::{Color, ColorStyle, ColorType, Effect, Link, PaletteColor};
 use enumset::EnumSet;
 
 /// Combine a color and an effect.
 ///
 /// Represents any transformation that can be applied to text.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Style {
     /// Effect to apply.
     ///
@@ -15,6 +15,11 @@ pub struct Style {
     ///
     /// `None` to keep using the previous colors.
     pub color: Option<ColorStyle>,
+
+    /// Hyperlink target.
+    ///
+    /// `None` if this text is not a link.
+    pub link: Option<Link>,
 }
 
 impl Default for Style {
@@ -29,25 +34,36 @@ impl Style {
         Style {
             effects: EnumSet::new(),
             color: None,
+            link: None,
         }
     }
 
     /// Returns a new `Style` by merging all given styles.
     ///
-    /// Will use the last non-`None` color, and will combine all effects.
+    /// Will use the last non-`None` color and link, and will combine all
+    /// effects.
     pub fn merge(styles: &[Style]) -> Self {
         let mut color = None;
+        let mut link = None;
         let mut effects = EnumSet::new();
 
         for style in styles {
             if style.color.is_some() {
-                color = style.color;
+                color = style.color.clone();
+            }
+
+            if style.link.is_some() {
+                link = style.link.clone();
             }
 
             effects.insert_all(style.effects);
         }
 
-        Style { color, effects }
+        Style {
+            color,
+            effects,
+            link,
+        }
     }
 
     /// Returns a combination of `self` and `other`.
@@ -64,6 +80,7 @@ impl From<Effect> for Style {
         Style {
             effects: enum_set!(Effect, effect),
             color: None,
+            link: None,
         }
     }
 }
@@ -73,6 +90,17 @@ impl From<ColorStyle> for Style {
         Style {
             effects: EnumSet::new(),
             color: Some(color),
+            link: None,
+        }
+    }
+}
+
+impl From<Link> for Style {
+    fn from(link: Link) -> Self {
+        Style {
+            effects: EnumSet::new(),
+            color: None,
+            link: Some(link),
         }
     }
 }
@@ -9,7 +9,9 @@
 //! ```
 
 #[doc(no_inline)]
-pub use view::{Boxable, Finder, Identifiable, Scrollable, View};
+pub use view::{
+    Boxable, Cacheable, Finder, Identifiable, Scrollable, View,
+};
 
 #[doc(no_inline)]
 pub use With;
@@ -0,0 +1,254 @@
+//! Animated transitions between screens and layers.
+//!
+//! Transitions are disabled by default. Once enabled with
+//! [`Cursive::set_transition`], switching screens and adding or removing
+//! layers is animated over a few frames.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use cursive::Cursive;
+//! # use cursive::transition::{EasingFunction, Transition};
+//! # let mut siv = Cursive::dummy();
+//! siv.set_transition(
+//!     Transition::slide()
+//!         .frames(12)
+//!         .easing(EasingFunction::EaseOut),
+//! );
+//! ```
+//!
+//! [`Cursive::set_transition`]: ../struct.Cursive.html#method.set_transition
+
+use theme::{BaseColor, Color, PaletteColor, Theme};
+use With;
+
+/// Frame rate of animations, when the application doesn't set one.
+pub(crate) const DEFAULT_FPS: u32 = 30;
+
+/// Curve followed by an animation.
+///
+/// Maps the elapsed fraction of the animation, from 0 to 1, to its progress,
+/// also from 0 to 1.
+#[derive(Clone, Copy)]
+pub enum EasingFunction {
+    /// Constant speed.
+    Linear,
+    /// Starts slowly, then speeds up.
+    EaseIn,
+    /// Starts fast, then slows down.
+    EaseOut,
+    /// Starts and ends slowly.
+    EaseInOut,
+    /// Any other curve.
+    Custom(fn(f64) -> f64),
+}
+
+impl EasingFunction {
+    /// Returns the progress after the given elapsed fraction.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            EasingFunction::Linear => t,
+            EasingFunction::EaseIn => t * t,
+            EasingFunction::EaseOut => t * (2.0 - t),
+            EasingFunction::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+            EasingFunction::Custom(f) => f(t),
+        }
+    }
+}
+
+/// Kind of animation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionStyle {
+    /// New screens slide in from the right, new layers from the bottom.
+    Slide,
+    /// New content fades in from the background color.
+    Fade,
+}
+
+/// Describes how changes are animated.
+///
+/// Removed layers are not available anymore once popped, so removing a
+/// layer always fades the remaining ones in.
+#[derive(Clone, Copy)]
+pub struct Transition {
+    /// Kind of animation.
+    pub style: TransitionStyle,
+    /// Number of frames the animation lasts.
+    pub frames: usize,
+    /// Curve followed by the animation.
+    pub easing: EasingFunction,
+}
+
+impl Transition {
+    /// Creates a new transition with the given style.
+    ///
+    /// It lasts 8 frames, with an `EaseInOut` curve.
+    pub fn new(style: TransitionStyle) -> Self {
+        Transition {
+            style,
+            frames: 8,
+            easing: EasingFunction::EaseInOut,
+        }
+    }
+
+    /// Creates a new sliding transition.
+    pub fn slide() -> Self {
+        Transition::new(TransitionStyle::Slide)
+    }
+
+    /// Creates a new fading transition.
+    pub fn fade() -> Self {
+        Transition::new(TransitionStyle::Fade)
+    }
+
+    /// Sets the number of frames the animation lasts.
+    ///
+    /// Chainable variant.
+    pub fn frames(self, frames: usize) -> Self {
+        self.with(|s| s.frames = frames.max(1))
+    }
+
+    /// Sets the curve followed by the animation.
+    ///
+    /// Chainable variant.
+    pub fn easing(self, easing: EasingFunction) -> Self {
+        self.with(|s| s.easing = easing)
+    }
+}
+
+/// What is being animated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Target {
+    /// The active screen replaced the given one.
+    Screen(usize),
+    /// A layer was added on top of the active screen.
+    LayerAdded,
+    /// A layer was removed from the active screen.
+    LayerRemoved,
+}
+
+/// A running animation.
+pub(crate) struct Animation {
+    pub transition: Transition,
+    pub target: Target,
+    pub frame: usize,
+}
+
+impl Animation {
+    pub fn new(transition: Transition, target: Target) -> Self {
+        Animation {
+            transition,
+            target,
+            frame: 0,
+        }
+    }
+
+    /// Returns the progress of the animation, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        let t = self.frame as f64 / self.transition.frames as f64;
+        self.transition.easing.apply(t)
+    }
+
+    /// Moves to the next frame.
+    ///
+    /// Returns `false` once the animation is over.
+    pub fn advance(&mut self) -> bool {
+        self.frame += 1;
+        self.frame < self.transition.frames
+    }
+}
+
+/// Returns `theme` with colors blended with the background.
+///
+/// At `progress` 0, everything has the background color. At 1, the colors
+/// are unchanged.
+pub(crate) fn faded(theme: &Theme, progress: f64) -> Theme {
+    let background = theme.palette[PaletteColor::Background];
+    theme.clone().with(|theme| {
+        theme
+            .palette
+            .map_colors(|color| blend(background, color, progress));
+    })
+}
+
+/// Mixes two colors.
+///
+/// Colors without a known value switch halfway.
+fn blend(from: Color, to: Color, t: f64) -> Color {
+    match (rgb(from), rgb(to)) {
+        (Some(from), Some(to)) => {
+            let mix = |a: u8, b: u8| {
+                (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round()
+                    as u8
+            };
+            Color::Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+        }
+        _ if t < 0.5 => from,
+        _ => to,
+    }
+}
+
+/// Returns the usual value of a color.
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let (base, high) = match color {
+        Color::TerminalDefault => return None,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        Color::RgbLowRes(r, g, b) => return Some((51 * r, 51 * g, 51 * b)),
+        Color::Dark(BaseColor::White) => return Some((192, 192, 192)),
+        Color::Light(BaseColor::Black) => return Some((128, 128, 128)),
+        Color::Dark(base) => (base, 128),
+        Color::Light(base) => (base, 255),
+    };
+    let channel = |bit: u8| if base as u8 & bit != 0 { high } else { 0 };
+    Some((channel(1), channel(2), channel(4)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing() {
+        for easing in &[
+            EasingFunction::Linear,
+            EasingFunction::EaseIn,
+            EasingFunction::EaseOut,
+            EasingFunction::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+        assert_eq!(EasingFunction::EaseIn.apply(0.5), 0.25);
+        assert_eq!(EasingFunction::EaseOut.apply(2.0), 1.0);
+    }
+
+    #[test]
+    fn animation() {
+        let transition = Transition::fade().frames(2);
+        let mut animation = Animation::new(transition, Target::LayerAdded);
+        assert_eq!(animation.progress(), 0.0);
+        assert!(animation.advance());
+        assert_eq!(animation.progress(), 0.5);
+        assert!(!animation.advance());
+    }
+
+    #[test]
+    fn colors() {
+        let red = Color::Light(BaseColor::Red);
+        let black = Color::Dark(BaseColor::Black);
+        assert_eq!(blend(black, red, 0.5), Color::Rgb(128, 0, 0));
+        assert_eq!(blend(black, red, 1.0), Color::Rgb(255, 0, 0));
+        assert_eq!(
+            blend(Color::TerminalDefault, red, 0.2),
+            Color::TerminalDefault
+        );
+        assert_eq!(rgb(Color::Dark(BaseColor::Yellow)), Some((128, 128, 0)));
+    }
+}
@@ -0,0 +1,47 @@
+//! Horizontal bars drawn with eighth-block characters.
+
+/// Returns a bar of `width` cells filled at `ratio`.
+///
+/// `ratio` is clamped to `[0, 1]`; a partly filled cell uses an eighth
+/// block, so the bar can be shorter than `width`.
+pub(crate) fn bar_string(ratio: f64, width: usize) -> String {
+    let ratio = if ratio > 0.0 { ratio.min(1.0) } else { 0.0 };
+    let eighths = (ratio * (width * 8) as f64).round() as usize;
+
+    let (full, partial) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(full);
+    if partial > 0 {
+        bar.push_str(horizontal_block(partial));
+    }
+    bar
+}
+
+/// Returns the block character for a cell filled up to `eighths`.
+fn horizontal_block(eighths: usize) -> &'static str {
+    match eighths {
+        0 => " ",
+        1 => "▏",
+        2 => "▎",
+        3 => "▍",
+        4 => "▌",
+        5 => "▋",
+        6 => "▊",
+        7 => "▉",
+        _ => "█",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars() {
+        assert_eq!(bar_string(0.0, 4), "");
+        assert_eq!(bar_string(1.0, 4), "████");
+        assert_eq!(bar_string(0.5, 3), "█▌");
+        assert_eq!(bar_string(0.1, 4), "▍");
+        assert_eq!(bar_string(2.0, 2), "██");
+        assert_eq!(bar_string(-0.1, 2), "");
+    }
+}
@@ -0,0 +1,166 @@
+//! Bidirectional text support.
+//!
+//! Requires the `bidi` feature.
+//!
+//! Text is stored in logical order: right-to-left scripts (like Arabic or
+//! Hebrew) must be reordered before being printed on the terminal.
+#![cfg(feature = "bidi")]
+
+use unicode_bidi::BidiInfo;
+use unicode_segmentation::UnicodeSegmentation;
+use utils::span::Span;
+
+/// A piece of text in display order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualSpan<'a, T: 'a> {
+    /// Text to print, already reordered.
+    pub content: String,
+    /// Attribute associated with this text.
+    pub attr: &'a T,
+}
+
+/// A row of text reordered for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualRow<'a, T: 'a> {
+    /// Spans to print from left to right.
+    pub spans: Vec<VisualSpan<'a, T>>,
+    /// `true` if the row starts with right-to-left text.
+    ///
+    /// Such rows should be aligned to the right by default.
+    pub rtl: bool,
+}
+
+/// Reorders a row of spans, given in logical order, for display.
+///
+/// `spans` should not contain any line break.
+pub fn reorder<'a, T>(spans: &[Span<'a, T>]) -> VisualRow<'a, T> {
+    let text: String = spans.iter().map(|span| span.content).collect();
+
+    let bidi_info = BidiInfo::new(&text, None);
+    if !bidi_info.has_rtl() {
+        let spans = spans
+            .iter()
+            .map(|span| VisualSpan {
+                content: span.content.to_string(),
+                attr: span.attr,
+            })
+            .collect();
+        return VisualRow { spans, rtl: false };
+    }
+
+    // Byte range of each span in `text`.
+    let mut ranges = Vec::with_capacity(spans.len());
+    let mut start = 0;
+    for span in spans {
+        ranges.push((start, start + span.content.len()));
+        start += span.content.len();
+    }
+
+    let mut result = Vec::new();
+    let mut rtl = false;
+
+    for (i, para) in bidi_info.paragraphs.iter().enumerate() {
+        if i == 0 {
+            rtl = para.level.is_rtl();
+        }
+
+        let (levels, runs) = bidi_info.visual_runs(para, para.range.clone());
+        for run in runs {
+            let run_rtl = levels[run.start].is_rtl();
+
+            // Parts of the spans covered by this run, in logical order.
+            let mut parts: Vec<_> = spans
+                .iter()
+                .zip(&ranges)
+                .filter_map(|(span, &(start, end))| {
+                    let start = start.max(run.start);
+                    let end = end.min(run.end);
+                    if start < end {
+                        Some((&text[start..end], span.attr))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            if run_rtl {
+                parts.reverse();
+            }
+
+            result.extend(parts.into_iter().map(|(content, attr)| {
+                let content = if run_rtl {
+                    content.graphemes(true).rev().collect()
+                } else {
+                    content.to_string()
+                };
+                VisualSpan { content, attr }
+            }));
+        }
+    }
+
+    VisualRow {
+        spans: result,
+        rtl,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(row: &VisualRow<()>) -> String {
+        row.spans.iter().map(|span| span.content.as_str()).collect()
+    }
+
+    #[test]
+    fn ltr_unchanged() {
+        let spans = [Span {
+            content: "hello",
+            attr: &(),
+        }];
+        let row = reorder(&spans);
+        assert!(!row.rtl);
+        assert_eq!(plain(&row), "hello");
+    }
+
+    #[test]
+    fn rtl_reversed() {
+        let spans = [Span {
+            content: "שלום",
+            attr: &(),
+        }];
+        let row = reorder(&spans);
+        assert!(row.rtl);
+        assert_eq!(plain(&row), "םולש");
+    }
+
+    #[test]
+    fn mixed_spans() {
+        let (a, b) = (1, 2);
+        let spans = [
+            Span {
+                content: "abc ",
+                attr: &a,
+            },
+            Span {
+                content: "אב",
+                attr: &b,
+            },
+        ];
+        let row = reorder(&spans);
+        assert!(!row.rtl);
+        assert_eq!(
+            row.spans,
+            vec![
+                VisualSpan {
+                    content: "abc ".to_string(),
+                    attr: &a,
+                },
+                VisualSpan {
+                    content: "בא".to_string(),
+                    attr: &b,
+                },
+            ]
+        );
+    }
+}
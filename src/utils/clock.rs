@@ -0,0 +1,76 @@
+//! Clock used to measure the time between input events.
+//!
+//! `std::time::Instant` is not available on every platform: on
+//! `wasm32-unknown-unknown`, it panics. There, the time is unknown unless
+//! the application provides a clock with [`set_clock`], for instance one
+//! reading `Date.now()`.
+//!
+//! [`set_clock`]: fn.set_clock.html
+
+use std::sync::RwLock;
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+lazy_static! {
+    static ref CLOCK: RwLock<Option<fn() -> Duration>> = RwLock::new(None);
+}
+
+/// Sets the function returning the current time.
+///
+/// The time can be counted from any point, as only differences are used.
+pub fn set_clock(clock: fn() -> Duration) {
+    *CLOCK.write().unwrap() = Some(clock);
+}
+
+/// Returns the current time, if it is known.
+pub fn now() -> Option<Duration> {
+    match *CLOCK.read().unwrap() {
+        Some(clock) => Some(clock()),
+        None => default_now(),
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn default_now() -> Option<Duration> {
+    lazy_static! {
+        static ref START: Instant = Instant::now();
+    }
+    Some(START.elapsed())
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn default_now() -> Option<Duration> {
+    None
+}
+
+/// Returns `true` if less than `delay` passed between `last` and `now`.
+///
+/// Returns `false` if either is unknown.
+pub(crate) fn within(
+    last: Option<Duration>, now: Option<Duration>, delay: Duration,
+) -> bool {
+    match (last, now) {
+        (Some(last), Some(now)) => {
+            now.checked_sub(last).is_some_and(|elapsed| elapsed <= delay)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn within_delay() {
+        let second = Duration::from_secs(1);
+        let delay = Duration::from_millis(500);
+        assert!(within(Some(second), Some(second + delay), delay));
+        assert!(!within(Some(second), Some(second * 2), delay));
+        // Time going backwards, or unknown.
+        assert!(!within(Some(second), Some(delay), delay));
+        assert!(!within(None, Some(second), delay));
+        assert!(!within(Some(second), None, delay));
+    }
+}
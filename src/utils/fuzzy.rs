@@ -0,0 +1,129 @@
+//! Fuzzy matching, to filter lists with a few typed characters.
+
+/// Score for each matched character.
+const MATCH: i64 = 16;
+/// Bonus when a character directly follows the previous match.
+const CONSECUTIVE: i64 = 8;
+/// Bonus when a character starts a word.
+const WORD_START: i64 = 8;
+/// Maximum penalty for the characters skipped between two matches.
+const MAX_GAP: i64 = 8;
+
+/// Result of a successful fuzzy match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Score of the match; higher is better.
+    pub score: i64,
+
+    /// Byte offset in the text of each matched character.
+    pub indices: Vec<usize>,
+}
+
+/// Matches `pattern` against `text`.
+///
+/// Each character of the pattern must appear in the text, in the same
+/// order. Matches on word starts and consecutive characters get a higher
+/// score.
+///
+/// The match is case-insensitive, unless the pattern has an uppercase
+/// character.
+///
+/// Returns `None` if the text doesn't match.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::utils::fuzzy::fuzzy_match;
+/// let open = fuzzy_match("of", "Open file").unwrap();
+/// assert_eq!(open.indices, vec![0, 5]);
+///
+/// let profile = fuzzy_match("of", "Profile").unwrap();
+/// assert!(open.score > profile.score);
+///
+/// assert_eq!(fuzzy_match("fo", "Open file"), None);
+/// ```
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Match> {
+    let case_sensitive = pattern.chars().any(char::is_uppercase);
+    let same = |a: char, b: char| {
+        if case_sensitive {
+            a == b
+        } else {
+            a.to_lowercase().eq(b.to_lowercase())
+        }
+    };
+
+    let mut pattern = pattern.chars().peekable();
+    let mut result = Match {
+        score: 0,
+        indices: Vec::new(),
+    };
+    let mut previous = None;
+    let mut last_match: Option<usize> = None;
+
+    for (position, (i, c)) in text.char_indices().enumerate() {
+        let expected = match pattern.peek() {
+            Some(&expected) => expected,
+            None => break,
+        };
+
+        if same(expected, c) {
+            result.score += MATCH;
+            let gap = match last_match {
+                Some(last) if last + 1 == position => {
+                    result.score += CONSECUTIVE;
+                    0
+                }
+                Some(last) => position - last - 1,
+                None => position,
+            };
+            result.score -= (gap as i64).min(MAX_GAP);
+            if is_word_start(previous, c) {
+                result.score += WORD_START;
+            }
+
+            result.indices.push(i);
+            last_match = Some(position);
+            pattern.next();
+        }
+
+        previous = Some(c);
+    }
+
+    if pattern.peek().is_some() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Returns `true` if `c` starts a word, after `previous`.
+fn is_word_start(previous: Option<char>, c: char) -> bool {
+    match previous {
+        None => true,
+        Some(previous) => {
+            !previous.is_alphanumeric()
+                || (previous.is_lowercase() && c.is_uppercase())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        assert_eq!(fuzzy_match("", "abc").unwrap().score, 0);
+        assert_eq!(fuzzy_match("abcd", "abc"), None);
+        assert_eq!(fuzzy_match("é", "café").unwrap().indices, vec![3]);
+
+        // Smart case
+        assert!(fuzzy_match("ab", "AB").is_some());
+        assert!(fuzzy_match("Ab", "ab").is_none());
+
+        let score = |pattern, text| fuzzy_match(pattern, text).unwrap().score;
+        assert!(score("sf", "saveFile") > score("sf", "transform"));
+        assert!(score("save", "save all") > score("save", "s_a_v_e"));
+        assert!(score("q", "quit") > score("q", "a quit"));
+    }
+}
@@ -10,7 +10,7 @@ mod row;
 pub use self::lines_iterator::LinesIterator;
 pub use self::row::Row;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
+use utils::width::StrWidth;
 
 /// The length and width of a part of a string.
 pub struct Span {
@@ -1,8 +1,8 @@
 use super::chunk::Chunk;
 use super::segment::Segment;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
 use utils::span::SpannedText;
+use utils::width::StrWidth;
 use xi_unicode::LineBreakLeafIter;
 
 /// Iterator that returns non-breakable chunks of text.
@@ -28,6 +28,13 @@ impl<S> ChunkIterator<S> {
             offset: 0,
         }
     }
+
+    /// Starts at the given position instead of the beginning.
+    pub fn starting_at(mut self, span_id: usize, offset: usize) -> Self {
+        self.current_span = span_id;
+        self.offset = offset;
+        self
+    }
 }
 
 /// This iterator produces chunks of non-breakable text.
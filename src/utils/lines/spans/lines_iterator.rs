@@ -7,8 +7,8 @@ use super::segment_merge_iterator::SegmentMergeIterator;
 use std::iter::Peekable;
 use std::rc::Rc;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 use utils::span::SpannedText;
+use utils::width::StrWidth;
 
 /// Generates rows of text in constrainted width.
 ///
@@ -57,6 +57,21 @@ where
         self.show_spaces = true;
         self
     }
+
+    /// Starts at the beginning of the given row.
+    ///
+    /// `row` must have been computed with the same width, on the same
+    /// content or on a prefix of it. This is used to only wrap again the
+    /// end of a text, after something was appended to it.
+    ///
+    /// Returns `None` if the row is empty.
+    pub fn starting_at(mut self, row: &Row) -> Option<Self> {
+        let start = row.segments.first()?;
+        self.iter = ChunkIterator::new(Rc::clone(&self.source))
+            .starting_at(start.span_id, start.start)
+            .peekable();
+        Some(self)
+    }
 }
 
 impl<S> Iterator for LinesIterator<S>
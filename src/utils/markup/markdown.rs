@@ -5,7 +5,7 @@
 extern crate pulldown_cmark;
 
 use self::pulldown_cmark::{Event, Tag};
-use theme::{Effect, Style};
+use theme::{Effect, Link, Style};
 use utils::markup::{StyledIndexedSpan, StyledString};
 use utils::span::IndexedCow;
 
@@ -82,7 +82,10 @@ impl<'a> Iterator for Parser<'a> {
                     }
                     Tag::Rule => return Some(self.literal("---")),
                     Tag::BlockQuote => return Some(self.literal("> ")),
-                    Tag::Link(_, _) => return Some(self.literal("[")),
+                    Tag::Link(link, _) => {
+                        self.stack.push(Style::from(Link::new(link)));
+                        return Some(self.literal("["));
+                    }
                     Tag::Code => return Some(self.literal("```")),
                     Tag::Strong => self.stack.push(Style::from(Effect::Bold)),
                     Tag::Paragraph if !self.first => {
@@ -95,7 +98,8 @@ impl<'a> Iterator for Parser<'a> {
                     Tag::Paragraph if self.first => self.first = false,
                     Tag::Header(_) => return Some(self.literal("\n\n")),
                     Tag::Link(link, _) => {
-                        return Some(self.literal(format!("]({})", link)))
+                        self.stack.pop().unwrap();
+                        return Some(self.literal(format!("]({})", link)));
                     }
                     Tag::Code => return Some(self.literal("```")),
                     Tag::Emphasis | Tag::Strong => {
@@ -5,6 +5,9 @@
 #[cfg(feature = "markdown")]
 pub mod markdown;
 
+#[cfg(feature = "syntect")]
+pub mod syntax;
+
 use theme::Style;
 use utils::span::{IndexedSpan, Span, SpannedString};
 
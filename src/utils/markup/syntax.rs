@@ -0,0 +1,151 @@
+//! Highlight source code.
+//!
+//! Needs the `syntect` feature to be enabled.
+//!
+//! Uses the syntax definitions and themes bundled with [syntect].
+//!
+//! [syntect]: https://docs.rs/syntect
+
+extern crate syntect;
+
+use self::syntect::easy::HighlightLines;
+use self::syntect::highlighting::{self, FontStyle, Theme, ThemeSet};
+use self::syntect::parsing::{SyntaxReference, SyntaxSet};
+use enumset::EnumSet;
+use theme::{Color, ColorStyle, Effect, PaletteColor, Style};
+use utils::markup::StyledString;
+
+/// Name of the theme used when none (or an unknown one) is given.
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+lazy_static! {
+    static ref SYNTAXES: SyntaxSet = SyntaxSet::load_defaults_newlines();
+    static ref THEMES: ThemeSet = ThemeSet::load_defaults();
+}
+
+/// Returns the names of the available themes.
+pub fn themes() -> Vec<&'static str> {
+    THEMES.themes.keys().map(String::as_str).collect()
+}
+
+/// Returns `true` if `language` is a known language name or extension.
+pub fn is_supported(language: &str) -> bool {
+    SYNTAXES.find_syntax_by_token(language).is_some()
+}
+
+/// Highlights `code`, written in the given language.
+///
+/// `language` can be a language name (like `"Rust"`) or a file extension
+/// (like `"rs"`). Unknown languages are shown as plain text, and unknown
+/// themes fall back to [`DEFAULT_THEME`].
+///
+/// Returns one styled string per line, without line endings.
+///
+/// [`DEFAULT_THEME`]: constant.DEFAULT_THEME.html
+pub fn highlight_lines(
+    code: &str, language: &str, theme: &str,
+) -> Vec<StyledString> {
+    let mut highlighter =
+        HighlightLines::new(syntax(language), get_theme(theme));
+
+    code.lines()
+        .map(|line| {
+            // Syntaxes were loaded for lines including the newline.
+            let line = format!("{}\n", line);
+            let mut result = StyledString::new();
+            match highlighter.highlight_line(&line, &SYNTAXES) {
+                Ok(regions) => {
+                    for (style, text) in regions {
+                        let text = text.trim_end_matches('\n');
+                        if !text.is_empty() {
+                            result.append_styled(text, translate(style));
+                        }
+                    }
+                }
+                Err(_) => result.append_plain(line.trim_end_matches('\n')),
+            }
+            result
+        })
+        .collect()
+}
+
+/// Highlights `code`, written in the given language.
+///
+/// Same as [`highlight_lines`], but returns a single string.
+///
+/// [`highlight_lines`]: fn.highlight_lines.html
+pub fn highlight(code: &str, language: &str, theme: &str) -> StyledString {
+    let mut result = StyledString::new();
+    for (i, line) in highlight_lines(code, language, theme)
+        .into_iter()
+        .enumerate()
+    {
+        if i > 0 {
+            result.append_plain("\n");
+        }
+        result.append(line);
+    }
+    result
+}
+
+fn syntax(language: &str) -> &'static SyntaxReference {
+    SYNTAXES
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+fn get_theme(name: &str) -> &'static Theme {
+    THEMES
+        .themes
+        .get(name)
+        .unwrap_or_else(|| &THEMES.themes[DEFAULT_THEME])
+}
+
+/// Converts a syntect style to a cursive style.
+///
+/// The theme background is ignored, to blend with the surrounding view.
+fn translate(style: highlighting::Style) -> Style {
+    let mut effects = EnumSet::new();
+    if style.font_style.contains(FontStyle::BOLD) {
+        effects.insert(Effect::Bold);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        effects.insert(Effect::Italic);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        effects.insert(Effect::Underline);
+    }
+
+    let fg = style.foreground;
+    Style {
+        effects,
+        color: Some(ColorStyle::new(
+            Color::Rgb(fg.r, fg.g, fg.b),
+            PaletteColor::View,
+        )),
+        link: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        let lines = highlight_lines("fn main() {\n}\n", "rs", DEFAULT_THEME);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].source(), "fn main() {");
+        assert!(lines[0].spans_raw().len() > 1);
+        assert_eq!(lines[1].source(), "}");
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        assert!(is_supported("Rust"));
+        assert!(!is_supported("not a language"));
+
+        let text = highlight("a\nb", "not a language", "not a theme");
+        assert_eq!(text.source(), "a\nb");
+    }
+}
@@ -1,12 +1,18 @@
 //! Toolbox to make text layout easier.
 
+pub(crate) mod bar;
+pub(crate) mod base64;
+pub mod bidi;
+pub mod clock;
 mod counter;
+pub mod fuzzy;
 #[macro_use]
 mod immutify;
 pub mod lines;
 pub mod markup;
 mod reader;
 pub mod span;
+pub mod width;
 
 pub use self::counter::Counter;
 pub use self::reader::ProgressReader;
@@ -3,7 +3,7 @@
 //! This module defines various structs describing a span of text from a
 //! larger string.
 use std::borrow::Cow;
-use unicode_width::UnicodeWidthStr;
+use utils::width::StrWidth;
 
 /// A string with associated spans.
 ///
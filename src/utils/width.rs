@@ -0,0 +1,85 @@
+//! Width of text on the terminal.
+//!
+//! Some characters (East Asian "ambiguous" characters, like `°` or `Ω`) are
+//! displayed on one cell by some terminals, and on two cells by others.
+//!
+//! By default, these characters are considered narrow. Use
+//! [`set_ambiguous_wide()`] if your terminal displays them as wide.
+//!
+//! The [`StrWidth`] and [`CharWidth`] traits compute widths according to
+//! this setting, and are used throughout cursive.
+//!
+//! [`set_ambiguous_wide()`]: fn.set_ambiguous_wide.html
+//! [`StrWidth`]: trait.StrWidth.html
+//! [`CharWidth`]: trait.CharWidth.html
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_width;
+
+static AMBIGUOUS_WIDE: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether ambiguous-width characters should be considered wide.
+///
+/// This should be set before starting the event loop: changing it later
+/// only affects views laid out after the change.
+pub fn set_ambiguous_wide(wide: bool) {
+    AMBIGUOUS_WIDE.store(wide, Ordering::Relaxed);
+}
+
+/// Returns `true` if ambiguous-width characters are considered wide.
+pub fn is_ambiguous_wide() -> bool {
+    AMBIGUOUS_WIDE.load(Ordering::Relaxed)
+}
+
+/// Width of a string, according to the ambiguous-width setting.
+pub trait StrWidth {
+    /// Returns the number of cells this string takes on the terminal.
+    fn width(&self) -> usize;
+}
+
+impl StrWidth for str {
+    fn width(&self) -> usize {
+        str_width(self, is_ambiguous_wide())
+    }
+}
+
+/// Width of a character, according to the ambiguous-width setting.
+pub trait CharWidth {
+    /// Returns the number of cells this character takes on the terminal.
+    ///
+    /// Returns `None` for control characters.
+    fn width(self) -> Option<usize>;
+}
+
+impl CharWidth for char {
+    fn width(self) -> Option<usize> {
+        if is_ambiguous_wide() {
+            unicode_width::UnicodeWidthChar::width_cjk(self)
+        } else {
+            unicode_width::UnicodeWidthChar::width(self)
+        }
+    }
+}
+
+fn str_width(text: &str, ambiguous_wide: bool) -> usize {
+    if ambiguous_wide {
+        unicode_width::UnicodeWidthStr::width_cjk(text)
+    } else {
+        unicode_width::UnicodeWidthStr::width(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ambiguous() {
+        assert_eq!(str_width("abc", false), 3);
+        assert_eq!(str_width("abc", true), 3);
+        assert_eq!(str_width("°C", false), 2);
+        assert_eq!(str_width("°C", true), 3);
+        assert_eq!(str_width("漢字", false), 4);
+        assert_eq!(str_width("漢字", true), 4);
+    }
+}
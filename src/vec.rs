@@ -87,6 +87,57 @@ impl XY<usize> {
         })
     }
 
+    /// Checked addition with a signed vec.
+    ///
+    /// Returns `None` if any coordinate would be negative or overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::vec::Vec2;
+    /// # use cursive::XY;
+    /// let u = Vec2::new(1, 2);
+    /// let v = XY::<isize>::new(-1, 1);
+    /// assert_eq!(u.checked_add(v), Some(Vec2::new(0, 3)));
+    /// assert_eq!(u.checked_add(v.map_x(|x| x - 1)), None);
+    /// assert_eq!(u.checked_add(v.map_x(|_| isize::min_value())), None);
+    /// ```
+    pub fn checked_add<O: Into<XY<isize>>>(&self, other: O) -> Option<Self> {
+        let other = other.into();
+
+        let result = self.zip_map(other, |s, o| {
+            if o > 0 {
+                s.checked_add(o as usize)
+            } else {
+                s.checked_sub(o.unsigned_abs())
+            }
+        });
+
+        match result {
+            XY {
+                x: Some(x),
+                y: Some(y),
+            } => Some(Vec2::new(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Returns the distance between `self` and `other` on each axis.
+    ///
+    /// Never panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::vec::Vec2;
+    /// let u = Vec2::new(1, 5);
+    /// assert_eq!(u.abs_diff((3, 2)), Vec2::new(2, 3));
+    /// ```
+    pub fn abs_diff<O: Into<Self>>(&self, other: O) -> Self {
+        let other = other.into();
+        self.zip_map(other, usize::abs_diff)
+    }
+
     /// Term-by-term integer division that rounds up.
     ///
     /// # Examples
@@ -227,6 +278,25 @@ impl<T: Ord> XY<T> {
     pub fn or_max<O: Into<XY<T>>>(self, other: O) -> Self {
         Self::max(self, other)
     }
+
+    /// Restricts each coordinate between `min` and `max`.
+    ///
+    /// If `min` is larger than `max` on an axis, `max` wins.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::vec::Vec2;
+    /// let v = Vec2::new(1, 8);
+    /// assert_eq!(v.clamp((2, 2), (5, 5)), Vec2::new(2, 5));
+    /// ```
+    pub fn clamp<A, B>(self, min: A, max: B) -> Self
+    where
+        A: Into<XY<T>>,
+        B: Into<XY<T>>,
+    {
+        self.or_max(min).or_min(max)
+    }
 }
 
 impl<T: Ord + Add<Output = T> + Clone> XY<T> {
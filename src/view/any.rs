@@ -11,6 +11,9 @@ pub trait AnyView {
     /// Downcast self to a mutable `Any`.
     fn as_any_mut(&mut self) -> &mut Any;
 
+    /// Upcast self to a `View` trait object.
+    fn as_view(&self) -> &View;
+
     /// Returns a boxed any from a boxed self.
     ///
     /// Can be used before `Box::downcast()`.
@@ -39,7 +42,80 @@ impl<T: View> AnyView for T {
         self
     }
 
+    fn as_view(&self) -> &View {
+        self
+    }
+
     fn as_boxed_any(self: Box<Self>) -> Box<Any> {
         self
     }
 }
+
+impl View {
+    /// Returns `true` if this view is of type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Attempts to downcast `self` to a concrete type.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Attempts to downcast `self` to a concrete type.
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+
+    /// Attempts to downcast a boxed view to a concrete type.
+    ///
+    /// Returns the boxed view unchanged if it is not of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::TextView;
+    /// # use cursive::view::View;
+    /// let boxed: Box<View> = Box::new(TextView::new("text"));
+    /// let text: Box<TextView> = boxed.downcast().ok().unwrap();
+    /// ```
+    pub fn downcast<T: Any>(self: Box<Self>) -> Result<Box<T>, Box<View>> {
+        if self.is::<T>() {
+            Ok(self.as_boxed_any().downcast().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::{DummyView, LinearLayout, TextView};
+
+    #[test]
+    fn downcast() {
+        let mut view: Box<View> = Box::new(TextView::new("text"));
+        assert!(view.is::<TextView>());
+        assert!(view.downcast_ref::<DummyView>().is_none());
+        view.downcast_mut::<TextView>().unwrap().set_content("other");
+
+        let view = view.downcast::<DummyView>().err().unwrap();
+        let text = view.downcast::<TextView>().ok().unwrap();
+        assert_eq!(text.get_content().source(), "other");
+    }
+
+    #[test]
+    fn boxed_children() {
+        // Views built at runtime, with different types.
+        let panels: Vec<Box<View>> =
+            vec![Box::new(TextView::new("a")), Box::new(DummyView)];
+
+        let mut layout = LinearLayout::vertical();
+        for panel in panels {
+            layout.add_child(panel);
+        }
+        assert_eq!(layout.len(), 2);
+        assert!(layout.get_child(0).unwrap().is::<TextView>());
+    }
+}
@@ -0,0 +1,15 @@
+use view::View;
+use views::CachedLayoutView;
+
+/// Makes a view wrappable in a [`CachedLayoutView`].
+///
+/// [`CachedLayoutView`]: ::views::CachedLayoutView
+pub trait Cacheable: View + Sized {
+    /// Wraps `self` in a `CachedLayoutView`, skipping layouts when nothing
+    /// changed.
+    fn cached_layout(self) -> CachedLayoutView<Self> {
+        CachedLayoutView::new(self)
+    }
+}
+
+impl<T: View> Cacheable for T {}
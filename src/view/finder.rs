@@ -1,5 +1,5 @@
-use std::any::Any;
-use view::{View, ViewPath, ViewWrapper};
+use std::any::{Any, TypeId};
+use view::{View, ViewWrapper};
 use views::IdView;
 
 /// Provides `call_on<V: View>` to views.
@@ -27,6 +27,29 @@ pub trait Finder {
     {
         self.call_on(&Selector::Id(id), callback)
     }
+
+    /// Runs a callback on every view of type `V`.
+    ///
+    /// Views wrapped in an [`IdView`] are found as well.
+    ///
+    /// [`IdView`]: ../views/struct.IdView.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::view::Finder;
+    /// # use cursive::views::{Button, LinearLayout};
+    /// let mut layout = LinearLayout::vertical()
+    ///     .child(Button::new("Ok", |s| s.quit()))
+    ///     .child(Button::new("Cancel", |s| s.quit()));
+    ///
+    /// // Disable every button during a long operation.
+    /// layout.call_on_all(|button: &mut Button| button.disable());
+    /// ```
+    fn call_on_all<V, F>(&mut self, callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V);
 }
 
 impl<T: View> Finder for T {
@@ -40,12 +63,16 @@ impl<T: View> Finder for T {
             let result_ref = &mut result;
 
             let mut callback = Some(callback);
+            // Several views may be selected: only keep the first one of
+            // the right type.
             let callback = |v: &mut Any| {
-                if let Some(callback) = callback.take() {
-                    if v.is::<V>() {
+                if v.is::<V>() {
+                    if let Some(callback) = callback.take() {
                         *result_ref =
                             v.downcast_mut::<V>().map(|v| callback(v));
-                    } else if v.is::<IdView<V>>() {
+                    }
+                } else if v.is::<IdView<V>>() {
+                    if let Some(callback) = callback.take() {
                         *result_ref = v
                             .downcast_mut::<IdView<V>>()
                             .and_then(|v| v.with_view_mut(callback));
@@ -56,12 +83,202 @@ impl<T: View> Finder for T {
         }
         result
     }
+
+    fn call_on_all<V, F>(&mut self, mut callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V),
+    {
+        let callback = |v: &mut Any| {
+            if let Some(v) = v.downcast_mut::<V>() {
+                callback(v);
+            }
+        };
+        self.call_on_any(&Selector::of_type::<V>(), Box::new(callback));
+    }
 }
 
 /// Selects a single view (if any) in the tree.
+#[derive(Clone, Copy)]
 pub enum Selector<'a> {
     /// Selects a view from its ID.
     Id(&'a str),
     /// Selects a view from its path.
-    Path(&'a ViewPath),
+    ///
+    /// The path is a list of segments separated by `/`, like
+    /// `"dialog/0/edit"`. Each segment is either the index of a child, or
+    /// the ID of a descendant. Views with a single child, like wrappers,
+    /// are skipped.
+    ///
+    /// An empty path selects the view it is given to.
+    Path(&'a str),
+    /// Selects every view of the given type.
+    Type(TypeId),
+}
+
+impl<'a> Selector<'a> {
+    /// Selects every view of type `V`.
+    pub fn of_type<V: View>() -> Self {
+        Selector::Type(TypeId::of::<V>())
+    }
+
+    /// Returns `true` if `view` itself is selected.
+    ///
+    /// This is the case for views of the type selected by `Type`, and for
+    /// the view at the end of a `Path`.
+    ///
+    /// Views overriding [`View::call_on_any`] should run the callback on
+    /// themselves when this returns `true`.
+    ///
+    /// [`View::call_on_any`]: trait.View.html#method.call_on_any
+    pub fn selects(&self, view: &Any) -> bool {
+        match *self {
+            Selector::Type(type_id) => view.type_id() == type_id,
+            Selector::Path(path) => path.is_empty(),
+            Selector::Id(_) => false,
+        }
+    }
+
+    /// Returns the selector to give to the child at `index`.
+    ///
+    /// Returns `None` if nothing can be selected in this child.
+    ///
+    /// Views with several children should use this to forward
+    /// [`View::call_on_any`] and [`View::focus_view`].
+    ///
+    /// [`View::call_on_any`]: trait.View.html#method.call_on_any
+    /// [`View::focus_view`]: trait.View.html#method.focus_view
+    pub fn for_child(&self, index: usize) -> Option<Selector<'a>> {
+        match *self {
+            Selector::Path(path) => match split_path(path) {
+                None => None,
+                Some((PathSegment::Index(i), rest)) => {
+                    if i == index {
+                        Some(Selector::Path(rest))
+                    } else {
+                        None
+                    }
+                }
+                // The view may be anywhere down there.
+                Some((PathSegment::Id(_), _)) => Some(*self),
+            },
+            _ => Some(*self),
+        }
+    }
+
+    /// Returns the selector to give to the content of the view `id`.
+    ///
+    /// Returns `None` if the selector doesn't go through this view.
+    pub(crate) fn for_id(&self, id: &str) -> Option<Selector<'a>> {
+        match *self {
+            Selector::Path(path) => match split_path(path) {
+                Some((PathSegment::Id(segment), rest)) if segment == id => {
+                    Some(Selector::Path(rest))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Part of a `Selector::Path`.
+enum PathSegment<'a> {
+    Index(usize),
+    Id(&'a str),
+}
+
+/// Splits the first segment from a path.
+///
+/// Returns `None` for an empty path.
+fn split_path(path: &str) -> Option<(PathSegment<'_>, &str)> {
+    if path.is_empty() {
+        return None;
+    }
+
+    let (segment, rest) = match path.find('/') {
+        Some(i) => (&path[..i], &path[i + 1..]),
+        None => (path, ""),
+    };
+    let segment = match segment.parse() {
+        Ok(index) => PathSegment::Index(index),
+        Err(_) => PathSegment::Id(segment),
+    };
+    Some((segment, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use view::Identifiable;
+    use views::{Button, Dialog, EditView, LinearLayout, Panel};
+
+    #[test]
+    fn find_by_type() {
+        let mut dialog = Dialog::around(
+            LinearLayout::vertical()
+                .child(Button::new("A", |_| ()))
+                .child(Panel::new(Button::new("B", |_| ())))
+                .child(Button::new("C", |_| ()).with_id("c")),
+        )
+        .button("Ok", |_| ());
+
+        let mut count = 0;
+        dialog.call_on_all(|button: &mut Button| {
+            button.disable();
+            count += 1;
+        });
+        assert_eq!(count, 4);
+
+        let mut enabled = 0;
+        dialog.call_on_all(|button: &mut Button| {
+            if button.is_enabled() {
+                enabled += 1;
+            }
+        });
+        assert_eq!(enabled, 0);
+
+        // Containers are selected too.
+        let mut layouts = 0;
+        dialog.call_on_all(|_: &mut LinearLayout| layouts += 1);
+        assert_eq!(layouts, 1);
+        assert!(dialog
+            .call_on(&Selector::of_type::<Dialog>(), |_: &mut Dialog| ())
+            .is_some());
+    }
+
+    #[test]
+    fn find_by_path() {
+        let mut dialog = Dialog::around(
+            LinearLayout::vertical()
+                .child(EditView::new().content("first"))
+                .child(Panel::new(EditView::new().content("second")))
+                .child(LinearLayout::horizontal().child(
+                    EditView::new().content("third").with_id("third"),
+                )),
+        )
+        .button("Ok", |_| ())
+        .with_id("dialog");
+
+        let content = |dialog: &mut IdView<Dialog>, path| {
+            dialog.call_on(&Selector::Path(path), |edit: &mut EditView| {
+                edit.get_content().to_string()
+            })
+        };
+        assert_eq!(content(&mut dialog, "dialog/0/0"), Some("first".into()));
+        // The panel is skipped.
+        assert_eq!(content(&mut dialog, "0/1"), Some("second".into()));
+        // IDs can skip several levels.
+        assert_eq!(content(&mut dialog, "dialog/third"), Some("third".into()));
+        assert_eq!(content(&mut dialog, "0/2/0"), Some("third".into()));
+        assert_eq!(content(&mut dialog, "0/3"), None);
+        assert_eq!(content(&mut dialog, "other/0/0"), None);
+
+        assert!(dialog
+            .call_on(&Selector::Path("1"), |_: &mut Button| ())
+            .is_some());
+
+        assert!(dialog.focus_view(&Selector::Path("0/1")).is_ok());
+        assert!(dialog.focus_view(&Selector::Path("0/4")).is_err());
+    }
 }
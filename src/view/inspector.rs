@@ -0,0 +1,127 @@
+use rect::Rect;
+use vec::Vec2;
+use view::View;
+
+/// Describes a direct child of a view.
+///
+/// This is given by [`View::inspect_children`] to its callback.
+///
+/// [`View::inspect_children`]: trait.View.html#method.inspect_children
+pub struct ChildInfo<'a> {
+    /// The child view itself.
+    pub view: &'a View,
+
+    /// Offset of the child, relative to its parent's top-left corner.
+    pub offset: Vec2,
+
+    /// Size allocated to the child.
+    pub size: Vec2,
+
+    /// Whether the child would be focused if its parent is.
+    pub focused: bool,
+}
+
+/// A single entry in a view tree snapshot.
+///
+/// Returned by [`inspect`] and used by the debug inspector.
+///
+/// [`inspect`]: fn.inspect.html
+#[derive(Clone, Debug)]
+pub struct ViewNode {
+    /// Depth of this node in the tree. The root has a depth of 0.
+    pub depth: usize,
+
+    /// Concrete type of the view, without its module path.
+    pub name: String,
+
+    /// Area of the screen allocated to the view.
+    pub area: Rect,
+
+    /// Whether the view currently has the focus.
+    pub focused: bool,
+}
+
+/// Builds a depth-first snapshot of the tree rooted at `view`.
+///
+/// `offset` and `size` describe the area allocated to the root.
+pub fn inspect(view: &View, offset: Vec2, size: Vec2) -> Vec<ViewNode> {
+    let mut nodes = Vec::new();
+    inspect_rec(view, offset, size, true, 0, &mut nodes);
+    nodes
+}
+
+fn inspect_rec(
+    view: &View, offset: Vec2, size: Vec2, focused: bool, depth: usize,
+    nodes: &mut Vec<ViewNode>,
+) {
+    nodes.push(ViewNode {
+        depth,
+        name: short_name(view.type_name()),
+        area: Rect::from_size(offset, size),
+        focused,
+    });
+
+    view.inspect_children(size, &mut |child| {
+        inspect_rec(
+            child.view,
+            offset + child.offset,
+            child.size,
+            focused && child.focused,
+            depth + 1,
+            nodes,
+        );
+    });
+}
+
+/// Strips module paths from a type name.
+///
+/// `cursive::views::BoxView<cursive::views::TextView>` becomes
+/// `BoxView<TextView>`.
+fn short_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut segment_start = 0;
+
+    for (i, c) in name.char_indices() {
+        if c == ':' {
+            segment_start = i + 1;
+        } else if !(c.is_alphanumeric() || c == '_') {
+            result.push_str(&name[segment_start..i]);
+            result.push(c);
+            segment_start = i + c.len_utf8();
+        }
+    }
+    result.push_str(&name[segment_start..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::{Dialog, TextView};
+
+    #[test]
+    fn short_names() {
+        assert_eq!(short_name("cursive::views::TextView"), "TextView");
+        assert_eq!(
+            short_name("cursive::views::BoxView<cursive::views::TextView>"),
+            "BoxView<TextView>"
+        );
+        assert_eq!(short_name("Box<dyn a::View>"), "Box<dyn View>");
+    }
+
+    #[test]
+    fn dialog_tree() {
+        let mut dialog = Dialog::around(TextView::new("Hello"));
+        let size = dialog.required_size(Vec2::new(20, 10));
+        dialog.layout(size);
+
+        let nodes = inspect(&dialog, Vec2::zero(), size);
+        assert_eq!(nodes[0].name, "Dialog");
+        assert_eq!(nodes[0].depth, 0);
+
+        let text = nodes.iter().find(|n| n.name == "TextView").unwrap();
+        assert!(text.focused);
+        assert_eq!(text.area.size(), Vec2::new(5, 1));
+    }
+}
@@ -41,15 +41,18 @@ mod view_wrapper;
 // Essentials components
 mod any;
 mod finder;
+mod inspector;
 mod margins;
 mod position;
 mod size_cache;
 mod size_constraint;
+mod state;
 mod view;
 mod view_path;
 
 // Helper bases
 mod boxable;
+mod cacheable;
 mod identifiable;
 mod scroll;
 mod scrollable;
@@ -58,15 +61,19 @@ mod into_boxed_view;
 
 pub use self::any::AnyView;
 pub use self::boxable::Boxable;
+pub use self::cacheable::Cacheable;
 pub use self::finder::{Finder, Selector};
 pub use self::identifiable::Identifiable;
+pub use self::inspector::{inspect, ChildInfo, ViewNode};
 pub use self::into_boxed_view::IntoBoxedView;
 pub use self::margins::Margins;
 pub use self::position::{Offset, Position};
-pub use self::scroll::{ScrollBase, ScrollStrategy};
+pub use self::scroll::{PageKeys, Paging, ScrollBase, ScrollStrategy};
 pub use self::scrollable::Scrollable;
 pub use self::size_cache::SizeCache;
 pub use self::size_constraint::SizeConstraint;
+pub(crate) use self::state::IdState;
+pub use self::state::ViewState;
 pub use self::view::View;
 pub use self::view_path::ViewPath;
 pub use self::view_wrapper::ViewWrapper;
@@ -67,15 +67,15 @@ impl Offset {
     pub fn compute_offset(
         &self, size: usize, available: usize, parent: usize,
     ) -> usize {
-        if size > available {
-            0
-        } else {
-            match *self {
-                Offset::Center => (available - size) / 2,
-                Offset::Absolute(offset) => min(offset, available - size),
-                Offset::Parent(offset) => {
-                    min((parent as isize + offset) as usize, available - size)
-                }
+        let extra = available.saturating_sub(size);
+
+        match *self {
+            Offset::Center => extra / 2,
+            Offset::Absolute(offset) => min(offset, extra),
+            Offset::Parent(offset) => {
+                // Don't go past the top or left of the screen.
+                let offset = (parent as isize + offset).max(0) as usize;
+                min(offset, extra)
             }
         }
     }
@@ -84,7 +84,7 @@ impl Offset {
 #[cfg(test)]
 mod tests {
 
-    use super::Position;
+    use super::{Offset, Position};
     use vec::Vec2;
 
     #[test]
@@ -97,4 +97,22 @@ mod tests {
         assert_eq!(Vec2::new(0, 0), c.compute_offset((5, 3), (5, 3), (0, 0)));
         assert_eq!(Vec2::new(0, 0), c.compute_offset((5, 3), (3, 1), (0, 0)));
     }
+
+    #[test]
+    fn test_absolute() {
+        let p = Position::absolute((2, 1));
+        assert_eq!(Vec2::new(2, 1), p.compute_offset((1, 1), (5, 3), (4, 2)));
+        assert_eq!(Vec2::new(1, 0), p.compute_offset((4, 3), (5, 3), (0, 0)));
+    }
+
+    #[test]
+    fn test_parent() {
+        let p = Position::parent((1, -1));
+        assert_eq!(Vec2::new(3, 1), p.compute_offset((1, 1), (5, 3), (2, 2)));
+        assert_eq!(Vec2::new(2, 0), p.compute_offset((3, 1), (5, 3), (4, 1)));
+        assert_eq!(Vec2::new(1, 0), p.compute_offset((1, 1), (5, 3), (0, 0)));
+
+        let p = Position::new(Offset::Center, Offset::Parent(1));
+        assert_eq!(Vec2::new(2, 2), p.compute_offset((1, 1), (5, 3), (0, 1)));
+    }
 }
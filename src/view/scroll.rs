@@ -1,6 +1,6 @@
 use div::div_up;
+use event::{Event, Key};
 use std::cmp::{max, min};
-use theme::ColorStyle;
 use vec::Vec2;
 use Printer;
 
@@ -52,6 +52,87 @@ impl Default for ScrollStrategy {
     }
 }
 
+/// Movement by a page or half a page in a scrolling view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Paging {
+    /// Up by one page.
+    PageUp,
+    /// Down by one page.
+    PageDown,
+    /// Up by half a page.
+    HalfPageUp,
+    /// Down by half a page.
+    HalfPageDown,
+}
+
+impl Paging {
+    /// Returns the number of rows to move, for a viewport `height` rows
+    /// high.
+    ///
+    /// Always at least 1.
+    pub fn amount(self, height: usize) -> usize {
+        match self {
+            Paging::PageUp | Paging::PageDown => max(height, 1),
+            Paging::HalfPageUp | Paging::HalfPageDown => max(height / 2, 1),
+        }
+    }
+
+    /// Returns `true` if this movement goes up.
+    pub fn is_up(self) -> bool {
+        match self {
+            Paging::PageUp | Paging::HalfPageUp => true,
+            Paging::PageDown | Paging::HalfPageDown => false,
+        }
+    }
+}
+
+/// Keys moving by half a page in scrolling views.
+///
+/// `PageUp` and `PageDown` always move by a full page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageKeys {
+    /// Key moving up by half a page. Defaults to `Ctrl+U`.
+    pub half_page_up: Option<Event>,
+    /// Key moving down by half a page. Defaults to `Ctrl+D`.
+    pub half_page_down: Option<Event>,
+}
+
+impl Default for PageKeys {
+    fn default() -> Self {
+        PageKeys {
+            half_page_up: Some(Event::CtrlChar('u')),
+            half_page_down: Some(Event::CtrlChar('d')),
+        }
+    }
+}
+
+impl PageKeys {
+    /// Returns keys without any half-page binding.
+    pub fn none() -> Self {
+        PageKeys {
+            half_page_up: None,
+            half_page_down: None,
+        }
+    }
+
+    /// Returns the movement triggered by `event`, if any.
+    pub fn paging(&self, event: &Event) -> Option<Paging> {
+        match *event {
+            Event::Key(Key::PageUp) => return Some(Paging::PageUp),
+            Event::Key(Key::PageDown) => return Some(Paging::PageDown),
+            _ => (),
+        }
+
+        if self.half_page_up.as_ref() == Some(event) {
+            Some(Paging::HalfPageUp)
+        } else if self.half_page_down.as_ref() == Some(event) {
+            Some(Paging::HalfPageDown)
+        } else {
+            None
+        }
+    }
+}
+
 impl ScrollBase {
     /// Creates a new, uninitialized scrollbar.
     pub fn new() -> Self {
@@ -276,21 +357,28 @@ impl ScrollBase {
             let height = self.scrollbar_thumb_height();
             let start = self.scrollbar_thumb_y(height);
 
-            let color = if printer.focused {
-                ColorStyle::highlight()
+            let style = &printer.theme.scrollbar;
+            let thumb = if self.is_dragging() {
+                &style.grabbed_thumb
             } else {
-                ColorStyle::highlight_inactive()
+                &style.thumb
             };
 
             let scrollbar_x = self.scrollbar_x(printer.size.x);
             // eprintln!("Drawing bar at x={}", scrollbar_x);
 
             // The background
-            printer.print_vline((scrollbar_x, 0), printer.size.y, "|");
+            printer.with_color(style.track_style(), |printer| {
+                printer.print_vline(
+                    (scrollbar_x, 0),
+                    printer.size.y,
+                    &style.vertical_track,
+                );
+            });
 
             // The scrollbar thumb
-            printer.with_color(color, |printer| {
-                printer.print_vline((scrollbar_x, start), height, "▒");
+            printer.with_color(style.thumb_style(printer.focused), |printer| {
+                printer.print_vline((scrollbar_x, start), height, thumb);
             });
         }
     }
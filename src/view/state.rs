@@ -0,0 +1,35 @@
+use vec::Vec2;
+
+/// State of a view, saved to restore it later.
+///
+/// Returned by [`View::state`], and used by [`Cursive::dump`] to save the
+/// state of views with an id.
+///
+/// [`View::state`]: trait.View.html#method.state
+/// [`Cursive::dump`]: ../struct.Cursive.html#method.dump
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ViewState {
+    /// Content of a text input.
+    Text(String),
+    /// Whether a checkbox is checked.
+    Checked(bool),
+    /// Index of the selected item.
+    Selected(usize),
+    /// Scroll offset of the content.
+    Offset(Vec2),
+}
+
+/// Slot given to `call_on_any` callbacks to save or restore the state of
+/// views with an id.
+///
+/// It is only selected with `Selector::Type(TypeId::of::<IdState>())`:
+/// `IdView` then runs the callback on this instead of itself.
+pub(crate) struct IdState {
+    /// Id of the view.
+    pub id: String,
+    /// Current state of the view.
+    pub state: Option<ViewState>,
+    /// State to restore, set by the callback.
+    pub restore: Option<ViewState>,
+}
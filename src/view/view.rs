@@ -3,8 +3,9 @@ use event::{AnyCb, Event, EventResult};
 use rect::Rect;
 use std::any::Any;
 use vec::Vec2;
-use view::{AnyView, Selector};
+use view::{AnyView, ChildInfo, Selector, ViewState};
 use Printer;
+use XY;
 
 /// Main trait defining a view behaviour.
 ///
@@ -72,18 +73,34 @@ pub trait View: Any + AnyView {
     ///
     /// If the selector doesn't find a match, the closure will not be run.
     ///
-    /// Default implementation is a no-op.
-    fn call_on_any<'a>(&mut self, _: &Selector, _: AnyCb<'a>) {
+    /// Default implementation only runs the closure on this view, if it is
+    /// selected by its type. Views overriding this should do the same,
+    /// using [`Selector::selects`].
+    ///
+    /// [`Selector::selects`]: enum.Selector.html#method.selects
+    fn call_on_any<'a>(
+        &mut self, selector: &Selector, mut callback: AnyCb<'a>,
+    ) {
         // TODO: FnMut -> FnOnce once it works
+        if selector.selects(self.as_any()) {
+            callback(self.as_any_mut());
+        }
     }
 
     /// Moves the focus to the view identified by the given selector.
     ///
     /// Returns `Ok(())` if the view was found and selected.
     ///
-    /// Default implementation simply returns `Err(())`.
-    fn focus_view(&mut self, &Selector) -> Result<(), ()> {
-        Err(())
+    /// Default implementation only succeeds if this view is selected, by
+    /// its type or at the end of a path, and accepts the focus.
+    fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
+        if selector.selects(self.as_any())
+            && self.take_focus(Direction::none())
+        {
+            Ok(())
+        } else {
+            Err(())
+        }
     }
 
     /// This view is offered focus. Will it take it?
@@ -97,6 +114,21 @@ pub trait View: Any + AnyView {
         false
     }
 
+    /// This view is offered focus, coming from the given position.
+    ///
+    /// `position` is relative to this view, and may be outside of it, even
+    /// above or to the left. It is used when moving focus with the arrow
+    /// keys: containers should focus the child closest to it, translating
+    /// `position` to the child's coordinates.
+    ///
+    /// Default implementation ignores `position` and calls `take_focus`.
+    fn take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        let _ = position;
+        self.take_focus(source)
+    }
+
     /// What part of the view is important and should be visible?
     ///
     /// When only part of this view can be visible, this helps
@@ -108,4 +140,47 @@ pub trait View: Any + AnyView {
     fn important_area(&self, view_size: Vec2) -> Rect {
         Rect::from_size((0, 0), view_size)
     }
+
+    /// Returns the state of this view worth restoring later, if any.
+    ///
+    /// This is used by [`Cursive::dump`] for views with an id, so
+    /// applications can resume where the user left off.
+    ///
+    /// Default implementation returns `None`.
+    ///
+    /// [`Cursive::dump`]: ../struct.Cursive.html#method.dump
+    fn state(&self) -> Option<ViewState> {
+        None
+    }
+
+    /// Restores a state previously returned by `state`.
+    ///
+    /// States that don't apply to this view should be ignored.
+    ///
+    /// Restoring is silent: callbacks like `on_change` are not run.
+    ///
+    /// Default implementation does nothing.
+    fn restore_state(&mut self, state: &ViewState) {
+        let _ = state;
+    }
+
+    /// Returns the name of the concrete type of this view.
+    ///
+    /// This is mostly used for debugging purposes, like the view inspector.
+    fn type_name(&self) -> &'static str {
+        ::std::any::type_name::<Self>()
+    }
+
+    /// Lists the direct children of this view.
+    ///
+    /// This is used to inspect the view tree for debugging purposes.
+    ///
+    /// It is given the view size (same size given to `layout`).
+    /// Views containing other views should call `f` once for each child.
+    ///
+    /// Default implementation does nothing.
+    fn inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        let _ = size;
+        let _ = f;
+    }
 }
@@ -3,8 +3,9 @@ use event::{AnyCb, Event, EventResult};
 use rect::Rect;
 use std::any::Any;
 use vec::Vec2;
-use view::{Selector, View};
+use view::{AnyView, ChildInfo, Selector, View, ViewState};
 use Printer;
+use XY;
 
 /// Generic wrapper around a view.
 ///
@@ -77,6 +78,14 @@ pub trait ViewWrapper: 'static {
             .unwrap_or(false)
     }
 
+    /// Wraps the `take_focus_near` method.
+    fn wrap_take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        self.with_view_mut(|v| v.take_focus_near(source, position))
+            .unwrap_or(false)
+    }
+
     /// Wraps the `find` method.
     fn wrap_call_on_any<'a>(
         &mut self, selector: &Selector, callback: AnyCb<'a>,
@@ -100,6 +109,31 @@ pub trait ViewWrapper: 'static {
         self.with_view(|v| v.important_area(size))
             .unwrap_or_else(|| Rect::from((0, 0)))
     }
+
+    /// Wraps the `state` method.
+    fn wrap_state(&self) -> Option<ViewState> {
+        self.with_view(|v| v.state()).unwrap_or(None)
+    }
+
+    /// Wraps the `restore_state` method.
+    fn wrap_restore_state(&mut self, state: &ViewState) {
+        self.with_view_mut(|v| v.restore_state(state));
+    }
+
+    /// Wraps the `inspect_children` method.
+    ///
+    /// By default, reports the wrapped view as a single child covering
+    /// the entire area.
+    fn wrap_inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        self.with_view(|v| {
+            f(ChildInfo {
+                view: v.as_view(),
+                offset: Vec2::zero(),
+                size,
+                focused: true,
+            })
+        });
+    }
 }
 
 // The main point of implementing ViewWrapper is to have View for free.
@@ -124,9 +158,19 @@ impl<T: ViewWrapper> View for T {
         self.wrap_take_focus(source)
     }
 
+    fn take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        self.wrap_take_focus_near(source, position)
+    }
+
     fn call_on_any<'a>(
-        &mut self, selector: &Selector, callback: Box<FnMut(&mut Any) + 'a>,
+        &mut self, selector: &Selector,
+        mut callback: Box<FnMut(&mut Any) + 'a>,
     ) {
+        if selector.selects(self) {
+            callback(self);
+        }
         self.wrap_call_on_any(selector, callback)
     }
 
@@ -141,6 +185,18 @@ impl<T: ViewWrapper> View for T {
     fn important_area(&self, size: Vec2) -> Rect {
         self.wrap_important_area(size)
     }
+
+    fn state(&self) -> Option<ViewState> {
+        self.wrap_state()
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        self.wrap_restore_state(state)
+    }
+
+    fn inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        self.wrap_inspect_children(size, f)
+    }
 }
 
 /// Convenient macro to implement the [`ViewWrapper`] trait.
@@ -226,3 +282,42 @@ macro_rules! inner_getters {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::{EditView, TextView};
+
+    /// Wrapper overriding a single method.
+    struct Tall<T: View> {
+        view: T,
+    }
+
+    impl<T: View> ViewWrapper for Tall<T> {
+        wrap_impl!(self.view: T);
+
+        fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+            self.view.required_size(req) + (0, 2)
+        }
+    }
+
+    #[test]
+    fn forwarding() {
+        let mut tall = Tall {
+            view: TextView::new("Hello"),
+        };
+        assert_eq!(tall.required_size(Vec2::new(10, 10)), Vec2::new(5, 3));
+        assert!(!tall.take_focus(Direction::none()));
+
+        // Everything else reaches the wrapped view.
+        let mut tall = Tall {
+            view: EditView::new(),
+        };
+        assert!(tall.take_focus(Direction::none()));
+        tall.on_event(Event::Char('a'));
+        assert_eq!(&*tall.view.get_content(), "a");
+
+        let view = tall.into_inner().ok().unwrap();
+        assert_eq!(&*view.get_content(), "a");
+    }
+}
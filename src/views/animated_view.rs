@@ -0,0 +1,292 @@
+use event::{Event, EventResult};
+use std::time::{Duration, Instant};
+use transition::EasingFunction;
+use vec::Vec2;
+use view::{View, ViewWrapper};
+use {Printer, With};
+
+/// Value moving toward a target.
+struct Tween {
+    from: Vec2,
+    to: Vec2,
+    start: Instant,
+}
+
+impl Tween {
+    fn new(from: Vec2, to: Vec2) -> Self {
+        Tween {
+            from,
+            to,
+            start: Instant::now(),
+        }
+    }
+
+    fn fixed(value: Vec2) -> Self {
+        Tween::new(value, value)
+    }
+
+    /// Returns the elapsed fraction of the animation, from 0 to 1.
+    fn elapsed(&self, now: Instant, duration: Duration) -> f64 {
+        if self.from == self.to || duration == Duration::from_secs(0) {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.start);
+        (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0)
+    }
+
+    fn value(
+        &self, now: Instant, duration: Duration, easing: EasingFunction,
+    ) -> Vec2 {
+        let progress = easing.apply(self.elapsed(now, duration));
+        let mix = |a: usize, b: usize| {
+            (a as f64 + (b as f64 - a as f64) * progress).round() as usize
+        };
+        Vec2::new(mix(self.from.x, self.to.x), mix(self.from.y, self.to.y))
+    }
+
+    fn is_done(&self, now: Instant, duration: Duration) -> bool {
+        self.elapsed(now, duration) >= 1.0
+    }
+}
+
+/// Wrapper animating the size or offset of a view.
+///
+/// Changes of size or offset made with [`animate_size`] and
+/// [`animate_offset`] are interpolated over time, which makes it easy to
+/// expand panels or slide sidebars in.
+///
+/// While the size changes, the wrapped view is laid out at the largest of
+/// the two sizes, and cropped to the current one, so its content doesn't
+/// move around.
+///
+/// Cursive only redraws the screen after events: to see the animation run
+/// smoothly, set a frame rate with [`Cursive::set_fps`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::{AnimatedView, TextView};
+/// # use std::time::Duration;
+/// let mut sidebar = AnimatedView::new(TextView::new("Files"))
+///     .duration(Duration::from_millis(300))
+///     .with_size((0, 10));
+///
+/// // Later, to open the sidebar:
+/// sidebar.animate_size((20, 10));
+/// ```
+///
+/// [`animate_size`]: #method.animate_size
+/// [`animate_offset`]: #method.animate_offset
+/// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+pub struct AnimatedView<V> {
+    view: V,
+    // `None` to use the size required by the wrapped view.
+    size: Option<Tween>,
+    offset: Tween,
+    duration: Duration,
+    easing: EasingFunction,
+
+    // Size given by the last layout.
+    last_size: Vec2,
+    // Size given to the wrapped view.
+    child_size: Vec2,
+}
+
+impl<V> AnimatedView<V> {
+    /// Wraps `view` in a new `AnimatedView`.
+    ///
+    /// Animations last 200ms by default, with an `EaseInOut` curve.
+    pub fn new(view: V) -> Self {
+        AnimatedView {
+            view,
+            size: None,
+            offset: Tween::fixed(Vec2::zero()),
+            duration: Duration::from_millis(200),
+            easing: EasingFunction::EaseInOut,
+            last_size: Vec2::zero(),
+            child_size: Vec2::zero(),
+        }
+    }
+
+    /// Sets how long animations last.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    /// Sets how long animations last.
+    ///
+    /// Chainable variant.
+    pub fn duration(self, duration: Duration) -> Self {
+        self.with(|s| s.set_duration(duration))
+    }
+
+    /// Sets the curve followed by animations.
+    pub fn set_easing(&mut self, easing: EasingFunction) {
+        self.easing = easing;
+    }
+
+    /// Sets the curve followed by animations.
+    ///
+    /// Chainable variant.
+    pub fn easing(self, easing: EasingFunction) -> Self {
+        self.with(|s| s.set_easing(easing))
+    }
+
+    /// Sets the size of this view right away.
+    pub fn set_size<S: Into<Vec2>>(&mut self, size: S) {
+        self.size = Some(Tween::fixed(size.into()));
+    }
+
+    /// Sets the size of this view right away.
+    ///
+    /// Chainable variant.
+    pub fn with_size<S: Into<Vec2>>(self, size: S) -> Self {
+        self.with(|s| s.set_size(size))
+    }
+
+    /// Uses the size required by the wrapped view again, right away.
+    pub fn clear_size(&mut self) {
+        self.size = None;
+    }
+
+    /// Starts resizing this view toward `size`.
+    ///
+    /// The animation starts from the current size.
+    pub fn animate_size<S: Into<Vec2>>(&mut self, size: S) {
+        let from = self.current_size(Instant::now());
+        self.size = Some(Tween::new(from, size.into()));
+    }
+
+    /// Returns the size this view is moving toward, if any.
+    pub fn target_size(&self) -> Option<Vec2> {
+        self.size.as_ref().map(|size| size.to)
+    }
+
+    /// Shifts the wrapped view right away.
+    pub fn set_offset<S: Into<Vec2>>(&mut self, offset: S) {
+        self.offset = Tween::fixed(offset.into());
+    }
+
+    /// Starts shifting the wrapped view toward `offset`.
+    ///
+    /// The wrapped view is moved to the right and to the bottom, and
+    /// cropped to fit this view.
+    pub fn animate_offset<S: Into<Vec2>>(&mut self, offset: S) {
+        let from = self.current_offset();
+        self.offset = Tween::new(from, offset.into());
+    }
+
+    /// Returns the offset the wrapped view is moving toward.
+    pub fn target_offset(&self) -> Vec2 {
+        self.offset.to
+    }
+
+    /// Returns `true` if the size or offset is still changing.
+    pub fn is_animating(&self) -> bool {
+        let now = Instant::now();
+        !self.offset.is_done(now, self.duration)
+            || self
+                .size
+                .as_ref()
+                .is_some_and(|size| !size.is_done(now, self.duration))
+    }
+
+    fn current_size(&self, now: Instant) -> Vec2 {
+        match self.size {
+            Some(ref size) => size.value(now, self.duration, self.easing),
+            None => self.last_size,
+        }
+    }
+
+    fn current_offset(&self) -> Vec2 {
+        self.offset
+            .value(Instant::now(), self.duration, self.easing)
+    }
+
+    inner_getters!(self.view: V);
+}
+
+impl<V: View> ViewWrapper for AnimatedView<V> {
+    wrap_impl!(self.view: V);
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        match self.size {
+            Some(ref size) => {
+                size.value(Instant::now(), self.duration, self.easing)
+            }
+            None => self.view.required_size(req),
+        }
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        self.last_size = size;
+        self.child_size = match self.size {
+            // Don't reflow the content during the animation.
+            Some(ref tween) => {
+                Vec2::max(size, Vec2::max(tween.from, tween.to))
+            }
+            None => size,
+        };
+        self.view.layout(self.child_size);
+    }
+
+    fn wrap_needs_relayout(&self) -> bool {
+        self.is_animating() || self.view.needs_relayout()
+    }
+
+    fn wrap_on_event(&mut self, event: Event) -> EventResult {
+        let offset = self.current_offset();
+        self.view.on_event(event.relativized(offset))
+    }
+
+    fn wrap_draw(&self, printer: &Printer) {
+        let printer = printer
+            .offset(self.current_offset())
+            .inner_size(self.child_size);
+        self.view.draw(&printer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::DummyView;
+
+    #[test]
+    fn tween() {
+        let duration = Duration::from_millis(100);
+        let mut tween = Tween::new(Vec2::new(0, 10), Vec2::new(20, 0));
+        let linear = EasingFunction::Linear;
+
+        let start = tween.start;
+        assert_eq!(tween.value(start, duration, linear), Vec2::new(0, 10));
+        let half = start + duration / 2;
+        assert_eq!(tween.value(half, duration, linear), Vec2::new(10, 5));
+        assert!(!tween.is_done(half, duration));
+
+        tween.start = start - duration * 2;
+        assert_eq!(tween.value(start, duration, linear), Vec2::new(20, 0));
+        assert!(tween.is_done(start, duration));
+    }
+
+    #[test]
+    fn sizes() {
+        let mut view = AnimatedView::new(DummyView).with_size((0, 5));
+        assert!(!view.is_animating());
+        assert_eq!(view.required_size(Vec2::new(80, 24)), Vec2::new(0, 5));
+
+        view.set_duration(Duration::from_secs(3600));
+        view.animate_size((30, 5));
+        assert!(view.is_animating());
+        assert!(view.needs_relayout());
+        assert_eq!(view.target_size(), Some(Vec2::new(30, 5)));
+
+        // The content is laid out at its final size.
+        view.layout(Vec2::new(1, 5));
+        assert_eq!(view.child_size, Vec2::new(30, 5));
+
+        view.set_duration(Duration::from_secs(0));
+        assert!(!view.is_animating());
+        assert_eq!(view.required_size(Vec2::new(80, 24)), Vec2::new(30, 5));
+    }
+}
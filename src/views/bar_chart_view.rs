@@ -0,0 +1,195 @@
+use theme::{ColorStyle, ColorType, PaletteColor};
+use utils::bar::bar_string;
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Preferred width of the bars.
+///
+/// Bars are stretched or shrunk to fill the width given to the view.
+const DEFAULT_BAR_WIDTH: usize = 20;
+
+/// Horizontal bar chart.
+///
+/// Each bar takes one row, with its label on the left and, optionally, its
+/// value on the right. Bars are drawn with eighths of a cell precision.
+///
+/// Bars are scaled so that the maximum (by default the largest value) fills
+/// the available width. Negative values are drawn as empty bars.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::BarChartView;
+/// let chart = BarChartView::new()
+///     .bar("cpu", 42.0)
+///     .bar("memory", 78.5)
+///     .max(100.0);
+/// ```
+pub struct BarChartView {
+    bars: Vec<(String, f64)>,
+    max: Option<f64>,
+    show_values: bool,
+    color: ColorType,
+}
+
+new_default!(BarChartView);
+
+impl BarChartView {
+    /// Creates a new, empty chart.
+    pub fn new() -> Self {
+        BarChartView {
+            bars: Vec::new(),
+            max: None,
+            show_values: true,
+            color: ColorType::Palette(PaletteColor::Highlight),
+        }
+    }
+
+    /// Adds a bar to the chart.
+    pub fn add_bar<S: Into<String>>(&mut self, label: S, value: f64) {
+        self.bars.push((label.into(), value));
+    }
+
+    /// Adds a bar to the chart.
+    ///
+    /// Chainable variant.
+    pub fn bar<S: Into<String>>(self, label: S, value: f64) -> Self {
+        self.with(|s| s.add_bar(label, value))
+    }
+
+    /// Sets the value of the bar with the given label.
+    ///
+    /// Adds a new bar if no bar has this label.
+    pub fn set_value(&mut self, label: &str, value: f64) {
+        match self.bars.iter_mut().find(|bar| bar.0 == label) {
+            Some(bar) => bar.1 = value,
+            None => self.add_bar(label, value),
+        }
+    }
+
+    /// Returns the value of the bar with the given label, if any.
+    pub fn get_value(&self, label: &str) -> Option<f64> {
+        self.bars.iter().find(|bar| bar.0 == label).map(|bar| bar.1)
+    }
+
+    /// Removes all bars.
+    pub fn clear(&mut self) {
+        self.bars.clear();
+    }
+
+    /// Sets the value of a full bar.
+    ///
+    /// `None` (the default) uses the largest value.
+    pub fn set_max<V: Into<Option<f64>>>(&mut self, max: V) {
+        self.max = max.into();
+    }
+
+    /// Sets the value of a full bar.
+    ///
+    /// Chainable variant.
+    pub fn max<V: Into<Option<f64>>>(self, max: V) -> Self {
+        self.with(|s| s.set_max(max))
+    }
+
+    /// Shows or hides the values after the bars.
+    ///
+    /// Values are shown by default.
+    pub fn set_show_values(&mut self, show: bool) {
+        self.show_values = show;
+    }
+
+    /// Shows or hides the values after the bars.
+    ///
+    /// Chainable variant.
+    pub fn show_values(self, show: bool) -> Self {
+        self.with(|s| s.set_show_values(show))
+    }
+
+    /// Sets the color of the bars.
+    ///
+    /// The default color is `PaletteColor::Highlight`.
+    pub fn set_color<C: Into<ColorType>>(&mut self, color: C) {
+        self.color = color.into();
+    }
+
+    /// Sets the color of the bars.
+    ///
+    /// Chainable variant.
+    pub fn with_color<C: Into<ColorType>>(self, color: C) -> Self {
+        self.with(|s| s.set_color(color))
+    }
+
+    fn label_width(&self) -> usize {
+        self.bars.iter().map(|bar| bar.0.width()).max().unwrap_or(0)
+    }
+
+    /// Width of the values column, including the separating space.
+    fn value_width(&self) -> usize {
+        if !self.show_values {
+            return 0;
+        }
+        self.bars
+            .iter()
+            .map(|bar| bar.1.to_string().len() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn scale(&self) -> f64 {
+        self.max.unwrap_or_else(|| {
+            self.bars.iter().map(|bar| bar.1).fold(0.0, f64::max)
+        })
+    }
+}
+
+impl View for BarChartView {
+    fn draw(&self, printer: &Printer) {
+        let label_width = self.label_width();
+        let value_width = self.value_width();
+        let bar_width =
+            printer.size.x.saturating_sub(label_width + 1 + value_width);
+        let scale = self.scale();
+        let color = ColorStyle::new(self.color.clone(), PaletteColor::View);
+
+        for (y, &(ref label, value)) in self.bars.iter().enumerate() {
+            printer.print((label_width - label.width(), y), label);
+
+            let ratio = if scale > 0.0 { value / scale } else { 0.0 };
+            let bar = bar_string(ratio, bar_width);
+            printer.with_color(color.clone(), |printer| {
+                printer.print((label_width + 1, y), &bar);
+            });
+
+            if self.show_values {
+                let x = label_width + 1 + bar_width + 1;
+                printer.print((x, y), &value.to_string());
+            }
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let width = self.label_width() + 1 + DEFAULT_BAR_WIDTH;
+
+        Vec2::new(width + self.value_width(), self.bars.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values() {
+        let mut chart = BarChartView::new().bar("a", 1.0).bar("long", 2.5);
+        chart.set_value("a", 3.0);
+        chart.set_value("b", 4.0);
+
+        assert_eq!(chart.get_value("a"), Some(3.0));
+        assert_eq!(chart.scale(), 4.0);
+        assert_eq!(chart.label_width(), 4);
+        assert_eq!(chart.value_width(), 4);
+        assert_eq!(chart.required_size(Vec2::new(10, 10)), Vec2::new(29, 3));
+    }
+}
@@ -3,7 +3,7 @@ use direction::Direction;
 use event::*;
 use rect::Rect;
 use theme::ColorStyle;
-use unicode_width::UnicodeWidthStr;
+use utils::width::StrWidth;
 use vec::Vec2;
 use view::View;
 use {Cursive, Printer, With};
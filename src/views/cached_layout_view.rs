@@ -0,0 +1,249 @@
+use event::{AnyCb, Event, EventResult};
+use std::collections::VecDeque;
+use vec::Vec2;
+use view::{Selector, View, ViewWrapper};
+
+/// Wrapper around a view that skips unnecessary layout computations.
+///
+/// Results of `required_size` are cached for the last few constraints, and
+/// `layout` is skipped when given the same size as last time.
+///
+/// The cache is cleared when the wrapped view reports it
+/// [needs a relayout], when it consumes an event, or when it is accessed
+/// through `call_on_any` or `get_inner_mut`. If the content changes some
+/// other way the view doesn't report, call [`invalidate`].
+///
+/// This is most useful around deep view trees which rarely change, and
+/// which implement `needs_relayout`, like `LinearLayout` and `TextView`.
+///
+/// [needs a relayout]: ../view/trait.View.html#method.needs_relayout
+/// [`invalidate`]: #method.invalidate
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::{LinearLayout, TextView};
+/// # use cursive::traits::*;
+/// let view = LinearLayout::vertical()
+///     .child(TextView::new("Lots"))
+///     .child(TextView::new("of"))
+///     .child(TextView::new("views"))
+///     .cached_layout();
+/// ```
+pub struct CachedLayoutView<T: View> {
+    view: T,
+
+    // Results of `required_size` for the last constraints, oldest first.
+    sizes: VecDeque<(Vec2, Vec2)>,
+
+    // Size given to the last `layout`, if it is still valid.
+    last_size: Option<Vec2>,
+}
+
+/// Number of constraints remembered by `required_size`.
+const CACHE_SIZE: usize = 4;
+
+impl<T: View> CachedLayoutView<T> {
+    /// Wraps `view` in a new `CachedLayoutView`.
+    pub fn new(view: T) -> Self {
+        CachedLayoutView {
+            view,
+            sizes: VecDeque::with_capacity(CACHE_SIZE),
+            last_size: None,
+        }
+    }
+
+    /// Clears the cache, so the next layout is computed again.
+    pub fn invalidate(&mut self) {
+        self.sizes.clear();
+        self.last_size = None;
+    }
+
+    /// Gets access to the inner view.
+    pub fn get_inner(&self) -> &T {
+        &self.view
+    }
+
+    /// Gets mutable access to the inner view.
+    ///
+    /// This clears the cache.
+    pub fn get_inner_mut(&mut self) -> &mut T {
+        self.invalidate();
+        &mut self.view
+    }
+}
+
+impl<T: View> ViewWrapper for CachedLayoutView<T> {
+    wrap_impl!(self.view: T);
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        if self.view.needs_relayout() {
+            self.invalidate();
+        }
+        if let Some(&(_, size)) = self.sizes.iter().find(|&&(r, _)| r == req)
+        {
+            return size;
+        }
+
+        let size = self.view.required_size(req);
+        if self.sizes.len() == CACHE_SIZE {
+            self.sizes.pop_front();
+        }
+        self.sizes.push_back((req, size));
+
+        // The view may have changed its state for this new constraint.
+        self.last_size = None;
+        size
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        if self.last_size == Some(size) && !self.view.needs_relayout() {
+            return;
+        }
+
+        self.view.layout(size);
+        self.last_size = Some(size);
+    }
+
+    fn wrap_needs_relayout(&self) -> bool {
+        self.last_size.is_none() || self.view.needs_relayout()
+    }
+
+    fn wrap_on_event(&mut self, event: Event) -> EventResult {
+        let result = self.view.on_event(event);
+        if result.is_consumed() {
+            self.invalidate();
+        }
+        result
+    }
+
+    fn wrap_call_on_any<'a>(
+        &mut self, selector: &Selector, mut callback: AnyCb<'a>,
+    ) {
+        let mut called = false;
+        self.view.call_on_any(
+            selector,
+            Box::new(|any| {
+                called = true;
+                callback(any);
+            }),
+        );
+        // The callback may change anything.
+        if called {
+            self.invalidate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::Key;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use view::{Cacheable, Identifiable};
+    use Printer;
+
+    /// Counts calls to `required_size` and `layout`.
+    #[derive(Default)]
+    struct Counter {
+        calls: Rc<Cell<(usize, usize)>>,
+        // Content changed since the last layout, as with a `TextContent`.
+        changed: Rc<Cell<bool>>,
+    }
+
+    impl View for Counter {
+        fn draw(&self, _: &Printer) {}
+
+        fn needs_relayout(&self) -> bool {
+            self.changed.get()
+        }
+
+        fn required_size(&mut self, _: Vec2) -> Vec2 {
+            let (sizes, layouts) = self.calls.get();
+            self.calls.set((sizes + 1, layouts));
+            Vec2::new(3, 1)
+        }
+
+        fn layout(&mut self, _: Vec2) {
+            let (sizes, layouts) = self.calls.get();
+            self.calls.set((sizes, layouts + 1));
+            self.changed.set(false);
+        }
+
+        fn on_event(&mut self, event: Event) -> EventResult {
+            match event {
+                Event::Key(Key::Enter) => EventResult::Consumed(None),
+                _ => EventResult::Ignored,
+            }
+        }
+    }
+
+    #[test]
+    fn cache() {
+        let counter = Counter::default();
+        let calls = Rc::clone(&counter.calls);
+        let mut view = CachedLayoutView::new(counter);
+
+        for _ in 0..3 {
+            view.required_size(Vec2::new(10, 10));
+            view.layout(Vec2::new(10, 10));
+        }
+        assert_eq!(calls.get(), (1, 1));
+        assert!(!view.needs_relayout());
+
+        // A new constraint.
+        view.required_size(Vec2::new(5, 5));
+        view.layout(Vec2::new(10, 10));
+        assert_eq!(calls.get(), (2, 2));
+
+        // Ignored events don't change anything.
+        view.on_event(Event::Key(Key::Esc));
+        view.layout(Vec2::new(10, 10));
+        assert_eq!(calls.get(), (2, 2));
+
+        view.on_event(Event::Key(Key::Enter));
+        assert!(view.needs_relayout());
+        view.required_size(Vec2::new(10, 10));
+        view.layout(Vec2::new(10, 10));
+        assert_eq!(calls.get(), (3, 3));
+
+        view.invalidate();
+        view.layout(Vec2::new(10, 10));
+        assert_eq!(calls.get(), (3, 4));
+    }
+
+    #[test]
+    fn inner_changes() {
+        let counter = Counter::default();
+        let calls = Rc::clone(&counter.calls);
+        let changed = Rc::clone(&counter.changed);
+        let mut view = CachedLayoutView::new(counter.with_id("counter"));
+        view.required_size(Vec2::new(10, 10));
+        view.layout(Vec2::new(10, 10));
+
+        changed.set(true);
+        assert!(view.needs_relayout());
+        view.required_size(Vec2::new(10, 10));
+        view.layout(Vec2::new(10, 10));
+        assert_eq!(calls.get(), (2, 2));
+
+        // Looking for another view doesn't clear the cache.
+        view.call_on_any(
+            &Selector::Id("other"),
+            Box::new(|_| panic!("no such view")),
+        );
+        assert!(!view.needs_relayout());
+        view.call_on_any(&Selector::Id("counter"), Box::new(|_| ()));
+        assert!(view.needs_relayout());
+    }
+
+    #[test]
+    fn bounded_cache() {
+        let mut view = Counter::default().cached_layout();
+        for width in 0..100 {
+            view.required_size(Vec2::new(width, 10));
+        }
+        assert_eq!(view.sizes.len(), CACHE_SIZE);
+    }
+}
@@ -0,0 +1,314 @@
+use align::HAlign;
+use std::collections::HashSet;
+use theme::{ColorStyle, Style};
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Width of a month: 7 days of 2 cells, separated by spaces.
+const MONTH_WIDTH: usize = 20;
+
+/// Height of a month: title, weekdays, and up to 6 weeks.
+const MONTH_HEIGHT: usize = 8;
+
+/// Space between two months.
+const GAP: Vec2 = Vec2 { x: 2, y: 1 };
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Weekday abbreviations, starting on Monday.
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the day of the week, from 0 (Monday) to 6 (Sunday).
+fn weekday(year: i32, month: u32, day: u32) -> u32 {
+    // Sakamoto's method, which counts from Sunday.
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let year = if month < 3 { year - 1 } else { year };
+    let sunday_based = (year + year.div_euclid(4) - year.div_euclid(100)
+        + year.div_euclid(400)
+        + OFFSETS[month as usize - 1]
+        + day as i32)
+        .rem_euclid(7);
+    ((sunday_based + 6) % 7) as u32
+}
+
+/// Returns the month following the given one.
+fn next_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
+/// Read-only calendar showing one or more months.
+///
+/// Dates given by the application, for instance days with events, are
+/// highlighted.
+///
+/// Consecutive months are laid out side by side, and wrap to new rows when
+/// the view is too narrow.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::CalendarView;
+/// let calendar = CalendarView::new(2019, 1)
+///     .months(3)
+///     .highlight(2019, 1, 14)
+///     .highlight(2019, 2, 3);
+/// ```
+pub struct CalendarView {
+    /// First month shown, as `(year, month)`.
+    start: (i32, u32),
+    months: usize,
+    sunday_first: bool,
+    highlights: HashSet<(i32, u32, u32)>,
+    highlight_style: Style,
+}
+
+impl CalendarView {
+    /// Creates a new calendar showing the given month.
+    ///
+    /// `month` goes from 1 (January) to 12 (December).
+    ///
+    /// # Panics
+    ///
+    /// If `month` is not between 1 and 12.
+    pub fn new(year: i32, month: u32) -> Self {
+        CalendarView {
+            start: (year, 1),
+            months: 1,
+            sunday_first: false,
+            highlights: HashSet::new(),
+            highlight_style: ColorStyle::highlight().into(),
+        }
+        .with(|s| s.set_month(year, month))
+    }
+
+    /// Sets the first month shown.
+    ///
+    /// # Panics
+    ///
+    /// If `month` is not between 1 and 12.
+    pub fn set_month(&mut self, year: i32, month: u32) {
+        assert!((1..=12).contains(&month), "Invalid month: {}", month);
+        self.start = (year, month);
+    }
+
+    /// Returns the first month shown, as `(year, month)`.
+    pub fn month(&self) -> (i32, u32) {
+        self.start
+    }
+
+    /// Shows the months starting one month later.
+    pub fn next_month(&mut self) {
+        self.start = next_month(self.start.0, self.start.1);
+    }
+
+    /// Shows the months starting one month earlier.
+    pub fn previous_month(&mut self) {
+        self.start = match self.start {
+            (year, 1) => (year - 1, 12),
+            (year, month) => (year, month - 1),
+        };
+    }
+
+    /// Sets the number of consecutive months shown.
+    pub fn set_months(&mut self, months: usize) {
+        self.months = months.max(1);
+    }
+
+    /// Sets the number of consecutive months shown.
+    ///
+    /// Chainable variant.
+    pub fn months(self, months: usize) -> Self {
+        self.with(|s| s.set_months(months))
+    }
+
+    /// Starts weeks on Sunday instead of Monday.
+    pub fn set_sunday_first(&mut self, sunday_first: bool) {
+        self.sunday_first = sunday_first;
+    }
+
+    /// Starts weeks on Sunday instead of Monday.
+    ///
+    /// Chainable variant.
+    pub fn sunday_first(self, sunday_first: bool) -> Self {
+        self.with(|s| s.set_sunday_first(sunday_first))
+    }
+
+    /// Highlights the given date.
+    pub fn add_highlight(&mut self, year: i32, month: u32, day: u32) {
+        self.highlights.insert((year, month, day));
+    }
+
+    /// Highlights the given date.
+    ///
+    /// Chainable variant.
+    pub fn highlight(self, year: i32, month: u32, day: u32) -> Self {
+        self.with(|s| s.add_highlight(year, month, day))
+    }
+
+    /// Stops highlighting the given date.
+    pub fn remove_highlight(&mut self, year: i32, month: u32, day: u32) {
+        self.highlights.remove(&(year, month, day));
+    }
+
+    /// Stops highlighting any date.
+    pub fn clear_highlights(&mut self) {
+        self.highlights.clear();
+    }
+
+    /// Returns `true` if the given date is highlighted.
+    pub fn is_highlighted(&self, year: i32, month: u32, day: u32) -> bool {
+        self.highlights.contains(&(year, month, day))
+    }
+
+    /// Sets the style of highlighted dates.
+    ///
+    /// Defaults to the highlight color.
+    pub fn set_highlight_style<S: Into<Style>>(&mut self, style: S) {
+        self.highlight_style = style.into();
+    }
+
+    /// Sets the style of highlighted dates.
+    ///
+    /// Chainable variant.
+    pub fn highlight_style<S: Into<Style>>(self, style: S) -> Self {
+        self.with(|s| s.set_highlight_style(style))
+    }
+
+    /// Returns the number of months fitting side by side in `width`.
+    fn months_per_row(&self, width: usize) -> usize {
+        let fit = (width + GAP.x) / (MONTH_WIDTH + GAP.x);
+        fit.max(1).min(self.months)
+    }
+
+    /// Returns the column of the given day of the week.
+    fn column(&self, weekday: u32) -> usize {
+        if self.sunday_first {
+            ((weekday + 1) % 7) as usize
+        } else {
+            weekday as usize
+        }
+    }
+
+    fn draw_month(&self, printer: &Printer, year: i32, month: u32) {
+        let title = format!("{} {}", MONTH_NAMES[month as usize - 1], year);
+        let x = HAlign::Center.get_offset(title.len(), MONTH_WIDTH);
+        printer.print((x, 0), &title);
+
+        printer.with_color(ColorStyle::secondary(), |printer| {
+            for (i, name) in WEEKDAYS.iter().enumerate() {
+                let x = 3 * self.column(i as u32);
+                printer.print((x, 1), name);
+            }
+        });
+
+        let offset = self.column(weekday(year, month, 1));
+        for day in 1..=days_in_month(year, month) {
+            let cell = offset + day as usize - 1;
+            let pos = (3 * (cell % 7), 2 + cell / 7);
+            let text = format!("{:>2}", day);
+            if self.is_highlighted(year, month, day) {
+                printer.with_style(self.highlight_style.clone(), |printer| {
+                    printer.print(pos, &text);
+                });
+            } else {
+                printer.print(pos, &text);
+            }
+        }
+    }
+}
+
+impl View for CalendarView {
+    fn draw(&self, printer: &Printer) {
+        let per_row = self.months_per_row(printer.size.x);
+        let (mut year, mut month) = self.start;
+
+        for i in 0..self.months {
+            let pos = Vec2::new(
+                (i % per_row) * (MONTH_WIDTH + GAP.x),
+                (i / per_row) * (MONTH_HEIGHT + GAP.y),
+            );
+            self.draw_month(&printer.offset(pos), year, month);
+
+            let next = next_month(year, month);
+            year = next.0;
+            month = next.1;
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let per_row = self.months_per_row(constraint.x);
+        let rows = self.months.div_ceil(per_row);
+
+        Vec2::new(
+            per_row * (MONTH_WIDTH + GAP.x) - GAP.x,
+            rows * (MONTH_HEIGHT + GAP.y) - GAP.y,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        assert_eq!(weekday(2019, 1, 1), 1);
+        assert_eq!(weekday(2000, 2, 29), 1);
+        assert_eq!(weekday(2024, 12, 29), 6);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(next_month(2018, 12), (2019, 1));
+    }
+
+    #[test]
+    fn layout() {
+        let mut view = CalendarView::new(2019, 11).months(3);
+        assert_eq!(view.required_size(Vec2::new(80, 30)), Vec2::new(64, 8));
+        assert_eq!(view.required_size(Vec2::new(50, 30)), Vec2::new(42, 17));
+
+        view.next_month();
+        view.next_month();
+        assert_eq!(view.month(), (2020, 1));
+        view.previous_month();
+        assert_eq!(view.month(), (2019, 12));
+    }
+
+    #[test]
+    fn highlights() {
+        let mut view = CalendarView::new(2019, 1).highlight(2019, 1, 14);
+        assert!(view.is_highlighted(2019, 1, 14));
+        view.remove_highlight(2019, 1, 14);
+        assert!(!view.is_highlighted(2019, 1, 14));
+    }
+}
@@ -271,7 +271,10 @@ impl<T: 'static> View for Canvas<T> {
         (self.important_area)(&self.state, view_size)
     }
 
-    fn call_on_any<'a>(&mut self, selector: &Selector, cb: AnyCb<'a>) {
+    fn call_on_any<'a>(&mut self, selector: &Selector, mut cb: AnyCb<'a>) {
+        if selector.selects(self) {
+            cb(self);
+        }
         (self.call_on_any)(&mut self.state, selector, cb);
     }
 }
@@ -2,17 +2,34 @@ use direction::Direction;
 use event::{Event, EventResult, Key, MouseButton, MouseEvent};
 use std::rc::Rc;
 use theme::ColorStyle;
+use utils::width::StrWidth;
 use vec::Vec2;
-use view::View;
+use view::{View, ViewState};
 use Cursive;
 use Printer;
 use With;
 
-/// Checkable box.
+/// Checkable box, with an optional label.
+///
+/// A checkbox can also be in an indeterminate state, for instance when it
+/// controls a group of options which are not all checked. Toggling it
+/// then checks it.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::Checkbox;
+/// let checkbox = Checkbox::labeled("Select all")
+///     .indeterminate()
+///     .on_change(|_, checked| println!("Checked: {}", checked));
+/// ```
 pub struct Checkbox {
     checked: bool,
+    indeterminate: bool,
     enabled: bool,
 
+    label: String,
+
     on_change: Option<Rc<Fn(&mut Cursive, bool)>>,
 }
 
@@ -25,11 +42,56 @@ impl Checkbox {
     pub fn new() -> Self {
         Checkbox {
             checked: false,
+            indeterminate: false,
             enabled: true,
+            label: String::new(),
             on_change: None,
         }
     }
 
+    /// Creates a new, unchecked checkbox with a label.
+    ///
+    /// Clicking the label also toggles the checkbox.
+    pub fn labeled<S: Into<String>>(label: S) -> Self {
+        Self::new().label(label)
+    }
+
+    /// Sets the label shown after the box.
+    pub fn set_label<S: Into<String>>(&mut self, label: S) {
+        self.label = label.into();
+    }
+
+    /// Sets the label shown after the box.
+    ///
+    /// Chainable variant.
+    pub fn label<S: Into<String>>(self, label: S) -> Self {
+        self.with(|s| s.set_label(label))
+    }
+
+    /// Returns the label shown after the box.
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+
+    /// Sets the checkbox in the indeterminate state.
+    ///
+    /// It is shown as `[-]` until it is checked or unchecked.
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        self.indeterminate = indeterminate;
+    }
+
+    /// Sets the checkbox in the indeterminate state.
+    ///
+    /// Chainable variant.
+    pub fn indeterminate(self) -> Self {
+        self.with(|s| s.set_indeterminate(true))
+    }
+
+    /// Returns `true` if the checkbox is in the indeterminate state.
+    pub fn is_indeterminate(&self) -> bool {
+        self.indeterminate
+    }
+
     /// Sets a callback to be used when the state changes.
     pub fn set_on_change<F: 'static + Fn(&mut Cursive, bool)>(
         &mut self, on_change: F,
@@ -47,8 +109,10 @@ impl Checkbox {
     }
 
     /// Toggles the checkbox state.
+    ///
+    /// An indeterminate checkbox becomes checked.
     pub fn toggle(&mut self) -> EventResult {
-        let checked = !self.checked;
+        let checked = self.indeterminate || !self.checked;
         self.set_checked(checked)
     }
 
@@ -67,6 +131,8 @@ impl Checkbox {
     }
 
     /// Returns `true` if the checkbox is checked.
+    ///
+    /// This ignores the indeterminate state.
     pub fn is_checked(&self) -> bool {
         self.checked
     }
@@ -86,8 +152,11 @@ impl Checkbox {
     }
 
     /// Sets the checkbox state.
+    ///
+    /// This leaves the indeterminate state.
     pub fn set_checked(&mut self, checked: bool) -> EventResult {
         self.checked = checked;
+        self.indeterminate = false;
         if let Some(ref on_change) = self.on_change {
             let on_change = Rc::clone(on_change);
             EventResult::with_cb(move |s| on_change(s, checked))
@@ -98,22 +167,48 @@ impl Checkbox {
 
     fn draw_internal(&self, printer: &Printer) {
         printer.print((0, 0), "[ ]");
-        if self.checked {
+        if self.indeterminate {
+            printer.print((1, 0), "-");
+        } else if self.checked {
             printer.print((1, 0), "X");
         }
     }
+
+    fn size(&self) -> Vec2 {
+        if self.label.is_empty() {
+            Vec2::new(3, 1)
+        } else {
+            Vec2::new(4 + self.label.width(), 1)
+        }
+    }
 }
 
 impl View for Checkbox {
     fn required_size(&mut self, _: Vec2) -> Vec2 {
-        Vec2::new(3, 1)
+        self.size()
     }
 
     fn take_focus(&mut self, _: Direction) -> bool {
         self.enabled
     }
 
+    fn state(&self) -> Option<ViewState> {
+        Some(ViewState::Checked(self.checked))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        // Like `set_checked`, but without running `on_change`.
+        if let ViewState::Checked(checked) = *state {
+            self.checked = checked;
+            self.indeterminate = false;
+        }
+    }
+
     fn draw(&self, printer: &Printer) {
+        if !self.label.is_empty() {
+            printer.print((4, 0), &self.label);
+        }
+
         if self.enabled && printer.enabled {
             printer.with_selection(printer.focused, |printer| {
                 self.draw_internal(printer)
@@ -132,8 +227,57 @@ impl View for Checkbox {
                 event: MouseEvent::Release(MouseButton::Left),
                 position,
                 offset,
-            } if position.fits_in_rect(offset, (3, 1)) => self.toggle(),
+            } if position.fits_in_rect(offset, self.size()) => self.toggle(),
             _ => EventResult::Ignored,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn label_and_states() {
+        let changes = Rc::new(Cell::new(None));
+        let last = Rc::clone(&changes);
+
+        let mut checkbox = Checkbox::labeled("Select all")
+            .indeterminate()
+            .on_change(move |_, checked| last.set(Some(checked)));
+        assert_eq!(checkbox.required_size(Vec2::zero()), Vec2::new(14, 1));
+        assert!(checkbox.is_indeterminate());
+
+        // Clicking the label checks the box.
+        let mut siv = Cursive::dummy();
+        checkbox
+            .on_event(Event::Mouse {
+                offset: Vec2::zero(),
+                position: Vec2::new(10, 0),
+                event: MouseEvent::Release(MouseButton::Left),
+            })
+            .process(&mut siv);
+        assert!(checkbox.is_checked());
+        assert!(!checkbox.is_indeterminate());
+        assert_eq!(changes.get(), Some(true));
+
+        checkbox.on_event(Event::Char(' ')).process(&mut siv);
+        assert_eq!(changes.get(), Some(false));
+    }
+
+    #[test]
+    fn restore() {
+        let changes = Rc::new(Cell::new(0));
+        let count = Rc::clone(&changes);
+
+        let mut checkbox = Checkbox::new()
+            .indeterminate()
+            .on_change(move |_, _| count.set(count.get() + 1));
+        checkbox.restore_state(&ViewState::Checked(true));
+        assert!(checkbox.is_checked());
+        assert!(!checkbox.is_indeterminate());
+        assert_eq!(checkbox.state(), Some(ViewState::Checked(true)));
+        assert_eq!(changes.get(), 0);
+    }
+}
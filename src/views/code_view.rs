@@ -0,0 +1,178 @@
+use std::cmp::max;
+use theme::ColorStyle;
+use utils::markup::{syntax, StyledString};
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Displays syntax-highlighted source code.
+///
+/// Requires the `syntect` feature.
+///
+/// Lines are not wrapped: wrap this view in a [`ScrollView`] to display
+/// long content.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::CodeView;
+/// let view = CodeView::new("fn main() {}", "rs").line_numbers(true);
+/// ```
+///
+/// [`ScrollView`]: struct.ScrollView.html
+pub struct CodeView {
+    code: String,
+    language: String,
+    theme: String,
+    show_line_numbers: bool,
+
+    /// Highlighted lines, updated when the code, language or theme change.
+    lines: Vec<StyledString>,
+}
+
+impl CodeView {
+    /// Creates a new view showing the given code.
+    ///
+    /// `language` can be a language name (like `"Rust"`) or a file extension
+    /// (like `"rs"`). Unknown languages are shown as plain text.
+    pub fn new<S, L>(code: S, language: L) -> Self
+    where
+        S: Into<String>,
+        L: Into<String>,
+    {
+        let mut view = CodeView {
+            code: code.into(),
+            language: language.into(),
+            theme: syntax::DEFAULT_THEME.to_string(),
+            show_line_numbers: false,
+            lines: Vec::new(),
+        };
+        view.highlight();
+        view
+    }
+
+    /// Replaces the code shown by this view.
+    pub fn set_content<S: Into<String>>(&mut self, code: S) {
+        self.code = code.into();
+        self.highlight();
+    }
+
+    /// Replaces the code shown by this view.
+    ///
+    /// Chainable variant.
+    pub fn content<S: Into<String>>(self, code: S) -> Self {
+        self.with(|s| s.set_content(code))
+    }
+
+    /// Returns the code shown by this view.
+    pub fn get_content(&self) -> &str {
+        &self.code
+    }
+
+    /// Sets the language used for highlighting.
+    pub fn set_language<S: Into<String>>(&mut self, language: S) {
+        self.language = language.into();
+        self.highlight();
+    }
+
+    /// Sets the language used for highlighting.
+    ///
+    /// Chainable variant.
+    pub fn language<S: Into<String>>(self, language: S) -> Self {
+        self.with(|s| s.set_language(language))
+    }
+
+    /// Sets the highlighting theme.
+    ///
+    /// See [`syntax::themes()`] for the available themes. Unknown themes
+    /// fall back to [`syntax::DEFAULT_THEME`].
+    ///
+    /// [`syntax::themes()`]: ../utils/markup/syntax/fn.themes.html
+    /// [`syntax::DEFAULT_THEME`]:
+    /// ../utils/markup/syntax/constant.DEFAULT_THEME.html
+    pub fn set_theme<S: Into<String>>(&mut self, theme: S) {
+        self.theme = theme.into();
+        self.highlight();
+    }
+
+    /// Sets the highlighting theme.
+    ///
+    /// Chainable variant.
+    pub fn theme<S: Into<String>>(self, theme: S) -> Self {
+        self.with(|s| s.set_theme(theme))
+    }
+
+    /// Shows or hides line numbers in a left gutter.
+    pub fn set_line_numbers(&mut self, show: bool) {
+        self.show_line_numbers = show;
+    }
+
+    /// Shows or hides line numbers in a left gutter.
+    ///
+    /// Chainable variant.
+    pub fn line_numbers(self, show: bool) -> Self {
+        self.with(|s| s.set_line_numbers(show))
+    }
+
+    /// Returns the number of lines of code.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn highlight(&mut self) {
+        self.lines =
+            syntax::highlight_lines(&self.code, &self.language, &self.theme);
+    }
+
+    /// Width of the line numbers, including the separating space.
+    fn gutter_width(&self) -> usize {
+        if self.show_line_numbers {
+            max(self.lines.len(), 1).to_string().len() + 1
+        } else {
+            0
+        }
+    }
+}
+
+impl View for CodeView {
+    fn draw(&self, printer: &Printer) {
+        let gutter = self.gutter_width();
+
+        for (y, line) in self.lines.iter().enumerate().take(printer.size.y) {
+            if gutter > 0 {
+                let number = format!("{:>1$}", y + 1, gutter - 1);
+                printer.with_color(ColorStyle::secondary(), |printer| {
+                    printer.print((0, y), &number);
+                });
+            }
+            printer
+                .offset((gutter, 0))
+                .print_styled((0, y), line.into());
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let width = self.lines.iter().map(|line| line.width()).max();
+
+        Vec2::new(self.gutter_width() + width.unwrap_or(0), self.lines.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_size() {
+        let code = (1..=12)
+            .map(|i| format!("let x{} = {};", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut view = CodeView::new(code, "rs");
+        assert_eq!(view.line_count(), 12);
+        assert_eq!(view.required_size(Vec2::zero()), Vec2::new(13, 12));
+
+        view.set_line_numbers(true);
+        assert_eq!(view.required_size(Vec2::zero()), Vec2::new(16, 12));
+    }
+}
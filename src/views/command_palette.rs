@@ -0,0 +1,205 @@
+use event::{Callback, Event, EventResult, Key};
+use view::{View, ViewWrapper};
+use views::{FuzzyFinder, Panel};
+use {Cursive, With};
+
+/// Searchable list of commands.
+///
+/// Typing filters the commands with a fuzzy search on their name; the best
+/// matches come first. `Enter` runs the selected command.
+///
+/// The palette is usually opened as a layer with a keyboard shortcut,
+/// using [`install`]. In that case, running a command or pressing `Esc`
+/// closes the layer.
+///
+/// [`install`]: #method.install
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::event::Event;
+/// # use cursive::views::CommandPalette;
+/// let mut siv = Cursive::dummy();
+///
+/// CommandPalette::new()
+///     .command("Quit", "Exit the application", |s| s.quit())
+///     .command("Theme: dark", "Use a dark theme", |_| ())
+///     .install(&mut siv, Event::CtrlChar('p'));
+/// ```
+#[derive(Clone)]
+pub struct CommandPalette {
+    finder: FuzzyFinder<Callback>,
+
+    /// `true` if this palette was opened as a layer.
+    layer: bool,
+}
+
+new_default!(CommandPalette);
+
+impl CommandPalette {
+    /// Creates a new, empty command palette.
+    pub fn new() -> Self {
+        CommandPalette {
+            finder: FuzzyFinder::new(),
+            layer: false,
+        }
+    }
+
+    /// Adds a command to this palette.
+    ///
+    /// `cb` is run when the command is selected.
+    pub fn add_command<S, D, F>(&mut self, name: S, description: D, cb: F)
+    where
+        S: Into<String>,
+        D: Into<String>,
+        F: 'static + Fn(&mut Cursive),
+    {
+        self.finder
+            .add_described_item(name, description, Callback::from_fn(cb));
+    }
+
+    /// Adds a command to this palette.
+    ///
+    /// Chainable variant.
+    pub fn command<S, D, F>(self, name: S, description: D, cb: F) -> Self
+    where
+        S: Into<String>,
+        D: Into<String>,
+        F: 'static + Fn(&mut Cursive),
+    {
+        self.with(|s| s.add_command(name, description, cb))
+    }
+
+    /// Returns the number of registered commands.
+    pub fn len(&self) -> usize {
+        self.finder.len()
+    }
+
+    /// Returns `true` if no command is registered.
+    pub fn is_empty(&self) -> bool {
+        self.finder.is_empty()
+    }
+
+    /// Returns the current query.
+    pub fn query(&self) -> &str {
+        self.finder.query()
+    }
+
+    /// Replaces the query, and selects the best match.
+    pub fn set_query<S: Into<String>>(&mut self, query: S) {
+        self.finder.set_query(query);
+    }
+
+    /// Returns the names of the commands matching the query, best first.
+    pub fn matches(&self) -> Vec<&str> {
+        self.finder.matches()
+    }
+
+    /// Returns the name of the selected command, if any.
+    pub fn selected(&self) -> Option<&str> {
+        self.finder.selected_label()
+    }
+
+    /// Opens a copy of this palette, with an empty query, in a new layer.
+    pub fn open(&self, siv: &mut Cursive) {
+        let palette = CommandPalette {
+            layer: true,
+            ..self.clone()
+        }
+        .with(|s| s.set_query(""));
+        siv.add_layer(Panel::new(palette).title("Commands"));
+    }
+
+    /// Opens this palette in a new layer when `shortcut` is triggered.
+    pub fn install<E: Into<Event>>(self, siv: &mut Cursive, shortcut: E) {
+        siv.add_global_callback(shortcut, move |s| self.open(s));
+    }
+
+    fn submit(&self) -> EventResult {
+        let callback = match self.finder.selection() {
+            Some(callback) => Callback::clone(&callback),
+            None => return EventResult::Consumed(None),
+        };
+        let layer = self.layer;
+
+        EventResult::with_cb(move |s| {
+            if layer {
+                s.pop_layer();
+            }
+            callback(s);
+        })
+    }
+}
+
+impl ViewWrapper for CommandPalette {
+    wrap_impl!(self.finder: FuzzyFinder<Callback>);
+
+    fn wrap_on_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Enter) => self.submit(),
+            Event::Key(Key::Esc) if self.layer => {
+                EventResult::with_cb(|s| {
+                    s.pop_layer();
+                })
+            }
+            event => self.finder.on_event(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use vec::Vec2;
+
+    #[test]
+    fn filtering() {
+        let mut palette = CommandPalette::new()
+            .command("Open file", "", |_| ())
+            .command("Save file", "", |_| ())
+            .command("Profile", "", |_| ());
+        palette.layout(Vec2::new(30, 10));
+        assert_eq!(palette.matches().len(), 3);
+
+        for c in "of".chars() {
+            palette.on_event(Event::Char(c));
+        }
+        assert_eq!(palette.matches(), vec!["Open file", "Profile"]);
+        assert_eq!(palette.selected(), Some("Open file"));
+
+        palette.on_event(Event::Key(Key::Down));
+        palette.on_event(Event::Key(Key::Down));
+        assert_eq!(palette.selected(), Some("Profile"));
+
+        palette.on_event(Event::Key(Key::Backspace));
+        assert_eq!(palette.query(), "o");
+        assert_eq!(palette.selected(), Some("Open file"));
+
+        palette.set_query("xyz");
+        assert_eq!(palette.selected(), None);
+    }
+
+    #[test]
+    fn open_and_run() {
+        let runs = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&runs);
+
+        let mut siv = Cursive::dummy();
+        CommandPalette::new()
+            .command("Count", "Increment the counter", move |_| {
+                counter.set(counter.get() + 1)
+            })
+            .install(&mut siv, Event::CtrlChar('p'));
+
+        siv.on_event(Event::CtrlChar('p'));
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+
+        siv.on_event(Event::Char('c'));
+        siv.on_event(Event::Key(Key::Enter));
+        assert_eq!(runs.get(), 1);
+        assert!(siv.screen().layer_sizes().is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use backend::buffer::PrintBuffer;
+use direction::Direction;
+use event::{Event, EventResult, Key, MouseButton, MouseEvent};
+use std::cmp::{max, min};
+use theme::Effect;
+use vec::Vec2;
+use view::View;
+use Printer;
+
+/// Shows a capture of the screen, to select and copy some text.
+///
+/// Terminals select text across borders and columns; this view instead
+/// selects from the rendered screen, either line by line or as a rectangle.
+///
+/// Usually opened with [`Cursive::enter_copy_mode`].
+///
+/// Keys:
+///
+/// * Arrows, `Home` and `End` move the cursor.
+/// * `v` or `Space` starts (or cancels) the selection at the cursor.
+/// * `r` switches between line and rectangular selection.
+/// * `Enter` or `y` copies the selection and leaves copy mode.
+/// * `Esc` or `q` leaves without copying.
+///
+/// The mouse can also select text: the selection is copied when the button
+/// is released.
+///
+/// [`Cursive::enter_copy_mode`]: ../struct.Cursive.html#method.enter_copy_mode
+pub struct CopyMode {
+    buffer: PrintBuffer,
+    cursor: Vec2,
+    /// Start of the selection, if any.
+    anchor: Option<Vec2>,
+    rectangular: bool,
+}
+
+impl CopyMode {
+    /// Creates a new copy mode on the given capture.
+    pub fn new(buffer: PrintBuffer) -> Self {
+        CopyMode {
+            buffer,
+            cursor: Vec2::zero(),
+            anchor: None,
+            rectangular: false,
+        }
+    }
+
+    /// Returns the position of the cursor.
+    pub fn cursor(&self) -> Vec2 {
+        self.cursor
+    }
+
+    /// Moves the cursor, and extends the selection if any.
+    pub fn set_cursor<S: Into<Vec2>>(&mut self, cursor: S) {
+        let max = self.buffer.size().saturating_sub((1, 1));
+        self.cursor = cursor.into().or_min(max);
+    }
+
+    /// Starts a selection at the cursor.
+    pub fn start_selection(&mut self) {
+        self.anchor = Some(self.cursor);
+    }
+
+    /// Sets whether the selection is a rectangle, instead of a range of
+    /// lines.
+    pub fn set_rectangular(&mut self, rectangular: bool) {
+        self.rectangular = rectangular;
+    }
+
+    /// Returns `true` if the selection is a rectangle.
+    pub fn is_rectangular(&self) -> bool {
+        self.rectangular
+    }
+
+    /// Returns the selected text.
+    ///
+    /// Trailing spaces are removed from each line.
+    pub fn selection(&self) -> String {
+        let anchor = match self.anchor {
+            Some(anchor) => anchor,
+            None => return String::new(),
+        };
+
+        (min(anchor.y, self.cursor.y)..=max(anchor.y, self.cursor.y))
+            .map(|y| {
+                let (start, end) = self.columns(anchor, y);
+                self.buffer.text(y, start, end).trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the selected columns on row `y`, the end being excluded.
+    fn columns(&self, anchor: Vec2, y: usize) -> (usize, usize) {
+        if self.rectangular {
+            let start = min(anchor.x, self.cursor.x);
+            return (start, max(anchor.x, self.cursor.x) + 1);
+        }
+
+        // From the first position to the last one, in reading order.
+        let (first, last) =
+            if (anchor.y, anchor.x) <= (self.cursor.y, self.cursor.x) {
+                (anchor, self.cursor)
+            } else {
+                (self.cursor, anchor)
+            };
+        let start = if y == first.y { first.x } else { 0 };
+        let end = if y == last.y {
+            last.x + 1
+        } else {
+            self.buffer.size().x
+        };
+        (start, end)
+    }
+
+    fn is_selected(&self, position: Vec2) -> bool {
+        let anchor = match self.anchor {
+            Some(anchor) => anchor,
+            None => return position == self.cursor,
+        };
+
+        if position.y < min(anchor.y, self.cursor.y)
+            || position.y > max(anchor.y, self.cursor.y)
+        {
+            return false;
+        }
+        let (start, end) = self.columns(anchor, position.y);
+        position.x >= start && position.x < end
+    }
+
+    fn copy(&self) -> EventResult {
+        let text = self.selection();
+        EventResult::with_cb(move |s| {
+            s.pop_layer();
+            if !text.is_empty() {
+                s.set_clipboard(text.as_str());
+            }
+        })
+    }
+
+    fn move_cursor(&mut self, key: Key) {
+        let size = self.buffer.size();
+        let cursor = self.cursor;
+        let cursor = match key {
+            Key::Left => cursor.saturating_sub((1, 0)),
+            Key::Right => cursor + (1, 0),
+            Key::Up => cursor.saturating_sub((0, 1)),
+            Key::Down => cursor + (0, 1),
+            Key::Home => Vec2::new(0, cursor.y),
+            Key::End => Vec2::new(size.x, cursor.y),
+            _ => cursor,
+        };
+        self.set_cursor(cursor);
+    }
+}
+
+impl View for CopyMode {
+    fn draw(&self, printer: &Printer) {
+        printer.print_buffer((0, 0), &self.buffer);
+
+        let size = self.buffer.size();
+        printer.with_effect(Effect::Reverse, |printer| {
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    if self.is_selected(Vec2::new(x, y)) {
+                        let text = self.buffer.text(y, x, x + 1);
+                        printer.print((x, y), &text);
+                    }
+                }
+            }
+        });
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        self.buffer.size()
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(key @ Key::Left)
+            | Event::Key(key @ Key::Right)
+            | Event::Key(key @ Key::Up)
+            | Event::Key(key @ Key::Down)
+            | Event::Key(key @ Key::Home)
+            | Event::Key(key @ Key::End) => self.move_cursor(key),
+            Event::Char('v') | Event::Char(' ') => {
+                if self.anchor.is_some() {
+                    self.anchor = None;
+                } else {
+                    self.start_selection();
+                }
+            }
+            Event::Char('r') => self.rectangular = !self.rectangular,
+            Event::Key(Key::Enter) | Event::Char('y') => return self.copy(),
+            Event::Key(Key::Esc) | Event::Char('q') => {
+                return EventResult::with_cb(|s| {
+                    s.pop_layer();
+                });
+            }
+            Event::Mouse {
+                event: MouseEvent::Press(MouseButton::Left),
+                position,
+                offset,
+            } => {
+                if let Some(position) = position.checked_sub(offset) {
+                    self.set_cursor(position);
+                    self.start_selection();
+                }
+            }
+            Event::Mouse {
+                event: MouseEvent::Hold(MouseButton::Left),
+                position,
+                offset,
+            } if self.anchor.is_some() => {
+                self.set_cursor(position.saturating_sub(offset));
+            }
+            Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                ..
+            } if self.anchor.is_some_and(|anchor| anchor != self.cursor) => {
+                return self.copy();
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme;
+
+    fn capture() -> CopyMode {
+        let theme = theme::load_default();
+        let buffer = PrintBuffer::new((12, 3));
+        {
+            let printer = buffer.printer(&theme);
+            printer.print((0, 0), "│ first   │");
+            printer.print((0, 1), "│ second  │");
+            printer.print((0, 2), "│ third   │");
+        }
+        CopyMode::new(buffer)
+    }
+
+    #[test]
+    fn lines() {
+        let mut copy = capture();
+        assert_eq!(copy.selection(), "");
+
+        copy.set_cursor((4, 0));
+        copy.on_event(Event::Char('v'));
+        copy.set_cursor((3, 1));
+        assert_eq!(copy.selection(), "rst   │\n│ se");
+
+        // Selecting backward gives the same text.
+        copy.anchor = Some(Vec2::new(3, 1));
+        copy.set_cursor((4, 0));
+        assert_eq!(copy.selection(), "rst   │\n│ se");
+    }
+
+    #[test]
+    fn rectangle() {
+        let mut copy = capture();
+        copy.set_cursor((2, 0));
+        copy.start_selection();
+        copy.on_event(Event::Char('r'));
+        for _ in 0..2 {
+            copy.on_event(Event::Key(Key::Down));
+        }
+        for _ in 0..5 {
+            copy.on_event(Event::Key(Key::Right));
+        }
+        assert_eq!(copy.cursor(), Vec2::new(7, 2));
+        assert_eq!(copy.selection(), "first\nsecond\nthird");
+    }
+}
@@ -0,0 +1,85 @@
+use log;
+use logger;
+use std::time::{SystemTime, UNIX_EPOCH};
+use theme::{BaseColor, Color, ColorStyle, PaletteColor};
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use Printer;
+
+/// Width of the `HH:MM:SS.mmm | [LEVEL] ` prefix.
+const PREFIX_WIDTH: usize = 23;
+
+/// View used for debugging, showing logs.
+///
+/// Displays the records collected by the [`logger`] module.
+///
+/// [`logger`]: ../logger/index.html
+pub struct DebugView;
+
+new_default!(DebugView);
+
+impl DebugView {
+    /// Creates a new `DebugView`.
+    pub fn new() -> Self {
+        DebugView
+    }
+}
+
+/// Formats a time of day as `HH:MM:SS.mmm` (UTC).
+fn format_time(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = elapsed.as_secs() % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+fn level_color(level: log::Level) -> ColorStyle {
+    let color = match level {
+        log::Level::Error => Color::Dark(BaseColor::Red),
+        log::Level::Warn => Color::Dark(BaseColor::Yellow),
+        log::Level::Info => Color::Light(BaseColor::Black),
+        log::Level::Debug => Color::Dark(BaseColor::Green),
+        log::Level::Trace => Color::Dark(BaseColor::Blue),
+    };
+    ColorStyle::new(color, PaletteColor::View)
+}
+
+impl View for DebugView {
+    fn draw(&self, printer: &Printer) {
+        let logs = logger::LOGS.lock().unwrap();
+
+        for (i, record) in logs.iter().enumerate() {
+            printer.print(
+                (0, i),
+                &format!(
+                    "{} | [     ] {}",
+                    format_time(record.time),
+                    record.message
+                ),
+            );
+            printer.with_color(level_color(record.level), |printer| {
+                printer.print((16, i), &format!("{:5}", record.level))
+            });
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let logs = logger::LOGS.lock().unwrap();
+
+        // The longest line sets the width.
+        let w = logs
+            .iter()
+            .map(|record| record.message.width() + PREFIX_WIDTH)
+            .max()
+            .unwrap_or(1);
+        let h = logs.len();
+
+        Vec2::new(w, h)
+    }
+}
@@ -1,17 +1,38 @@
 use align::*;
+use crossbeam_channel::{self, Receiver, Sender};
 use direction::Direction;
 use event::{AnyCb, Event, EventResult, Key};
 use rect::Rect;
 use std::cell::Cell;
 use std::cmp::max;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use theme::ColorStyle;
-use unicode_width::UnicodeWidthStr;
+use utils::width::StrWidth;
+use utils::Counter;
 use vec::Vec2;
-use view::{Margins, Selector, View};
-use views::{Button, DummyView, SizedView, TextView, ViewBox};
+use view::{ChildInfo, IntoBoxedView, Margins, Selector, View};
+use views::{
+    Button, DummyView, EditView, IdView, ProgressBar, SizedView, TextView,
+    ViewBox,
+};
+use CbFunc;
 use Cursive;
 use Printer;
 use With;
+use XY;
+
+/// Width of the bar in progress dialogs.
+const PROGRESS_WIDTH: usize = 30;
+
+/// Used to give a unique id to views in built-in dialogs.
+static DIALOG_IDS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a new unique id starting with `prefix`.
+pub(crate) fn unique_id(prefix: &str) -> String {
+    let id = DIALOG_IDS.fetch_add(1, Ordering::Relaxed);
+    format!("_cursive_{}_{}", prefix, id)
+}
 
 /// Identifies currently focused element in [`Dialog`].
 ///
@@ -91,7 +112,7 @@ impl Dialog {
     }
 
     /// Creates a new `Dialog` with the given content.
-    pub fn around<V: View + 'static>(view: V) -> Self {
+    pub fn around<V: IntoBoxedView>(view: V) -> Self {
         Dialog {
             content: SizedView::new(ViewBox::boxed(view)),
             buttons: Vec::new(),
@@ -108,7 +129,7 @@ impl Dialog {
     /// Sets the content for this dialog.
     ///
     /// Chainable variant.
-    pub fn content<V: View + 'static>(self, view: V) -> Self {
+    pub fn content<V: IntoBoxedView>(self, view: V) -> Self {
         self.with(|s| s.set_content(view))
     }
 
@@ -137,7 +158,7 @@ impl Dialog {
     /// Sets the content for this dialog.
     ///
     /// Previous content will be dropped.
-    pub fn set_content<V: View + 'static>(&mut self, view: V) {
+    pub fn set_content<V: IntoBoxedView>(&mut self, view: V) {
         self.content = SizedView::new(ViewBox::boxed(view));
         self.invalidate();
     }
@@ -223,6 +244,164 @@ impl Dialog {
         })
     }
 
+    /// Creates a dialog following the progress of `counter`.
+    ///
+    /// The counter goes from 0 to 100, and can be updated from any thread.
+    /// Once it reaches 100, the dialog closes itself on the next event,
+    /// usually an `Event::Refresh`.
+    ///
+    /// Use [`Cursive::set_fps`] so the progress moves, and the dialog
+    /// closes, without user input. Add a [`cancel_button`] to let the user
+    /// stop the task.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Dialog;
+    /// # use cursive::utils::Counter;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use std::sync::Arc;
+    /// # use std::thread;
+    /// let mut siv = Cursive::dummy();
+    /// let counter = Counter::new(0);
+    /// let cancelled = Arc::new(AtomicBool::new(false));
+    ///
+    /// siv.set_fps(10);
+    /// siv.add_layer(
+    ///     Dialog::progress("Copying", counter.clone())
+    ///         .cancel_button("Cancel", Arc::clone(&cancelled)),
+    /// );
+    ///
+    /// thread::spawn(move || {
+    ///     for _ in 0..100 {
+    ///         if cancelled.load(Ordering::SeqCst) {
+    ///             return;
+    ///         }
+    ///         counter.tick(1);
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+    /// [`cancel_button`]: #method.cancel_button
+    pub fn progress<S: Into<String>>(title: S, counter: Counter) -> Self {
+        let id = unique_id("progress");
+        let content = ProgressContent {
+            bar: ProgressBar::new().with_value(counter.clone()),
+            counter,
+            id: id.clone(),
+            done: false,
+        };
+
+        Dialog::around(IdView::new(id, content)).title(title)
+    }
+
+    /// Adds a button setting `cancelled` to `true`, and dismissing the
+    /// dialog.
+    ///
+    /// The task behind the dialog should check this flag regularly, and stop
+    /// once it is set.
+    pub fn cancel_button<S: Into<String>>(
+        self, label: S, cancelled: Arc<AtomicBool>,
+    ) -> Self {
+        self.button(label, move |s| {
+            cancelled.store(true, Ordering::SeqCst);
+            s.pop_layer();
+        })
+    }
+
+    /// Asks a yes/no question, and sends the answer over a channel.
+    ///
+    /// The dialog is added through `cb_sink`, so this can be called from
+    /// any thread: background logic can then wait for the answer on the
+    /// returned receiver. `cb_sink` should come from [`Cursive::cb_sink`].
+    ///
+    /// If the dialog is closed without an answer, for instance with `Esc`,
+    /// the receiver is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Dialog;
+    /// # use std::thread;
+    /// let siv = Cursive::dummy();
+    /// let cb_sink = siv.cb_sink().clone();
+    ///
+    /// thread::spawn(move || {
+    ///     let answer = Dialog::confirm_async(&cb_sink, "Overwrite file?");
+    ///     if answer.recv() == Ok(true) {
+    ///         // Overwrite the file
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`Cursive::cb_sink`]: ../struct.Cursive.html#method.cb_sink
+    pub fn confirm_async<S: Into<String>>(
+        cb_sink: &Sender<Box<CbFunc>>, text: S,
+    ) -> Receiver<bool> {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let text = text.into();
+
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            let no = sender.clone();
+            s.add_layer(
+                Dialog::text(text)
+                    .button("Yes", move |s| {
+                        let _ = sender.send(true);
+                        s.pop_layer();
+                    })
+                    .button("No", move |s| {
+                        let _ = no.send(false);
+                        s.pop_layer();
+                    }),
+            );
+        }));
+
+        receiver
+    }
+
+    /// Asks for a line of text, and sends it over a channel.
+    ///
+    /// Like [`confirm_async`], this can be called from any thread. The text
+    /// is sent when the user presses `Enter` or the `Ok` button.
+    ///
+    /// If the dialog is closed without an answer, for instance with `Esc`,
+    /// the receiver is disconnected.
+    ///
+    /// [`confirm_async`]: #method.confirm_async
+    pub fn prompt_async<S: Into<String>>(
+        cb_sink: &Sender<Box<CbFunc>>, title: S,
+    ) -> Receiver<String> {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let title = title.into();
+
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            let id = unique_id("prompt");
+            let submit = sender.clone();
+            let edit = EditView::new().on_submit(move |s, text| {
+                let _ = submit.send(text.to_string());
+                s.pop_layer();
+            });
+
+            s.add_layer(
+                Dialog::around(IdView::new(id.clone(), edit))
+                    .title(title)
+                    .button("Ok", move |s| {
+                        let text = s
+                            .call_on_id(&id, |edit: &mut EditView| {
+                                edit.get_content()
+                            }).unwrap();
+                        let _ = sender.send(text.to_string());
+                        s.pop_layer();
+                    }),
+            );
+        }));
+
+        receiver
+    }
+
     /// Sets the title of the dialog.
     ///
     /// If not empty, it will be visible at the top.
@@ -406,10 +585,9 @@ impl Dialog {
         if printer.size.x < overhead.horizontal() {
             return None;
         }
-        let mut offset = overhead.left + self
-            .align
-            .h
-            .get_offset(width, printer.size.x - overhead.horizontal());
+        let available = printer.size.saturating_sub(overhead.combined());
+        let mut offset = overhead.left
+            + self.align.compute_offset((width, 0), available).x;
 
         let overhead_bottom = self.padding.bottom + self.borders.bottom + 1;
 
@@ -466,10 +644,11 @@ impl Dialog {
             let spacing = 3; //minimum distance to borders
             let x = spacing + self
                 .title_position
-                .get_offset(len, printer.size.x - 2 * spacing);
+                .get_offset(len, printer.size.x.saturating_sub(2 * spacing));
             printer.with_high_border(false, |printer| {
-                printer.print((x - 2, 0), "┤ ");
-                printer.print((x + len, 0), " ├");
+                let chars = printer.border_chars();
+                printer.print((x - 2, 0), &format!("{} ", chars.right_tee));
+                printer.print((x + len, 0), &format!(" {}", chars.left_tee));
             });
 
             printer.with_color(ColorStyle::title_primary(), |p| {
@@ -606,6 +785,19 @@ impl View for Dialog {
         // First: some mouse events can instantly change the focus.
         self.check_focus_grab(&event);
 
+        // Refreshes let the content update, even if a button is focused.
+        if event == Event::Refresh {
+            return self.content.on_event(event);
+        }
+
+        // Wheel events scroll the content, even if a button is focused.
+        if let Event::Mouse { event: mouse, .. } = event {
+            if mouse.is_wheel() {
+                let offset = (self.padding + self.borders).top_left();
+                return self.content.on_event(event.relativized(offset));
+            }
+        }
+
         match self.focus {
             // If we are on the content, we can only go down.
             // TODO: Careful if/when we add buttons elsewhere on the dialog!
@@ -629,12 +821,42 @@ impl View for Dialog {
         }
     }
 
-    fn call_on_any<'a>(&mut self, selector: &Selector, callback: AnyCb<'a>) {
-        self.content.call_on_any(selector, callback);
+    fn take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        let offset = (self.padding + self.borders).top_left();
+        if self.content.take_focus_near(source, position - offset) {
+            self.focus = DialogFocus::Content;
+            true
+        } else {
+            self.take_focus(source)
+        }
+    }
+
+    fn call_on_any<'a>(
+        &mut self, selector: &Selector, mut callback: AnyCb<'a>,
+    ) {
+        if selector.selects(self) {
+            callback(self);
+        }
+        // The content comes first, followed by the buttons.
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            if let Some(selector) = selector.for_child(i + 1) {
+                button
+                    .button
+                    .call_on_any(&selector, Box::new(|any| callback(any)));
+            }
+        }
+        if let Some(selector) = selector.for_child(0) {
+            self.content.call_on_any(&selector, callback);
+        }
     }
 
     fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
-        self.content.focus_view(selector)
+        match selector.for_child(0) {
+            Some(selector) => self.content.focus_view(&selector),
+            None => Err(()),
+        }
     }
 
     fn important_area(&self, _: Vec2) -> Rect {
@@ -646,4 +868,150 @@ impl View for Dialog {
     fn needs_relayout(&self) -> bool {
         self.invalidated || self.content.needs_relayout()
     }
+
+    fn inspect_children(&self, _: Vec2, f: &mut FnMut(ChildInfo)) {
+        f(ChildInfo {
+            view: &*self.content.view,
+            offset: self.borders.top_left() + self.padding.top_left(),
+            size: self.content.size,
+            focused: self.focus == DialogFocus::Content,
+        });
+
+        for (i, button) in self.buttons.iter().enumerate() {
+            f(ChildInfo {
+                view: &button.button.view,
+                offset: button.offset.get(),
+                size: button.button.size,
+                focused: self.focus == DialogFocus::Button(i),
+            });
+        }
+    }
+}
+
+/// Content of a progress dialog, closing it once complete.
+struct ProgressContent {
+    bar: ProgressBar,
+    counter: Counter,
+    // Id of the content, to find the layer to close.
+    id: String,
+    // `true` once the closing callback was returned.
+    done: bool,
+}
+
+impl View for ProgressContent {
+    fn draw(&self, printer: &Printer) {
+        self.bar.draw(printer);
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(PROGRESS_WIDTH, 1)
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.bar.layout(size);
+    }
+
+    fn on_event(&mut self, _: Event) -> EventResult {
+        if self.done || self.counter.get() < 100 {
+            return EventResult::Ignored;
+        }
+        self.done = true;
+
+        let id = self.id.clone();
+        EventResult::with_cb(move |s| {
+            if let Some(position) = s.screen_mut().find_layer_from_id(&id) {
+                s.screen_mut().remove_layer(position);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel;
+
+    #[test]
+    fn progress_closes_when_complete() {
+        let counter = Counter::new(0);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut siv = Cursive::dummy();
+        siv.add_layer(TextView::new("Main"));
+        // The button takes the focus: refreshes must still reach the bar.
+        siv.add_layer(
+            Dialog::progress("Test", counter.clone())
+                .cancel_button("Cancel", cancelled),
+        );
+        siv.screen_mut().layout(Vec2::new(40, 10));
+
+        let layers = |siv: &Cursive| siv.screen().layer_sizes().len();
+        siv.on_event(Event::Refresh);
+        assert_eq!(layers(&siv), 2);
+
+        counter.set(100);
+        siv.on_event(Event::Refresh);
+        assert_eq!(layers(&siv), 1);
+    }
+
+    #[test]
+    fn cancel_button() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut siv = Cursive::dummy();
+        siv.add_layer(
+            Dialog::progress("Test", Counter::new(0))
+                .cancel_button("Cancel", Arc::clone(&cancelled)),
+        );
+        siv.screen_mut().layout(Vec2::new(40, 10));
+
+        // The button is the only focusable child.
+        siv.on_event(Event::Key(Key::Enter));
+        assert!(cancelled.load(Ordering::SeqCst));
+        assert_eq!(siv.screen().layer_sizes().len(), 0);
+    }
+
+    /// Runs the callbacks sent to `cb_source`.
+    fn run_callbacks(siv: &mut Cursive, cb_source: &Receiver<Box<CbFunc>>) {
+        while let Ok(cb) = cb_source.try_recv() {
+            cb.call_box(siv);
+        }
+    }
+
+    #[test]
+    fn confirm_async() {
+        let (cb_sink, cb_source) = crossbeam_channel::unbounded();
+        let mut siv = Cursive::dummy();
+
+        let answer = Dialog::confirm_async(&cb_sink, "Sure?");
+        run_callbacks(&mut siv, &cb_source);
+        siv.screen_mut().layout(Vec2::new(40, 10));
+        assert!(answer.try_recv().is_err());
+
+        // Focus "No", then press it.
+        siv.on_event(Event::Key(Key::Right));
+        siv.on_event(Event::Key(Key::Enter));
+        assert_eq!(answer.try_recv(), Ok(false));
+        assert_eq!(siv.screen().layer_sizes().len(), 0);
+
+        // Closing the dialog disconnects the receiver.
+        let answer = Dialog::confirm_async(&cb_sink, "Sure?");
+        run_callbacks(&mut siv, &cb_source);
+        siv.pop_layer();
+        assert!(answer.recv().is_err());
+    }
+
+    #[test]
+    fn prompt_async() {
+        let (cb_sink, cb_source) = crossbeam_channel::unbounded();
+        let mut siv = Cursive::dummy();
+
+        let answer = Dialog::prompt_async(&cb_sink, "Name");
+        run_callbacks(&mut siv, &cb_source);
+        siv.screen_mut().layout(Vec2::new(40, 10));
+        siv.on_event(Event::Char('J'));
+        siv.on_event(Event::Char('o'));
+        siv.on_event(Event::Key(Key::Enter));
+
+        assert_eq!(answer.try_recv(), Ok("Jo".to_string()));
+        assert_eq!(siv.screen().layer_sizes().len(), 0);
+    }
 }
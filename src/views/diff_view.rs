@@ -0,0 +1,734 @@
+use direction::Direction;
+use event::{Event, EventResult, Key, MouseEvent};
+use std::cmp::min;
+use theme::{BaseColor, Color, ColorStyle, PaletteColor};
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// A line from one of the compared texts.
+struct Line {
+    /// 1-based line number.
+    number: usize,
+    text: String,
+}
+
+/// A row of the view, showing one line of each text side by side.
+///
+/// A row with no line on either side separates two hunks.
+struct Row {
+    old: Option<Line>,
+    new: Option<Line>,
+    changed: bool,
+}
+
+impl Row {
+    fn is_separator(&self) -> bool {
+        self.old.is_none() && self.new.is_none()
+    }
+}
+
+/// Aligns removed and added lines before adding them as rows.
+///
+/// Consecutive removals and additions are paired on the same rows.
+#[derive(Default)]
+struct RowBuilder {
+    rows: Vec<Row>,
+    removed: Vec<Line>,
+    added: Vec<Line>,
+}
+
+impl RowBuilder {
+    fn equal(&mut self, old: Line, new: Line) {
+        self.flush();
+        self.rows.push(Row {
+            old: Some(old),
+            new: Some(new),
+            changed: false,
+        });
+    }
+
+    fn separator(&mut self) {
+        self.flush();
+        self.rows.push(Row {
+            old: None,
+            new: None,
+            changed: false,
+        });
+    }
+
+    fn flush(&mut self) {
+        let mut removed = self.removed.drain(..);
+        let mut added = self.added.drain(..);
+        loop {
+            let (old, new) = (removed.next(), added.next());
+            if old.is_none() && new.is_none() {
+                break;
+            }
+            self.rows.push(Row {
+                old,
+                new,
+                changed: true,
+            });
+        }
+    }
+
+    fn finish(mut self) -> Vec<Row> {
+        self.flush();
+        self.rows
+    }
+}
+
+/// Operation turning the old text into the new one.
+#[derive(Debug, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Computes a line diff, with Myers' algorithm.
+///
+/// Runs in linear space, by splitting the texts around the middle of an
+/// optimal edit path.
+fn diff(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    diff_range(old, new, (0, 0), &mut ops);
+    ops
+}
+
+/// Appends the operations turning `old` into `new` to `ops`.
+///
+/// `start` is the position of both slices in the full texts.
+fn diff_range(
+    old: &[&str], new: &[&str], start: (usize, usize), ops: &mut Vec<Op>,
+) {
+    // The common prefix and suffix are kept as-is.
+    let prefix = old.iter().zip(new).take_while(|&(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    let (i, j) = (start.0 + prefix, start.1 + prefix);
+
+    ops.extend((0..prefix).map(|k| Op::Equal(start.0 + k, start.1 + k)));
+    match middle(a, b) {
+        Some((x, y)) => {
+            diff_range(&a[..x], &b[..y], (i, j), ops);
+            diff_range(&a[x..], &b[y..], (i + x, j + y), ops);
+        }
+        None => {
+            ops.extend((0..a.len()).map(|k| Op::Remove(i + k)));
+            ops.extend((0..b.len()).map(|k| Op::Add(j + k)));
+        }
+    }
+    ops.extend((0..suffix).map(|k| {
+        Op::Equal(i + a.len() + k, j + b.len() + k)
+    }));
+}
+
+/// Finds where an optimal edit path crosses the middle diagonal.
+///
+/// Walks the path from both ends at once, and returns the position in
+/// `a` and `b` where they meet. Returns `None` if the texts have nothing
+/// in common, or if either is empty.
+///
+/// `a` and `b` must not start or end with the same line.
+fn middle(a: &[&str], b: &[&str]) -> Option<(usize, usize)> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d + 1;
+    // Furthest position in `a` reached on each diagonal, from each end.
+    let mut forward = vec![-1isize; 2 * offset as usize + 1];
+    let mut backward = forward.clone();
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+
+    let delta = n - m;
+    // With an odd delta, the paths meet during a forward step.
+    let odd = delta % 2 != 0;
+    // Diagonals going out of the texts are skipped.
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d {
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let k = (offset + k1) as usize;
+            let mut x = if k1 == -d
+                || (k1 != d && forward[k - 1] < forward[k + 1])
+            {
+                forward[k + 1]
+            } else {
+                forward[k - 1] + 1
+            };
+            let mut y = x - k1;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[k] = x;
+
+            if x > n {
+                k1_end += 2;
+            } else if y > m {
+                k1_start += 2;
+            } else if odd {
+                let k2 = offset + delta - k1;
+                if k2 >= 0
+                    && (k2 as usize) < backward.len()
+                    && backward[k2 as usize] != -1
+                    && x >= n - backward[k2 as usize]
+                {
+                    return Some((x as usize, y as usize));
+                }
+            }
+            k1 += 2;
+        }
+
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let k = (offset + k2) as usize;
+            let mut x = if k2 == -d
+                || (k2 != d && backward[k - 1] < backward[k + 1])
+            {
+                backward[k + 1]
+            } else {
+                backward[k - 1] + 1
+            };
+            let mut y = x - k2;
+            while x < n
+                && y < m
+                && a[(n - x - 1) as usize] == b[(m - y - 1) as usize]
+            {
+                x += 1;
+                y += 1;
+            }
+            backward[k] = x;
+
+            if x > n {
+                k2_end += 2;
+            } else if y > m {
+                k2_start += 2;
+            } else if !odd {
+                let k1 = offset + delta - k2;
+                if k1 >= 0
+                    && (k1 as usize) < forward.len()
+                    && forward[k1 as usize] != -1
+                {
+                    let x1 = forward[k1 as usize];
+                    let y1 = offset + x1 - k1;
+                    if x1 >= n - x {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+
+    None
+}
+
+/// Parses a hunk header like `@@ -12,5 +12,7 @@`.
+///
+/// Returns the start and length of the hunk in the old and new texts.
+fn parse_hunk_header(
+    header: &str,
+) -> Option<((usize, usize), (usize, usize))> {
+    let mut parts = header.split_whitespace().skip(1);
+    let mut range = |prefix: char| -> Option<(usize, usize)> {
+        let part = parts.next()?;
+        if !part.starts_with(prefix) {
+            return None;
+        }
+        let mut numbers = part[1..].splitn(2, ',');
+        let start = numbers.next()?.parse().ok()?;
+        let len = match numbers.next() {
+            Some(len) => len.parse().ok()?,
+            None => 1,
+        };
+        Some((start, len))
+    };
+    let old = range('-')?;
+    let new = range('+')?;
+    Some((old, new))
+}
+
+/// Compares two texts side by side.
+///
+/// The old text is shown on the left and the new one on the right. Both
+/// panes always scroll together. Removed lines are shown in red, added lines
+/// in green.
+///
+/// # Bindings
+///
+/// * `Up`, `Down`, `PageUp`, `PageDown`, `Home`, `End`: scroll.
+/// * `n`, `p`: go to the next or previous hunk.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::DiffView;
+/// let view = DiffView::new("a\nb\nc", "a\nB\nc");
+/// ```
+pub struct DiffView {
+    rows: Vec<Row>,
+
+    /// Index of the first visible row.
+    top: usize,
+
+    /// Width of the line numbers, including the separating space.
+    gutter: usize,
+
+    last_size: Vec2,
+}
+
+impl DiffView {
+    /// Creates a new view comparing two texts.
+    pub fn new<S1, S2>(old: S1, new: S2) -> Self
+    where
+        S1: AsRef<str>,
+        S2: AsRef<str>,
+    {
+        DiffView::empty().with(|s| s.set_texts(old, new))
+    }
+
+    /// Creates a new view showing a unified diff.
+    ///
+    /// Only the hunks present in the diff are shown, separated by an empty
+    /// row.
+    pub fn from_unified<S: AsRef<str>>(diff: S) -> Self {
+        DiffView::empty().with(|s| s.set_unified(diff))
+    }
+
+    fn empty() -> Self {
+        DiffView {
+            rows: Vec::new(),
+            top: 0,
+            gutter: 0,
+            last_size: Vec2::zero(),
+        }
+    }
+
+    /// Replaces the compared texts.
+    pub fn set_texts<S1, S2>(&mut self, old: S1, new: S2)
+    where
+        S1: AsRef<str>,
+        S2: AsRef<str>,
+    {
+        let old: Vec<&str> = old.as_ref().lines().collect();
+        let new: Vec<&str> = new.as_ref().lines().collect();
+
+        let line = |lines: &[&str], i: usize| Line {
+            number: i + 1,
+            text: lines[i].to_string(),
+        };
+
+        let mut builder = RowBuilder::default();
+        for op in diff(&old, &new) {
+            match op {
+                Op::Equal(i, j) => builder.equal(line(&old, i), line(&new, j)),
+                Op::Remove(i) => builder.removed.push(line(&old, i)),
+                Op::Add(j) => builder.added.push(line(&new, j)),
+            }
+        }
+        self.set_rows(builder.finish());
+    }
+
+    /// Replaces the content with the given unified diff.
+    ///
+    /// Lines outside of hunks, like file headers, are ignored.
+    pub fn set_unified<S: AsRef<str>>(&mut self, diff: S) {
+        let mut builder = RowBuilder::default();
+        // Next line number and remaining lines in the current hunk.
+        let (mut old, mut new) = ((1, 0), (1, 0));
+
+        for text in diff.as_ref().lines() {
+            if old.1 == 0 && new.1 == 0 {
+                if let Some(ranges) = parse_hunk_header(text) {
+                    if !builder.rows.is_empty()
+                        || !builder.removed.is_empty()
+                        || !builder.added.is_empty()
+                    {
+                        builder.separator();
+                    }
+                    old = ranges.0;
+                    new = ranges.1;
+                }
+                continue;
+            }
+
+            let line = |number| Line {
+                number,
+                text: text.get(1..).unwrap_or("").to_string(),
+            };
+            match text.chars().next() {
+                Some('-') => {
+                    builder.removed.push(line(old.0));
+                    old = (old.0 + 1, old.1.saturating_sub(1));
+                }
+                Some('+') => {
+                    builder.added.push(line(new.0));
+                    new = (new.0 + 1, new.1.saturating_sub(1));
+                }
+                Some('\\') => {
+                    // "\ No newline at end of file"
+                }
+                _ => {
+                    // Some tools strip the space of empty context lines.
+                    builder.equal(line(old.0), line(new.0));
+                    old = (old.0 + 1, old.1.saturating_sub(1));
+                    new = (new.0 + 1, new.1.saturating_sub(1));
+                }
+            }
+        }
+        self.set_rows(builder.finish());
+    }
+
+    fn set_rows(&mut self, rows: Vec<Row>) {
+        self.rows = rows;
+        self.gutter = self.gutter_width();
+        self.top = min(self.top, self.max_top());
+    }
+
+    /// Returns the number of rows in this view.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the index of the first visible row.
+    pub fn top_row(&self) -> usize {
+        self.top
+    }
+
+    /// Scrolls so that the given row is the first one visible.
+    pub fn scroll_to_row(&mut self, row: usize) {
+        self.top = min(row, self.max_top());
+    }
+
+    /// Returns the number of hunks, that is groups of changed rows.
+    pub fn hunk_count(&self) -> usize {
+        self.hunk_starts().count()
+    }
+
+    /// Scrolls to the next hunk, if any.
+    pub fn next_hunk(&mut self) {
+        let top = self.top;
+        let next = self.hunk_starts().find(|&row| row > top);
+        if let Some(row) = next {
+            self.scroll_to_row(row);
+        }
+    }
+
+    /// Scrolls to the previous hunk, if any.
+    pub fn previous_hunk(&mut self) {
+        let top = self.top;
+        let previous = self.hunk_starts().filter(|&row| row < top).last();
+        if let Some(row) = previous {
+            self.scroll_to_row(row);
+        }
+    }
+
+    /// Indexes of rows starting a group of changes.
+    fn hunk_starts<'a>(&'a self) -> impl Iterator<Item = usize> + 'a {
+        self.rows.iter().enumerate().filter_map(move |(i, row)| {
+            let previous_changed = i > 0 && self.rows[i - 1].changed;
+            if row.changed && !previous_changed {
+                Some(i)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn max_top(&self) -> usize {
+        self.rows.len().saturating_sub(self.last_size.y)
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        self.top = self.top.saturating_sub(n);
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        self.top = min(self.top + n, self.max_top());
+    }
+
+    /// Width of the line numbers, including the separating space.
+    fn gutter_width(&self) -> usize {
+        let last = self
+            .rows
+            .iter()
+            .flat_map(|row| row.old.iter().chain(row.new.iter()))
+            .map(|line| line.number)
+            .max()
+            .unwrap_or(0);
+        last.to_string().len() + 1
+    }
+
+    fn draw_line(
+        &self, printer: &Printer, y: usize, line: &Line, color: ColorStyle,
+    ) {
+        let gutter = self.gutter;
+        let number = format!("{:>1$}", line.number, gutter - 1);
+        printer.with_color(ColorStyle::secondary(), |printer| {
+            printer.print((0, y), &number);
+        });
+        printer.with_color(color, |printer| {
+            printer.print((gutter, y), &line.text);
+        });
+    }
+}
+
+impl View for DiffView {
+    fn draw(&self, printer: &Printer) {
+        let pane = printer.size.x.saturating_sub(1) / 2;
+        let left = printer.cropped((pane, printer.size.y));
+        let right = printer.offset((pane + 1, 0)).cropped((
+            printer.size.x.saturating_sub(pane + 1),
+            printer.size.y,
+        ));
+
+        let removed =
+            ColorStyle::new(Color::Dark(BaseColor::Red), PaletteColor::View);
+        let added =
+            ColorStyle::new(Color::Dark(BaseColor::Green), PaletteColor::View);
+
+        let chars = printer.border_chars();
+        printer.print_vline((pane, 0), printer.size.y, chars.vertical);
+
+        let rows = self.rows.iter().skip(self.top).take(printer.size.y);
+        for (y, row) in rows.enumerate() {
+            if row.is_separator() {
+                printer.with_color(ColorStyle::secondary(), |printer| {
+                    printer.print_hline((0, y), pane, chars.horizontal);
+                    printer.print((pane, y), chars.cross);
+                    printer.print_hline(
+                        (pane + 1, y),
+                        printer.size.x.saturating_sub(pane + 1),
+                        chars.horizontal,
+                    );
+                });
+                continue;
+            }
+
+            let (old_color, new_color) = if row.changed {
+                (removed.clone(), added.clone())
+            } else {
+                (ColorStyle::primary(), ColorStyle::primary())
+            };
+            if let Some(ref line) = row.old {
+                self.draw_line(&left, y, line, old_color);
+            }
+            if let Some(ref line) = row.new {
+                self.draw_line(&right, y, line, new_color);
+            }
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let width = self
+            .rows
+            .iter()
+            .flat_map(|row| row.old.iter().chain(row.new.iter()))
+            .map(|line| line.text.width())
+            .max()
+            .unwrap_or(0);
+
+        let pane = self.gutter + width;
+        Vec2::new(2 * pane + 1, self.rows.len())
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.last_size = size;
+        self.top = min(self.top, self.max_top());
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let page = self.last_size.y.max(1);
+
+        match event {
+            Event::Key(Key::Up) => self.scroll_up(1),
+            Event::Key(Key::Down) => self.scroll_down(1),
+            Event::Key(Key::PageUp) => self.scroll_up(page),
+            Event::Key(Key::PageDown) => self.scroll_down(page),
+            Event::Key(Key::Home) => self.top = 0,
+            Event::Key(Key::End) => self.top = self.max_top(),
+            Event::Char('n') => self.next_hunk(),
+            Event::Char('p') => self.previous_hunk(),
+            Event::Mouse {
+                event: MouseEvent::WheelUp,
+                ..
+            } => self.scroll_up(3),
+            Event::Mouse {
+                event: MouseEvent::WheelDown,
+                ..
+            } => self.scroll_down(3),
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Describes rows as `old|new` strings.
+    fn describe(view: &DiffView) -> Vec<String> {
+        let text = |line: &Option<Line>| match *line {
+            Some(ref line) => format!("{}:{}", line.number, line.text),
+            None => String::new(),
+        };
+        view.rows
+            .iter()
+            .map(|row| format!("{}|{}", text(&row.old), text(&row.new)))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(
+            diff(&["a", "b", "c"], &["a", "c", "d"]),
+            vec![Op::Equal(0, 0), Op::Remove(1), Op::Equal(2, 1), Op::Add(2),]
+        );
+    }
+
+    #[test]
+    fn diff_is_minimal() {
+        // Length of the longest common subsequence, the slow way.
+        fn lcs(a: &[&str], b: &[&str]) -> usize {
+            let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+            for i in 0..a.len() {
+                for j in 0..b.len() {
+                    table[i + 1][j + 1] = if a[i] == b[j] {
+                        table[i][j] + 1
+                    } else {
+                        table[i][j + 1].max(table[i + 1][j])
+                    };
+                }
+            }
+            table[a.len()][b.len()]
+        }
+
+        let words = ["a", "b", "c", "d"];
+        let mut seed = 17u32;
+        let mut text = |len: usize| -> Vec<&str> {
+            (0..len)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(1_103_515_245)
+                        .wrapping_add(12345);
+                    words[(seed >> 16) as usize % words.len()]
+                })
+                .collect()
+        };
+
+        for len in 0..40 {
+            let (old, new) = (text(len), text(len / 2 + len % 7));
+            let ops = diff(&old, &new);
+
+            // Replaying the operations gives back both texts.
+            let (mut i, mut j, mut equal) = (0, 0, 0);
+            for op in ops {
+                match op {
+                    Op::Equal(a, b) => {
+                        assert_eq!((a, b), (i, j));
+                        assert_eq!(old[a], new[b]);
+                        i += 1;
+                        j += 1;
+                        equal += 1;
+                    }
+                    Op::Remove(a) => {
+                        assert_eq!(a, i);
+                        i += 1;
+                    }
+                    Op::Add(b) => {
+                        assert_eq!(b, j);
+                        j += 1;
+                    }
+                }
+            }
+            assert_eq!((i, j), (old.len(), new.len()));
+            assert_eq!(equal, lcs(&old, &new));
+        }
+    }
+
+    #[test]
+    fn texts_aligned() {
+        let view = DiffView::new("a\nb\nc\nd", "a\nB\nx\nd");
+        assert_eq!(
+            describe(&view),
+            vec!["1:a|1:a", "2:b|2:B", "3:c|3:x", "4:d|4:d"]
+        );
+        assert_eq!(view.hunk_count(), 1);
+
+        let view = DiffView::new("a\nd", "a\nb\nc\nd");
+        assert_eq!(
+            describe(&view),
+            vec!["1:a|1:a", "|2:b", "|3:c", "2:d|4:d"]
+        );
+    }
+
+    #[test]
+    fn unified() {
+        let diff = "\
+--- a/file
++++ b/file
+@@ -1,3 +1,3 @@
+ one
+-two
++deux
+ three
+@@ -10,2 +10,3 @@
+ ten
++ten and a half
+ eleven
+";
+        let view = DiffView::from_unified(diff);
+        assert_eq!(
+            describe(&view),
+            vec![
+                "1:one|1:one",
+                "2:two|2:deux",
+                "3:three|3:three",
+                "|",
+                "10:ten|10:ten",
+                "|11:ten and a half",
+                "11:eleven|12:eleven",
+            ]
+        );
+        assert_eq!(view.hunk_count(), 2);
+    }
+
+    #[test]
+    fn hunk_navigation() {
+        let old: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let mut new = old.clone();
+        new[30] = "changed".to_string();
+        new[60] = "changed".to_string();
+
+        let mut view = DiffView::new(old.join("\n"), new.join("\n"));
+        view.layout(Vec2::new(40, 10));
+
+        view.next_hunk();
+        assert_eq!(view.top_row(), 30);
+        view.next_hunk();
+        assert_eq!(view.top_row(), 60);
+        view.previous_hunk();
+        assert_eq!(view.top_row(), 30);
+    }
+}
@@ -1,14 +1,16 @@
+use clipboard::{self, ClipboardKeys};
 use direction::Direction;
-use event::{Callback, Event, EventResult, Key, MouseEvent};
+use event::{Callback, Event, EventResult, Key, MouseButton, MouseEvent};
 use rect::Rect;
 use std::cell::RefCell;
+use std::cmp::max;
 use std::rc::Rc;
 use theme::{ColorStyle, Effect};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use utils::lines::simple::{simple_prefix, simple_suffix};
+use utils::width::{CharWidth, StrWidth};
 use vec::Vec2;
-use view::View;
+use view::{View, ViewState};
 use {Cursive, Printer, With};
 
 /// Closure type for callbacks when the content is modified.
@@ -80,6 +82,9 @@ pub struct EditView {
     /// Cursor position in the content, in bytes.
     cursor: usize,
 
+    /// Selected byte range, from a double or triple click.
+    selection: Option<(usize, usize)>,
+
     /// Number of bytes to skip at the beginning of the content.
     ///
     /// (When the content is too long for the display, we hide part of it)
@@ -110,6 +115,9 @@ pub struct EditView {
     enabled: bool,
 
     style: ColorStyle,
+
+    /// Keys copying and pasting the content.
+    clipboard_keys: ClipboardKeys,
 }
 
 new_default!(EditView);
@@ -120,6 +128,7 @@ impl EditView {
         EditView {
             content: Rc::new(String::new()),
             cursor: 0,
+            selection: None,
             offset: 0,
             last_length: 0, // scrollable: false,
             on_edit: None,
@@ -129,9 +138,22 @@ impl EditView {
             filler: "_".to_string(),
             enabled: true,
             style: ColorStyle::secondary(),
+            clipboard_keys: ClipboardKeys::default(),
         }
     }
 
+    /// Sets the keys copying and pasting the content.
+    pub fn set_clipboard_keys(&mut self, clipboard_keys: ClipboardKeys) {
+        self.clipboard_keys = clipboard_keys;
+    }
+
+    /// Sets the keys copying and pasting the content.
+    ///
+    /// Chainable variant.
+    pub fn clipboard_keys(self, clipboard_keys: ClipboardKeys) -> Self {
+        self.with(|s| s.set_clipboard_keys(clipboard_keys))
+    }
+
     /// Sets a maximum width for the content.
     ///
     /// Input will be rejected if it would make the content exceed this width.
@@ -356,6 +378,7 @@ impl EditView {
         let len = content.len();
 
         self.content = Rc::new(content);
+        self.selection = None;
         self.offset = 0;
         self.set_cursor(len);
 
@@ -377,6 +400,46 @@ impl EditView {
         self
     }
 
+    /// Returns the selected text, if any.
+    ///
+    /// Double-clicking selects a word, triple-clicking selects everything.
+    pub fn selection(&self) -> Option<&str> {
+        self.selection.map(|(start, end)| &self.content[start..end])
+    }
+
+    /// Selects the entire content.
+    pub fn select_all(&mut self) {
+        if !self.content.is_empty() {
+            self.selection = Some((0, self.content.len()));
+        }
+    }
+
+    /// Selects the word at the cursor.
+    ///
+    /// At the end of the content, selects the last word.
+    fn select_word(&mut self) {
+        let cursor = self.cursor;
+        self.selection = self
+            .content
+            .split_word_bound_indices()
+            .map(|(start, word)| (start, start + word.len()))
+            .take_while(|&(start, _)| start <= cursor)
+            .last();
+    }
+
+    /// Removes the selected text, and moves the cursor in its place.
+    ///
+    /// Returns `false` if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = match self.selection.take() {
+            Some(selection) => selection,
+            None => return false,
+        };
+        Rc::make_mut(&mut self.content).drain(start..end);
+        self.set_cursor(start);
+        true
+    }
+
     /// Sets the cursor position.
     pub fn set_cursor(&mut self, cursor: usize) {
         self.cursor = cursor;
@@ -406,6 +469,8 @@ impl EditView {
         // It means it'll just return a ref if no one else has a ref,
         // and it will clone it into `self.content` otherwise.
 
+        // The selected range would not match the new content.
+        self.selection = None;
         Rc::make_mut(&mut self.content).insert(self.cursor, ch);
         self.cursor += ch.len_utf8();
 
@@ -414,6 +479,37 @@ impl EditView {
         self.make_edit_cb().unwrap_or_else(Callback::dummy)
     }
 
+    /// Insert `text` at the current cursor position.
+    ///
+    /// Control characters (including newlines) are skipped, and the text is
+    /// truncated if it would exceed the maximum content width.
+    ///
+    /// Returns a callback in response to content change.
+    ///
+    /// You should run this callback with a `&mut Cursive`.
+    pub fn insert_str(&mut self, text: &str) -> Callback {
+        let mut text: String =
+            text.chars().filter(|c| !c.is_control()).collect();
+
+        if let Some(width) = self.max_content_width {
+            let available = width.saturating_sub(self.content.width());
+            let length = simple_prefix(&text, available).length;
+            text.truncate(length);
+        }
+
+        if text.is_empty() {
+            return Callback::dummy();
+        }
+
+        self.selection = None;
+        Rc::make_mut(&mut self.content).insert_str(self.cursor, &text);
+        self.cursor += text.len();
+
+        self.keep_cursor_in_view();
+
+        self.make_edit_cb().unwrap_or_else(Callback::dummy)
+    }
+
     /// Remove the character at the current cursor position.
     ///
     /// Returns a callback in response to content change.
@@ -422,6 +518,7 @@ impl EditView {
     pub fn remove(&mut self, len: usize) -> Callback {
         let start = self.cursor;
         let end = self.cursor + len;
+        self.selection = None;
         for _ in Rc::make_mut(&mut self.content).drain(start..end) {}
 
         self.keep_cursor_in_view();
@@ -441,6 +538,35 @@ impl EditView {
         })
     }
 
+    /// Highlights the visible part of the `start..end` byte range.
+    fn draw_selection(&self, printer: &Printer, start: usize, end: usize) {
+        // The content is shown from `offset` only when it doesn't fit.
+        let first = if self.content.width() < self.last_length {
+            0
+        } else {
+            self.offset
+        };
+        let start = max(start, first);
+        if start >= end {
+            return;
+        }
+
+        let x = self.content[first..start].width();
+        // The selection can start past the view, after it was shrunk.
+        if x >= self.last_length {
+            return;
+        }
+        let text = &self.content[start..end];
+        let text = &text[..simple_prefix(text, self.last_length - x).length];
+        printer.with_color(ColorStyle::highlight(), |printer| {
+            if self.secret {
+                printer.print_hline((x, 0), text.width(), "*");
+            } else {
+                printer.print((x, 0), text);
+            }
+        });
+    }
+
     fn keep_cursor_in_view(&mut self) {
         // keep cursor in [offset, offset+last_length] by changing offset
         // so keep offset in [last_length-cursor,cursor]
@@ -510,7 +636,7 @@ impl View for EditView {
         );
 
         let width = self.content.width();
-        printer.with_color(self.style, |printer| {
+        printer.with_color(self.style.clone(), |printer| {
             let effect = if self.enabled && printer.enabled {
                 Effect::Reverse
             } else {
@@ -568,6 +694,10 @@ impl View for EditView {
                 }
             });
 
+            if let Some((start, end)) = self.selection {
+                self.draw_selection(printer, start, end);
+            }
+
             // Now print cursor
             if printer.focused {
                 let c: &str = if self.cursor == self.content.len() {
@@ -591,6 +721,7 @@ impl View for EditView {
                 };
                 let offset = self.content[self.offset..self.cursor].width();
                 printer.print((offset, 0), c);
+                printer.show_cursor((offset, 0));
             }
         });
     }
@@ -605,10 +736,45 @@ impl View for EditView {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        // Handled events clear the selection, unless they act on it.
+        let selection = self.selection.take();
+
+        if self.clipboard_keys.is_copy(&event) {
+            self.selection = selection;
+            // Don't leak passwords to the clipboard.
+            if self.secret {
+                return EventResult::Ignored;
+            }
+            let text = self.selection().unwrap_or(&self.content).to_string();
+            return EventResult::with_cb(move |s| {
+                s.set_clipboard(text.as_str())
+            });
+        }
+
+        if self.clipboard_keys.is_paste(&event) {
+            let text = clipboard::get();
+            self.selection = selection;
+            self.delete_selection();
+            return EventResult::Consumed(Some(self.insert_str(&text)));
+        }
+
         match event {
             Event::Char(ch) => {
+                self.selection = selection;
+                self.delete_selection();
                 return EventResult::Consumed(Some(self.insert(ch)));
             }
+            Event::Paste(ref text) => {
+                self.selection = selection;
+                self.delete_selection();
+                return EventResult::Consumed(Some(self.insert_str(text)));
+            }
+            Event::Key(Key::Backspace) | Event::Key(Key::Del)
+                if selection.is_some() =>
+            {
+                self.selection = selection;
+                self.delete_selection();
+            }
             // TODO: handle ctrl-key?
             Event::Key(Key::Home) => self.set_cursor(0),
             Event::Key(Key::End) => {
@@ -672,7 +838,22 @@ impl View for EditView {
                         .length;
                 }
             }
-            _ => return EventResult::Ignored,
+            Event::Mouse {
+                event: MouseEvent::MultiPress(MouseButton::Left, count),
+                position,
+                offset,
+            } if position.fits_in_rect(offset, (self.last_length, 1)) => {
+                if count == 2 {
+                    self.select_word();
+                } else {
+                    self.select_all();
+                }
+                return EventResult::Consumed(None);
+            }
+            _ => {
+                self.selection = selection;
+                return EventResult::Ignored;
+            }
         }
 
         // self.keep_cursor_in_view();
@@ -680,6 +861,16 @@ impl View for EditView {
         EventResult::Consumed(self.make_edit_cb())
     }
 
+    fn state(&self) -> Option<ViewState> {
+        Some(ViewState::Text((*self.content).clone()))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Text(ref content) = *state {
+            self.set_content(content.clone());
+        }
+    }
+
     fn important_area(&self, _: Vec2) -> Rect {
         let char_width = if self.cursor >= self.content.len() {
             // Show a space if we're at the end of the content
@@ -698,3 +889,109 @@ impl View for EditView {
         Rect::from_size((x, 0), (char_width, 1))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::buffer::PrintBuffer;
+    use event::{Event, Key};
+    use theme;
+
+    fn type_str(view: &mut EditView, text: &str) {
+        for ch in text.chars() {
+            view.on_event(Event::Char(ch));
+        }
+    }
+
+    #[test]
+    fn combining_characters() {
+        let mut view = EditView::new();
+        view.layout(Vec2::new(10, 1));
+
+        // `e` followed by a combining acute accent: a single grapheme.
+        type_str(&mut view, "ae\u{301}b");
+        assert_eq!(view.cursor, 5);
+
+        view.on_event(Event::Key(Key::Left));
+        view.on_event(Event::Key(Key::Left));
+        assert_eq!(view.cursor, 1);
+
+        view.on_event(Event::Key(Key::Del));
+        assert_eq!(&*view.get_content(), "ab");
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut view = EditView::new();
+        view.layout(Vec2::new(4, 1));
+
+        type_str(&mut view, "漢字a");
+        assert_eq!(view.cursor, 7);
+        // The cursor needs one column, so `漢` is scrolled out.
+        assert_eq!(view.offset, 3);
+
+        view.on_event(Event::Key(Key::Home));
+        view.on_event(Event::Key(Key::Right));
+        assert_eq!(view.cursor, 3);
+
+        view.on_event(Event::Key(Key::Backspace));
+        assert_eq!(&*view.get_content(), "字a");
+        assert_eq!(view.cursor, 0);
+    }
+
+    #[test]
+    fn clipboard_keys() {
+        let paste = Event::CtrlChar('v');
+        let mut view = EditView::new().clipboard_keys(ClipboardKeys::none());
+        assert!(!view.on_event(paste.clone()).is_consumed());
+
+        view.set_clipboard_keys(ClipboardKeys::default());
+        assert!(view.on_event(paste).is_consumed());
+    }
+
+    #[test]
+    fn double_click() {
+        let mut view = EditView::new().content("hello big world");
+        view.layout(Vec2::new(20, 1));
+        let click = |event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(7, 0),
+            event,
+        };
+        let left = MouseButton::Left;
+
+        view.on_event(click(MouseEvent::Press(left)));
+        view.on_event(click(MouseEvent::MultiPress(left, 2)));
+        view.on_event(click(MouseEvent::Release(left)));
+        assert_eq!(view.selection(), Some("big"));
+
+        // Typing replaces the selection.
+        type_str(&mut view, "small");
+        assert_eq!(&*view.get_content(), "hello small world");
+        assert_eq!(view.selection(), None);
+
+        view.on_event(click(MouseEvent::MultiPress(left, 3)));
+        assert_eq!(view.selection(), Some("hello small world"));
+        view.on_event(Event::Key(Key::Backspace));
+        assert_eq!(&*view.get_content(), "");
+    }
+
+    #[test]
+    fn stale_selection() {
+        let mut view = EditView::new().content("hello big world");
+        view.layout(Vec2::new(20, 1));
+        view.set_cursor(0);
+        view.selection = Some((10, 15));
+
+        // The selection is now out of view.
+        view.layout(Vec2::new(5, 1));
+        let buffer = PrintBuffer::new((5, 1));
+        view.draw(&buffer.printer(&theme::load_default()));
+        assert_eq!(buffer.text(0, 0, 5), "hello");
+
+        // Removing text drops the selection.
+        view.remove(6);
+        assert_eq!(view.selection(), None);
+        assert_eq!(&*view.get_content(), "big world");
+    }
+}
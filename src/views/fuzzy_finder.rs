@@ -0,0 +1,643 @@
+use crossbeam_channel::{self, Receiver, Sender};
+use direction::Direction;
+use event::{Callback, Event, EventResult, Key, MouseEvent};
+use rect::Rect;
+use std::cmp::{min, Reverse};
+use std::rc::Rc;
+use theme::{ColorStyle, Effect};
+use utils::fuzzy::fuzzy_match;
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use {Cursive, Printer, With};
+
+/// Describes an item in the preview pane.
+type Preview<T> = Fn(&T) -> String;
+
+/// Callback for the submitted item.
+type OnSubmit<T> = Fn(&mut Cursive, &T);
+
+/// An item matching the current query.
+#[derive(Clone)]
+struct Entry {
+    /// Index of the item.
+    item: usize,
+    score: i64,
+    /// Byte offset of each matched character in the label.
+    indices: Vec<usize>,
+}
+
+/// Embeddable fuzzy finder, similar to `fzf`.
+///
+/// Shows a prompt, and the items matching the typed query, best first.
+/// `Enter` gives the selected item to the submit callback.
+///
+/// Items can be added directly, or sent from another thread with
+/// [`items_sender`]. An optional preview pane shows details about the
+/// selected item.
+///
+/// Clones share the receiving end of `items_sender`: each item sent is
+/// received by only one of them.
+///
+/// [`items_sender`]: #method.items_sender
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::{FuzzyFinder, TextView};
+/// let finder = FuzzyFinder::new()
+///     .item("Cargo.toml", 1)
+///     .item("src/lib.rs", 2)
+///     .preview(|size: &usize| format!("{} bytes", size))
+///     .on_submit(|s, size: &usize| {
+///         s.add_layer(TextView::new(format!("{} bytes", size)));
+///     });
+/// ```
+pub struct FuzzyFinder<T: 'static> {
+    labels: Vec<String>,
+    /// Shown after the labels; empty if the item has no description.
+    descriptions: Vec<String>,
+    values: Vec<Rc<T>>,
+
+    /// Items sent from other threads, received on layout.
+    receiver: Option<Receiver<(String, T)>>,
+
+    prompt: String,
+    query: String,
+
+    /// Items matching the query, best first.
+    entries: Vec<Entry>,
+
+    /// Index of the selected entry.
+    selected: usize,
+
+    /// Index of the first visible entry.
+    top: usize,
+
+    preview: Option<Rc<Preview<T>>>,
+    on_submit: Option<Rc<OnSubmit<T>>>,
+
+    last_size: Vec2,
+}
+
+impl<T: 'static> Clone for FuzzyFinder<T> {
+    fn clone(&self) -> Self {
+        FuzzyFinder {
+            labels: self.labels.clone(),
+            descriptions: self.descriptions.clone(),
+            values: self.values.clone(),
+            receiver: self.receiver.clone(),
+            prompt: self.prompt.clone(),
+            query: self.query.clone(),
+            entries: self.entries.clone(),
+            selected: self.selected,
+            top: self.top,
+            preview: self.preview.clone(),
+            on_submit: self.on_submit.clone(),
+            last_size: self.last_size,
+        }
+    }
+}
+
+impl<T: 'static> Default for FuzzyFinder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> FuzzyFinder<T> {
+    /// Creates a new, empty fuzzy finder.
+    pub fn new() -> Self {
+        FuzzyFinder {
+            labels: Vec::new(),
+            descriptions: Vec::new(),
+            values: Vec::new(),
+            receiver: None,
+            prompt: "> ".to_string(),
+            query: String::new(),
+            entries: Vec::new(),
+            selected: 0,
+            top: 0,
+            preview: None,
+            on_submit: None,
+            last_size: Vec2::zero(),
+        }
+    }
+
+    /// Adds an item, shown with the given label.
+    pub fn add_item<S: Into<String>>(&mut self, label: S, value: T) {
+        self.add_described_item(label, String::new(), value);
+    }
+
+    /// Adds an item, shown with the given label and description.
+    ///
+    /// Only the label is searched; the description is shown after it.
+    pub fn add_described_item<S, D>(
+        &mut self, label: S, description: D, value: T,
+    ) where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        let i = self.push_item(label.into(), description.into(), value);
+        self.match_items(i..i + 1);
+    }
+
+    /// Adds an item, shown with the given label.
+    ///
+    /// Chainable variant.
+    pub fn item<S: Into<String>>(self, label: S, value: T) -> Self {
+        self.with(|s| s.add_item(label, value))
+    }
+
+    /// Adds an item, shown with the given label and description.
+    ///
+    /// Chainable variant.
+    pub fn described_item<S, D>(
+        self, label: S, description: D, value: T,
+    ) -> Self
+    where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        self.with(|s| s.add_described_item(label, description, value))
+    }
+
+    /// Returns a sender to add items from another thread.
+    ///
+    /// Items are received the next time this view is laid out. Use
+    /// [`Cursive::set_fps`], or send a callback with
+    /// [`Cursive::cb_sink`], so new items are shown right away.
+    ///
+    /// The previous sender, if any, is disconnected.
+    ///
+    /// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+    /// [`Cursive::cb_sink`]: ../struct.Cursive.html#method.cb_sink
+    pub fn items_sender(&mut self) -> Sender<(String, T)> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        self.receiver = Some(receiver);
+        sender
+    }
+
+    /// Receives the items sent with `items_sender`.
+    pub fn receive_items(&mut self) {
+        let items: Vec<_> = match self.receiver {
+            Some(ref receiver) => receiver.try_iter().collect(),
+            None => return,
+        };
+        let start = self.labels.len();
+        for (label, value) in items {
+            self.push_item(label, String::new(), value);
+        }
+        let end = self.labels.len();
+        self.match_items(start..end);
+    }
+
+    /// Returns the number of items, matching or not.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns `true` if there is no item.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Sets the prompt shown before the query.
+    ///
+    /// Defaults to `"> "`.
+    pub fn set_prompt<S: Into<String>>(&mut self, prompt: S) {
+        self.prompt = prompt.into();
+    }
+
+    /// Sets the prompt shown before the query.
+    ///
+    /// Chainable variant.
+    pub fn prompt<S: Into<String>>(self, prompt: S) -> Self {
+        self.with(|s| s.set_prompt(prompt))
+    }
+
+    /// Returns the current query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Replaces the query, and selects the best match.
+    pub fn set_query<S: Into<String>>(&mut self, query: S) {
+        let query = query.into();
+        let narrowing = query.starts_with(self.query.as_str());
+        self.query = query;
+
+        if narrowing {
+            // Items which didn't match won't match a longer query.
+            let candidates: Vec<usize> =
+                self.entries.iter().map(|entry| entry.item).collect();
+            self.entries.clear();
+            self.match_items(candidates);
+        } else {
+            self.entries.clear();
+            let len = self.labels.len();
+            self.match_items(0..len);
+        }
+        self.selected = 0;
+        self.top = 0;
+    }
+
+    /// Returns the number of items matching the query.
+    pub fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the labels of the items matching the query, best first.
+    pub fn matches(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .map(|entry| self.labels[entry.item].as_str())
+            .collect()
+    }
+
+    /// Returns the label of the selected item, if any.
+    pub fn selected_label(&self) -> Option<&str> {
+        self.entries
+            .get(self.selected)
+            .map(|entry| self.labels[entry.item].as_str())
+    }
+
+    /// Returns the selected item, if any.
+    pub fn selection(&self) -> Option<Rc<T>> {
+        self.entries
+            .get(self.selected)
+            .map(|entry| Rc::clone(&self.values[entry.item]))
+    }
+
+    /// Sets a function describing the selected item.
+    ///
+    /// Its result is shown in a pane to the right of the list.
+    pub fn set_preview<F>(&mut self, preview: F)
+    where
+        F: 'static + Fn(&T) -> String,
+    {
+        self.preview = Some(Rc::new(preview));
+    }
+
+    /// Sets a function describing the selected item.
+    ///
+    /// Chainable variant.
+    pub fn preview<F>(self, preview: F) -> Self
+    where
+        F: 'static + Fn(&T) -> String,
+    {
+        self.with(|s| s.set_preview(preview))
+    }
+
+    /// Sets a callback to be used when `<Enter>` is pressed.
+    ///
+    /// The item currently selected will be given to the callback.
+    pub fn set_on_submit<F>(&mut self, cb: F)
+    where
+        F: 'static + Fn(&mut Cursive, &T),
+    {
+        self.on_submit = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when `<Enter>` is pressed.
+    ///
+    /// Chainable variant.
+    pub fn on_submit<F>(self, cb: F) -> Self
+    where
+        F: 'static + Fn(&mut Cursive, &T),
+    {
+        self.with(|s| s.set_on_submit(cb))
+    }
+
+    /// Stores a new item, without matching it. Returns its index.
+    fn push_item(
+        &mut self, label: String, description: String, value: T,
+    ) -> usize {
+        self.labels.push(label);
+        self.descriptions.push(description);
+        self.values.push(Rc::new(value));
+        self.labels.len() - 1
+    }
+
+    /// Adds the given items to the entries if they match the query.
+    fn match_items<I: IntoIterator<Item = usize>>(&mut self, items: I) {
+        let query = &self.query;
+        let labels = &self.labels;
+        self.entries.extend(items.into_iter().filter_map(|i| {
+            fuzzy_match(query, &labels[i]).map(|m| Entry {
+                item: i,
+                score: m.score,
+                indices: m.indices,
+            })
+        }));
+
+        // Keep entries sorted by score, then by item.
+        // Sorting once per batch keeps large streams linearithmic.
+        self.entries
+            .sort_by_key(|entry| (Reverse(entry.score), entry.item));
+    }
+
+    /// Width of the label column.
+    fn label_width(&self) -> usize {
+        self.labels
+            .iter()
+            .map(|label| label.width())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Width of the description column.
+    fn description_width(&self) -> usize {
+        self.descriptions
+            .iter()
+            .map(|description| description.width())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn draw_entry(&self, printer: &Printer, entry: &Entry, selected: bool) {
+        printer.print_hline((0, 0), printer.size.x, " ");
+        draw_match(printer, &self.labels[entry.item], &entry.indices);
+
+        let description = &self.descriptions[entry.item];
+        if description.is_empty() {
+            return;
+        }
+        let x = self.label_width() + 2;
+        if selected {
+            printer.print((x, 0), description);
+        } else {
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print((x, 0), description);
+            });
+        }
+    }
+
+    /// Number of rows available for the entries.
+    fn page_height(&self) -> usize {
+        self.last_size.y.saturating_sub(1)
+    }
+
+    fn select(&mut self, i: usize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.selected = min(i, self.entries.len() - 1);
+
+        // Keep the selection visible.
+        let height = self.page_height().max(1);
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + height {
+            self.top = self.selected + 1 - height;
+        }
+    }
+
+    fn submit(&self) -> EventResult {
+        let cb = match self.on_submit {
+            Some(ref cb) => Rc::clone(cb),
+            None => return EventResult::Ignored,
+        };
+        EventResult::Consumed(
+            self.selection()
+                .map(|v| Callback::from_fn(move |s| cb(s, &v))),
+        )
+    }
+
+    /// Width of the list, leaving the rest for the preview.
+    fn list_width(&self, width: usize) -> usize {
+        if self.preview.is_some() {
+            width / 2
+        } else {
+            width
+        }
+    }
+
+    fn draw_preview(&self, printer: &Printer) {
+        let preview = match (&self.preview, self.selection()) {
+            (Some(preview), Some(value)) => preview(&value),
+            _ => return,
+        };
+        for (y, line) in preview.lines().take(printer.size.y).enumerate() {
+            printer.print((0, y), line);
+        }
+    }
+}
+
+/// Prints `text`, underlining the characters at the given byte offsets.
+fn draw_match(printer: &Printer, text: &str, indices: &[usize]) {
+    let mut x = 0;
+    for (i, c) in text.char_indices() {
+        let text = &text[i..i + c.len_utf8()];
+        if indices.contains(&i) {
+            printer.with_effect(Effect::Underline, |printer| {
+                printer.print((x, 0), text);
+            });
+        } else {
+            printer.print((x, 0), text);
+        }
+        x += text.width();
+    }
+}
+
+impl<T: 'static> View for FuzzyFinder<T> {
+    fn draw(&self, printer: &Printer) {
+        printer.print((0, 0), &self.prompt);
+        let x = self.prompt.width();
+        printer.print((x, 0), &self.query);
+        if printer.focused {
+            let x = x + self.query.width();
+            printer.with_effect(Effect::Reverse, |printer| {
+                printer.print((x, 0), " ");
+            });
+        }
+
+        let count = format!("{}/{}", self.entries.len(), self.labels.len());
+        let x = printer.size.x.saturating_sub(count.width());
+        printer.with_color(ColorStyle::secondary(), |printer| {
+            printer.print((x, 0), &count);
+        });
+
+        let width = self.list_width(printer.size.x);
+        let list = printer.offset((0, 1)).cropped((width, printer.size.y));
+        let height = self.page_height();
+        for (y, i) in (self.top..self.entries.len()).take(height).enumerate()
+        {
+            let selected = i == self.selected;
+            let printer = list.offset((0, y));
+            printer.with_selection(selected, |printer| {
+                self.draw_entry(printer, &self.entries[i], selected);
+            });
+        }
+
+        if self.preview.is_some() {
+            let printer = printer.offset((width, 1));
+            let vertical = printer.border_chars().vertical;
+            printer.print_vline((0, 0), printer.size.y, vertical);
+            self.draw_preview(&printer.offset((2, 0)));
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        self.receive_items();
+
+        let description = match self.description_width() {
+            0 => 0,
+            width => width + 2,
+        };
+        let list = (self.label_width() + description)
+            .max(self.prompt.width() + self.query.width() + 1);
+        let width = if self.preview.is_some() {
+            // Leave as much room for the preview.
+            2 * list + 2
+        } else {
+            list
+        };
+
+        Vec2::new(width, self.labels.len() + 1)
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.receive_items();
+        self.last_size = size;
+        let selected = self.selected;
+        self.select(selected);
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let page = self.page_height().max(1);
+
+        match event {
+            Event::Char(c) => {
+                let query = format!("{}{}", self.query, c);
+                self.set_query(query);
+            }
+            Event::Key(Key::Backspace) => {
+                let mut query = self.query.clone();
+                if query.pop().is_none() {
+                    return EventResult::Ignored;
+                }
+                self.set_query(query);
+            }
+            Event::Key(Key::Up) | Event::CtrlChar('p') => {
+                let i = self.selected.saturating_sub(1);
+                self.select(i);
+            }
+            Event::Key(Key::Down) | Event::CtrlChar('n') => {
+                let i = self.selected + 1;
+                self.select(i);
+            }
+            Event::Key(Key::PageUp) => {
+                let i = self.selected.saturating_sub(page);
+                self.select(i);
+            }
+            Event::Key(Key::PageDown) => {
+                let i = self.selected + page;
+                self.select(i);
+            }
+            Event::Mouse {
+                event: MouseEvent::WheelUp,
+                ..
+            } => {
+                let i = self.selected.saturating_sub(1);
+                self.select(i);
+            }
+            Event::Mouse {
+                event: MouseEvent::WheelDown,
+                ..
+            } => {
+                let i = self.selected + 1;
+                self.select(i);
+            }
+            Event::Key(Key::Enter) => return self.submit(),
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn important_area(&self, size: Vec2) -> Rect {
+        let y = self.selected.saturating_sub(self.top) + 1;
+        Rect::from_size((0, y), (self.list_width(size.x), 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::thread;
+
+    fn type_query(finder: &mut FuzzyFinder<usize>, query: &str) {
+        for c in query.chars() {
+            finder.on_event(Event::Char(c));
+        }
+    }
+
+    #[test]
+    fn filtering() {
+        let mut finder = FuzzyFinder::new()
+            .item("src/views/mod.rs", 0)
+            .item("src/lib.rs", 1)
+            .item("Cargo.toml", 2);
+        finder.layout(Vec2::new(30, 10));
+        assert_eq!(finder.match_count(), 3);
+
+        type_query(&mut finder, "s");
+        assert_eq!(finder.matches(), vec!["src/views/mod.rs", "src/lib.rs"]);
+        type_query(&mut finder, "l");
+        assert_eq!(finder.matches(), vec!["src/lib.rs"]);
+        assert_eq!(finder.selection(), Some(Rc::new(1)));
+
+        type_query(&mut finder, "x");
+        assert_eq!(finder.match_count(), 0);
+        assert_eq!(finder.selection(), None);
+
+        finder.on_event(Event::Key(Key::Backspace));
+        finder.on_event(Event::Key(Key::Backspace));
+        assert_eq!(finder.query(), "s");
+        assert_eq!(finder.match_count(), 2);
+    }
+
+    #[test]
+    fn streaming() {
+        let mut finder = FuzzyFinder::new().item("zero", 0);
+        finder.set_query("o");
+        let sender = finder.items_sender();
+
+        thread::spawn(move || {
+            for (i, label) in ["one", "two", "three"].iter().enumerate() {
+                sender.send((label.to_string(), i + 1)).unwrap();
+            }
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(finder.len(), 1);
+        finder.layout(Vec2::new(30, 10));
+        assert_eq!(finder.len(), 4);
+        assert_eq!(finder.matches(), vec!["one", "two", "zero"]);
+    }
+
+    #[test]
+    fn submit() {
+        let submitted = Rc::new(Cell::new(None));
+        let result = Rc::clone(&submitted);
+
+        let mut finder = FuzzyFinder::new()
+            .item("a", 1)
+            .item("b", 2)
+            .preview(|v: &usize| format!("value {}", v))
+            .on_submit(move |_, v: &usize| result.set(Some(*v)));
+        finder.layout(Vec2::new(30, 10));
+        finder.on_event(Event::Key(Key::Down));
+
+        let mut siv = Cursive::dummy();
+        finder.on_event(Event::Key(Key::Enter)).process(&mut siv);
+        assert_eq!(submitted.get(), Some(2));
+    }
+}
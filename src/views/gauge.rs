@@ -0,0 +1,217 @@
+use std::cmp;
+use theme::{BaseColor, Color, ColorStyle, PaletteColor};
+use utils::bar::bar_string;
+use utils::width::StrWidth;
+use utils::Counter;
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Preferred width of the bar, excluding the brackets.
+const DEFAULT_BAR_WIDTH: usize = 20;
+
+/// Meter showing a value within a range.
+///
+/// The bar is green, turns yellow when the value reaches the warning
+/// threshold, and red when it reaches the critical threshold. The
+/// percentage is shown after the bar.
+///
+/// Like [`ProgressBar`], the value is stored in a [`Counter`], which can be
+/// shared with other threads to update the gauge.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::Gauge;
+/// # use cursive::utils::Counter;
+/// let load = Counter::new(0);
+/// let gauge = Gauge::new()
+///     .label("CPU")
+///     .thresholds(50, 80)
+///     .with_value(load.clone());
+///
+/// // Later, from any thread:
+/// load.set(65);
+/// ```
+///
+/// [`ProgressBar`]: struct.ProgressBar.html
+/// [`Counter`]: ../utils/struct.Counter.html
+pub struct Gauge {
+    min: usize,
+    max: usize,
+    value: Counter,
+    warning: usize,
+    critical: usize,
+    label: String,
+}
+
+new_default!(Gauge);
+
+impl Gauge {
+    /// Creates a new gauge.
+    ///
+    /// Default values:
+    ///
+    /// * `min`: 0
+    /// * `max`: 100
+    /// * `value`: 0
+    /// * warning threshold: 70
+    /// * critical threshold: 90
+    pub fn new() -> Self {
+        Gauge {
+            min: 0,
+            max: 100,
+            value: Counter::new(0),
+            warning: 70,
+            critical: 90,
+            label: String::new(),
+        }
+    }
+
+    /// Sets the value to follow.
+    pub fn with_value(mut self, value: Counter) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Returns a handle to the value shown.
+    ///
+    /// Setting the value of this counter updates the gauge.
+    pub fn counter(&self) -> Counter {
+        self.value.clone()
+    }
+
+    /// Sets the current value.
+    pub fn set_value(&mut self, value: usize) {
+        self.value.set(value);
+    }
+
+    /// Returns the current value.
+    pub fn get_value(&self) -> usize {
+        self.value.get()
+    }
+
+    /// Sets the `min` and `max` range for the value.
+    ///
+    /// If `min > max`, swap the two values.
+    pub fn set_range(&mut self, min: usize, max: usize) {
+        self.min = cmp::min(min, max);
+        self.max = cmp::max(min, max);
+    }
+
+    /// Sets the `min` and `max` range for the value.
+    ///
+    /// Chainable variant.
+    pub fn range(self, min: usize, max: usize) -> Self {
+        self.with(|s| s.set_range(min, max))
+    }
+
+    /// Sets the values from which the gauge turns yellow, then red.
+    pub fn set_thresholds(&mut self, warning: usize, critical: usize) {
+        self.warning = warning;
+        self.critical = critical;
+    }
+
+    /// Sets the values from which the gauge turns yellow, then red.
+    ///
+    /// Chainable variant.
+    pub fn thresholds(self, warning: usize, critical: usize) -> Self {
+        self.with(|s| s.set_thresholds(warning, critical))
+    }
+
+    /// Sets the label shown before the bar.
+    pub fn set_label<S: Into<String>>(&mut self, label: S) {
+        self.label = label.into();
+    }
+
+    /// Sets the label shown before the bar.
+    ///
+    /// Chainable variant.
+    pub fn label<S: Into<String>>(self, label: S) -> Self {
+        self.with(|s| s.set_label(label))
+    }
+
+    /// Returns the color for the given value.
+    fn color(&self, value: usize) -> Color {
+        if value >= self.critical {
+            Color::Dark(BaseColor::Red)
+        } else if value >= self.warning {
+            Color::Dark(BaseColor::Yellow)
+        } else {
+            Color::Dark(BaseColor::Green)
+        }
+    }
+
+    /// Returns the position of `value` in the range, between 0 and 1.
+    fn ratio(&self, value: usize) -> f64 {
+        if self.max == self.min {
+            return 1.0;
+        }
+        let value = cmp::min(cmp::max(value, self.min), self.max);
+        (value - self.min) as f64 / (self.max - self.min) as f64
+    }
+
+    /// Width taken by the label, including the separating space.
+    fn label_width(&self) -> usize {
+        if self.label.is_empty() {
+            0
+        } else {
+            self.label.width() + 1
+        }
+    }
+}
+
+impl View for Gauge {
+    fn draw(&self, printer: &Printer) {
+        let value = self.value.get();
+        let ratio = self.ratio(value);
+        let percent = format!("{:>4}", format!("{:.0}%", ratio * 100.0));
+
+        let label_width = self.label_width();
+        printer.print((0, 0), &self.label);
+
+        // Brackets, and a space before the percentage.
+        let bar_width = printer
+            .size
+            .x
+            .saturating_sub(label_width + 3 + percent.len());
+        printer.print((label_width, 0), "[");
+        printer.print((label_width + 1 + bar_width, 0), "]");
+
+        let color = ColorStyle::new(self.color(value), PaletteColor::View);
+        printer.with_color(color, |printer| {
+            printer.print((label_width + 1, 0), &bar_string(ratio, bar_width));
+        });
+
+        printer.print((label_width + bar_width + 3, 0), &percent);
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        // "[", bar, "] ", then "100%".
+        Vec2::new(self.label_width() + DEFAULT_BAR_WIDTH + 7, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_value() {
+        let gauge = Gauge::new().range(100, 0);
+        let counter = gauge.counter();
+
+        counter.set(50);
+        assert_eq!(gauge.get_value(), 50);
+        assert_eq!(gauge.ratio(50), 0.5);
+        assert_eq!(gauge.ratio(150), 1.0);
+    }
+
+    #[test]
+    fn thresholds() {
+        let gauge = Gauge::new().thresholds(50, 80);
+        assert_eq!(gauge.color(10), Color::Dark(BaseColor::Green));
+        assert_eq!(gauge.color(50), Color::Dark(BaseColor::Yellow));
+        assert_eq!(gauge.color(95), Color::Dark(BaseColor::Red));
+    }
+}
@@ -0,0 +1,438 @@
+use direction::Direction;
+use event::{Callback, Event, EventResult, Key, MouseButton, MouseEvent};
+use rect::Rect;
+use std::cmp::min;
+use std::rc::Rc;
+use theme::ColorStyle;
+use vec::Vec2;
+use view::View;
+use {Cursive, Printer, With};
+
+/// Width of the offset column, including the separating spaces.
+const OFFSET_WIDTH: usize = 10;
+
+/// Width taken by a row, excluding the bytes.
+const MARGIN: usize = OFFSET_WIDTH + 1;
+
+/// Bytes per row when the width is not constrained.
+const DEFAULT_BYTES_PER_ROW: usize = 16;
+
+/// Closure type for callbacks when a byte is modified.
+///
+/// Arguments are the `Cursive`, the offset of the byte and its new value.
+pub type OnByteEdit = Fn(&mut Cursive, usize, u8);
+
+/// Displays binary data as a hex dump.
+///
+/// Each row shows the offset of its first byte, the bytes in hexadecimal,
+/// and their ASCII representation.
+///
+/// Use the arrow keys, `PageUp`/`PageDown` and `Home`/`End` to move the
+/// cursor. When the view is editable, typing hexadecimal digits changes the
+/// byte under the cursor.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::HexView;
+/// let view = HexView::new(vec![0xca, 0xfe, 0xba, 0xbe]).editable(true);
+/// ```
+pub struct HexView {
+    data: Vec<u8>,
+
+    /// Offset of the selected byte.
+    cursor: usize,
+
+    /// `true` if the high nibble of the selected byte was just typed.
+    half_typed: bool,
+
+    editable: bool,
+
+    /// Fixed number of bytes per row, or `None` to fill the width.
+    fixed_row_len: Option<usize>,
+
+    /// Number of bytes per row, computed during layout.
+    row_len: usize,
+
+    /// Index of the first visible row.
+    top: usize,
+
+    on_edit: Option<Rc<OnByteEdit>>,
+
+    last_size: Vec2,
+}
+
+impl HexView {
+    /// Creates a new view showing the given data.
+    pub fn new<D: Into<Vec<u8>>>(data: D) -> Self {
+        HexView {
+            data: data.into(),
+            cursor: 0,
+            half_typed: false,
+            editable: false,
+            fixed_row_len: None,
+            row_len: DEFAULT_BYTES_PER_ROW,
+            top: 0,
+            on_edit: None,
+            last_size: Vec2::zero(),
+        }
+    }
+
+    /// Replaces the data shown by this view.
+    pub fn set_data<D: Into<Vec<u8>>>(&mut self, data: D) {
+        self.data = data.into();
+        self.half_typed = false;
+        self.set_cursor(self.cursor);
+    }
+
+    /// Returns the data shown by this view.
+    ///
+    /// Includes the modifications made by the user.
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Enables or disables editing.
+    pub fn set_editable(&mut self, editable: bool) {
+        self.editable = editable;
+        self.half_typed = false;
+    }
+
+    /// Enables or disables editing.
+    ///
+    /// Chainable variant.
+    pub fn editable(self, editable: bool) -> Self {
+        self.with(|s| s.set_editable(editable))
+    }
+
+    /// Returns `true` if the user can edit the data.
+    pub fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    /// Sets the number of bytes shown per row.
+    ///
+    /// With `None` (the default), rows fill the available width.
+    pub fn set_bytes_per_row<N: Into<Option<usize>>>(&mut self, n: N) {
+        self.fixed_row_len = n.into().map(|n| n.max(1));
+        if let Some(n) = self.fixed_row_len {
+            self.row_len = n;
+        }
+    }
+
+    /// Sets the number of bytes shown per row.
+    ///
+    /// Chainable variant.
+    pub fn bytes_per_row<N: Into<Option<usize>>>(self, n: N) -> Self {
+        self.with(|s| s.set_bytes_per_row(n))
+    }
+
+    /// Returns the number of bytes currently shown per row.
+    pub fn row_len(&self) -> usize {
+        self.row_len
+    }
+
+    /// Returns the offset of the selected byte.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Selects the byte at the given offset.
+    pub fn set_cursor(&mut self, offset: usize) {
+        self.cursor = min(offset, self.data.len().saturating_sub(1));
+        self.half_typed = false;
+        self.scroll_to_cursor();
+    }
+
+    /// Sets a callback to be called when a byte is modified.
+    ///
+    /// `callback` will be called with the offset and the new value of the
+    /// byte.
+    pub fn set_on_edit<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut Cursive, usize, u8) + 'static,
+    {
+        self.on_edit = Some(Rc::new(callback));
+    }
+
+    /// Sets a callback to be called when a byte is modified.
+    ///
+    /// Chainable variant.
+    pub fn on_edit<F>(self, callback: F) -> Self
+    where
+        F: Fn(&mut Cursive, usize, u8) + 'static,
+    {
+        self.with(|s| s.set_on_edit(callback))
+    }
+
+    /// Returns the number of bytes per row fitting in the given width.
+    ///
+    /// More than 8 bytes are rounded down to a multiple of 8.
+    fn fit_row_len(width: usize) -> usize {
+        // Each byte takes 3 cells in the hex part, and 1 in the ASCII part.
+        let n = width.saturating_sub(MARGIN) / 4;
+        if n > 8 {
+            n - n % 8
+        } else {
+            n.max(1)
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        let rows = self.data.len().div_ceil(self.row_len);
+        rows.max(1)
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let row = self.cursor / self.row_len;
+        let height = self.last_size.y.max(1);
+        if row < self.top {
+            self.top = row;
+        } else if row >= self.top + height {
+            self.top = row + 1 - height;
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let target = self.cursor as isize + delta;
+        if target >= 0 && (target as usize) < self.data.len() {
+            self.set_cursor(target as usize);
+        }
+    }
+
+    /// Types an hexadecimal digit at the cursor.
+    fn type_digit(&mut self, digit: u8) -> EventResult {
+        let byte = &mut self.data[self.cursor];
+        if self.half_typed {
+            *byte = (*byte & 0xF0) | digit;
+        } else {
+            *byte = (*byte & 0x0F) | (digit << 4);
+        }
+        let (offset, value) = (self.cursor, *byte);
+
+        if self.half_typed {
+            self.half_typed = false;
+            self.move_cursor(1);
+        } else {
+            self.half_typed = true;
+        }
+
+        EventResult::Consumed(
+            self.on_edit
+                .clone()
+                .map(|cb| Callback::from_fn(move |s| cb(s, offset, value))),
+        )
+    }
+
+    /// Returns the offset of the byte at the given position, if any.
+    fn byte_at(&self, position: Vec2) -> Option<usize> {
+        let row = self.top + position.y;
+        let hex_end = OFFSET_WIDTH + 3 * self.row_len;
+        let column = if position.x >= OFFSET_WIDTH && position.x < hex_end {
+            (position.x - OFFSET_WIDTH) / 3
+        } else if position.x > hex_end && position.x <= hex_end + self.row_len
+        {
+            position.x - hex_end - 1
+        } else {
+            return None;
+        };
+
+        let offset = row * self.row_len + column;
+        if offset < self.data.len() {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+}
+
+impl View for HexView {
+    fn draw(&self, printer: &Printer) {
+        let hex_end = OFFSET_WIDTH + 3 * self.row_len;
+        let selected = if printer.focused {
+            ColorStyle::highlight()
+        } else {
+            ColorStyle::highlight_inactive()
+        };
+
+        let rows = (self.top..self.row_count()).take(printer.size.y);
+        for (y, row) in rows.enumerate() {
+            let start = row * self.row_len;
+            let end = min(start + self.row_len, self.data.len());
+
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print((0, y), &format!("{:08x}", start));
+            });
+
+            for (i, &byte) in self.data[start..end].iter().enumerate() {
+                let hex = format!("{:02x}", byte);
+                let ascii = if byte.is_ascii_graphic() || byte == b' ' {
+                    (byte as char).to_string()
+                } else {
+                    ".".to_string()
+                };
+
+                let hex_pos = (OFFSET_WIDTH + 3 * i, y);
+                let ascii_pos = (hex_end + 1 + i, y);
+                if start + i == self.cursor {
+                    printer.with_color(selected.clone(), |printer| {
+                        printer.print(hex_pos, &hex);
+                        printer.print(ascii_pos, &ascii);
+                    });
+                } else {
+                    printer.print(hex_pos, &hex);
+                    printer.print(ascii_pos, &ascii);
+                }
+            }
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let row_len = match self.fixed_row_len {
+            Some(n) => n,
+            None if constraint.x == usize::MAX => DEFAULT_BYTES_PER_ROW,
+            None => HexView::fit_row_len(constraint.x),
+        };
+        let rows = self.data.len().div_ceil(row_len);
+
+        Vec2::new(MARGIN + 4 * row_len, rows.max(1))
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.last_size = size;
+        if self.fixed_row_len.is_none() {
+            self.row_len = HexView::fit_row_len(size.x);
+        }
+        self.top = min(self.top, self.row_count().saturating_sub(size.y));
+        self.scroll_to_cursor();
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let row_len = self.row_len as isize;
+        let page = self.last_size.y.max(1) as isize * row_len;
+
+        match event {
+            Event::Key(Key::Left) => self.move_cursor(-1),
+            Event::Key(Key::Right) => self.move_cursor(1),
+            Event::Key(Key::Up) => self.move_cursor(-row_len),
+            Event::Key(Key::Down) => self.move_cursor(row_len),
+            Event::Key(Key::PageUp) => {
+                let offset = self.cursor.saturating_sub(page as usize);
+                self.set_cursor(offset);
+            }
+            Event::Key(Key::PageDown) => {
+                let offset = self.cursor + page as usize;
+                self.set_cursor(offset);
+            }
+            Event::Key(Key::Home) => {
+                let offset = self.cursor - self.cursor % self.row_len;
+                self.set_cursor(offset);
+            }
+            Event::Key(Key::End) => {
+                let offset = self.cursor - self.cursor % self.row_len;
+                self.set_cursor(offset + self.row_len - 1);
+            }
+            Event::Char(c)
+                if self.editable
+                    && !self.data.is_empty()
+                    && c.is_ascii_hexdigit() =>
+            {
+                let digit = c.to_digit(16).unwrap() as u8;
+                return self.type_digit(digit);
+            }
+            Event::Mouse {
+                event: MouseEvent::Press(MouseButton::Left),
+                position,
+                offset,
+            } => match position
+                .checked_sub(offset)
+                .and_then(|position| self.byte_at(position))
+            {
+                Some(byte) => self.set_cursor(byte),
+                None => return EventResult::Ignored,
+            },
+            Event::Mouse {
+                event: MouseEvent::WheelUp,
+                ..
+            } => self.top = self.top.saturating_sub(3),
+            Event::Mouse {
+                event: MouseEvent::WheelDown,
+                ..
+            } => {
+                let max_top =
+                    self.row_count().saturating_sub(self.last_size.y);
+                self.top = min(self.top + 3, max_top);
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn important_area(&self, _: Vec2) -> Rect {
+        let row = self.cursor / self.row_len;
+        let column = self.cursor % self.row_len;
+        Rect::from_size(
+            (OFFSET_WIDTH + 3 * column, row.saturating_sub(self.top)),
+            (2, 1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_len_fits_width() {
+        assert_eq!(HexView::fit_row_len(0), 1);
+        assert_eq!(HexView::fit_row_len(MARGIN + 4 * 5), 5);
+        assert_eq!(HexView::fit_row_len(MARGIN + 4 * 17), 16);
+
+        let mut view = HexView::new(vec![0; 100]);
+        assert_eq!(view.required_size(Vec2::new(80, 10)), Vec2::new(75, 7));
+        view.layout(Vec2::new(80, 10));
+        assert_eq!(view.row_len(), 16);
+
+        view.set_bytes_per_row(4);
+        assert_eq!(view.required_size(Vec2::new(80, 10)), Vec2::new(27, 25));
+    }
+
+    #[test]
+    fn navigation() {
+        let mut view = HexView::new(vec![0; 100]).bytes_per_row(16);
+        view.layout(Vec2::new(75, 3));
+
+        view.on_event(Event::Key(Key::Down));
+        view.on_event(Event::Key(Key::Right));
+        assert_eq!(view.cursor(), 17);
+
+        view.on_event(Event::Key(Key::End));
+        assert_eq!(view.cursor(), 31);
+
+        view.on_event(Event::Key(Key::PageDown));
+        assert_eq!(view.cursor(), 79);
+        assert_eq!(view.top, 2);
+
+        view.on_event(Event::Key(Key::PageDown));
+        assert_eq!(view.cursor(), 99);
+    }
+
+    #[test]
+    fn editing() {
+        let mut view = HexView::new(vec![0; 4]);
+        view.on_event(Event::Char('a'));
+        assert_eq!(view.get_data(), &[0, 0, 0, 0]);
+
+        view.set_editable(true);
+        for c in "cafe".chars() {
+            view.on_event(Event::Char(c));
+        }
+        assert_eq!(view.get_data(), &[0xca, 0xfe, 0, 0]);
+        assert_eq!(view.cursor(), 2);
+    }
+}
@@ -1,9 +1,9 @@
 use owning_ref::{OwningHandle, RcRef};
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::cell::{RefCell, RefMut};
 use std::ops::DerefMut;
 use std::rc::Rc;
-use view::{Selector, View, ViewWrapper};
+use view::{IdState, Selector, View, ViewWrapper};
 
 /// Wrapper around a view to provide interior mutability.
 pub struct IdView<V: View> {
@@ -79,24 +79,70 @@ impl<T: View + 'static> ViewWrapper for IdView<T> {
     fn wrap_call_on_any<'a>(
         &mut self, selector: &Selector, mut callback: BoxedCallback<'a>,
     ) {
-        match selector {
-            &Selector::Id(id) if id == self.id => callback(self),
-            s => {
+        let inner = selector.for_id(&self.id);
+        match (selector, inner) {
+            (&Selector::Id(id), _) if id == self.id => callback(self),
+            (&Selector::Type(t), _) if t == TypeId::of::<IdState>() => {
+                // Saving or restoring states: expose ours, then recurse.
                 if let Ok(mut v) = self.view.try_borrow_mut() {
-                    v.deref_mut().call_on_any(s, callback);
+                    let mut slot = IdState {
+                        id: self.id.clone(),
+                        state: v.state(),
+                        restore: None,
+                    };
+                    callback(&mut slot);
+                    if let Some(ref state) = slot.restore {
+                        v.restore_state(state);
+                    }
+                    v.deref_mut().call_on_any(selector, callback);
+                }
+            }
+            (_, Some(Selector::Path(""))) => callback(self),
+            (s, inner) => {
+                if let Ok(mut v) = self.view.try_borrow_mut() {
+                    v.deref_mut()
+                        .call_on_any(inner.as_ref().unwrap_or(s), callback);
                 }
             }
         }
     }
 
     fn wrap_focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
-        match selector {
-            &Selector::Id(id) if id == self.id => Ok(()),
-            s => self
+        let inner = selector.for_id(&self.id);
+        match (selector, inner) {
+            (&Selector::Id(id), _) if id == self.id => Ok(()),
+            (_, Some(Selector::Path(""))) => Ok(()),
+            (s, inner) => self
                 .view
                 .try_borrow_mut()
                 .map_err(|_| ())
-                .and_then(|mut v| v.deref_mut().focus_view(s)),
+                .and_then(|mut v| {
+                    v.deref_mut().focus_view(inner.as_ref().unwrap_or(s))
+                }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use view::Identifiable;
+    use views::{EditView, LinearLayout, TextView};
+    use Cursive;
+
+    #[test]
+    fn several_refs() {
+        let mut siv = Cursive::dummy();
+        siv.add_layer(
+            LinearLayout::vertical()
+                .child(EditView::new().content("input").with_id("edit"))
+                .child(TextView::new("").with_id("text")),
+        );
+
+        // Both views can be borrowed at the same time.
+        let edit: ViewRef<EditView> = siv.find_id("edit").unwrap();
+        let mut text: ViewRef<TextView> = siv.find_id("text").unwrap();
+        text.set_content(edit.get_content().to_uppercase());
+        assert_eq!(text.get_content().source(), "INPUT");
+    }
+}
@@ -0,0 +1,489 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageResult, Rgba, RgbaImage};
+use std::cell::RefCell;
+use std::env;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use theme::{Color, ColorStyle, ColorType, PaletteColor};
+use utils::base64;
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Identifiers for kitty images, so each view replaces its own image.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Size of base64 chunks in kitty graphics sequences.
+const KITTY_CHUNK: usize = 4096;
+
+/// Way an [`ImageView`] displays its image.
+///
+/// [`ImageView`]: struct.ImageView.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// Kitty graphics protocol, supported by kitty, WezTerm, ghostty...
+    Kitty,
+    /// Sixel graphics, supported by xterm (in some modes), foot, mlterm...
+    Sixel,
+    /// Colored half-block characters, supported by any terminal with
+    /// true colors.
+    Blocks,
+}
+
+impl ImageProtocol {
+    /// Guesses the best protocol for the current terminal.
+    ///
+    /// This only looks at environment variables like `TERM`, and falls back
+    /// to `Blocks`.
+    pub fn detect() -> Self {
+        detect_from(|name| env::var(name).ok())
+    }
+}
+
+fn detect_from<F>(var: F) -> ImageProtocol
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let term = var("TERM").unwrap_or_default();
+    let program = var("TERM_PROGRAM").unwrap_or_default();
+
+    if var("KITTY_WINDOW_ID").is_some()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || program == "WezTerm"
+    {
+        ImageProtocol::Kitty
+    } else if term.contains("sixel")
+        || term.starts_with("foot")
+        || term.starts_with("mlterm")
+    {
+        ImageProtocol::Sixel
+    } else {
+        ImageProtocol::Blocks
+    }
+}
+
+/// Displays an image.
+///
+/// Requires the `image-view` feature.
+///
+/// Images are drawn using the kitty graphics protocol or sixels when
+/// available (see [`ImageProtocol::detect`]), and with colored half blocks
+/// otherwise. Graphics protocols are only supported by some backends (like
+/// termion): other backends always use blocks.
+///
+/// The image is scaled down to fit the view, keeping its aspect ratio.
+///
+/// With kitty, the image is only transmitted when its size changes, and
+/// later frames only place it again. Sixels are part of the cells, so they
+/// are sent on every frame.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cursive::views::ImageView;
+/// let view = ImageView::open("preview.png").unwrap();
+/// ```
+///
+/// [`ImageProtocol::detect`]: enum.ImageProtocol.html#method.detect
+pub struct ImageView {
+    image: RgbaImage,
+    protocol: ImageProtocol,
+
+    /// Size of a cell, in pixels.
+    cell_size: Vec2,
+
+    /// Identifier for the kitty protocol.
+    id: usize,
+
+    /// Graphics sequence for the last size drawn.
+    graphics: RefCell<Option<Graphics>>,
+
+    /// Image resized for the last size drawn with blocks.
+    blocks: RefCell<Option<(Vec2, RgbaImage)>>,
+}
+
+/// Graphics sequence showing the image at a given size.
+struct Graphics {
+    size: Vec2,
+    data: String,
+    /// `true` once the kitty image was transmitted, so it only needs to be
+    /// placed.
+    sent: bool,
+}
+
+impl ImageView {
+    /// Creates a new view showing the given image.
+    ///
+    /// The protocol is detected with [`ImageProtocol::detect`].
+    ///
+    /// [`ImageProtocol::detect`]: enum.ImageProtocol.html#method.detect
+    pub fn new<I: Into<DynamicImage>>(image: I) -> Self {
+        ImageView {
+            image: image.into().to_rgba8(),
+            protocol: ImageProtocol::detect(),
+            cell_size: Vec2::new(10, 20),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            graphics: RefCell::new(None),
+            blocks: RefCell::new(None),
+        }
+    }
+
+    /// Creates a new view showing the image in the given file.
+    pub fn open<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        image::open(path).map(ImageView::new)
+    }
+
+    /// Replaces the image shown.
+    pub fn set_image<I: Into<DynamicImage>>(&mut self, image: I) {
+        self.image = image.into().to_rgba8();
+        self.invalidate();
+    }
+
+    /// Sets the protocol used to display the image.
+    pub fn set_protocol(&mut self, protocol: ImageProtocol) {
+        self.protocol = protocol;
+        self.invalidate();
+    }
+
+    /// Sets the protocol used to display the image.
+    ///
+    /// Chainable variant.
+    pub fn protocol(self, protocol: ImageProtocol) -> Self {
+        self.with(|s| s.set_protocol(protocol))
+    }
+
+    /// Sets the size of a terminal cell, in pixels.
+    ///
+    /// Used to keep the aspect ratio of images. Defaults to 10x20.
+    pub fn set_cell_size<S: Into<Vec2>>(&mut self, size: S) {
+        self.cell_size = size.into().or_max((1, 1));
+        self.invalidate();
+    }
+
+    /// Sets the size of a terminal cell, in pixels.
+    ///
+    /// Chainable variant.
+    pub fn cell_size<S: Into<Vec2>>(self, size: S) -> Self {
+        self.with(|s| s.set_cell_size(size))
+    }
+
+    fn invalidate(&mut self) {
+        *self.graphics.borrow_mut() = None;
+        *self.blocks.borrow_mut() = None;
+    }
+
+    /// Returns the size, in cells, of the image scaled to fit `available`.
+    fn fit(&self, available: Vec2) -> Vec2 {
+        let (width, height) = self.image.dimensions();
+        if width == 0 || height == 0 {
+            return Vec2::zero();
+        }
+
+        // Size in cells, as floats to keep the aspect ratio.
+        let natural_x = f64::from(width) / self.cell_size.x as f64;
+        let natural_y = f64::from(height) / self.cell_size.y as f64;
+        let scale = (available.x as f64 / natural_x)
+            .min(available.y as f64 / natural_y)
+            .min(1.0);
+
+        Vec2::new(
+            ((natural_x * scale).round() as usize).max(1),
+            ((natural_y * scale).round() as usize).max(1),
+        )
+        .or_min(available)
+    }
+
+    /// Returns the image resized to fill `size` cells with pixels.
+    fn resized(&self, size: Vec2) -> RgbaImage {
+        let pixels = size * self.cell_size;
+        imageops::resize(
+            &self.image,
+            pixels.x as u32,
+            pixels.y as u32,
+            FilterType::Triangle,
+        )
+    }
+
+    fn draw_graphics(&self, printer: &Printer, size: Vec2) -> bool {
+        let mut cache = self.graphics.borrow_mut();
+        if cache.as_ref().map(|cached| cached.size) != Some(size) {
+            let image = self.resized(size);
+            let data = match self.protocol {
+                ImageProtocol::Kitty => encode_kitty(&image, self.id),
+                ImageProtocol::Sixel => encode_sixel(&image),
+                ImageProtocol::Blocks => return false,
+            };
+            *cache = Some(Graphics {
+                size,
+                data,
+                sent: false,
+            });
+        }
+        let graphics = cache.as_mut().unwrap();
+
+        if self.protocol != ImageProtocol::Kitty {
+            return printer.print_graphics((0, 0), size, &graphics.data);
+        }
+
+        let placement = place_kitty(size, self.id);
+        if graphics.sent {
+            return printer.print_graphics((0, 0), size, &placement);
+        }
+        let data = format!("{}{}", graphics.data, placement);
+        graphics.sent = printer.print_graphics((0, 0), size, &data);
+        if graphics.sent {
+            // The terminal keeps the image from now on.
+            graphics.data = String::new();
+        }
+        graphics.sent
+    }
+
+    fn draw_blocks(&self, printer: &Printer, size: Vec2) {
+        let mut cache = self.blocks.borrow_mut();
+        if cache.as_ref().map(|cached| cached.0) != Some(size) {
+            let image = imageops::resize(
+                &self.image,
+                size.x as u32,
+                2 * size.y as u32,
+                FilterType::Triangle,
+            );
+            *cache = Some((size, image));
+        }
+        let image = &cache.as_ref().unwrap().1;
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let top = image.get_pixel(x as u32, 2 * y as u32);
+                let bottom = image.get_pixel(x as u32, 2 * y as u32 + 1);
+                let (text, style) = half_block(*top, *bottom);
+                printer.with_color(style, |printer| {
+                    printer.print((x, y), text);
+                });
+            }
+        }
+    }
+}
+
+/// Returns the color of a pixel, or `None` if it is mostly transparent.
+fn pixel_color(pixel: Rgba<u8>) -> Option<ColorType> {
+    let [r, g, b, a] = pixel.0;
+    if a < 128 {
+        None
+    } else {
+        Some(ColorType::Color(Color::Rgb(r, g, b)))
+    }
+}
+
+/// Returns the character and colors showing two pixels in one cell.
+fn half_block(top: Rgba<u8>, bottom: Rgba<u8>) -> (&'static str, ColorStyle) {
+    let view = ColorType::Palette(PaletteColor::View);
+    match (pixel_color(top), pixel_color(bottom)) {
+        (Some(top), Some(bottom)) => ("▀", ColorStyle::new(top, bottom)),
+        (Some(top), None) => ("▀", ColorStyle::new(top, view)),
+        (None, Some(bottom)) => ("▄", ColorStyle::new(bottom, view)),
+        (None, None) => (" ", ColorStyle::new(view, view)),
+    }
+}
+
+/// Encodes an image with the kitty graphics protocol.
+///
+/// The image is only transmitted: see `place_kitty` to display it.
+fn encode_kitty(image: &RgbaImage, id: usize) -> String {
+    let payload = base64::encode(image.as_raw());
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+
+    let mut result = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        // Chunks only contain base64 characters.
+        let chunk = ::std::str::from_utf8(chunk).unwrap();
+        if i == 0 {
+            // q=2 silences responses.
+            result.push_str(&format!(
+                "\x1B_Ga=t,f=32,s={},v={},i={},q=2,m={};{}\x1B\\",
+                image.width(),
+                image.height(),
+                id,
+                more,
+                chunk
+            ));
+        } else {
+            result.push_str(&format!("\x1B_Gm={};{}\x1B\\", more, chunk));
+        }
+    }
+    result
+}
+
+/// Displays a kitty image transmitted before, scaled to `size` cells.
+///
+/// Placing it again moves the previous placement.
+fn place_kitty(size: Vec2, id: usize) -> String {
+    // C=1 keeps the cursor in place.
+    format!(
+        "\x1B_Ga=p,i={},p=1,c={},r={},q=2,C=1\x1B\\",
+        id, size.x, size.y
+    )
+}
+
+/// Returns the index of a pixel in a 6x6x6 color cube.
+fn cube_index(pixel: Rgba<u8>) -> Option<usize> {
+    let [r, g, b, a] = pixel.0;
+    if a < 128 {
+        return None;
+    }
+    let level = |c: u8| (usize::from(c) * 5 + 127) / 255;
+    Some(36 * level(r) + 6 * level(g) + level(b))
+}
+
+/// Appends a run of identical sixel characters.
+fn push_run(result: &mut String, c: u8, count: usize) {
+    if count > 3 {
+        result.push_str(&format!("!{}{}", count, c as char));
+    } else {
+        for _ in 0..count {
+            result.push(c as char);
+        }
+    }
+}
+
+/// Encodes an image as sixels, using a 216 colors palette.
+///
+/// Transparent pixels are left untouched.
+fn encode_sixel(image: &RgbaImage) -> String {
+    let (width, height) = image.dimensions();
+    let mut result = format!("\x1BP0;1;0q\"1;1;{};{}", width, height);
+
+    // Define the colors used.
+    let mut used = [false; 216];
+    for pixel in image.pixels() {
+        if let Some(i) = cube_index(*pixel) {
+            used[i] = true;
+        }
+    }
+    for (i, _) in used.iter().enumerate().filter(|&(_, &used)| used) {
+        let percent = |level: usize| level * 100 / 5;
+        result.push_str(&format!(
+            "#{};2;{};{};{}",
+            i,
+            percent(i / 36),
+            percent(i / 6 % 6),
+            percent(i % 6)
+        ));
+    }
+
+    // Each band covers 6 rows of pixels.
+    for band in 0..height.div_ceil(6) {
+        let mut columns: Vec<Option<Vec<u8>>> = vec![None; 216];
+        for x in 0..width {
+            for k in 0..6 {
+                let y = band * 6 + k;
+                if y >= height {
+                    break;
+                }
+                if let Some(i) = cube_index(*image.get_pixel(x, y)) {
+                    let column = columns[i]
+                        .get_or_insert_with(|| vec![0; width as usize]);
+                    column[x as usize] |= 1 << k;
+                }
+            }
+        }
+
+        for (i, column) in columns.iter().enumerate() {
+            let column = match *column {
+                Some(ref column) => column,
+                None => continue,
+            };
+            result.push_str(&format!("#{}", i));
+
+            let mut run = (63 + column[0], 0);
+            for &bits in column {
+                let c = 63 + bits;
+                if c != run.0 {
+                    push_run(&mut result, run.0, run.1);
+                    run = (c, 0);
+                }
+                run.1 += 1;
+            }
+            push_run(&mut result, run.0, run.1);
+            result.push('$');
+        }
+        result.push('-');
+    }
+
+    result.push_str("\x1B\\");
+    result
+}
+
+impl View for ImageView {
+    fn draw(&self, printer: &Printer) {
+        let size = self.fit(printer.size);
+        if size.x == 0 || size.y == 0 {
+            return;
+        }
+
+        if self.protocol != ImageProtocol::Blocks
+            && self.draw_graphics(printer, size)
+        {
+            return;
+        }
+        self.draw_blocks(printer, size);
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        self.fit(constraint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detection() {
+        let detect = |vars: &[(&str, &str)]| {
+            detect_from(|name| {
+                vars.iter()
+                    .find(|var| var.0 == name)
+                    .map(|var| var.1.to_string())
+            })
+        };
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), ImageProtocol::Kitty);
+        assert_eq!(detect(&[("TERM", "foot")]), ImageProtocol::Sixel);
+        assert_eq!(detect(&[("TERM", "xterm")]), ImageProtocol::Blocks);
+        assert_eq!(detect(&[]), ImageProtocol::Blocks);
+    }
+
+    #[test]
+    fn fit() {
+        let image = RgbaImage::new(200, 100);
+        let mut view = ImageView::new(image).protocol(ImageProtocol::Blocks);
+
+        assert_eq!(view.required_size(Vec2::new(80, 24)), Vec2::new(20, 5));
+        assert_eq!(view.required_size(Vec2::new(10, 24)), Vec2::new(10, 3));
+    }
+
+    #[test]
+    fn sixel() {
+        let mut image = RgbaImage::new(2, 1);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+
+        assert_eq!(
+            encode_sixel(&image),
+            "\x1BP0;1;0q\"1;1;2;1#180;2;100;0;0#180@?$-\x1B\\"
+        );
+    }
+
+    #[test]
+    fn kitty() {
+        let image = RgbaImage::new(1, 1);
+        assert_eq!(
+            encode_kitty(&image, 3),
+            "\x1B_Ga=t,f=32,s=1,v=1,i=3,q=2,m=0;AAAAAA==\x1B\\"
+        );
+        assert_eq!(
+            place_kitty(Vec2::new(1, 1), 3),
+            "\x1B_Ga=p,i=3,p=1,c=1,r=1,q=2,C=1\x1B\\"
+        );
+    }
+}
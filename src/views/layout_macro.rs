@@ -0,0 +1,161 @@
+/// Builds a tree of views with a compact syntax.
+///
+/// Instead of chaining calls to `child`, views are listed in nested
+/// blocks:
+///
+/// * `vertical { ... }` and `horizontal { ... }` create a
+///   [`LinearLayout`] with the views listed inside, separated by commas.
+/// * `dialog(title) { ... }` creates a [`Dialog`]. Inside, a view becomes
+///   its content, and `button label => callback` adds a button.
+/// * `panel(title) { ... }` creates a [`Panel`] around a single view.
+/// * Any other expression is used as a view. `view => id` wraps it in an
+///   [`IdView`].
+///
+/// A nested `layout!` can be used anywhere a view is expected, for
+/// instance to give an id to a layout.
+///
+/// [`LinearLayout`]: views/struct.LinearLayout.html
+/// [`Dialog`]: views/struct.Dialog.html
+/// [`Panel`]: views/struct.Panel.html
+/// [`IdView`]: views/struct.IdView.html
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate cursive;
+/// # use cursive::Cursive;
+/// # use cursive::views::{Button, DummyView, EditView, TextView};
+/// # fn main() {
+/// let mut siv = Cursive::dummy();
+/// siv.add_layer(layout! {
+///     dialog("Login") {
+///         vertical {
+///             TextView::new("Name:"),
+///             EditView::new() => "name",
+///             TextView::new("Password:"),
+///             EditView::new().secret() => "password",
+///             horizontal {
+///                 DummyView,
+///                 Button::new("Help", |s| s.quit()),
+///             },
+///         },
+///         button "Ok" => |s| s.quit(),
+///         button "Cancel" => |s| s.quit(),
+///     }
+/// });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! layout {
+    (vertical { $($body:tt)* }) => {
+        $crate::layout!(@children
+            $crate::views::LinearLayout::vertical(); $($body)*)
+    };
+    (horizontal { $($body:tt)* }) => {
+        $crate::layout!(@children
+            $crate::views::LinearLayout::horizontal(); $($body)*)
+    };
+    (dialog($title:expr) { $($body:tt)* }) => {
+        $crate::layout!(@dialog
+            $crate::views::Dialog::new().title($title); $($body)*)
+    };
+    (panel($title:expr) { $($body:tt)* }) => {
+        $crate::views::Panel::new($crate::layout!($($body)*)).title($title)
+    };
+    ($view:expr => $id:expr) => {
+        $crate::views::IdView::new($id, $view)
+    };
+    ($view:expr) => {
+        $view
+    };
+
+    // Adds each view to a `LinearLayout`.
+    (@children $layout:expr;) => {
+        $layout
+    };
+    (@children $layout:expr;
+        $kind:ident $(($title:expr))? { $($body:tt)* } $(, $($rest:tt)*)?
+    ) => {
+        $crate::layout!(@children
+            $layout.child(
+                $crate::layout!($kind $(($title))? { $($body)* })
+            );
+            $($($rest)*)?)
+    };
+    (@children $layout:expr;
+        $view:expr => $id:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::layout!(@children
+            $layout.child($crate::layout!($view => $id));
+            $($($rest)*)?)
+    };
+    (@children $layout:expr; $view:expr $(, $($rest:tt)*)?) => {
+        $crate::layout!(@children $layout.child($view); $($($rest)*)?)
+    };
+
+    // Sets the content and buttons of a `Dialog`.
+    (@dialog $dialog:expr;) => {
+        $dialog
+    };
+    (@dialog $dialog:expr;
+        button $label:expr => $cb:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::layout!(@dialog $dialog.button($label, $cb); $($($rest)*)?)
+    };
+    (@dialog $dialog:expr;
+        $kind:ident $(($title:expr))? { $($body:tt)* } $(, $($rest:tt)*)?
+    ) => {
+        $crate::layout!(@dialog
+            $dialog.content(
+                $crate::layout!($kind $(($title))? { $($body)* })
+            );
+            $($($rest)*)?)
+    };
+    (@dialog $dialog:expr;
+        $view:expr => $id:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::layout!(@dialog
+            $dialog.content($crate::layout!($view => $id));
+            $($($rest)*)?)
+    };
+    (@dialog $dialog:expr; $view:expr $(, $($rest:tt)*)?) => {
+        $crate::layout!(@dialog $dialog.content($view); $($($rest)*)?)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use view::{Finder, View};
+    use views::{Button, Dialog, EditView, LinearLayout, TextView};
+
+    #[test]
+    fn layout() {
+        let mut view: Dialog = layout! {
+            dialog("Title") {
+                vertical {
+                    TextView::new("Name:"),
+                    EditView::new().content("Bob") => "name",
+                    layout!(horizontal {
+                        Button::new("A", |_| ()),
+                        Button::new("B", |_| ()),
+                    }) => "buttons",
+                },
+                button "Ok" => |s| s.quit(),
+            }
+        };
+
+        assert_eq!(view.buttons_len(), 1);
+        let content = view
+            .find_id("name", |v: &mut EditView| v.get_content())
+            .unwrap();
+        assert_eq!(content.as_str(), "Bob");
+        let children = view
+            .find_id("buttons", |v: &mut LinearLayout| v.len())
+            .unwrap();
+        assert_eq!(children, 2);
+
+        let mut panel = layout!(panel("P") { TextView::new("a") => "a" });
+        assert!(panel.find_id("a", |_: &mut TextView| ()).is_some());
+        assert_eq!(panel.required_size((10, 10).into()).y, 3);
+    }
+}
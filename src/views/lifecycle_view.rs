@@ -0,0 +1,192 @@
+use vec::Vec2;
+use view::{View, ViewWrapper};
+use With;
+
+type Hook<V> = Box<FnMut(&mut V)>;
+
+/// Wrapper running callbacks when a view enters or leaves the view tree.
+///
+/// This lets a view start background work (timers, file watchers, ...)
+/// only while it is part of the UI.
+///
+/// * The [`on_attach`] callback runs the first time the view is laid out,
+///   which happens once it was added to the tree.
+/// * The [`on_detach`] callback runs when the view is dropped, for instance
+///   after its layer was popped, if it was attached before.
+///
+/// [`on_attach`]: #method.on_attach
+/// [`on_detach`]: #method.on_detach
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::{LifecycleView, TimerView};
+/// # use std::time::Duration;
+/// let timer = TimerView::new(Duration::from_secs(60));
+/// let view = LifecycleView::new(timer)
+///     .on_attach(|timer| timer.start())
+///     .on_detach(|timer| timer.pause());
+/// ```
+pub struct LifecycleView<V> {
+    view: V,
+    on_attach: Option<Hook<V>>,
+    on_detach: Option<Hook<V>>,
+    attached: bool,
+}
+
+impl<V> LifecycleView<V> {
+    /// Wraps `view` in a new `LifecycleView`, without callbacks.
+    pub fn new(view: V) -> Self {
+        LifecycleView {
+            view,
+            on_attach: None,
+            on_detach: None,
+            attached: false,
+        }
+    }
+
+    /// Sets the callback to run when the view is added to the tree.
+    pub fn set_on_attach<F>(&mut self, cb: F)
+    where
+        F: 'static + FnMut(&mut V),
+    {
+        self.on_attach = Some(Box::new(cb));
+    }
+
+    /// Sets the callback to run when the view is added to the tree.
+    ///
+    /// Chainable variant.
+    pub fn on_attach<F>(self, cb: F) -> Self
+    where
+        F: 'static + FnMut(&mut V),
+    {
+        self.with(|s| s.set_on_attach(cb))
+    }
+
+    /// Sets the callback to run when the view is removed from the tree.
+    pub fn set_on_detach<F>(&mut self, cb: F)
+    where
+        F: 'static + FnMut(&mut V),
+    {
+        self.on_detach = Some(Box::new(cb));
+    }
+
+    /// Sets the callback to run when the view is removed from the tree.
+    ///
+    /// Chainable variant.
+    pub fn on_detach<F>(self, cb: F) -> Self
+    where
+        F: 'static + FnMut(&mut V),
+    {
+        self.with(|s| s.set_on_detach(cb))
+    }
+
+    /// Returns `true` if the view was added to the tree.
+    pub fn is_attached(&self) -> bool {
+        self.attached
+    }
+
+    /// Runs the detach callback now, if the view is attached.
+    ///
+    /// Use this when taking the view out of the tree without dropping it.
+    /// The attach callback will run again on the next layout.
+    pub fn detach(&mut self) {
+        if !self.attached {
+            return;
+        }
+        self.attached = false;
+        if let Some(ref mut cb) = self.on_detach {
+            cb(&mut self.view);
+        }
+    }
+
+    fn attach(&mut self) {
+        if self.attached {
+            return;
+        }
+        self.attached = true;
+        if let Some(ref mut cb) = self.on_attach {
+            cb(&mut self.view);
+        }
+    }
+
+    inner_getters!(self.view: V);
+}
+
+impl<V> Drop for LifecycleView<V> {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+impl<V: View> ViewWrapper for LifecycleView<V> {
+    type V = V;
+
+    fn with_view<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&Self::V) -> R,
+    {
+        Some(f(&self.view))
+    }
+
+    fn with_view_mut<F, R>(&mut self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Self::V) -> R,
+    {
+        Some(f(&mut self.view))
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        self.attach();
+        self.view.layout(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use views::DummyView;
+    use Cursive;
+
+    #[test]
+    fn hooks() {
+        let counter = Rc::new(Cell::new(0));
+        let (attach, detach) = (Rc::clone(&counter), Rc::clone(&counter));
+        let view = LifecycleView::new(DummyView)
+            .on_attach(move |_| attach.set(attach.get() + 1))
+            .on_detach(move |_| detach.set(detach.get() - 10));
+
+        let mut siv = Cursive::dummy();
+        siv.add_layer(view);
+        assert_eq!(counter.get(), 0);
+
+        // The dummy backend stops after the first frame.
+        siv.run();
+        siv.run();
+        assert_eq!(counter.get(), 1);
+
+        siv.pop_layer();
+        assert_eq!(counter.get(), -9);
+    }
+
+    #[test]
+    fn manual_detach() {
+        let counter = Rc::new(Cell::new(0));
+        let detach = Rc::clone(&counter);
+        let mut view = LifecycleView::new(DummyView)
+            .on_detach(move |_| detach.set(detach.get() + 1));
+
+        // Never attached: nothing to detach.
+        view.detach();
+        assert_eq!(counter.get(), 0);
+
+        view.layout(Vec2::zero());
+        assert!(view.is_attached());
+        view.detach();
+        assert!(!view.is_attached());
+        drop(view);
+        assert_eq!(counter.get(), 1);
+    }
+}
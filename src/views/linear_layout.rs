@@ -4,7 +4,7 @@ use rect::Rect;
 use std::cmp::min;
 use std::ops::Deref;
 use vec::Vec2;
-use view::{Selector, SizeCache, View};
+use view::{ChildInfo, IntoBoxedView, Selector, SizeCache, View};
 use Printer;
 use With;
 use XY;
@@ -131,14 +131,14 @@ impl LinearLayout {
     /// Adds a child to the layout.
     ///
     /// Chainable variant.
-    pub fn child<V: View + 'static>(self, view: V) -> Self {
+    pub fn child<V: IntoBoxedView>(self, view: V) -> Self {
         self.with(|s| s.add_child(view))
     }
 
     /// Adds a child to the layout.
-    pub fn add_child<V: View + 'static>(&mut self, view: V) {
+    pub fn add_child<V: IntoBoxedView>(&mut self, view: V) {
         self.children.push(Child {
-            view: Box::new(view),
+            view: view.as_boxed_view(),
             size: Vec2::zero(),
             weight: 0,
         });
@@ -258,16 +258,36 @@ impl LinearLayout {
         }
     }
 
+    // Offset of each child along our orientation.
+    fn child_offsets(&self) -> Vec<usize> {
+        ChildIterator::new(self.children.iter(), self.orientation, usize::MAX)
+            .map(|item| item.offset)
+            .collect()
+    }
+
     fn move_focus(&mut self, source: direction::Direction) -> EventResult {
-        let i = if let Some(i) =
-            source.relative(self.orientation).and_then(|rel| {
-                // The iterator starts at the focused element.
-                // We don't want that one.
-                self.iter_mut(true, rel)
-                    .skip(1)
-                    .filter_map(|p| try_focus(p, source))
-                    .next()
-            }) {
+        // Focus the view closest to the currently focused one.
+        let area = self.important_area(Vec2::zero());
+        let origin = (area.top_left() + area.size() / 2).signed();
+        let offsets = self.child_offsets();
+        let o = self.orientation;
+
+        let i = if let Some(i) = source.relative(o).and_then(|rel| {
+            // The iterator starts at the focused element.
+            // We don't want that one.
+            self.iter_mut(true, rel)
+                .skip(1)
+                .filter_map(|(i, child)| {
+                    let main = origin.get(o) - offsets[i] as isize;
+                    let position = origin.with_axis(o, main);
+                    if child.view.take_focus_near(source, position) {
+                        Some(i)
+                    } else {
+                        None
+                    }
+                })
+                .next()
+        }) {
             i
         } else {
             return EventResult::Ignored;
@@ -289,38 +309,58 @@ impl LinearLayout {
                 return;
             }
 
-            let position = match position.checked_sub(offset) {
+            let i = match position
+                .checked_sub(offset)
+                .and_then(|position| self.child_at(position))
+            {
                 None => return,
-                Some(pos) => pos,
+                Some(i) => i,
             };
 
-            // Find the selected child
-            // Let's only care about the coordinate for our orientation.
-            let position = *position.get(self.orientation);
-
-            // Iterate on the views and find the one
-            // We need a mutable ref to call take_focus later on.
-            for (i, item) in ChildIterator::new(
-                self.children.iter_mut(),
-                self.orientation,
-                // TODO: get actual width (not super important)
-                usize::max_value(),
-            ).enumerate()
+            if self.children[i]
+                .view
+                .take_focus(direction::Direction::none())
             {
-                // Get the child size:
-                // this will give us the allowed window for a click.
-                let child_size = item.child.size.get(self.orientation);
-
-                if item.offset + child_size > position {
-                    if item.child.view.take_focus(direction::Direction::none())
-                    {
-                        self.focus = i;
-                    }
-                    return;
-                }
+                self.focus = i;
             }
         }
     }
+
+    /// Returns the index of the child at the given position, if any.
+    fn child_at(&self, position: Vec2) -> Option<usize> {
+        // Let's only care about the coordinate for our orientation.
+        let position = *position.get(self.orientation);
+
+        ChildIterator::new(
+            self.children.iter(),
+            self.orientation,
+            // TODO: get actual width (not super important)
+            usize::max_value(),
+        )
+        .position(|item| {
+            // Get the child size:
+            // this will give us the allowed window for a click.
+            let child_size = item.child.size.get(self.orientation);
+            item.offset + child_size > position
+        })
+    }
+
+    /// Returns the child receiving the given event.
+    ///
+    /// This is the focused child, except for mouse wheel events, which go
+    /// to the child under the cursor.
+    fn event_target(&self, event: &Event) -> Option<usize> {
+        match *event {
+            Event::Mouse {
+                offset,
+                position,
+                event,
+            } if event.is_wheel() => position
+                .checked_sub(offset)
+                .and_then(|position| self.child_at(position)),
+            _ => Some(self.focus),
+        }
+    }
 }
 
 fn try_focus(
@@ -535,6 +575,44 @@ impl View for LinearLayout {
         true
     }
 
+    fn take_focus_near(
+        &mut self, source: direction::Direction, position: XY<isize>,
+    ) -> bool {
+        let o = self.orientation;
+        let target = *position.get(o);
+
+        // Children in the order we should try them.
+        let mut items: Vec<(usize, isize, isize)> =
+            ChildIterator::new(self.children.iter(), o, usize::MAX)
+                .enumerate()
+                .map(|(i, item)| {
+                    (i, item.offset as isize, item.length as isize)
+                })
+                .collect();
+        match source.relative(o) {
+            Some(direction::Relative::Front) => (),
+            Some(direction::Relative::Back) => items.reverse(),
+            // Coming from the side: try the closest children first.
+            None => items.sort_by_key(|&(_, offset, length)| {
+                if target < offset {
+                    offset - target
+                } else {
+                    (target + 1 - offset - length).max(0)
+                }
+            }),
+        }
+
+        for (i, offset, _) in items {
+            let position = position.with_axis(o, target - offset);
+            if self.children[i].view.take_focus_near(source, position) {
+                self.focus = i;
+                return true;
+            }
+        }
+
+        false
+    }
+
     fn on_event(&mut self, event: Event) -> EventResult {
         if self.is_empty() {
             return EventResult::Ignored;
@@ -542,13 +620,18 @@ impl View for LinearLayout {
 
         self.check_focus_grab(&event);
 
+        let target = match self.event_target(&event) {
+            Some(target) => target,
+            None => return EventResult::Ignored,
+        };
+
         let result = {
             let mut iterator = ChildIterator::new(
                 self.children.iter_mut(),
                 self.orientation,
                 usize::max_value(),
             );
-            let item = iterator.nth(self.focus).unwrap();
+            let item = iterator.nth(target).unwrap();
             let offset = self.orientation.make_vec(item.offset, 0);
             item.child.view.on_event(event.relativized(offset))
         };
@@ -599,16 +682,25 @@ impl View for LinearLayout {
     fn call_on_any<'a>(
         &mut self, selector: &Selector, mut callback: AnyCb<'a>,
     ) {
-        for child in &mut self.children {
-            child
-                .view
-                .call_on_any(selector, Box::new(|any| callback(any)));
+        if selector.selects(self) {
+            callback(self);
+        }
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Some(selector) = selector.for_child(i) {
+                child
+                    .view
+                    .call_on_any(&selector, Box::new(|any| callback(any)));
+            }
         }
     }
 
     fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
         for (i, child) in self.children.iter_mut().enumerate() {
-            if child.view.focus_view(selector).is_ok() {
+            let selector = match selector.for_child(i) {
+                Some(selector) => selector,
+                None => continue,
+            };
+            if child.view.focus_view(&selector).is_ok() {
                 self.focus = i;
                 return Ok(());
             }
@@ -642,4 +734,131 @@ impl View for LinearLayout {
         // Add `offset` to the rect.
         rect + offset
     }
+
+    fn inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        for (i, item) in ChildIterator::new(
+            self.children.iter(),
+            self.orientation,
+            *size.get(self.orientation),
+        ).enumerate()
+        {
+            let offset = self.orientation.make_vec(item.offset, 0);
+            f(ChildInfo {
+                view: item.child.as_view(),
+                offset,
+                size: Vec2::min(item.child.size, size.saturating_sub(offset)),
+                focused: i == self.focus,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::Key;
+    use views::{Button, TextView};
+
+    fn focus_of(layout: &LinearLayout, row: usize) -> usize {
+        layout
+            .get_child(row)
+            .and_then(|row| row.downcast_ref::<LinearLayout>())
+            .map(LinearLayout::get_focus_index)
+            .unwrap()
+    }
+
+    #[test]
+    fn spatial_focus() {
+        let mut layout = LinearLayout::vertical()
+            .child(
+                LinearLayout::horizontal()
+                    .child(Button::new("A", |_| ()))
+                    .child(Button::new("B", |_| ())),
+            )
+            .child(TextView::new("Not focusable"))
+            .child(
+                LinearLayout::horizontal()
+                    .child(Button::new("C", |_| ()))
+                    .child(Button::new("D", |_| ()))
+                    .child(Button::new("E", |_| ())),
+            );
+        let size = layout.required_size(Vec2::new(20, 10));
+        layout.layout(size);
+
+        assert!(layout.focus_view(&Selector::Path("0/1")).is_ok());
+
+        // B is right above D.
+        assert!(layout.on_event(Event::Key(Key::Down)).is_consumed());
+        assert_eq!(layout.get_focus_index(), 2);
+        assert_eq!(focus_of(&layout, 2), 1);
+
+        assert!(layout.on_event(Event::Key(Key::Right)).is_consumed());
+        assert_eq!(focus_of(&layout, 2), 2);
+
+        // E is closest to B.
+        assert!(layout.on_event(Event::Key(Key::Up)).is_consumed());
+        assert_eq!(layout.get_focus_index(), 0);
+        assert_eq!(focus_of(&layout, 0), 1);
+    }
+
+    #[test]
+    fn spatial_focus_through_wrappers() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use views::{PaddedView, Panel};
+        use Cursive;
+
+        let pressed = Rc::new(Cell::new(""));
+        let button = |label: &'static str| {
+            let pressed = Rc::clone(&pressed);
+            Button::new(label, move |_| pressed.set(label))
+        };
+
+        // The second row is moved 2 columns right by the panel border and
+        // the padding: C ends right below the middle of B.
+        let mut layout = LinearLayout::vertical()
+            .child(
+                LinearLayout::horizontal()
+                    .child(button("A"))
+                    .child(button("B")),
+            )
+            .child(Panel::new(PaddedView::new(
+                ((1, 0), (0, 0)),
+                LinearLayout::horizontal()
+                    .child(button("C"))
+                    .child(button("D")),
+            )));
+        let size = layout.required_size(Vec2::new(20, 10));
+        layout.layout(size);
+
+        let mut siv = Cursive::dummy();
+        assert!(layout.focus_view(&Selector::Path("0/1")).is_ok());
+        assert!(layout.on_event(Event::Key(Key::Down)).is_consumed());
+        layout.on_event(Event::Key(Key::Enter)).process(&mut siv);
+        assert_eq!(pressed.get(), "C");
+    }
+
+    #[test]
+    fn wheel_to_hovered() {
+        use event::MouseEvent;
+        use view::Boxable;
+        use views::{EditView, ScrollView};
+
+        let log = vec!["line"; 20].join("\n");
+        let mut layout = LinearLayout::vertical()
+            .child(EditView::new())
+            .child(ScrollView::new(TextView::new(log)).fixed_height(5));
+        let size = layout.required_size(Vec2::new(20, 10));
+        layout.layout(size);
+        assert_eq!(layout.get_focus_index(), 0);
+
+        let wheel = Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(2, 3),
+            event: MouseEvent::WheelDown,
+        };
+        assert!(layout.on_event(wheel).is_consumed());
+        // The log scrolled, but the input kept the focus.
+        assert_eq!(layout.get_focus_index(), 0);
+    }
 }
@@ -1,14 +1,22 @@
 use direction;
-use event::{AnyCb, Callback, Event, EventResult, Key};
+use event::{
+    AnyCb, Callback, Event, EventResult, Key, MouseButton, MouseEvent,
+};
 use rect::Rect;
+use std::cell::Cell;
+use std::cmp::min;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
+use theme::Effect;
+use utils::width::StrWidth;
 use vec::Vec2;
-use view::{Selector, View};
+use view::{ChildInfo, IntoBoxedView, PageKeys, Selector, View};
 use Cursive;
 use Printer;
 use With;
 
+/// Callback for moved rows, given their old and new positions.
+type OnReorder = Fn(&mut Cursive, usize, usize);
+
 /// Represents a child from a [`ListView`].
 ///
 /// [`ListView`]: struct.ListView.html
@@ -36,12 +44,28 @@ impl ListChild {
 }
 
 /// Displays a list of elements.
+///
+/// Rows can be reordered by the user, see [`set_reorderable`].
+///
+/// [`set_reorderable`]: #method.set_reorderable
 pub struct ListView {
     children: Vec<ListChild>,
     focus: usize,
     // This callback is called when the selection is changed.
     on_select: Option<Rc<Fn(&mut Cursive, &String)>>,
     last_size: Vec2,
+    // Number of rows visible during the last draw, to move by pages.
+    last_height: Cell<usize>,
+    page_keys: PageKeys,
+    // If `true`, the user can move rows around.
+    reorderable: bool,
+    // Original position of the row being moved, if any.
+    // The moved row is always the focused one.
+    grabbed: Option<usize>,
+    // `true` if the mouse was last pressed on a label.
+    label_pressed: bool,
+    // Called with the old and new positions of a moved row.
+    on_reorder: Option<Rc<OnReorder>>,
 }
 
 new_default!(ListView);
@@ -54,6 +78,12 @@ impl ListView {
             focus: 0,
             on_select: None,
             last_size: Vec2::zero(),
+            last_height: Cell::new(0),
+            page_keys: PageKeys::default(),
+            reorderable: false,
+            grabbed: None,
+            label_pressed: false,
+            on_reorder: None,
         }
     }
 
@@ -89,22 +119,44 @@ impl ListView {
     }
 
     /// Adds a view to the end of the list.
-    pub fn add_child<V: View + 'static>(&mut self, label: &str, mut view: V) {
+    pub fn add_child<V: IntoBoxedView>(&mut self, label: &str, view: V) {
+        let mut view = view.as_boxed_view();
         view.take_focus(direction::Direction::none());
         self.children
-            .push(ListChild::Row(label.to_string(), Box::new(view)));
+            .push(ListChild::Row(label.to_string(), view));
     }
 
     /// Removes all children from this view.
     pub fn clear(&mut self) {
         self.children.clear();
         self.focus = 0;
+        self.grabbed = None;
+    }
+
+    /// Moves the child at position `from` to position `to`.
+    ///
+    /// The focused child stays the same.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_child(&mut self, from: usize, to: usize) {
+        let child = self.children.remove(from);
+        self.children.insert(to, child);
+
+        if self.focus == from {
+            self.focus = to;
+        } else if from < self.focus && self.focus <= to {
+            self.focus -= 1;
+        } else if to <= self.focus && self.focus < from {
+            self.focus += 1;
+        }
     }
 
     /// Adds a view to the end of the list.
     ///
     /// Chainable variant.
-    pub fn child<V: View + 'static>(self, label: &str, view: V) -> Self {
+    pub fn child<V: IntoBoxedView>(self, label: &str, view: V) -> Self {
         self.with(|s| s.add_child(label, view))
     }
 
@@ -120,6 +172,60 @@ impl ListView {
         self.with(Self::add_delimiter)
     }
 
+    /// Sets the keys moving the focus by half a page.
+    ///
+    /// `PageUp` and `PageDown` always move by a full page.
+    pub fn set_page_keys(&mut self, page_keys: PageKeys) {
+        self.page_keys = page_keys;
+    }
+
+    /// Sets the keys moving the focus by half a page.
+    ///
+    /// Chainable variant.
+    pub fn page_keys(self, page_keys: PageKeys) -> Self {
+        self.with(|s| s.set_page_keys(page_keys))
+    }
+
+    /// Sets whether the user can move rows around.
+    ///
+    /// When enabled, `Space` grabs the focused row, if its view doesn't use
+    /// this key. The arrow keys, `Home` and `End` then move it, and `Space`
+    /// or `Enter` drops it. `Esc` puts it back where it was.
+    ///
+    /// Rows can also be dragged with the mouse by their label.
+    pub fn set_reorderable(&mut self, reorderable: bool) {
+        self.reorderable = reorderable;
+    }
+
+    /// Lets the user move rows around.
+    ///
+    /// Chainable variant.
+    pub fn reorderable(self) -> Self {
+        self.with(|s| s.set_reorderable(true))
+    }
+
+    /// Sets a callback to be used when the user moves a row.
+    ///
+    /// It is given the old and new positions of the row, when it is
+    /// dropped. This also makes the view reorderable.
+    pub fn set_on_reorder<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive, usize, usize) + 'static,
+    {
+        self.reorderable = true;
+        self.on_reorder = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when the user moves a row.
+    ///
+    /// Chainable variant.
+    pub fn on_reorder<F>(self, cb: F) -> Self
+    where
+        F: Fn(&mut Cursive, usize, usize) + 'static,
+    {
+        self.with(|s| s.set_on_reorder(cb))
+    }
+
     /// Sets a callback to be used when an item is selected.
     pub fn set_on_select<F>(&mut self, cb: F)
     where
@@ -196,11 +302,95 @@ impl ListView {
         self.children
             .iter()
             .map(ListChild::label)
-            .map(UnicodeWidthStr::width)
+            .map(StrWidth::width)
             .max()
             .unwrap_or(0)
     }
 
+    /// Moves the grabbed row to position `to`, or to the end.
+    fn move_grabbed(&mut self, to: usize) {
+        let from = self.focus;
+        let to = min(to, self.children.len() - 1);
+        self.move_child(from, to);
+    }
+
+    /// Drops the grabbed row where it is.
+    fn drop_grabbed(&mut self) -> EventResult {
+        self.label_pressed = false;
+        let from = match self.grabbed.take() {
+            Some(from) => from,
+            None => return EventResult::Ignored,
+        };
+        let to = self.focus;
+        EventResult::Consumed(match self.on_reorder {
+            Some(ref cb) if from != to => {
+                let cb = Rc::clone(cb);
+                Some(Callback::from_fn(move |s| cb(s, from, to)))
+            }
+            _ => None,
+        })
+    }
+
+    fn on_event_grabbed(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Up) => {
+                let to = self.focus.saturating_sub(1);
+                self.move_grabbed(to);
+            }
+            Event::Key(Key::Down) => {
+                let to = self.focus + 1;
+                self.move_grabbed(to);
+            }
+            Event::Key(Key::Home) => self.move_grabbed(0),
+            Event::Key(Key::End) => self.move_grabbed(usize::MAX),
+            Event::Mouse {
+                event: MouseEvent::Hold(MouseButton::Left),
+                position,
+                offset,
+            } => self.move_grabbed(position.y.saturating_sub(offset.y)),
+            Event::Char(' ')
+            | Event::Key(Key::Enter)
+            | Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                ..
+            } => return self.drop_grabbed(),
+            Event::Key(Key::Esc) => {
+                if let Some(from) = self.grabbed.take() {
+                    self.move_grabbed(from);
+                }
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    /// Returns `true` if `event` starts dragging a row by its label.
+    fn check_label_drag(&mut self, event: &Event) -> bool {
+        let (event, position, offset) = match *event {
+            Event::Mouse {
+                event,
+                position,
+                offset,
+            } => (event, position, offset),
+            _ => return false,
+        };
+
+        match event {
+            MouseEvent::Press(_) => {
+                let labels_width = self.labels_width();
+                self.label_pressed = position
+                    .checked_sub(offset)
+                    .is_some_and(|position| position.x <= labels_width);
+                false
+            }
+            MouseEvent::Hold(MouseButton::Left) => {
+                self.reorderable && self.label_pressed
+            }
+            _ => false,
+        }
+    }
+
     fn check_focus_grab(&mut self, event: &Event) {
         if let Event::Mouse {
             offset,
@@ -251,6 +441,9 @@ fn try_focus(
 
 impl View for ListView {
     fn draw(&self, printer: &Printer) {
+        self.last_height
+            .set(min(printer.output_size.y, printer.size.y));
+
         if self.children.is_empty() {
             return;
         }
@@ -261,7 +454,14 @@ impl View for ListView {
         for (i, child) in self.children.iter().enumerate() {
             match child {
                 ListChild::Row(ref label, ref view) => {
-                    printer.print((0, i), label);
+                    if i == self.focus && self.grabbed.is_some() {
+                        // Show the row being moved.
+                        printer.with_effect(Effect::Underline, |printer| {
+                            printer.print((0, i), label)
+                        });
+                    } else {
+                        printer.print((0, i), label);
+                    }
                     view.draw(
                         &printer.offset((offset, i)).focused(i == self.focus),
                     );
@@ -277,7 +477,7 @@ impl View for ListView {
             .children
             .iter()
             .map(ListChild::label)
-            .map(UnicodeWidthStr::width)
+            .map(StrWidth::width)
             .max()
             .unwrap_or(0);
 
@@ -300,7 +500,7 @@ impl View for ListView {
             .children
             .iter()
             .map(ListChild::label)
-            .map(UnicodeWidthStr::width)
+            .map(StrWidth::width)
             .max()
             .unwrap_or(0);
 
@@ -320,14 +520,36 @@ impl View for ListView {
             return EventResult::Ignored;
         }
 
+        if self.grabbed.is_some() {
+            return self.on_event_grabbed(event);
+        }
+
         self.check_focus_grab(&event);
+        if self.check_label_drag(&event) {
+            self.grabbed = Some(self.focus);
+            return self.on_event_grabbed(event);
+        }
 
         // Send the event to the focused child.
+        // Wheel events go to the child under the cursor instead.
+        let target = match event {
+            Event::Mouse {
+                offset,
+                position,
+                event,
+            } if event.is_wheel() => {
+                match position.checked_sub(offset) {
+                    Some(position) => position.y,
+                    None => return EventResult::Ignored,
+                }
+            }
+            _ => self.focus,
+        };
         let labels_width = self.labels_width();
-        if let ListChild::Row(_, ref mut view) = self.children[self.focus] {
+        if let Some(ListChild::Row(_, view)) = self.children.get_mut(target) {
             // If self.focus < self.scrollbase.start_line, it means the focus is not
             // in view. Something's fishy, so don't send the event.
-            let offset = (labels_width + 1, self.focus);
+            let offset = (labels_width + 1, target);
             let result = view.on_event(event.relativized(offset));
             if result.is_consumed() {
                 return result;
@@ -335,6 +557,20 @@ impl View for ListView {
         }
 
         // If the child ignored this event, change the focus.
+        if let Some(paging) = self.page_keys.paging(&event) {
+            // Before the first draw, assume the whole list is visible.
+            let height = match self.last_height.get() {
+                0 => self.last_size.y,
+                height => height,
+            };
+            let source = if paging.is_up() {
+                direction::Direction::down()
+            } else {
+                direction::Direction::up()
+            };
+            return self.move_focus(paging.amount(height), source);
+        }
+
         match event {
             Event::Key(Key::Up) if self.focus > 0 => {
                 self.move_focus(1, direction::Direction::down())
@@ -342,12 +578,6 @@ impl View for ListView {
             Event::Key(Key::Down) if self.focus + 1 < self.children.len() => {
                 self.move_focus(1, direction::Direction::up())
             }
-            Event::Key(Key::PageUp) => {
-                self.move_focus(10, direction::Direction::down())
-            }
-            Event::Key(Key::PageDown) => {
-                self.move_focus(10, direction::Direction::up())
-            }
             Event::Key(Key::Home) | Event::Ctrl(Key::Home) => self
                 .move_focus(usize::max_value(), direction::Direction::back()),
             Event::Key(Key::End) | Event::Ctrl(Key::End) => self
@@ -358,6 +588,10 @@ impl View for ListView {
             Event::Shift(Key::Tab) => {
                 self.move_focus(1, direction::Direction::back())
             }
+            Event::Char(' ') if self.reorderable => {
+                self.grabbed = Some(self.focus);
+                EventResult::Consumed(None)
+            }
             _ => EventResult::Ignored,
         }
     }
@@ -381,8 +615,15 @@ impl View for ListView {
     fn call_on_any<'a>(
         &mut self, selector: &Selector, mut callback: AnyCb<'a>,
     ) {
-        for view in self.children.iter_mut().filter_map(ListChild::view) {
-            view.call_on_any(selector, Box::new(|any| callback(any)));
+        if selector.selects(self) {
+            callback(self);
+        }
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let (Some(view), Some(selector)) =
+                (child.view(), selector.for_child(i))
+            {
+                view.call_on_any(&selector, Box::new(|any| callback(any)));
+            }
         }
     }
 
@@ -392,7 +633,8 @@ impl View for ListView {
             .iter_mut()
             .enumerate()
             .filter_map(|(i, v)| v.view().map(|v| (i, v)))
-            .filter_map(|(i, v)| v.focus_view(selector).ok().map(|_| i))
+            .filter_map(|(i, v)| selector.for_child(i).map(|s| (i, v, s)))
+            .filter_map(|(i, v, s)| v.focus_view(&s).ok().map(|_| i))
             .next()
         {
             self.focus = i;
@@ -420,4 +662,59 @@ impl View for ListView {
 
         area + (0, self.focus)
     }
+
+    fn inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        let offset = self.labels_width() + 1;
+
+        for (i, child) in self.children.iter().enumerate() {
+            if let ListChild::Row(_, ref view) = *child {
+                f(ChildInfo {
+                    view: &**view,
+                    offset: Vec2::new(offset, i),
+                    size: Vec2::new(size.x.saturating_sub(offset), 1),
+                    focused: i == self.focus,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::TextView;
+
+    #[test]
+    fn reorder() {
+        let mut list = ListView::new()
+            .child("a", TextView::new("1"))
+            .child("b", TextView::new("2"))
+            .delimiter()
+            .child("c", TextView::new("3"))
+            .reorderable();
+        list.layout(Vec2::new(10, 4));
+        list.take_focus(direction::Direction::none());
+
+        // Drag `a` by its label, below the delimiter.
+        let mouse = |event, y| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(0, y),
+            event,
+        };
+        let left = MouseButton::Left;
+        list.on_event(mouse(MouseEvent::Press(left), 0));
+        list.on_event(mouse(MouseEvent::Hold(left), 2));
+        list.on_event(mouse(MouseEvent::Release(left), 2));
+        let labels: Vec<_> =
+            list.children().iter().map(ListChild::label).collect();
+        assert_eq!(labels, vec!["b", "", "a", "c"]);
+        assert_eq!(list.focus(), 2);
+
+        // Move it back up with the keyboard.
+        list.on_event(Event::Char(' '));
+        list.on_event(Event::Key(Key::Home));
+        list.on_event(Event::Key(Key::Enter));
+        assert_eq!(list.get_row(0).label(), "a");
+        assert_eq!(list.focus(), 0);
+    }
 }
@@ -4,36 +4,61 @@ use menu::{MenuItem, MenuTree};
 use rect::Rect;
 use std::cmp::min;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
+use utils::width::StrWidth;
 use vec::Vec2;
-use view::{Position, ScrollBase, View};
+use view::{PageKeys, Position, ScrollBase, View};
 use views::OnEventView;
 use Cursive;
 use Printer;
 use With;
 
 /// Popup that shows a list of items.
+///
+/// It is used by the menubar and by `SelectView`'s popup mode, but it can
+/// also show any menu tree, for instance as a context menu:
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::menu::MenuTree;
+/// # use cursive::view::Position;
+/// # use cursive::views::MenuPopup;
+/// # let mut siv = Cursive::dummy();
+/// let menu = MenuTree::new()
+///     .leaf("Copy", |_| ())
+///     .leaf("Paste", |_| ());
+/// MenuPopup::new(menu).show(&mut siv, Position::absolute((10, 4)));
+/// ```
+///
+/// By default, the popup removes its layer when an item is activated or
+/// when it is dismissed. To embed it in another view instead, use
+/// [`set_popup(false)`].
+///
+/// [`set_popup(false)`]: #method.set_popup
 pub struct MenuPopup {
     menu: Rc<MenuTree>,
     focus: usize,
+    popup: bool,
     scrollbase: ScrollBase,
     align: Align,
     on_dismiss: Option<Callback>,
     on_action: Option<Callback>,
     last_size: Vec2,
+    page_keys: PageKeys,
 }
 
 impl MenuPopup {
     /// Creates a new `MenuPopup` using the given menu tree.
-    pub fn new(menu: Rc<MenuTree>) -> Self {
+    pub fn new<M: Into<Rc<MenuTree>>>(menu: M) -> Self {
         MenuPopup {
-            menu,
+            menu: menu.into(),
             focus: 0,
+            popup: true,
             scrollbase: ScrollBase::new().scrollbar_offset(1).right_padding(0),
             align: Align::top_left(),
             on_dismiss: None,
             on_action: None,
             last_size: Vec2::zero(),
+            page_keys: PageKeys::default(),
         }
     }
 
@@ -49,6 +74,47 @@ impl MenuPopup {
         self.with(|s| s.set_focus(focus))
     }
 
+    /// Sets the keys moving the focus by half a page.
+    ///
+    /// `PageUp` and `PageDown` always move by a full page.
+    pub fn set_page_keys(&mut self, page_keys: PageKeys) {
+        self.page_keys = page_keys;
+    }
+
+    /// Sets the keys moving the focus by half a page.
+    ///
+    /// Chainable variant.
+    pub fn page_keys(self, page_keys: PageKeys) -> Self {
+        self.with(|s| s.set_page_keys(page_keys))
+    }
+
+    /// Sets whether this view is shown in its own layer.
+    ///
+    /// If `true` (the default), activating an item or dismissing the popup
+    /// removes the top layer. If `false`, the view can be embedded in
+    /// another one: `<Esc>` is then ignored unless a dismiss callback is
+    /// set.
+    pub fn set_popup(&mut self, popup: bool) {
+        self.popup = popup;
+    }
+
+    /// Sets whether this view is shown in its own layer.
+    ///
+    /// Chainable variant.
+    pub fn popup(self, popup: bool) -> Self {
+        self.with(|s| s.set_popup(popup))
+    }
+
+    /// Returns the menu tree shown by this view.
+    pub fn menu(&self) -> &MenuTree {
+        &self.menu
+    }
+
+    /// Shows this popup in a new layer, at the given position.
+    pub fn show(self, siv: &mut Cursive, position: Position) {
+        siv.screen_mut().add_layer_at(position, self.popup(true));
+    }
+
     fn item_width(item: &MenuItem) -> usize {
         match *item {
             MenuItem::Delimiter => 1,
@@ -174,9 +240,12 @@ impl MenuPopup {
             MenuItem::Leaf(_, ref cb) => {
                 let cb = cb.clone();
                 let action_cb = self.on_action.clone();
+                let popup = self.popup;
                 EventResult::with_cb(move |s| {
                     // Remove ourselves from the face of the earth
-                    s.pop_layer();
+                    if popup {
+                        s.pop_layer();
+                    }
                     // If we had prior orders, do it now.
                     if let Some(ref action_cb) = action_cb {
                         action_cb.clone()(s);
@@ -191,12 +260,19 @@ impl MenuPopup {
     }
 
     fn dismiss(&mut self) -> EventResult {
+        if !self.popup && self.on_dismiss.is_none() {
+            return EventResult::Ignored;
+        }
+
         let dismiss_cb = self.on_dismiss.clone();
+        let popup = self.popup;
         EventResult::with_cb(move |s| {
             if let Some(ref cb) = dismiss_cb {
                 cb.clone()(s);
             }
-            s.pop_layer();
+            if popup {
+                s.pop_layer();
+            }
         })
     }
 }
@@ -267,12 +343,33 @@ impl View for MenuPopup {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        if self.menu.is_empty() {
+            // Nothing to select.
+            return match event {
+                Event::Key(Key::Esc)
+                | Event::Mouse {
+                    event: MouseEvent::Press(_),
+                    ..
+                } => self.dismiss(),
+                _ => EventResult::Ignored,
+            };
+        }
+
+        if let Some(paging) = self.page_keys.paging(&event) {
+            let amount = paging.amount(self.scrollbase.view_height);
+            if paging.is_up() {
+                self.scroll_up(amount, false);
+            } else {
+                self.scroll_down(amount, false);
+            }
+            self.scrollbase.scroll_to(self.focus);
+            return EventResult::Consumed(None);
+        }
+
         let mut fix_scroll = true;
         match event {
             Event::Key(Key::Up) => self.scroll_up(1, true),
-            Event::Key(Key::PageUp) => self.scroll_up(5, false),
             Event::Key(Key::Down) => self.scroll_down(1, true),
-            Event::Key(Key::PageDown) => self.scroll_down(5, false),
 
             Event::Key(Key::Home) => self.focus = 0,
             Event::Key(Key::End) => {
@@ -408,3 +505,42 @@ impl View for MenuPopup {
         Rect::from_size((0, self.focus), (size.x, 1))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use views::TextView;
+
+    #[test]
+    fn popup() {
+        let runs = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&runs);
+        let menu = MenuTree::new()
+            .leaf("A", |_| ())
+            .delimiter()
+            .leaf("B", move |_| counter.set(counter.get() + 1));
+
+        let mut siv = Cursive::dummy();
+        siv.add_layer(TextView::new("Background"));
+        MenuPopup::new(menu.clone())
+            .show(&mut siv, Position::absolute((0, 0)));
+        assert_eq!(siv.screen().layer_sizes().len(), 2);
+
+        siv.on_event(Event::Key(Key::Up));
+        siv.on_event(Event::Key(Key::Enter));
+        assert_eq!(runs.get(), 1);
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+
+        // Embedded, the layer stays.
+        let mut popup = MenuPopup::new(menu).popup(false);
+        popup.on_event(Event::Key(Key::Down));
+        popup.on_event(Event::Key(Key::Enter)).process(&mut siv);
+        assert_eq!(runs.get(), 2);
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+        assert!(!popup.on_event(Event::Key(Key::Esc)).is_consumed());
+
+        let mut empty = MenuPopup::new(MenuTree::new()).popup(false);
+        assert!(!empty.on_event(Event::Key(Key::Enter)).is_consumed());
+    }
+}
@@ -2,9 +2,11 @@ use direction;
 use event::*;
 use menu::{MenuItem, MenuTree};
 use rect::Rect;
+use std::cmp::Reverse;
 use std::rc::Rc;
 use theme::ColorStyle;
-use unicode_width::UnicodeWidthStr;
+use utils::fuzzy::fuzzy_match;
+use utils::width::StrWidth;
 use vec::Vec2;
 use view::{Position, View};
 use views::{MenuPopup, OnEventView};
@@ -31,6 +33,10 @@ enum State {
 /// The [`Cursive`] root already includes a menubar
 /// that you just need to configure.
 ///
+/// Typing while the menubar is selected searches the leaves of every
+/// submenu, and shows the matches in a popup. Characters matching no leaf
+/// are not consumed, so global callbacks still receive them.
+///
 /// [`Cursive`]: ../struct.Cursive.html#method.menubar
 pub struct Menubar {
     /// Menu items in this menubar.
@@ -42,6 +48,9 @@ pub struct Menubar {
 
     // TODO: make Menubar impl View and take out the State management
     state: State,
+
+    /// Text typed to search the menu leaves.
+    query: String,
 }
 
 new_default!(Menubar);
@@ -54,12 +63,14 @@ impl Menubar {
             autohide: true,
             state: State::Inactive,
             focus: 0,
+            query: String::new(),
         }
     }
 
     /// Hides the menubar.
     fn hide(&mut self) {
         self.state = State::Inactive;
+        self.query.clear();
     }
 
     /// True if we should be receiving events.
@@ -176,6 +187,66 @@ impl Menubar {
         self.root.remove(i);
     }
 
+    /// Returns the leaves matching `query`, from every submenu.
+    ///
+    /// Leaves are matched on their label with a fuzzy search, best first.
+    /// In the returned tree, each label is prefixed with the path to the
+    /// leaf, like `"File > Open"`.
+    pub fn search(&self, query: &str) -> MenuTree {
+        let mut leaves = Vec::new();
+        collect_leaves(&self.root, "", &mut leaves);
+
+        let mut matches: Vec<_> = leaves
+            .into_iter()
+            .filter_map(|(path, label, cb)| {
+                fuzzy_match(query, label).map(|m| (m.score, path, cb))
+            })
+            .collect();
+        matches.sort_by_key(|&(score, _, _)| Reverse(score));
+
+        MenuTree {
+            children: matches
+                .into_iter()
+                .map(|(_, path, cb)| MenuItem::Leaf(path, cb))
+                .collect(),
+        }
+    }
+
+    /// Returns the current search query.
+    ///
+    /// It is empty unless the user is typing in the menubar.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the labels of the leaves, from every submenu.
+    fn leaf_labels(&self) -> Vec<String> {
+        let mut leaves = Vec::new();
+        collect_leaves(&self.root, "", &mut leaves);
+        leaves
+            .into_iter()
+            .map(|(_, label, _)| label.to_string())
+            .collect()
+    }
+
+    /// Shows the leaves matching the current query.
+    fn show_search(&mut self) -> EventResult {
+        let results = self.search(&self.query);
+        if self.query.is_empty() || results.is_empty() {
+            // Keep the menubar selected to edit the query.
+            self.state = State::Selected;
+            return EventResult::with_cb(|s| s.clear());
+        }
+
+        self.state = State::Submenu;
+        let offset = Vec2::new(0, if self.autohide { 1 } else { 0 });
+        let results = Rc::new(results);
+        let search = Rc::new((self.query.clone(), self.leaf_labels()));
+        EventResult::with_cb(move |s| {
+            show_search(s, offset, Rc::clone(&results), Rc::clone(&search))
+        })
+    }
+
     fn child_at(&self, x: usize) -> Option<usize> {
         if x == 0 {
             return None;
@@ -260,6 +331,68 @@ fn show_child(s: &mut Cursive, offset: Vec2, menu: Rc<MenuTree>) {
     );
 }
 
+/// Adds every leaf in `tree` to `leaves`, as `(path, label, callback)`.
+fn collect_leaves<'a>(
+    tree: &'a MenuTree, path: &str,
+    leaves: &mut Vec<(String, &'a str, Callback)>,
+) {
+    for item in &tree.children {
+        match *item {
+            MenuItem::Leaf(ref label, ref cb) => {
+                leaves.push((format!("{}{}", path, label), label, cb.clone()))
+            }
+            MenuItem::Subtree(ref label, ref tree) => {
+                collect_leaves(tree, &format!("{}{} > ", path, label), leaves)
+            }
+            MenuItem::Delimiter => (),
+        }
+    }
+}
+
+/// Returns `true` if `query` matches any of the labels.
+fn any_match(labels: &[String], query: &str) -> bool {
+    labels
+        .iter()
+        .any(|label| fuzzy_match(query, label).is_some())
+}
+
+/// Shows the search results in a popup.
+///
+/// `search` is the current query, and the labels it is matched against.
+fn show_search(
+    s: &mut Cursive, offset: Vec2, results: Rc<MenuTree>,
+    search: Rc<(String, Vec<String>)>,
+) {
+    // Typing in the popup edits the query: the popup is then replaced with
+    // the new results.
+    s.screen_mut().add_layer_at(
+        Position::absolute(offset),
+        OnEventView::new(
+            MenuPopup::new(results)
+                .on_dismiss(|s| {
+                    s.menubar().query.clear();
+                    s.select_menubar();
+                })
+                .on_action(|s| s.menubar().hide()),
+        )
+        .on_event_inner(EventTrigger::any(), move |_, event| match *event {
+            Event::Char(c)
+                if !any_match(&search.1, &format!("{}{}", search.0, c)) =>
+            {
+                None
+            }
+            Event::Char(_) | Event::Key(Key::Backspace) => {
+                let event = event.clone();
+                Some(EventResult::with_cb(move |s| {
+                    s.pop_layer();
+                    s.menubar().on_event(event.clone()).process(s);
+                }))
+            }
+            _ => None,
+        }),
+    );
+}
+
 impl View for Menubar {
     fn draw(&self, printer: &Printer) {
         // Draw the bar at the top
@@ -281,9 +414,33 @@ impl View for Menubar {
             });
             offset += title.width() + 2;
         }
+
+        if !self.query.is_empty() {
+            let search = format!(" Search: {} ", self.query);
+            let x = printer.size.x.saturating_sub(search.width());
+            printer.with_selection(true, |printer| {
+                printer.print((x, 0), &search);
+            });
+        }
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Char(c) => {
+                let query = format!("{}{}", self.query, c);
+                if !any_match(&self.leaf_labels(), &query) {
+                    return EventResult::Ignored;
+                }
+                self.query = query;
+                return self.show_search();
+            }
+            Event::Key(Key::Backspace) if !self.query.is_empty() => {
+                self.query.pop();
+                return self.show_search();
+            }
+            _ => self.query.clear(),
+        }
+
         match event {
             Event::Key(Key::Esc) => {
                 self.hide();
@@ -403,3 +560,79 @@ impl View for Menubar {
         Rect::from_size((x, 0), (width, 1))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn labels(tree: &MenuTree) -> Vec<&str> {
+        tree.children.iter().map(MenuItem::label).collect()
+    }
+
+    #[test]
+    fn search() {
+        let mut menubar = Menubar::new();
+        menubar
+            .add_subtree(
+                "File",
+                MenuTree::new()
+                    .leaf("Open", |_| ())
+                    .subtree(
+                        "Recent",
+                        MenuTree::new().leaf("notes.txt", |_| ()),
+                    )
+                    .delimiter()
+                    .leaf("Quit", |_| ()),
+            )
+            .add_leaf("Options", |_| ());
+
+        assert_eq!(
+            labels(&menubar.search("o")),
+            vec!["File > Open", "Options", "File > Recent > notes.txt"]
+        );
+        assert_eq!(labels(&menubar.search("qt")), vec!["File > Quit"]);
+        assert!(menubar.search("xyz").is_empty());
+    }
+
+    #[test]
+    fn search_popup() {
+        let runs = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&runs);
+
+        let mut siv = Cursive::dummy();
+        siv.menubar().add_subtree(
+            "File",
+            MenuTree::new()
+                .leaf("Open", |_| ())
+                .leaf("Quit", move |_| counter.set(counter.get() + 1)),
+        );
+        let ignored = Rc::new(Cell::new(0));
+        let count = Rc::clone(&ignored);
+        siv.add_global_callback('x', move |_| count.set(count.get() + 1));
+        siv.select_menubar();
+
+        // Characters matching no leaf go to the global callbacks.
+        siv.on_event(Event::Char('x'));
+        assert_eq!(ignored.get(), 1);
+        assert_eq!(siv.menubar().query(), "");
+
+        siv.on_event(Event::Char('q'));
+        assert_eq!(siv.menubar().query(), "q");
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+        siv.on_event(Event::Char('x'));
+        assert_eq!(ignored.get(), 2);
+        assert_eq!(siv.menubar().query(), "q");
+
+        // Typing replaces the popup.
+        siv.on_event(Event::Key(Key::Backspace));
+        assert!(siv.screen().layer_sizes().is_empty());
+        siv.on_event(Event::Char('q'));
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+
+        siv.on_event(Event::Key(Key::Enter));
+        assert_eq!(runs.get(), 1);
+        assert_eq!(siv.menubar().query(), "");
+        assert!(!siv.menubar().receive_events());
+    }
+}
@@ -35,64 +35,117 @@ macro_rules! impl_enabled {
     }
 }
 
+mod animated_view;
+mod bar_chart_view;
 mod box_view;
 mod button;
+mod cached_layout_view;
+mod calendar_view;
 mod canvas;
 mod checkbox;
+#[cfg(feature = "syntect")]
+mod code_view;
+mod command_palette;
+mod copy_mode;
+mod debug_view;
 mod dialog;
+mod diff_view;
 mod dummy;
 mod edit_view;
 mod enableable_view;
+mod fuzzy_finder;
+mod gauge;
+mod hex_view;
 mod hideable_view;
 mod id_view;
+#[cfg(feature = "image")]
+mod image_view;
 mod layer;
+mod layout_macro;
+mod lifecycle_view;
 mod linear_layout;
 mod list_view;
 mod menu_popup;
 mod menubar;
 mod on_event_view;
 mod padded_view;
+mod pager;
 mod panel;
+mod plot_canvas;
+#[cfg(feature = "profiling")]
+mod profiled_view;
 mod progress_bar;
 mod radio;
+mod screen_tabs;
 mod scroll_view;
 mod select_view;
 mod shadow_view;
 mod sized_view;
 mod slider_view;
+mod sparkline_view;
 mod stack_view;
 mod text_area;
+#[cfg(all(unix, feature = "vt100"))]
+mod terminal_view;
 mod text_view;
+mod timer_view;
 mod tracked_view;
 mod view_box;
 
+pub use self::animated_view::AnimatedView;
+pub use self::bar_chart_view::BarChartView;
 pub use self::box_view::BoxView;
 pub use self::button::Button;
+pub use self::cached_layout_view::CachedLayoutView;
+pub use self::calendar_view::CalendarView;
 pub use self::canvas::Canvas;
 pub use self::checkbox::Checkbox;
+#[cfg(feature = "syntect")]
+pub use self::code_view::CodeView;
+pub use self::command_palette::CommandPalette;
+pub use self::copy_mode::CopyMode;
+pub use self::debug_view::DebugView;
 pub use self::dialog::{Dialog, DialogFocus};
+pub(crate) use self::dialog::unique_id;
+pub use self::diff_view::DiffView;
 pub use self::dummy::DummyView;
 pub use self::edit_view::EditView;
 pub use self::enableable_view::EnableableView;
+pub use self::fuzzy_finder::FuzzyFinder;
+pub use self::gauge::Gauge;
+pub use self::hex_view::{HexView, OnByteEdit};
 pub use self::hideable_view::HideableView;
 pub use self::id_view::{IdView, ViewRef};
+#[cfg(feature = "image")]
+pub use self::image_view::{ImageProtocol, ImageView};
 pub use self::layer::Layer;
+pub use self::lifecycle_view::LifecycleView;
 pub use self::linear_layout::LinearLayout;
 pub use self::list_view::{ListChild, ListView};
 pub use self::menu_popup::MenuPopup;
 pub use self::menubar::Menubar;
 pub use self::on_event_view::OnEventView;
 pub use self::padded_view::PaddedView;
+pub use self::pager::Pager;
 pub use self::panel::Panel;
+pub use self::plot_canvas::{Marker, PlotCanvas};
+#[cfg(feature = "profiling")]
+pub use self::profiled_view::ProfiledView;
 pub use self::progress_bar::ProgressBar;
 pub use self::radio::{RadioButton, RadioGroup};
+pub(crate) use self::screen_tabs::ScreenList;
+pub use self::screen_tabs::ScreenTabs;
 pub use self::scroll_view::ScrollView;
 pub use self::select_view::SelectView;
 pub use self::shadow_view::ShadowView;
 pub use self::sized_view::SizedView;
 pub use self::slider_view::SliderView;
+pub use self::sparkline_view::SparklineView;
 pub use self::stack_view::{LayerPosition, StackView};
 pub use self::text_area::TextArea;
+#[cfg(all(unix, feature = "vt100"))]
+pub use self::terminal_view::TerminalView;
 pub use self::text_view::{TextContent, TextContentRef, TextView};
+pub use self::timer_view::{StopwatchView, TimerView};
 pub use self::tracked_view::TrackedView;
 pub use self::view_box::ViewBox;
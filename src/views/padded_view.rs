@@ -1,7 +1,9 @@
+use direction::Direction;
 use event::{Event, EventResult};
 use vec::Vec2;
-use view::{Margins, View, ViewWrapper};
+use view::{ChildInfo, Margins, View, ViewWrapper};
 use Printer;
+use XY;
 
 /// Adds padding to another view.
 ///
@@ -56,10 +58,26 @@ impl<V: View> ViewWrapper for PaddedView<V> {
         self.view.on_event(event.relativized(padding))
     }
 
+    fn wrap_take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        let padding = self.margins.top_left();
+        self.view.take_focus_near(source, position - padding)
+    }
+
     fn wrap_draw(&self, printer: &Printer) {
         let top_left = self.margins.top_left();
         let bot_right = self.margins.bot_right();
         let printer = &printer.offset(top_left).shrinked(bot_right);
         self.view.draw(printer);
     }
+
+    fn wrap_inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        f(ChildInfo {
+            view: &self.view,
+            offset: self.margins.top_left(),
+            size: size.saturating_sub(self.margins.combined()),
+            focused: true,
+        });
+    }
 }
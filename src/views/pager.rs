@@ -0,0 +1,443 @@
+use direction::Direction;
+use event::{Event, EventResult, Key, MouseEvent};
+use rect::Rect;
+use std::cmp::min;
+use theme::{ColorStyle, Effect};
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Input currently being typed in the status line.
+enum Prompt {
+    /// Search pattern, started with `/`.
+    Search(String),
+    /// Line number, started with `:`.
+    Line(String),
+}
+
+/// Scrollable text viewer, similar to `less`.
+///
+/// The last row shows a status line with the current position.
+///
+/// # Bindings
+///
+/// * `Up`/`k`, `Down`/`j`: scroll by one line.
+/// * `PageUp`/`b`, `PageDown`/`Space`: scroll by one page.
+/// * `Home`/`g`, `End`/`G`: go to the beginning or end of the text.
+/// * `/`: search for a pattern.
+/// * `n`, `N`: go to the next or previous match.
+/// * `:`: jump to a line number.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::Pager;
+/// let pager = Pager::new("A long text...\nwith many lines.");
+/// ```
+pub struct Pager {
+    lines: Vec<String>,
+
+    /// Index of the first visible line.
+    top: usize,
+
+    /// Current search pattern.
+    pattern: Option<String>,
+
+    /// Matches for the current pattern, as `(line, start, end)`.
+    ///
+    /// `start` and `end` are byte offsets in the line.
+    matches: Vec<(usize, usize, usize)>,
+
+    /// Index of the selected match in `matches`.
+    current_match: Option<usize>,
+
+    prompt: Option<Prompt>,
+
+    /// Message shown in the status line, like "Pattern not found".
+    message: Option<String>,
+
+    last_size: Vec2,
+}
+
+impl Pager {
+    /// Creates a new pager showing the given content.
+    pub fn new<S: Into<String>>(content: S) -> Self {
+        Pager {
+            lines: Vec::new(),
+            top: 0,
+            pattern: None,
+            matches: Vec::new(),
+            current_match: None,
+            prompt: None,
+            message: None,
+            last_size: Vec2::zero(),
+        }
+        .content(content)
+    }
+
+    /// Replaces the content of this pager.
+    ///
+    /// Keeps the current search pattern, if any.
+    pub fn set_content<S: Into<String>>(&mut self, content: S) {
+        self.lines = content.into().lines().map(String::from).collect();
+        self.top = min(self.top, self.max_top());
+
+        if let Some(pattern) = self.pattern.take() {
+            self.find_matches(pattern);
+        }
+    }
+
+    /// Replaces the content of this pager.
+    ///
+    /// Chainable variant.
+    pub fn content<S: Into<String>>(self, content: S) -> Self {
+        self.with(|s| s.set_content(content))
+    }
+
+    /// Returns the number of lines in the content.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the index of the first visible line.
+    pub fn top_line(&self) -> usize {
+        self.top
+    }
+
+    /// Scrolls so that the given line is the first one visible.
+    ///
+    /// `line` is 0-based.
+    pub fn scroll_to_line(&mut self, line: usize) {
+        self.top = min(line, self.max_top());
+    }
+
+    /// Searches for `pattern` and jumps to the first match.
+    ///
+    /// Returns `false` if the pattern was not found.
+    pub fn search<S: Into<String>>(&mut self, pattern: S) -> bool {
+        self.find_matches(pattern.into());
+
+        // Start from the first match on screen, or after.
+        let top = self.top;
+        let mut first =
+            self.matches.iter().position(|&(line, _, _)| line >= top);
+        if first.is_none() && !self.matches.is_empty() {
+            // Wrap around to the beginning.
+            first = Some(0);
+        }
+        self.select_match(first);
+
+        self.current_match.is_some()
+    }
+
+    /// Jumps to the next match, wrapping around at the end.
+    pub fn next_match(&mut self) {
+        let next = match self.current_match {
+            _ if self.matches.is_empty() => None,
+            Some(i) => Some((i + 1) % self.matches.len()),
+            None => Some(0),
+        };
+        self.select_match(next);
+    }
+
+    /// Jumps to the previous match, wrapping around at the beginning.
+    pub fn previous_match(&mut self) {
+        let previous = match self.current_match {
+            _ if self.matches.is_empty() => None,
+            Some(0) | None => Some(self.matches.len() - 1),
+            Some(i) => Some(i - 1),
+        };
+        self.select_match(previous);
+    }
+
+    /// Returns the number of matches for the current pattern.
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn find_matches(&mut self, pattern: String) {
+        self.matches.clear();
+        self.current_match = None;
+
+        if !pattern.is_empty() {
+            for (i, line) in self.lines.iter().enumerate() {
+                self.matches.extend(
+                    line.match_indices(pattern.as_str())
+                        .map(|(start, m)| (i, start, start + m.len())),
+                );
+            }
+        }
+
+        self.pattern = Some(pattern);
+    }
+
+    fn select_match(&mut self, i: Option<usize>) {
+        self.current_match = i;
+        match i {
+            Some(i) => {
+                let line = self.matches[i].0;
+                let height = self.page_height();
+                if line < self.top || line >= self.top + height {
+                    self.scroll_to_line(line);
+                }
+                self.message = None;
+            }
+            None => {
+                self.message = Some("Pattern not found".to_string());
+            }
+        }
+    }
+
+    /// Number of rows available for the content.
+    fn page_height(&self) -> usize {
+        self.last_size.y.saturating_sub(1)
+    }
+
+    fn max_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.page_height())
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        self.top = self.top.saturating_sub(n);
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        self.top = min(self.top + n, self.max_top());
+    }
+
+    fn status(&self) -> String {
+        let total = self.lines.len();
+        let bottom = min(self.top + self.page_height(), total);
+        let percent = (bottom * 100).checked_div(total).unwrap_or(100);
+
+        format!("lines {}-{}/{} {}%", self.top + 1, bottom, total, percent)
+    }
+
+    fn on_prompt_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Esc) => {
+                self.prompt = None;
+            }
+            Event::Key(Key::Enter) => match self.prompt.take() {
+                Some(Prompt::Search(pattern)) => {
+                    self.search(pattern);
+                }
+                Some(Prompt::Line(line)) => match line.parse::<usize>() {
+                    Ok(line) => self.scroll_to_line(line.saturating_sub(1)),
+                    Err(_) => {
+                        self.message = Some("Invalid line number".to_string())
+                    }
+                },
+                None => (),
+            },
+            Event::Key(Key::Backspace) => {
+                let empty = match self.prompt {
+                    Some(Prompt::Search(ref mut input))
+                    | Some(Prompt::Line(ref mut input)) => {
+                        input.pop().is_none()
+                    }
+                    None => false,
+                };
+                if empty {
+                    self.prompt = None;
+                }
+            }
+            Event::Char(c) => match self.prompt {
+                Some(Prompt::Search(ref mut input)) => input.push(c),
+                Some(Prompt::Line(ref mut input)) if c.is_ascii_digit() => {
+                    input.push(c)
+                }
+                _ => (),
+            },
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn draw_line(&self, printer: &Printer, y: usize, i: usize) {
+        let line = &self.lines[i];
+        printer.print((0, y), line);
+
+        for (j, &(_, start, end)) in self
+            .matches
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(line, _, _))| line == i)
+        {
+            let x = line[..start].width();
+            let text = &line[start..end];
+            if Some(j) == self.current_match {
+                printer.with_color(ColorStyle::highlight(), |printer| {
+                    printer.print((x, y), text);
+                });
+            } else {
+                printer.with_effect(Effect::Reverse, |printer| {
+                    printer.print((x, y), text);
+                });
+            }
+        }
+    }
+}
+
+impl View for Pager {
+    fn draw(&self, printer: &Printer) {
+        let height = self.page_height();
+
+        for (y, i) in (self.top..self.lines.len()).take(height).enumerate() {
+            self.draw_line(printer, y, i);
+        }
+
+        let y = printer.size.y.saturating_sub(1);
+        match self.prompt {
+            Some(Prompt::Search(ref input)) => {
+                printer.print((0, y), &format!("/{}", input));
+            }
+            Some(Prompt::Line(ref input)) => {
+                printer.print((0, y), &format!(":{}", input));
+            }
+            None => {
+                let status = self.status();
+                let x = printer.size.x.saturating_sub(status.width());
+                printer.with_effect(Effect::Reverse, |printer| {
+                    printer.print((x, y), &status);
+                });
+                if let Some(ref message) = self.message {
+                    printer.print((0, y), message);
+                }
+            }
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let width = self
+            .lines
+            .iter()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0)
+            .max(self.status().width());
+
+        Vec2::new(width, self.lines.len() + 1)
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.last_size = size;
+        self.top = min(self.top, self.max_top());
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        if self.prompt.is_some() {
+            return self.on_prompt_event(event);
+        }
+
+        let page = self.page_height().max(1);
+        self.message = None;
+
+        match event {
+            Event::Key(Key::Up) | Event::Char('k') => self.scroll_up(1),
+            Event::Key(Key::Down) | Event::Char('j') => self.scroll_down(1),
+            Event::Key(Key::PageUp) | Event::Char('b') => self.scroll_up(page),
+            Event::Key(Key::PageDown) | Event::Char(' ') => {
+                self.scroll_down(page)
+            }
+            Event::Key(Key::Home) | Event::Char('g') => self.top = 0,
+            Event::Key(Key::End) | Event::Char('G') => {
+                self.top = self.max_top()
+            }
+            Event::Char('/') => {
+                self.prompt = Some(Prompt::Search(String::new()))
+            }
+            Event::Char(':') => {
+                self.prompt = Some(Prompt::Line(String::new()))
+            }
+            Event::Char('n') => self.next_match(),
+            Event::Char('N') => self.previous_match(),
+            Event::Mouse {
+                event: MouseEvent::WheelUp,
+                ..
+            } => self.scroll_up(3),
+            Event::Mouse {
+                event: MouseEvent::WheelDown,
+                ..
+            } => self.scroll_down(3),
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn important_area(&self, size: Vec2) -> Rect {
+        // The status line is where things happen.
+        Rect::from_size((0, size.y.saturating_sub(1)), (size.x, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pager(lines: usize) -> Pager {
+        let content: Vec<String> =
+            (0..lines).map(|i| format!("line {}", i)).collect();
+        let mut pager = Pager::new(content.join("\n"));
+        pager.layout(Vec2::new(20, 11));
+        pager
+    }
+
+    #[test]
+    fn scrolling() {
+        let mut pager = pager(100);
+
+        pager.on_event(Event::Key(Key::PageDown));
+        assert_eq!(pager.top_line(), 10);
+
+        pager.on_event(Event::Key(Key::End));
+        assert_eq!(pager.top_line(), 90);
+
+        pager.on_event(Event::Key(Key::Down));
+        assert_eq!(pager.top_line(), 90);
+    }
+
+    #[test]
+    fn search() {
+        let mut pager = pager(100);
+
+        // "line 5", "line 50" to "line 59"
+        assert!(pager.search("line 5"));
+        assert_eq!(pager.match_count(), 11);
+        assert_eq!(pager.top_line(), 0);
+
+        pager.next_match();
+        assert_eq!(pager.top_line(), 50);
+
+        pager.previous_match();
+        pager.previous_match();
+        assert_eq!(pager.top_line(), 59);
+
+        assert!(!pager.search("nothing"));
+    }
+
+    #[test]
+    fn prompts() {
+        let mut pager = pager(100);
+
+        for c in ":42".chars() {
+            pager.on_event(Event::Char(c));
+        }
+        pager.on_event(Event::Key(Key::Enter));
+        assert_eq!(pager.top_line(), 41);
+
+        for c in "/line 7".chars() {
+            pager.on_event(Event::Char(c));
+        }
+        pager.on_event(Event::Key(Key::Enter));
+        assert_eq!(pager.match_count(), 11);
+        assert_eq!(pager.top_line(), 70);
+    }
+}
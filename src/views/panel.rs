@@ -1,12 +1,14 @@
 use align::*;
+use direction::Direction;
 use event::{Event, EventResult};
 use rect::Rect;
 use theme::ColorStyle;
-use unicode_width::UnicodeWidthStr;
+use utils::width::StrWidth;
 use vec::Vec2;
-use view::{View, ViewWrapper};
+use view::{ChildInfo, View, ViewWrapper};
 use Printer;
 use With;
+use XY;
 
 /// Draws a border around a wrapped view.
 #[derive(Debug)]
@@ -71,10 +73,11 @@ impl<V: View> Panel<V> {
             let spacing = 3; //minimum distance to borders
             let x = spacing + self
                 .title_position
-                .get_offset(len, printer.size.x - 2 * spacing);
+                .get_offset(len, printer.size.x.saturating_sub(2 * spacing));
             printer.with_high_border(false, |printer| {
-                printer.print((x - 2, 0), "┤ ");
-                printer.print((x + len, 0), " ├");
+                let chars = printer.border_chars();
+                printer.print((x - 2, 0), &format!("{} ", chars.right_tee));
+                printer.print((x + len, 0), &format!(" {}", chars.left_tee));
             });
 
             printer.with_color(ColorStyle::title_primary(), |p| {
@@ -95,6 +98,12 @@ impl<V: View> ViewWrapper for Panel<V> {
         self.view.on_event(event.relativized((1, 1)))
     }
 
+    fn wrap_take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        self.view.take_focus_near(source, position - Vec2::new(1, 1))
+    }
+
     fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
         // TODO: make borders conditional?
         let req = req.saturating_sub((2, 2));
@@ -119,6 +128,15 @@ impl<V: View> ViewWrapper for Panel<V> {
         self.view.important_area(inner_size) + (1, 1)
     }
 
+    fn wrap_inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        f(ChildInfo {
+            view: &self.view,
+            offset: Vec2::new(1, 1),
+            size: size.saturating_sub((2, 2)),
+            focused: true,
+        });
+    }
+
     fn wrap_needs_relayout(&self) -> bool {
         self.invalidated || self.view.needs_relayout()
     }
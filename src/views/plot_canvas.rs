@@ -0,0 +1,515 @@
+use std::char;
+use theme::{ColorStyle, ColorType, PaletteColor};
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Characters used to draw on a [`PlotCanvas`].
+///
+/// [`PlotCanvas`]: struct.PlotCanvas.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Marker {
+    /// Braille patterns, with 2x4 dots per cell.
+    Braille,
+    /// Half blocks, with 1x2 dots per cell.
+    ///
+    /// Lower resolution, but supported by more fonts.
+    HalfBlock,
+}
+
+impl Marker {
+    /// Returns the number of dots per cell, horizontally and vertically.
+    pub fn resolution(self) -> Vec2 {
+        match self {
+            Marker::Braille => Vec2::new(2, 4),
+            Marker::HalfBlock => Vec2::new(1, 2),
+        }
+    }
+
+    /// Returns the bit representing the given dot in a cell.
+    fn bit(self, dot: Vec2) -> u8 {
+        match self {
+            Marker::Braille => {
+                const BITS: [[u8; 2]; 4] =
+                    [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+                BITS[dot.y][dot.x]
+            }
+            Marker::HalfBlock => 1 << dot.y,
+        }
+    }
+
+    /// Returns the character for a cell with the given dots set.
+    fn symbol(self, bits: u8) -> char {
+        match self {
+            Marker::Braille => {
+                char::from_u32(0x2800 + u32::from(bits)).unwrap_or(' ')
+            }
+            Marker::HalfBlock => match bits {
+                0 => ' ',
+                1 => '▀',
+                2 => '▄',
+                _ => '█',
+            },
+        }
+    }
+}
+
+/// Something drawn on the canvas, in data coordinates.
+enum Shape {
+    Points(Vec<(f64, f64)>),
+    Lines(Vec<(f64, f64)>),
+}
+
+/// Grid of cells, each holding a few dots.
+struct Grid<'a> {
+    marker: Marker,
+    /// Size in cells.
+    size: Vec2,
+    cells: Vec<(u8, Option<&'a ColorType>)>,
+}
+
+impl<'a> Grid<'a> {
+    fn new(marker: Marker, size: Vec2) -> Self {
+        Grid {
+            marker,
+            size,
+            cells: vec![(0, None); size.x * size.y],
+        }
+    }
+
+    /// Size in dots.
+    fn dots(&self) -> Vec2 {
+        self.size * self.marker.resolution()
+    }
+
+    fn set(&mut self, x: isize, y: isize, color: &'a ColorType) {
+        let dots = self.dots();
+        if x < 0 || y < 0 || x as usize >= dots.x || y as usize >= dots.y {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let resolution = self.marker.resolution();
+        let cell = (x / resolution.x) + (y / resolution.y) * self.size.x;
+        let dot = Vec2::new(x % resolution.x, y % resolution.y);
+
+        self.cells[cell].0 |= self.marker.bit(dot);
+        self.cells[cell].1 = Some(color);
+    }
+
+    /// Draws a line using Bresenham's algorithm.
+    fn line(
+        &mut self, (x0, y0): (isize, isize), (x1, y1): (isize, isize),
+        color: &'a ColorType,
+    ) {
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+        let (mut x, mut y) = (x0, y0);
+        let mut error = dx + dy;
+
+        loop {
+            self.set(x, y, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Returns the character and color of a cell, if any dot is set.
+    fn cell(&self, x: usize, y: usize) -> Option<(char, &'a ColorType)> {
+        let (bits, color) = self.cells[x + y * self.size.x];
+        color.map(|color| (self.marker.symbol(bits), color))
+    }
+}
+
+/// Clips a segment to the `[0, width] x [0, height]` box, using the
+/// Liang-Barsky algorithm.
+///
+/// Returns `None` if the segment is outside, or not finite.
+fn clip(
+    (x0, y0): (f64, f64), (x1, y1): (f64, f64), (width, height): (f64, f64),
+) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    if !dx.is_finite() || !dy.is_finite() {
+        return None;
+    }
+
+    // Range of the segment inside the box, as fractions of its length.
+    let (mut start, mut end) = (0.0f64, 1.0f64);
+    let edges = [(-dx, x0), (dx, width - x0), (-dy, y0), (dy, height - y0)];
+    for &(p, q) in &edges {
+        if p == 0.0 {
+            // Parallel to this edge: either fully inside or outside.
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            start = start.max(q / p);
+        } else {
+            end = end.min(q / p);
+        }
+    }
+    if start > end {
+        return None;
+    }
+
+    Some((
+        (x0 + start * dx, y0 + start * dy),
+        (x0 + end * dx, y0 + end * dy),
+    ))
+}
+
+/// Makes sure a range of values is not empty, so it can be scaled.
+fn widen((min, max): (f64, f64)) -> (f64, f64) {
+    if min == max {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    }
+}
+
+/// Drawing surface for plots, with sub-cell resolution.
+///
+/// Shapes are given in data coordinates, and scaled to the view size
+/// according to the x and y bounds (both `[0, 1]` by default). The y axis
+/// points up.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::PlotCanvas;
+/// # use cursive::theme::{BaseColor, Color};
+/// let points: Vec<(f64, f64)> = (0..100)
+///     .map(|i| f64::from(i) / 10.0)
+///     .map(|x| (x, x.sin()))
+///     .collect();
+///
+/// let canvas = PlotCanvas::new()
+///     .x_bounds(0.0, 10.0)
+///     .y_bounds(-1.0, 1.0)
+///     .axes(true)
+///     .polyline(points, Color::Dark(BaseColor::Blue));
+/// ```
+pub struct PlotCanvas {
+    marker: Marker,
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+    show_axes: bool,
+    shapes: Vec<(Shape, ColorType)>,
+}
+
+new_default!(PlotCanvas);
+
+impl PlotCanvas {
+    /// Creates a new, empty canvas.
+    pub fn new() -> Self {
+        PlotCanvas {
+            marker: Marker::Braille,
+            x_bounds: (0.0, 1.0),
+            y_bounds: (0.0, 1.0),
+            show_axes: false,
+            shapes: Vec::new(),
+        }
+    }
+
+    /// Sets the characters used to draw.
+    ///
+    /// Defaults to `Marker::Braille`.
+    pub fn set_marker(&mut self, marker: Marker) {
+        self.marker = marker;
+    }
+
+    /// Sets the characters used to draw.
+    ///
+    /// Chainable variant.
+    pub fn marker(self, marker: Marker) -> Self {
+        self.with(|s| s.set_marker(marker))
+    }
+
+    /// Sets the range of x values shown, from left to right.
+    ///
+    /// If `min == max`, the range is widened to `[min - 0.5, max + 0.5]`.
+    pub fn set_x_bounds(&mut self, min: f64, max: f64) {
+        self.x_bounds = widen((min, max));
+    }
+
+    /// Sets the range of x values shown, from left to right.
+    ///
+    /// Chainable variant.
+    pub fn x_bounds(self, min: f64, max: f64) -> Self {
+        self.with(|s| s.set_x_bounds(min, max))
+    }
+
+    /// Sets the range of y values shown, from bottom to top.
+    ///
+    /// If `min == max`, the range is widened to `[min - 0.5, max + 0.5]`.
+    pub fn set_y_bounds(&mut self, min: f64, max: f64) {
+        self.y_bounds = widen((min, max));
+    }
+
+    /// Sets the range of y values shown, from bottom to top.
+    ///
+    /// Chainable variant.
+    pub fn y_bounds(self, min: f64, max: f64) -> Self {
+        self.with(|s| s.set_y_bounds(min, max))
+    }
+
+    /// Shows or hides axes on the left and bottom sides.
+    ///
+    /// Axes are labeled with the bounds.
+    pub fn set_axes(&mut self, show: bool) {
+        self.show_axes = show;
+    }
+
+    /// Shows or hides axes on the left and bottom sides.
+    ///
+    /// Chainable variant.
+    pub fn axes(self, show: bool) -> Self {
+        self.with(|s| s.set_axes(show))
+    }
+
+    /// Draws the given points.
+    pub fn add_points<I, C>(&mut self, points: I, color: C)
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+        C: Into<ColorType>,
+    {
+        let points = points.into_iter().collect();
+        self.shapes.push((Shape::Points(points), color.into()));
+    }
+
+    /// Draws the given points.
+    ///
+    /// Chainable variant.
+    pub fn points<I, C>(self, points: I, color: C) -> Self
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+        C: Into<ColorType>,
+    {
+        self.with(|s| s.add_points(points, color))
+    }
+
+    /// Draws a line between two points.
+    pub fn add_line<C>(&mut self, from: (f64, f64), to: (f64, f64), color: C)
+    where
+        C: Into<ColorType>,
+    {
+        self.add_polyline(vec![from, to], color);
+    }
+
+    /// Draws a line between two points.
+    ///
+    /// Chainable variant.
+    pub fn line<C>(self, from: (f64, f64), to: (f64, f64), color: C) -> Self
+    where
+        C: Into<ColorType>,
+    {
+        self.with(|s| s.add_line(from, to, color))
+    }
+
+    /// Draws lines joining the given points, in order.
+    pub fn add_polyline<I, C>(&mut self, points: I, color: C)
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+        C: Into<ColorType>,
+    {
+        let points = points.into_iter().collect();
+        self.shapes.push((Shape::Lines(points), color.into()));
+    }
+
+    /// Draws lines joining the given points, in order.
+    ///
+    /// Chainable variant.
+    pub fn polyline<I, C>(self, points: I, color: C) -> Self
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+        C: Into<ColorType>,
+    {
+        self.with(|s| s.add_polyline(points, color))
+    }
+
+    /// Removes all shapes.
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    fn labels(&self) -> [String; 4] {
+        [
+            self.x_bounds.0.to_string(),
+            self.x_bounds.1.to_string(),
+            self.y_bounds.0.to_string(),
+            self.y_bounds.1.to_string(),
+        ]
+    }
+
+    /// Rasterizes the shapes on a grid of the given size, in cells.
+    fn rasterize<'a>(&'a self, size: Vec2) -> Grid<'a> {
+        let mut grid = Grid::new(self.marker, size);
+        let dots = grid.dots();
+        if dots.x == 0 || dots.y == 0 {
+            return grid;
+        }
+
+        // Converts data coordinates to dots, still as floats.
+        let scale = |(x, y): (f64, f64)| -> (f64, f64) {
+            let (x_min, x_max) = self.x_bounds;
+            let (y_min, y_max) = self.y_bounds;
+            let x = (x - x_min) / (x_max - x_min) * (dots.x - 1) as f64;
+            let y = (y_max - y) / (y_max - y_min) * (dots.y - 1) as f64;
+            (x, y)
+        };
+        let round =
+            |(x, y): (f64, f64)| (x.round() as isize, y.round() as isize);
+        let max = ((dots.x - 1) as f64, (dots.y - 1) as f64);
+
+        for &(ref shape, ref color) in &self.shapes {
+            match *shape {
+                Shape::Points(ref points) => {
+                    for &point in points {
+                        // A single point is a segment too.
+                        let point = scale(point);
+                        if let Some((point, _)) = clip(point, point, max) {
+                            let (x, y) = round(point);
+                            grid.set(x, y, color);
+                        }
+                    }
+                }
+                Shape::Lines(ref points) => {
+                    for pair in points.windows(2) {
+                        let (from, to) = (scale(pair[0]), scale(pair[1]));
+                        // Only walk the visible part of the segment.
+                        if let Some((from, to)) = clip(from, to, max) {
+                            grid.line(round(from), round(to), color);
+                        }
+                    }
+                }
+            }
+        }
+
+        grid
+    }
+}
+
+impl View for PlotCanvas {
+    fn draw(&self, printer: &Printer) {
+        let mut origin = Vec2::zero();
+        let mut size = printer.size;
+
+        if self.show_axes {
+            let [x_min, x_max, y_min, y_max] = self.labels();
+            let label_width = y_min.width().max(y_max.width());
+            let height = printer.size.y.saturating_sub(2);
+
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print((0, 0), &y_max);
+                if height > 1 {
+                    printer.print((0, height - 1), &y_min);
+                }
+                printer.print_vline((label_width, 0), height, "│");
+                printer.print((label_width, height), "└");
+                printer.print_hline(
+                    (label_width + 1, height),
+                    printer.size.x.saturating_sub(label_width + 1),
+                    "─",
+                );
+                printer.print((label_width + 1, height + 1), &x_min);
+                let x = printer.size.x.saturating_sub(x_max.width());
+                printer.print((x, height + 1), &x_max);
+            });
+
+            origin = Vec2::new(label_width + 1, 0);
+            size = Vec2::new(
+                printer.size.x.saturating_sub(label_width + 1),
+                height,
+            );
+        }
+
+        let grid = self.rasterize(size);
+        let printer = printer.offset(origin);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                if let Some((c, color)) = grid.cell(x, y) {
+                    let style =
+                        ColorStyle::new(color.clone(), PaletteColor::View);
+                    printer.with_color(style, |printer| {
+                        printer.print((x, y), &c.to_string());
+                    });
+                }
+            }
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(40, 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::Color;
+
+    fn render(grid: &Grid) -> String {
+        let mut result = String::new();
+        for y in 0..grid.size.y {
+            for x in 0..grid.size.x {
+                result.push(grid.cell(x, y).map_or(' ', |cell| cell.0));
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    #[test]
+    fn braille_dots() {
+        let color = ColorType::Color(Color::TerminalDefault);
+        let mut grid = Grid::new(Marker::Braille, Vec2::new(1, 1));
+        grid.set(0, 0, &color);
+        assert_eq!(render(&grid), "⠁\n");
+        grid.set(1, 3, &color);
+        assert_eq!(render(&grid), "⢁\n");
+    }
+
+    #[test]
+    fn diagonal() {
+        let canvas = PlotCanvas::new().marker(Marker::HalfBlock).line(
+            (0.0, 0.0),
+            (1.0, 1.0),
+            Color::TerminalDefault,
+        );
+        let grid = canvas.rasterize(Vec2::new(4, 2));
+        assert_eq!(render(&grid), "  ▄▀\n▄▀  \n");
+    }
+
+    #[test]
+    fn far_lines() {
+        let canvas = PlotCanvas::new()
+            .marker(Marker::HalfBlock)
+            .line((-1e12, 0.5), (1e12, 0.5), Color::TerminalDefault)
+            .line((0.0, 1e12), (0.0, f64::NAN), Color::TerminalDefault)
+            .points(vec![(1e12, 0.0)], Color::TerminalDefault);
+        let grid = canvas.rasterize(Vec2::new(4, 2));
+        assert_eq!(render(&grid), "    \n▀▀▀▀\n");
+    }
+
+    #[test]
+    fn empty_bounds() {
+        let canvas = PlotCanvas::new()
+            .marker(Marker::HalfBlock)
+            .x_bounds(1.0, 1.0)
+            .y_bounds(1.0, 1.0)
+            .points(vec![(1.0, 1.0)], Color::TerminalDefault);
+        assert_eq!(canvas.x_bounds, (0.5, 1.5));
+        let grid = canvas.rasterize(Vec2::new(3, 1));
+        assert_eq!(render(&grid), " ▄ \n");
+    }
+}
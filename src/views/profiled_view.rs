@@ -0,0 +1,77 @@
+use profiling;
+use std::time::Instant;
+use vec::Vec2;
+use view::{View, ViewWrapper};
+use Printer;
+
+/// Wrapper around a view that measures how long it takes to lay out and
+/// draw.
+///
+/// Timings are reported in [`Cursive::last_frame_stats`], under the name
+/// of the view. They include the time spent in child views.
+///
+/// Requires the `profiling` feature.
+///
+/// [`Cursive::last_frame_stats`]: ../struct.Cursive.html#method.last_frame_stats
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::views::{ProfiledView, TextView};
+/// let mut siv = Cursive::dummy();
+/// siv.add_layer(ProfiledView::new(TextView::new("Hello")).name("hello"));
+/// ```
+pub struct ProfiledView<T: View> {
+    view: T,
+    name: String,
+}
+
+impl<T: View> ProfiledView<T> {
+    /// Wraps `view`, reporting timings under its type name.
+    pub fn new(view: T) -> Self {
+        let name = view.type_name().to_string();
+        ProfiledView { view, name }
+    }
+
+    /// Sets the name used to report timings.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = name.into();
+    }
+
+    /// Sets the name used to report timings.
+    ///
+    /// Chainable variant.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.set_name(name);
+        self
+    }
+
+    inner_getters!(self.view: T);
+}
+
+impl<T: View> ViewWrapper for ProfiledView<T> {
+    wrap_impl!(self.view: T);
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        let start = Instant::now();
+        let size = self.view.required_size(req);
+        let elapsed = start.elapsed();
+        profiling::record(&self.name, |stats| stats.layout += elapsed);
+        size
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        let start = Instant::now();
+        self.view.layout(size);
+        let elapsed = start.elapsed();
+        profiling::record(&self.name, |stats| stats.layout += elapsed);
+    }
+
+    fn wrap_draw(&self, printer: &Printer) {
+        let start = Instant::now();
+        self.view.draw(printer);
+        let elapsed = start.elapsed();
+        profiling::record(&self.name, |stats| stats.draw += elapsed);
+    }
+}
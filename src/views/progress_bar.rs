@@ -244,7 +244,7 @@ impl View for ProgressBar {
         let offset = HAlign::Center.get_offset(label.len(), printer.size.x);
 
         let color_style =
-            ColorStyle::new(ColorStyle::highlight().front, self.color);
+            ColorStyle::new(ColorStyle::highlight().front, self.color.clone());
 
         printer.with_color(color_style, |printer| {
             // Draw the right half of the label in reverse
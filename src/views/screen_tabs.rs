@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use direction::Direction;
+use event::{Event, EventResult, Key, MouseButton, MouseEvent};
+use theme::ColorStyle;
+use utils::width::StrWidth;
+use vec::Vec2;
+use view::View;
+use {Printer, ScreenId};
+
+/// Lists the screens of the application, like terminal tabs.
+///
+/// Each screen is shown with its number and its title, if it has one. The
+/// active screen is highlighted, and clicking a screen activates it.
+/// Clicking also gives it the focus, so the arrow keys can activate the
+/// previous or next screen; keyboard navigation skips it otherwise.
+///
+/// [`Cursive::screen_tabs`] gives a list kept up to date by Cursive: add
+/// one to each screen, for instance at the bottom of a fullscreen layout.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::views::{LinearLayout, TextView};
+/// # use cursive::traits::*;
+/// let mut siv = Cursive::dummy();
+/// siv.set_screen_title(0, "Files");
+///
+/// let tabs = siv.screen_tabs();
+/// siv.add_fullscreen_layer(
+///     LinearLayout::vertical()
+///         .child(TextView::new("Content").full_height())
+///         .child(tabs),
+/// );
+/// ```
+///
+/// [`Cursive::screen_tabs`]: ../struct.Cursive.html#method.screen_tabs
+pub struct ScreenTabs {
+    screens: Rc<RefCell<ScreenList>>,
+}
+
+/// Screens listed by `ScreenTabs`, shared with the Cursive root.
+#[derive(Default)]
+pub(crate) struct ScreenList {
+    /// Title of each screen, empty for untitled ones.
+    pub titles: Vec<String>,
+    pub active: ScreenId,
+}
+
+new_default!(ScreenTabs);
+
+impl ScreenTabs {
+    /// Creates a new, empty list of screens.
+    ///
+    /// It is only updated by [`set_screens`].
+    ///
+    /// [`set_screens`]: #method.set_screens
+    pub fn new() -> Self {
+        ScreenTabs::with_screens(Rc::default())
+    }
+
+    /// Creates a list showing the given screens.
+    pub(crate) fn with_screens(screens: Rc<RefCell<ScreenList>>) -> Self {
+        ScreenTabs { screens }
+    }
+
+    /// Sets the screens to list, and the active one.
+    ///
+    /// Untitled screens have an empty title.
+    pub fn set_screens(&mut self, titles: Vec<String>, active: ScreenId) {
+        *self.screens.borrow_mut() = ScreenList { titles, active };
+    }
+
+    /// Returns the label of each screen.
+    fn labels(&self) -> Vec<String> {
+        let screens = self.screens.borrow();
+        screens
+            .titles
+            .iter()
+            .enumerate()
+            .map(|(id, title)| match &**title {
+                "" => format!(" {} ", id + 1),
+                title => format!(" {}:{} ", id + 1, title),
+            })
+            .collect()
+    }
+
+    /// Returns the screen shown at column `x`, if any.
+    fn screen_at(&self, x: usize) -> Option<ScreenId> {
+        let mut end = 0;
+        self.labels().iter().position(|label| {
+            end += label.width();
+            x < end
+        })
+    }
+}
+
+impl View for ScreenTabs {
+    fn draw(&self, printer: &Printer) {
+        let active = self.screens.borrow().active;
+        let mut x = 0;
+        for (id, label) in self.labels().iter().enumerate() {
+            let color = if id == active {
+                ColorStyle::highlight()
+            } else {
+                ColorStyle::primary()
+            };
+            printer.with_color(color, |printer| printer.print((x, 0), label));
+            x += label.width();
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let width = self.labels().iter().map(|label| label.width()).sum();
+        Vec2::new(width, 1)
+    }
+
+    fn take_focus(&mut self, source: Direction) -> bool {
+        // Layouts only send mouse clicks to the focused view, and give the
+        // focus to the clicked one with no direction.
+        source == Direction::none()
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Left) => EventResult::with_cb(|s| s.prev_screen()),
+            Event::Key(Key::Right) => {
+                EventResult::with_cb(|s| s.next_screen())
+            }
+            Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                position,
+                offset,
+            } => {
+                let screen = position
+                    .checked_sub(offset)
+                    .filter(|p| p.y == 0)
+                    .and_then(|p| self.screen_at(p.x));
+                match screen {
+                    Some(id) => {
+                        EventResult::with_cb(move |s| s.set_screen(id))
+                    }
+                    None => EventResult::Ignored,
+                }
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::buffer::PrintBuffer;
+    use theme;
+
+    #[test]
+    fn labels() {
+        let mut tabs = ScreenTabs::new();
+        tabs.set_screens(vec!["Files".to_string(), String::new()], 1);
+        assert_eq!(tabs.required_size(Vec2::zero()), Vec2::new(12, 1));
+
+        let buffer = PrintBuffer::new((15, 1));
+        tabs.draw(&buffer.printer(&theme::load_default()));
+        assert_eq!(buffer.text(0, 0, 15), " 1:Files  2    ");
+
+        assert_eq!(tabs.screen_at(0), Some(0));
+        assert_eq!(tabs.screen_at(8), Some(0));
+        assert_eq!(tabs.screen_at(9), Some(1));
+        assert_eq!(tabs.screen_at(12), None);
+    }
+}
@@ -3,8 +3,10 @@ use std::cmp::min;
 use direction::{Direction, Orientation};
 use event::{AnyCb, Event, EventResult, Key, MouseButton, MouseEvent};
 use rect::Rect;
-use theme::ColorStyle;
-use view::{ScrollStrategy, Selector, SizeCache, View};
+use view::{
+    ChildInfo, PageKeys, Paging, ScrollStrategy, Selector, SizeCache, View,
+    ViewState,
+};
 use {Printer, Vec2, With, XY};
 
 /// Wraps a view in a scrollable area.
@@ -31,12 +33,17 @@ pub struct ScrollView<V> {
     /// Should we show scrollbars?
     ///
     /// Even if this is true, no scrollbar will be printed if we don't need to
-    /// scroll.
+    /// scroll, unless `auto_hide_scrollbars` is false.
     ///
-    /// TODO: have an option to always show the scrollbar.
     /// TODO: have an option to show scrollbar on top/left.
     show_scrollbars: bool,
 
+    /// Should we hide scrollbars when the content fits?
+    auto_hide_scrollbars: bool,
+
+    /// Number of columns (or rows) taken by each scrollbar.
+    scrollbar_width: usize,
+
     /// How much padding should be between content and scrollbar?
     ///
     /// scrollbar_padding.x is the horizontal padding before the vertical scrollbar.
@@ -50,6 +57,9 @@ pub struct ScrollView<V> {
 
     /// Defines how to update the offset when the view size changes.
     scroll_strategy: ScrollStrategy,
+
+    /// Keys scrolling by half a page.
+    page_keys: PageKeys,
 }
 
 impl<V> ScrollView<V>
@@ -65,10 +75,13 @@ where
             last_size: Vec2::zero(),
             enabled: XY::new(false, true),
             show_scrollbars: true,
+            auto_hide_scrollbars: true,
+            scrollbar_width: 1,
             scrollbar_padding: Vec2::new(1, 0),
             thumb_grab: None,
             size_cache: None,
             scroll_strategy: ScrollStrategy::KeepRow,
+            page_keys: PageKeys::default(),
         }
     }
 
@@ -96,6 +109,20 @@ where
         self.with(|s| s.set_scroll_strategy(strategy))
     }
 
+    /// Sets the keys scrolling by half a page.
+    ///
+    /// `PageUp` and `PageDown` always scroll by a full page.
+    pub fn set_page_keys(&mut self, page_keys: PageKeys) {
+        self.page_keys = page_keys;
+    }
+
+    /// Sets the keys scrolling by half a page.
+    ///
+    /// Chainable variant.
+    pub fn page_keys(self, page_keys: PageKeys) -> Self {
+        self.with(|s| s.set_page_keys(page_keys))
+    }
+
     /// Control whether scroll bars are visibile.
     ///
     /// Defaults to `true`.
@@ -110,6 +137,39 @@ where
         self.with(|s| s.set_show_scrollbars(show_scrollbars))
     }
 
+    /// Controls whether scroll bars are hidden when the content fits.
+    ///
+    /// When `false`, scroll bars are always shown on the axes where this
+    /// view can scroll.
+    ///
+    /// Defaults to `true`.
+    pub fn set_auto_hide_scrollbars(&mut self, auto_hide: bool) {
+        self.auto_hide_scrollbars = auto_hide;
+        self.invalidate_cache();
+    }
+
+    /// Controls whether scroll bars are hidden when the content fits.
+    ///
+    /// Chainable variant.
+    pub fn auto_hide_scrollbars(self, auto_hide: bool) -> Self {
+        self.with(|s| s.set_auto_hide_scrollbars(auto_hide))
+    }
+
+    /// Sets the width of the scroll bars.
+    ///
+    /// Defaults to 1.
+    pub fn set_scrollbar_width(&mut self, width: usize) {
+        self.scrollbar_width = width;
+        self.invalidate_cache();
+    }
+
+    /// Sets the width of the scroll bars.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_width(self, width: usize) -> Self {
+        self.with(|s| s.set_scrollbar_width(width))
+    }
+
     /// Sets the scroll offset to the given value
     pub fn set_offset<S>(&mut self, offset: S)
     where
@@ -179,6 +239,28 @@ where
         self.set_offset((max_x, curr_y));
     }
 
+    /// Scrolls vertically by a page or half a page.
+    ///
+    /// Returns `false` if the view could not scroll in that direction.
+    fn page(&mut self, paging: Paging) -> bool {
+        if !self.enabled.y {
+            return false;
+        }
+
+        let height = self.available_size().y;
+        let max_y = self.inner_size.y.saturating_sub(height);
+        let amount = paging.amount(height);
+        let y = if paging.is_up() {
+            self.offset.y.saturating_sub(amount)
+        } else {
+            min(max_y, self.offset.y + amount)
+        };
+
+        let moved = y != self.offset.y;
+        self.offset.y = y;
+        moved
+    }
+
     /// Clears the cache.
     fn invalidate_cache(&mut self) {
         self.size_cache = None;
@@ -200,9 +282,26 @@ where
     ///
     /// The scrollbar_size().x will be the horizontal space taken by the vertical scrollbar.
     fn scrollbar_size(&self) -> Vec2 {
-        self.is_scrolling()
+        self.shown_scrollbars()
             .swap()
-            .select_or(self.scrollbar_padding + (1, 1), Vec2::zero())
+            .select_or(self.scrollbar_thickness(), Vec2::zero())
+    }
+
+    /// Returns the space taken by a scrollbar and its padding.
+    fn scrollbar_thickness(&self) -> Vec2 {
+        let width = self.scrollbar_width;
+        self.scrollbar_padding + (width, width)
+    }
+
+    /// Returns for each axis if a scrollbar is shown.
+    fn shown_scrollbars(&self) -> XY<bool> {
+        if !self.show_scrollbars {
+            XY::new(false, false)
+        } else if self.auto_hide_scrollbars {
+            self.is_scrolling()
+        } else {
+            self.enabled
+        }
     }
 
     /// Returns the size available for the child view.
@@ -225,7 +324,7 @@ where
         // This is the size taken by the scrollbars.
         let scrollbar_size = scrollable
             .swap()
-            .select_or(self.scrollbar_padding + (1, 1), Vec2::zero());
+            .select_or(self.scrollbar_thickness(), Vec2::zero());
 
         let available = constraint.saturating_sub(scrollbar_size);
 
@@ -260,18 +359,17 @@ where
     ///
     /// Returns `true` if the event was consumed.
     fn start_drag(&mut self, position: Vec2) -> bool {
-        // For each scrollbar, how far it is.
-        let scrollbar_pos = self.last_size.saturating_sub((1, 1));
+        let width = self.scrollbar_width;
         let lengths = self.scrollbar_thumb_lengths();
         let offsets = self.scrollbar_thumb_offsets(lengths);
         let available = self.available_size();
 
         // This is true for Y if we grabbed the vertical scrollbar
         // More specifically, we need both (for instance for the vertical bar):
-        // * To be in the right column: X == scrollbar_pos
+        // * To be in the right columns: the last `width` ones
         // * To be in the right range: Y < available
         let grabbed = position
-            .zip_map(scrollbar_pos, |p, s| p == s)
+            .zip_map(self.last_size, |p, s| p < s && p + width >= s)
             .swap()
             .and(position.zip_map(available, |p, a| p < a));
 
@@ -358,6 +456,14 @@ where
             );
         }
 
+        if self.show_scrollbars && !self.auto_hide_scrollbars {
+            // Scrollbars are always there.
+            let enabled = self.enabled;
+            let (inner_size, size, _) =
+                self.sizes_when_scrolling(constraint, enabled, strict);
+            return (inner_size, size);
+        }
+
         // Attempt 1: try without scrollbars
         let (inner_size, size, scrollable) = self.sizes_when_scrolling(
             constraint,
@@ -398,7 +504,9 @@ where
         let available = self.available_size();
         // The length should be (visible / total) * visible
 
-        (available * available / self.inner_size.or_max((1, 1))).or_max((1, 1))
+        (available * available / self.inner_size.or_max((1, 1)))
+            .or_min(available)
+            .or_max((1, 1))
     }
 
     fn scrollbar_thumb_offsets(&self, lengths: Vec2) -> Vec2 {
@@ -433,56 +541,71 @@ where
 {
     fn draw(&self, printer: &Printer) {
         // Draw scrollbar?
-        let scrolling = self.is_scrolling();
+        let scrolling = self.shown_scrollbars();
 
         let lengths = self.scrollbar_thumb_lengths();
         let offsets = self.scrollbar_thumb_offsets(lengths);
 
-        let line_c = XY::new("-", "|");
-
-        let color = if printer.focused {
-            ColorStyle::highlight()
-        } else {
-            ColorStyle::highlight_inactive()
-        };
-
+        let style = &printer.theme.scrollbar;
+        let width = self.scrollbar_width;
         let size = self.available_size();
 
         // Draw the scrollbars
-        XY::zip5(lengths, offsets, size, line_c, Orientation::pair()).run_if(
+        XY::zip4(lengths, offsets, size, Orientation::pair()).run_if(
             scrolling,
-            |(length, offset, size, c, orientation)| {
-                let start = printer
-                    .size
-                    .saturating_sub((1, 1))
-                    .with_axis(orientation, 0);
+            |(length, offset, size, orientation)| {
                 let offset = orientation.make_vec(offset, 0);
 
-                printer.print_line(orientation, start, size, c);
-
-                let thumb_c = if self
+                let thumb = if self
                     .thumb_grab
                     .map(|(o, _)| o == orientation)
                     .unwrap_or(false)
                 {
-                    " "
+                    &style.grabbed_thumb
                 } else {
-                    "▒"
+                    &style.thumb
                 };
-                printer.with_color(color, |printer| {
-                    printer.print_line(
-                        orientation,
-                        start + offset,
-                        length,
-                        thumb_c,
+
+                for i in 1..=width {
+                    let start = printer
+                        .size
+                        .saturating_sub((i, i))
+                        .with_axis(orientation, 0);
+
+                    printer.with_color(style.track_style(), |printer| {
+                        printer.print_line(
+                            orientation,
+                            start,
+                            size,
+                            style.track(orientation),
+                        );
+                    });
+
+                    printer.with_color(
+                        style.thumb_style(printer.focused),
+                        |printer| {
+                            printer.print_line(
+                                orientation,
+                                start + offset,
+                                length,
+                                thumb,
+                            );
+                        },
                     );
-                });
+                }
             },
         );
 
-        // Draw the X between the two scrollbars.
+        // Draw the corner between the two scrollbars.
         if scrolling.both() {
-            printer.print(printer.size.saturating_sub((1, 1)), "╳");
+            for x in 1..=width {
+                for y in 1..=width {
+                    printer.print(
+                        printer.size.saturating_sub((x, y)),
+                        &style.corner,
+                    );
+                }
+            }
         }
 
         // Draw content
@@ -530,7 +653,14 @@ where
                 // If it's a mouse scroll, try to scroll as well.
                 // Also allow Ctrl+arrow to move the view,
                 // but not the selection.
+                let paged = match self.page_keys.paging(&event) {
+                    Some(paging) => self.page(paging),
+                    None => false,
+                };
                 match event {
+                    _ if paged => {
+                        // Already scrolled.
+                    }
                     Event::Mouse {
                         event: MouseEvent::WheelUp,
                         ..
@@ -598,18 +728,6 @@ where
                     {
                         self.offset.y -= 1;
                     }
-                    Event::Key(Key::PageUp)
-                        if self.enabled.y && self.offset.y > 0 =>
-                    {
-                        self.offset.y = self.offset.y.saturating_sub(5);
-                    }
-                    Event::Key(Key::PageDown)
-                        if self.enabled.y
-                            && (self.offset.y + self.available_size().y
-                                < self.inner_size.y) =>
-                    {
-                        self.offset.y += 5;
-                    }
                     Event::Ctrl(Key::Down) | Event::Key(Key::Down)
                         if self.enabled.y
                             && (self.offset.y + self.available_size().y
@@ -692,7 +810,10 @@ where
         size
     }
 
-    fn call_on_any<'a>(&mut self, selector: &Selector, cb: AnyCb<'a>) {
+    fn call_on_any<'a>(&mut self, selector: &Selector, mut cb: AnyCb<'a>) {
+        if selector.selects(self) {
+            cb(self);
+        }
         self.inner.call_on_any(selector, cb)
     }
 
@@ -704,4 +825,78 @@ where
         let is_scrollable = self.is_scrolling().any();
         self.inner.take_focus(source) || is_scrollable
     }
+
+    fn take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        let is_scrollable = self.is_scrolling().any();
+        self.inner.take_focus_near(source, position + self.offset)
+            || is_scrollable
+    }
+
+    fn state(&self) -> Option<ViewState> {
+        Some(ViewState::Offset(self.offset))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Offset(offset) = *state {
+            // The next layout keeps it in the valid range.
+            self.offset = offset;
+        }
+    }
+
+    fn inspect_children(&self, _: Vec2, f: &mut FnMut(ChildInfo)) {
+        // Only report the visible part of the content.
+        f(ChildInfo {
+            view: &self.inner,
+            offset: Vec2::zero(),
+            size: self.available_size(),
+            focused: true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::TextView;
+
+    fn lines(n: usize) -> TextView {
+        TextView::new(vec!["line"; n].join("\n"))
+    }
+
+    #[test]
+    fn scrollbars() {
+        let mut view = ScrollView::new(lines(20)).scrollbar_width(2);
+        view.required_size(Vec2::new(10, 5));
+        view.layout(Vec2::new(10, 5));
+        // One column of padding, and two for the scrollbar.
+        assert_eq!(view.content_viewport().width(), 7);
+
+        // Grab the thumb from either column of the scrollbar.
+        let press = |x, y| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, y),
+            event: MouseEvent::Press(MouseButton::Left),
+        };
+        assert!(view.on_event(press(8, 4)).is_consumed());
+        assert!(view.content_viewport().top() > 0);
+        view.on_event(Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(8, 0),
+            event: MouseEvent::Hold(MouseButton::Left),
+        });
+        assert_eq!(view.content_viewport().top(), 0);
+
+        // Short content only gets a scrollbar if asked to.
+        let mut view = ScrollView::new(lines(2));
+        view.required_size(Vec2::new(10, 5));
+        view.layout(Vec2::new(10, 5));
+        assert_eq!(view.content_viewport().width(), 10);
+
+        view.set_auto_hide_scrollbars(false);
+        view.required_size(Vec2::new(10, 5));
+        view.layout(Vec2::new(10, 5));
+        assert_eq!(view.content_viewport().width(), 8);
+    }
 }
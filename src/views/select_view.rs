@@ -5,21 +5,39 @@ use menu::MenuTree;
 use rect::Rect;
 use std::borrow::Borrow;
 use std::cell::Cell;
-use std::cmp::min;
+use std::cmp::{min, Ordering};
 use std::rc::Rc;
-use theme::ColorStyle;
+use std::time::Duration;
+use theme::{ColorStyle, Effect, Style};
+use utils::clock;
 use utils::markup::StyledString;
 use vec::Vec2;
-use view::{Position, View};
+use view::{PageKeys, Paging, Position, View, ViewState};
 use views::MenuPopup;
 use Cursive;
 use Printer;
 use With;
 
+/// Callback for moved items, given their old and new positions.
+type OnReorder = Fn(&mut Cursive, usize, usize);
+
+/// Maximum delay between two letters of a type-ahead prefix, in ms.
+const TYPEAHEAD_DELAY: u64 = 1000;
+
 /// View to select an item among a list.
 ///
 /// It contains a list of values of type T, with associated labels.
 ///
+/// Each item can also be decorated with a prefix, like an icon, and a
+/// style, like a color for its status. See [`set_item_prefix`] and
+/// [`set_item_style`].
+///
+/// Items can be reordered by the user, see [`set_reorderable`].
+///
+/// [`set_reorderable`]: #method.set_reorderable
+/// [`set_item_prefix`]: #method.set_item_prefix
+/// [`set_item_style`]: #method.set_item_style
+///
 /// # Examples
 ///
 /// ```rust
@@ -69,6 +87,23 @@ pub struct SelectView<T = String> {
     // with this character.
     autojump: bool,
 
+    // If `true`, a double click submits an item, instead of a single one.
+    submit_on_double_click: bool,
+
+    // If `true`, the user can move items around.
+    reorderable: bool,
+
+    // Original position of the item being moved, if any.
+    // The moved item is always the focused one.
+    grabbed: Option<usize>,
+
+    // Called with the old and new positions of a moved item.
+    on_reorder: Option<Rc<OnReorder>>,
+
+    // Characters typed recently, to jump to an item with this prefix.
+    typeahead: String,
+    last_typed: Option<Duration>,
+
     align: Align,
 
     // `true` if we show a one-line view, with popup on selection.
@@ -78,6 +113,11 @@ pub struct SelectView<T = String> {
     // We "cache" it during the draw, so we need interior mutability.
     last_offset: Cell<Vec2>,
     last_size: Vec2,
+
+    // Number of rows visible during the last draw, to move by pages.
+    last_height: Cell<usize>,
+
+    page_keys: PageKeys,
 }
 
 impl<T: 'static> Default for SelectView<T> {
@@ -98,15 +138,41 @@ impl<T: 'static> SelectView<T> {
             align: Align::top_left(),
             popup: false,
             autojump: false,
+            submit_on_double_click: false,
+            reorderable: false,
+            grabbed: None,
+            on_reorder: None,
+            typeahead: String::new(),
+            last_typed: None,
             last_offset: Cell::new(Vec2::zero()),
             last_size: Vec2::zero(),
+            last_height: Cell::new(0),
+            page_keys: PageKeys::default(),
         }
     }
 
+    /// Sets the keys moving the selection by half a page.
+    ///
+    /// `PageUp` and `PageDown` always move by a full page.
+    pub fn set_page_keys(&mut self, page_keys: PageKeys) {
+        self.page_keys = page_keys;
+    }
+
+    /// Sets the keys moving the selection by half a page.
+    ///
+    /// Chainable variant.
+    pub fn page_keys(self, page_keys: PageKeys) -> Self {
+        self.with(|s| s.set_page_keys(page_keys))
+    }
+
     /// Sets the "auto-jump" property for this view.
     ///
     /// If enabled, when a key is pressed, the selection will jump to the next
     /// item beginning with the pressed letter.
+    ///
+    /// Letters typed in quick succession form a prefix: typing `"ba"` jumps
+    /// to `"banana"`, even after `"blueberry"`. Repeating the same letter
+    /// cycles through the items starting with it.
     pub fn set_autojump(&mut self, autojump: bool) {
         self.autojump = autojump;
     }
@@ -121,6 +187,61 @@ impl<T: 'static> SelectView<T> {
         self.with(|s| s.set_autojump(true))
     }
 
+    /// Sets whether items are submitted with a double click.
+    ///
+    /// By default, clicking on the selected item submits it. With this
+    /// option, a click only selects items, and a double click submits them.
+    pub fn set_submit_on_double_click(&mut self, double_click: bool) {
+        self.submit_on_double_click = double_click;
+    }
+
+    /// Makes items submitted with a double click, instead of a single one.
+    ///
+    /// Chainable variant.
+    pub fn submit_on_double_click(self) -> Self {
+        self.with(|s| s.set_submit_on_double_click(true))
+    }
+
+    /// Sets whether the user can move items around.
+    ///
+    /// When enabled, `Space` grabs the selected item. The arrow keys, `Home`
+    /// and `End` then move it, and `Space` or `Enter` drops it. `Esc` puts
+    /// it back where it was.
+    ///
+    /// Items can also be dragged with the mouse.
+    pub fn set_reorderable(&mut self, reorderable: bool) {
+        self.reorderable = reorderable;
+    }
+
+    /// Lets the user move items around.
+    ///
+    /// Chainable variant.
+    pub fn reorderable(self) -> Self {
+        self.with(|s| s.set_reorderable(true))
+    }
+
+    /// Sets a callback to be used when the user moves an item.
+    ///
+    /// It is given the old and new positions of the item, when it is
+    /// dropped. This also makes the view reorderable.
+    pub fn set_on_reorder<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive, usize, usize) + 'static,
+    {
+        self.reorderable = true;
+        self.on_reorder = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to be used when the user moves an item.
+    ///
+    /// Chainable variant.
+    pub fn on_reorder<F>(self, cb: F) -> Self
+    where
+        F: Fn(&mut Cursive, usize, usize) + 'static,
+    {
+        self.with(|s| s.set_on_reorder(cb))
+    }
+
     /// Turns `self` into a popup select view.
     ///
     /// Chainable variant.
@@ -250,6 +371,7 @@ impl<T: 'static> SelectView<T> {
     pub fn clear(&mut self) {
         self.items.clear();
         self.focus.set(0);
+        self.grabbed = None;
     }
 
     /// Adds a item to the list, with given label and value.
@@ -287,6 +409,33 @@ impl<T: 'static> SelectView<T> {
         }
     }
 
+    /// Sets the prefix shown before the label of the item at position `i`.
+    ///
+    /// This can be used to show an icon or a marker. It is not shown in
+    /// popup mode.
+    ///
+    /// Does nothing if `i >= self.len()`.
+    pub fn set_item_prefix<S>(&mut self, i: usize, prefix: S)
+    where
+        S: Into<StyledString>,
+    {
+        if let Some(item) = self.items.get_mut(i) {
+            item.prefix = prefix.into();
+        }
+    }
+
+    /// Sets the style of the item at position `i`.
+    ///
+    /// It applies to the label, except when the item is selected. It is not
+    /// used in popup mode.
+    ///
+    /// Does nothing if `i >= self.len()`.
+    pub fn set_item_style<S: Into<Style>>(&mut self, i: usize, style: S) {
+        if let Some(item) = self.items.get_mut(i) {
+            item.style = Some(style.into());
+        }
+    }
+
     /// Iterate on the items in this view.
     ///
     /// Returns an iterator with each item and their labels.
@@ -303,6 +452,7 @@ impl<T: 'static> SelectView<T> {
     /// You should run this callback with a `&mut Cursive`.
     pub fn remove_item(&mut self, id: usize) -> Callback {
         self.items.remove(id);
+        self.grabbed = None;
         let focus = self.focus();
         if focus >= id && focus > 0 {
             self.focus.set(focus - 1);
@@ -313,11 +463,83 @@ impl<T: 'static> SelectView<T> {
 
     /// Inserts an item at position `index`, shifting all elements after it to
     /// the right.
+    ///
+    /// The selected item stays the same.
     pub fn insert_item<S>(&mut self, index: usize, label: S, value: T)
     where
         S: Into<StyledString>,
     {
         self.items.insert(index, Item::new(label.into(), value));
+        let focus = self.focus();
+        if index <= focus && self.items.len() > 1 {
+            self.focus.set(focus + 1);
+        }
+    }
+
+    /// Moves the item at position `from` to position `to`.
+    ///
+    /// The selected item stays the same.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+
+        let focus = self.focus();
+        if focus == from {
+            self.focus.set(to);
+        } else if from < focus && focus <= to {
+            self.focus.set(focus - 1);
+        } else if to <= focus && focus < from {
+            self.focus.set(focus + 1);
+        }
+    }
+
+    /// Sorts the items by label.
+    ///
+    /// The selected item stays the same.
+    pub fn sort_by_label(&mut self) {
+        self.sort_items_by(|a, b| a.label.source().cmp(b.label.source()));
+    }
+
+    /// Sorts the items by value, using the given comparison function.
+    ///
+    /// The selected item stays the same.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.sort_items_by(|a, b| compare(&a.value, &b.value));
+    }
+
+    /// Sorts the items by value, using the given key extraction function.
+    ///
+    /// The selected item stays the same.
+    pub fn sort_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_items_by(|a, b| key(&a.value).cmp(&key(&b.value)));
+    }
+
+    fn sort_items_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&Item<T>, &Item<T>) -> Ordering,
+    {
+        let selected = self.selection();
+        self.items.sort_by(compare);
+
+        if let Some(selected) = selected {
+            let focus = self
+                .items
+                .iter()
+                .position(|item| Rc::ptr_eq(&item.value, &selected))
+                .unwrap_or(0);
+            self.focus.set(focus);
+        }
     }
 
     /// Chainable variant of add_item
@@ -336,6 +558,36 @@ impl<T: 'static> SelectView<T> {
         }
     }
 
+    /// Replaces all items with the ones from an iterator.
+    ///
+    /// Unlike `clear` followed by `add_all`, this keeps the selection on
+    /// the item with the same label, or at the same position if there is
+    /// no such item. This is useful for views updated while in use.
+    pub fn replace_all<S, I>(&mut self, iter: I)
+    where
+        S: Into<StyledString>,
+        I: IntoIterator<Item = (S, T)>,
+    {
+        let focus = self.focus();
+        let selected = self
+            .items
+            .get(focus)
+            .map(|item| item.label.source().to_string());
+
+        self.items.clear();
+        self.grabbed = None;
+        self.add_all(iter);
+
+        let focus = selected
+            .and_then(|label| {
+                self.items
+                    .iter()
+                    .position(|item| item.label.source() == label)
+            })
+            .unwrap_or(focus);
+        self.focus.set(min(focus, self.items.len().saturating_sub(1)));
+    }
+
     /// Adds all items from from an iterator.
     ///
     /// Chainable variant.
@@ -348,10 +600,21 @@ impl<T: 'static> SelectView<T> {
     }
 
     fn draw_item(&self, printer: &Printer, i: usize) {
-        let l = self.items[i].label.width();
+        let item = &self.items[i];
+        let l = item.width();
         let x = self.align.h.get_offset(l, printer.size.x);
         printer.print_hline((0, 0), x, " ");
-        printer.print_styled((x, 0), (&self.items[i].label).into());
+        printer.print_styled((x, 0), (&item.prefix).into());
+
+        let label_x = x + item.prefix.width();
+        match item.style {
+            Some(ref style) if i != self.focus() => {
+                printer.with_style(style.clone(), |printer| {
+                    printer.print_styled((label_x, 0), (&item.label).into());
+                });
+            }
+            _ => printer.print_styled((label_x, 0), (&item.label).into()),
+        }
         if l < printer.size.x {
             assert!((l + x) <= printer.size.x);
             printer.print_hline((x + l, 0), printer.size.x - (l + x), " ");
@@ -453,6 +716,21 @@ impl<T: 'static> SelectView<T> {
         self.focus.set(focus);
     }
 
+    /// Moves the focus by a page or half a page.
+    fn page(&mut self, paging: Paging) {
+        // Before the first draw, assume the whole list is visible.
+        let height = match self.last_height.get() {
+            0 => self.last_size.y,
+            height => height,
+        };
+        let amount = paging.amount(height);
+        if paging.is_up() {
+            self.focus_up(amount);
+        } else {
+            self.focus_down(amount);
+        }
+    }
+
     fn submit(&mut self) -> EventResult {
         let cb = self.on_submit.clone().unwrap();
         // We return a Callback Rc<|s| cb(s, &*v)>
@@ -463,19 +741,35 @@ impl<T: 'static> SelectView<T> {
     }
 
     fn on_char_event(&mut self, c: char) -> EventResult {
+        // Without a clock, each character starts a new search.
+        let now = clock::now();
+        let delay = Duration::from_millis(TYPEAHEAD_DELAY);
+        if !clock::within(self.last_typed, now, delay) {
+            self.typeahead.clear();
+        }
+        self.typeahead.push(c);
+        self.last_typed = now;
+
+        // Repeating a letter cycles through the items starting with it.
+        // Otherwise, the current item can still match a longer prefix.
+        let (prefix, start) = if self.typeahead.chars().all(|t| t == c) {
+            (c.to_string(), self.focus() + 1)
+        } else {
+            (self.typeahead.clone(), self.focus())
+        };
+
         let i = {
-            // * Starting from the current focus, find the first item that
-            //   match the char.
+            // * Starting from `start`, find the first item that match the
+            //   prefix.
             // * Cycle back to the beginning of the list when we reach the end.
             // * This is achieved by chaining twice the iterator.
             let iter = self.iter().chain(self.iter());
 
             // We'll do a lowercase check.
-            let lower_c: Vec<char> = c.to_lowercase().collect();
-            let lower_c: &[char] = &lower_c;
+            let prefix = prefix.to_lowercase();
 
-            if let Some((i, _)) = iter.enumerate().skip(self.focus() + 1).find(
-                |&(_, (label, _))| label.to_lowercase().starts_with(lower_c),
+            if let Some((i, _)) = iter.enumerate().skip(start).find(
+                |&(_, (label, _))| label.to_lowercase().starts_with(&prefix),
             ) {
                 i % self.len()
             } else {
@@ -483,20 +777,83 @@ impl<T: 'static> SelectView<T> {
             }
         };
 
-        self.focus.set(i);
         // Apply modulo in case we have a hit from the chained iterator
         let cb = self.set_selection(i);
         EventResult::Consumed(Some(cb))
     }
 
+    /// Moves the grabbed item to position `to`, or to the end.
+    fn move_grabbed(&mut self, to: usize) {
+        let from = self.focus();
+        let to = min(to, self.items.len() - 1);
+        self.move_item(from, to);
+    }
+
+    /// Drops the grabbed item where it is.
+    fn drop_grabbed(&mut self) -> EventResult {
+        let from = match self.grabbed.take() {
+            Some(from) => from,
+            None => return EventResult::Ignored,
+        };
+        let to = self.focus();
+        EventResult::Consumed(match self.on_reorder {
+            Some(ref cb) if from != to => {
+                let cb = Rc::clone(cb);
+                Some(Callback::from_fn(move |s| cb(s, from, to)))
+            }
+            _ => None,
+        })
+    }
+
+    fn on_event_grabbed(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(Key::Up) => {
+                let to = self.focus().saturating_sub(1);
+                self.move_grabbed(to);
+            }
+            Event::Key(Key::Down) => {
+                let to = self.focus() + 1;
+                self.move_grabbed(to);
+            }
+            Event::Key(Key::Home) => self.move_grabbed(0),
+            Event::Key(Key::End) => self.move_grabbed(usize::MAX),
+            Event::Mouse {
+                event: MouseEvent::Hold(MouseButton::Left),
+                position,
+                offset,
+            } => self.move_grabbed(position.y.saturating_sub(offset.y)),
+            Event::Char(' ')
+            | Event::Key(Key::Enter)
+            | Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                ..
+            } => return self.drop_grabbed(),
+            Event::Key(Key::Esc) => {
+                if let Some(from) = self.grabbed.take() {
+                    self.move_grabbed(from);
+                }
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
     fn on_event_regular(&mut self, event: Event) -> EventResult {
+        if self.grabbed.is_some() {
+            return self.on_event_grabbed(event);
+        }
+
+        if let Some(paging) = self.page_keys.paging(&event) {
+            self.page(paging);
+            return EventResult::Consumed(self.make_select_cb());
+        }
+
         match event {
             Event::Key(Key::Up) if self.focus() > 0 => self.focus_up(1),
             Event::Key(Key::Down) if self.focus() + 1 < self.items.len() => {
                 self.focus_down(1)
             }
-            Event::Key(Key::PageUp) => self.focus_up(10),
-            Event::Key(Key::PageDown) => self.focus_down(10),
             Event::Key(Key::Home) => self.focus.set(0),
             Event::Key(Key::End) => {
                 self.focus.set(self.items.len().saturating_sub(1))
@@ -519,18 +876,40 @@ impl<T: 'static> SelectView<T> {
                 position,
                 offset,
             } if self.on_submit.is_some()
-                && position
-                    .checked_sub(offset)
-                    .map(|position| {
-                        position < self.last_size && position.y == self.focus()
-                    })
-                    .unwrap_or(false) =>
+                && !self.submit_on_double_click
+                && self.is_focus_at(position, offset) =>
             {
                 return self.submit();
             }
+            Event::Mouse {
+                event: MouseEvent::MultiPress(MouseButton::Left, 2),
+                position,
+                offset,
+            } if self.on_submit.is_some()
+                && self.submit_on_double_click
+                && self.is_focus_at(position, offset) =>
+            {
+                return self.submit();
+            }
+            Event::Mouse {
+                event: MouseEvent::Hold(MouseButton::Left),
+                position,
+                offset,
+            } if self.reorderable
+                && !self.items.is_empty()
+                && position.y.saturating_sub(offset.y) != self.focus() =>
+            {
+                // Dragging an item grabs it.
+                self.grabbed = Some(self.focus());
+                return self.on_event_grabbed(event);
+            }
             Event::Key(Key::Enter) if self.on_submit.is_some() => {
                 return self.submit();
             }
+            Event::Char(' ') if self.reorderable && !self.items.is_empty() => {
+                self.grabbed = Some(self.focus());
+                return EventResult::Consumed(None);
+            }
             Event::Char(c) if self.autojump => return self.on_char_event(c),
             _ => return EventResult::Ignored,
         }
@@ -538,6 +917,13 @@ impl<T: 'static> SelectView<T> {
         EventResult::Consumed(self.make_select_cb())
     }
 
+    /// Returns `true` if the mouse `position` is on the focused item.
+    fn is_focus_at(&self, position: Vec2, offset: Vec2) -> bool {
+        position.checked_sub(offset).is_some_and(|position| {
+            position < self.last_size && position.y == self.focus()
+        })
+    }
+
     /// Returns a callback from selection change.
     fn make_select_cb(&self) -> Option<Callback> {
         self.on_select.clone().and_then(|cb| {
@@ -617,6 +1003,15 @@ impl<T: 'static> SelectView<T> {
     }
 }
 
+impl<T: 'static + Ord> SelectView<T> {
+    /// Sorts the items by value.
+    ///
+    /// The selected item stays the same.
+    pub fn sort(&mut self) {
+        self.sort_by(Ord::cmp);
+    }
+}
+
 impl SelectView<String> {
     /// Convenient method to use the label as value.
     pub fn add_item_str<S: Into<String>>(&mut self, label: S) {
@@ -672,6 +1067,8 @@ impl SelectView<String> {
 impl<T: 'static> View for SelectView<T> {
     fn draw(&self, printer: &Printer) {
         self.last_offset.set(printer.offset);
+        self.last_height
+            .set(min(printer.output_size.y, printer.size.y));
 
         if self.popup {
             // Popup-select only draw the active element.
@@ -712,7 +1109,12 @@ impl<T: 'static> View for SelectView<T> {
                 printer.offset((0, i)).with_selection(
                     i == self.focus(),
                     |printer| {
-                        if i != self.focus()
+                        if i == self.focus() && self.grabbed.is_some() {
+                            // Show the item being moved.
+                            printer.with_effect(Effect::Underline, |printer| {
+                                self.draw_item(printer, i)
+                            });
+                        } else if i != self.focus()
                             && !(self.enabled && printer.enabled)
                         {
                             printer.with_color(
@@ -735,7 +1137,13 @@ impl<T: 'static> View for SelectView<T> {
         let w = self
             .items
             .iter()
-            .map(|item| item.label.width())
+            .map(|item| {
+                if self.popup {
+                    item.label.width()
+                } else {
+                    item.width()
+                }
+            })
             .max()
             .unwrap_or(1);
         if self.popup {
@@ -763,6 +1171,18 @@ impl<T: 'static> View for SelectView<T> {
         self.last_size = size;
     }
 
+    fn state(&self) -> Option<ViewState> {
+        self.selected_id().map(ViewState::Selected)
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Selected(i) = *state {
+            if i < self.len() {
+                self.focus.set(i);
+            }
+        }
+    }
+
     fn important_area(&self, size: Vec2) -> Rect {
         self.selected_id()
             .map(|i| Rect::from_size((0, i), (size.x, 1)))
@@ -773,12 +1193,210 @@ impl<T: 'static> View for SelectView<T> {
 // We wrap each value in a `Rc` and add a label
 struct Item<T> {
     label: StyledString,
+    prefix: StyledString,
+    style: Option<Style>,
     value: Rc<T>,
 }
 
 impl<T> Item<T> {
     fn new(label: StyledString, value: T) -> Self {
         let value = Rc::new(value);
-        Item { label, value }
+        Item {
+            label,
+            prefix: StyledString::new(),
+            style: None,
+            value,
+        }
+    }
+
+    /// Width of the prefix and the label.
+    fn width(&self) -> usize {
+        self.prefix.width() + self.label.width()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reorder() {
+        let moves = Rc::new(Cell::new((0, 0)));
+        let last_move = Rc::clone(&moves);
+        let mut select = SelectView::new()
+            .item("a", 1)
+            .item("b", 2)
+            .item("c", 3)
+            .on_reorder(move |_, from, to| last_move.set((from, to)));
+        select.layout(Vec2::new(5, 3));
+
+        // Move `a` to the end with the keyboard.
+        select.on_event(Event::Char(' '));
+        select.on_event(Event::Key(Key::Down));
+        select.on_event(Event::Key(Key::Down));
+        select.on_event(Event::Key(Key::Down));
+        let labels: Vec<_> = select.iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, vec!["b", "c", "a"]);
+        assert_eq!(select.selected_id(), Some(2));
+        assert!(select.on_event(Event::Char(' ')).has_callback());
+
+        // `Esc` cancels the move.
+        select.on_event(Event::Char(' '));
+        select.on_event(Event::Key(Key::Home));
+        select.on_event(Event::Key(Key::Esc));
+        assert_eq!(select.get_item(2), Some(("a", &1)));
+
+        // Drag `b` to the middle with the mouse.
+        let mouse = |event, y| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(0, y),
+            event,
+        };
+        let left = MouseButton::Left;
+        select.on_event(mouse(MouseEvent::Press(left), 0));
+        select.on_event(mouse(MouseEvent::Hold(left), 1));
+        let drop = select.on_event(mouse(MouseEvent::Release(left), 1));
+        assert!(drop.has_callback());
+        let labels: Vec<_> = select.iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn double_click() {
+        let mut select = SelectView::new()
+            .item("a", 1)
+            .item("b", 2)
+            .on_submit(|_, _| ())
+            .submit_on_double_click();
+        select.layout(Vec2::new(5, 2));
+
+        let click = |event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(0, 1),
+            event,
+        };
+        let left = MouseButton::Left;
+
+        select.on_event(click(MouseEvent::Press(left)));
+        assert_eq!(select.selected_id(), Some(1));
+        let release = select.on_event(click(MouseEvent::Release(left)));
+        assert!(!release.has_callback());
+
+        select.on_event(click(MouseEvent::Press(left)));
+        let submit = select.on_event(click(MouseEvent::MultiPress(left, 2)));
+        assert!(submit.has_callback());
+    }
+
+    #[test]
+    fn keep_selection() {
+        let mut select = SelectView::new()
+            .item("a", 1)
+            .item("b", 2)
+            .item("c", 3);
+        select.set_selection(1);
+
+        select.insert_item(0, "z", 0);
+        assert_eq!(select.selected_id(), Some(2));
+        assert_eq!(select.selection(), Some(Rc::new(2)));
+
+        // The selected label moved.
+        select.replace_all(vec![("b", 2), ("c", 3)]);
+        assert_eq!(select.selected_id(), Some(0));
+
+        // The selected label is gone: keep the position.
+        select.set_selection(1);
+        select.replace_all(vec![("x", 4), ("y", 5), ("z", 6)]);
+        assert_eq!(select.selected_id(), Some(1));
+
+        select.replace_all(vec![("x", 4)]);
+        assert_eq!(select.selected_id(), Some(0));
+    }
+
+    #[test]
+    fn sorting() {
+        let mut select = SelectView::new()
+            .item("b", 3)
+            .item("c", 1)
+            .item("a", 2);
+        select.set_selection(1);
+
+        select.sort_by_label();
+        assert_eq!(select.get_item(0), Some(("a", &2)));
+        assert_eq!(select.selection(), Some(Rc::new(1)));
+        assert_eq!(select.selected_id(), Some(2));
+
+        select.sort();
+        assert_eq!(select.selected_id(), Some(0));
+
+        select.sort_by_key(|&v| ::std::cmp::Reverse(v));
+        assert_eq!(select.get_item(0), Some(("b", &3)));
+        assert_eq!(select.selected_id(), Some(2));
+
+        select.sort_by(|a, b| a.cmp(b));
+        assert_eq!(select.selection(), Some(Rc::new(1)));
+        assert_eq!(select.selected_id(), Some(0));
+    }
+
+    #[test]
+    fn typeahead() {
+        let mut select = SelectView::new()
+            .with_all_str(vec!["apple", "blueberry", "banana", "cherry"])
+            .autojump();
+
+        select.on_event(Event::Char('b'));
+        assert_eq!(select.selected_id(), Some(1));
+        select.on_event(Event::Char('a'));
+        assert_eq!(select.selected_id(), Some(2));
+
+        // Repeated letters cycle.
+        select.last_typed = None;
+        select.on_event(Event::Char('b'));
+        select.on_event(Event::Char('b'));
+        assert_eq!(select.selected_id(), Some(2));
+
+        // No match: the event is ignored.
+        assert!(!select.on_event(Event::Char('z')).is_consumed());
+        assert_eq!(select.selected_id(), Some(2));
+
+        select.last_typed = None;
+        select.on_event(Event::Char('C'));
+        assert_eq!(select.selected_id(), Some(3));
+    }
+
+    #[test]
+    fn decorations() {
+        use theme::{BaseColor, Color};
+
+        let mut select = SelectView::new().item("running", 1).item("idle", 2);
+        select.set_item_prefix(0, "▶ ");
+        select.set_item_style(1, Color::Dark(BaseColor::Red));
+        select.set_item_prefix(5, "ignored");
+
+        assert_eq!(select.required_size(Vec2::zero()), Vec2::new(9, 2));
+        assert_eq!(select.get_item(0), Some(("running", &1)));
+
+        select.set_popup(true);
+        assert_eq!(select.required_size(Vec2::zero()), Vec2::new(9, 1));
+    }
+
+    #[test]
+    fn paging() {
+        let mut select = SelectView::new()
+            .with_all((0..20).map(|i| (i.to_string(), i)));
+        select.layout(Vec2::new(5, 20));
+        // Only 6 rows were visible, for instance inside a `ScrollView`.
+        select.last_height.set(6);
+
+        select.on_event(Event::Key(Key::PageDown));
+        assert_eq!(select.selected_id(), Some(6));
+        select.on_event(Event::CtrlChar('d'));
+        assert_eq!(select.selected_id(), Some(9));
+        select.on_event(Event::CtrlChar('u'));
+        assert_eq!(select.selected_id(), Some(6));
+        select.on_event(Event::Key(Key::PageUp));
+        assert_eq!(select.selected_id(), Some(0));
+
+        select.set_page_keys(PageKeys::none());
+        assert!(!select.on_event(Event::CtrlChar('d')).is_consumed());
     }
 }
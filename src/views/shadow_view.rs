@@ -1,7 +1,8 @@
 use event::{Event, EventResult};
+use rect::Rect;
 use theme::ColorStyle;
 use vec::Vec2;
-use view::{View, ViewWrapper};
+use view::{ChildInfo, View, ViewWrapper};
 use Printer;
 
 /// Wrapper view that adds a shadow.
@@ -28,7 +29,7 @@ impl<T: View> ShadowView<T> {
         self.top_left_padding() + (1, 1)
     }
 
-    fn top_left_padding(&self) -> Vec2 {
+    pub(crate) fn top_left_padding(&self) -> Vec2 {
         Vec2::new(self.left_padding as usize, self.top_padding as usize)
     }
 
@@ -100,4 +101,18 @@ impl<T: View> ViewWrapper for ShadowView<T> {
         let printer = printer.shrinked((1, 1));
         self.view.draw(&printer);
     }
+
+    fn wrap_important_area(&self, size: Vec2) -> Rect {
+        let inner = size.saturating_sub(self.padding());
+        self.view.important_area(inner) + self.top_left_padding()
+    }
+
+    fn wrap_inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        f(ChildInfo {
+            view: &self.view,
+            offset: self.top_left_padding(),
+            size: size.saturating_sub(self.padding()),
+            focused: true,
+        });
+    }
 }
@@ -0,0 +1,255 @@
+use std::collections::VecDeque;
+use theme::{ColorStyle, ColorType, PaletteColor};
+use vec::Vec2;
+use view::View;
+use {Printer, With};
+
+/// Compact line chart of a numeric series.
+///
+/// Each value is drawn as a vertical bar of block characters, with one
+/// column per value. When the view is too narrow, only the most recent
+/// values are shown.
+///
+/// Values are scaled between a minimum and maximum, which default to the
+/// extreme values of the series.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::SparklineView;
+/// let mut sparkline = SparklineView::new().max_len(60).height(2);
+/// sparkline.push(0.4);
+/// sparkline.push(0.7);
+/// ```
+pub struct SparklineView {
+    data: VecDeque<f64>,
+
+    /// Maximum number of values kept.
+    max_len: Option<usize>,
+
+    min: Option<f64>,
+    max: Option<f64>,
+
+    height: usize,
+    color: ColorType,
+}
+
+new_default!(SparklineView);
+
+impl SparklineView {
+    /// Creates a new, empty sparkline.
+    pub fn new() -> Self {
+        SparklineView {
+            data: VecDeque::new(),
+            max_len: None,
+            min: None,
+            max: None,
+            height: 1,
+            color: ColorType::Palette(PaletteColor::Highlight),
+        }
+    }
+
+    /// Replaces the series shown.
+    pub fn set_data<I: IntoIterator<Item = f64>>(&mut self, data: I) {
+        self.data = data.into_iter().collect();
+        self.truncate();
+    }
+
+    /// Replaces the series shown.
+    ///
+    /// Chainable variant.
+    pub fn data<I: IntoIterator<Item = f64>>(self, data: I) -> Self {
+        self.with(|s| s.set_data(data))
+    }
+
+    /// Returns the values currently kept.
+    pub fn get_data(&self) -> &VecDeque<f64> {
+        &self.data
+    }
+
+    /// Appends a value to the series.
+    ///
+    /// If a maximum length was set, the oldest values are dropped.
+    pub fn push(&mut self, value: f64) {
+        self.data.push_back(value);
+        self.truncate();
+    }
+
+    /// Sets the maximum number of values kept.
+    ///
+    /// Useful when regularly pushing new values. Defaults to unlimited.
+    pub fn set_max_len<N: Into<Option<usize>>>(&mut self, max_len: N) {
+        self.max_len = max_len.into();
+        self.truncate();
+    }
+
+    /// Sets the maximum number of values kept.
+    ///
+    /// Chainable variant.
+    pub fn max_len<N: Into<Option<usize>>>(self, max_len: N) -> Self {
+        self.with(|s| s.set_max_len(max_len))
+    }
+
+    /// Sets the value drawn as the lowest bar.
+    ///
+    /// `None` (the default) uses the smallest value of the series.
+    pub fn set_min<V: Into<Option<f64>>>(&mut self, min: V) {
+        self.min = min.into();
+    }
+
+    /// Sets the value drawn as the lowest bar.
+    ///
+    /// Chainable variant.
+    pub fn min<V: Into<Option<f64>>>(self, min: V) -> Self {
+        self.with(|s| s.set_min(min))
+    }
+
+    /// Sets the value drawn as the highest bar.
+    ///
+    /// `None` (the default) uses the largest value of the series.
+    pub fn set_max<V: Into<Option<f64>>>(&mut self, max: V) {
+        self.max = max.into();
+    }
+
+    /// Sets the value drawn as the highest bar.
+    ///
+    /// Chainable variant.
+    pub fn max<V: Into<Option<f64>>>(self, max: V) -> Self {
+        self.with(|s| s.set_max(max))
+    }
+
+    /// Sets the number of rows used by the chart.
+    ///
+    /// Defaults to 1.
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height.max(1);
+    }
+
+    /// Sets the number of rows used by the chart.
+    ///
+    /// Chainable variant.
+    pub fn height(self, height: usize) -> Self {
+        self.with(|s| s.set_height(height))
+    }
+
+    /// Sets the color of the bars.
+    ///
+    /// The default color is `PaletteColor::Highlight`.
+    pub fn set_color<C: Into<ColorType>>(&mut self, color: C) {
+        self.color = color.into();
+    }
+
+    /// Sets the color of the bars.
+    ///
+    /// Chainable variant.
+    pub fn with_color<C: Into<ColorType>>(self, color: C) -> Self {
+        self.with(|s| s.set_color(color))
+    }
+
+    fn truncate(&mut self) {
+        if let Some(max_len) = self.max_len {
+            while self.data.len() > max_len {
+                self.data.pop_front();
+            }
+        }
+    }
+
+    /// Returns the range used for scaling.
+    fn range(&self) -> (f64, f64) {
+        let values = self.data.iter().cloned().filter(|v| !v.is_nan());
+        let min = self
+            .min
+            .unwrap_or_else(|| values.clone().fold(f64::INFINITY, f64::min));
+        let max = self
+            .max
+            .unwrap_or_else(|| values.fold(f64::NEG_INFINITY, f64::max));
+        (min, max)
+    }
+}
+
+/// Returns the height of a bar, in eighths of a cell.
+///
+/// Values in range are drawn with at least one eighth, so the minimum
+/// stays visible.
+fn bar_level(value: f64, (min, max): (f64, f64), height: usize) -> usize {
+    let levels = height * 8;
+    if levels == 0 || value.is_nan() || value < min {
+        return 0;
+    }
+    if max <= min {
+        return 1;
+    }
+
+    let ratio = ((value - min) / (max - min)).min(1.0);
+    1 + (ratio * (levels - 1) as f64).round() as usize
+}
+
+/// Returns the block character for a cell filled up to `eighths`.
+fn vertical_block(eighths: usize) -> &'static str {
+    match eighths {
+        0 => " ",
+        1 => "▁",
+        2 => "▂",
+        3 => "▃",
+        4 => "▄",
+        5 => "▅",
+        6 => "▆",
+        7 => "▇",
+        _ => "█",
+    }
+}
+
+impl View for SparklineView {
+    fn draw(&self, printer: &Printer) {
+        let range = self.range();
+        let height = printer.size.y.min(self.height);
+        let skip = self.data.len().saturating_sub(printer.size.x);
+        let color = ColorStyle::new(self.color.clone(), PaletteColor::View);
+
+        printer.with_color(color, |printer| {
+            for (x, &value) in self.data.iter().skip(skip).enumerate() {
+                let level = bar_level(value, range, height);
+                for row in 0..height {
+                    let eighths = level.saturating_sub(row * 8);
+                    if eighths == 0 {
+                        break;
+                    }
+                    let y = height - 1 - row;
+                    printer.print((x, y), vertical_block(eighths));
+                }
+            }
+        });
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(self.data.len(), self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels() {
+        let range = (0.0, 10.0);
+        assert_eq!(bar_level(0.0, range, 1), 1);
+        assert_eq!(bar_level(10.0, range, 1), 8);
+        assert_eq!(bar_level(20.0, range, 1), 8);
+        assert_eq!(bar_level(-1.0, range, 1), 0);
+        assert_eq!(bar_level(10.0, range, 3), 24);
+        assert_eq!(bar_level(5.0, (5.0, 5.0), 2), 1);
+        assert_eq!(bar_level(10.0, range, 0), 0);
+    }
+
+    #[test]
+    fn max_len() {
+        let mut view = SparklineView::new().max_len(3);
+        for i in 0..5 {
+            view.push(f64::from(i));
+        }
+        assert_eq!(view.get_data(), &[2.0, 3.0, 4.0]);
+        assert_eq!(view.range(), (2.0, 4.0));
+        assert_eq!(view.min(0.0).range(), (0.0, 4.0));
+    }
+}
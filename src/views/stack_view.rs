@@ -1,16 +1,34 @@
+use align::Align;
 use direction::Direction;
-use event::{AnyCb, Event, EventResult};
+use event::{
+    AnyCb, Callback, Event, EventResult, Key, MouseButton, MouseEvent,
+};
+use rect::Rect;
 use std::cell;
+use std::collections::HashMap;
 use std::ops::Deref;
-use theme::ColorStyle;
+use theme::{ColorStyle, Theme};
 use vec::Vec2;
-use view::{IntoBoxedView, Offset, Position, Selector, View, ViewWrapper};
+use view::{
+    ChildInfo, IntoBoxedView, Offset, Position, Selector, View, ViewWrapper,
+};
 use views::{Layer, ShadowView, ViewBox};
+use Cursive;
 use Printer;
 use With;
+use XY;
+
+/// Smallest size of the view in a resized layer.
+const MIN_WINDOW_SIZE: Vec2 = Vec2 { x: 4, y: 2 };
 
 /// Simple stack of views.
 /// Only the top-most view is active and can receive input.
+///
+/// Layers are modal by default: events they ignore are dropped. Events
+/// ignored by a [non-modal layer] go to the layer below instead; mouse
+/// events only if they hit it.
+///
+/// [non-modal layer]: #method.set_modal
 pub struct StackView {
     // Store layers from back to front.
     layers: Vec<Child>,
@@ -18,8 +36,12 @@ pub struct StackView {
     // Flag indicates if undrawn areas of the background are exposed
     // and therefore need redrawing.
     bg_dirty: cell::Cell<bool>,
+    // Callbacks active while this is the active screen.
+    callbacks: Callbacks,
 }
 
+type Callbacks = HashMap<Event, Vec<Callback>>;
+
 enum Placement {
     Floating(Position),
     Fullscreen,
@@ -44,6 +66,11 @@ impl Placement {
         P: Into<Vec2>,
     {
         match *self {
+            Placement::Floating(ref position)
+                if *position == Position::center() =>
+            {
+                Align::center().compute_offset(size, available)
+            }
             Placement::Floating(ref position) => {
                 position.compute_offset(size, available, parent)
             }
@@ -102,6 +129,19 @@ impl<T: View> ChildWrapper<T> {
             ChildWrapper::Plain(ref mut layer) => layer,
         }
     }
+
+    /// Returns the space taken around the inner view.
+    ///
+    /// The first value is before the view (top and left), the second is
+    /// after it (bottom and right).
+    fn decorations(&self) -> (Vec2, Vec2) {
+        match *self {
+            ChildWrapper::Shadow(ref shadow) => {
+                (shadow.top_left_padding(), Vec2::new(1, 1))
+            }
+            _ => (Vec2::zero(), Vec2::zero()),
+        }
+    }
 }
 
 // TODO: use macros to make this less ugly?
@@ -146,6 +186,22 @@ impl<T: View> View for ChildWrapper<T> {
         }
     }
 
+    fn take_focus_near(
+        &mut self, source: Direction, position: XY<isize>,
+    ) -> bool {
+        match *self {
+            ChildWrapper::Shadow(ref mut v) => {
+                v.take_focus_near(source, position)
+            }
+            ChildWrapper::Backfilled(ref mut v) => {
+                v.take_focus_near(source, position)
+            }
+            ChildWrapper::Plain(ref mut v) => {
+                v.take_focus_near(source, position)
+            }
+        }
+    }
+
     fn call_on_any<'a>(&mut self, selector: &Selector, callback: AnyCb<'a>) {
         match *self {
             ChildWrapper::Shadow(ref mut v) => {
@@ -167,6 +223,30 @@ impl<T: View> View for ChildWrapper<T> {
             ChildWrapper::Plain(ref mut v) => v.focus_view(selector),
         }
     }
+
+    fn important_area(&self, size: Vec2) -> Rect {
+        match *self {
+            ChildWrapper::Shadow(ref v) => v.important_area(size),
+            ChildWrapper::Backfilled(ref v) => v.important_area(size),
+            ChildWrapper::Plain(ref v) => v.important_area(size),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match *self {
+            ChildWrapper::Shadow(ref v) => v.type_name(),
+            ChildWrapper::Backfilled(ref v) => v.type_name(),
+            ChildWrapper::Plain(ref v) => v.type_name(),
+        }
+    }
+
+    fn inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        match *self {
+            ChildWrapper::Shadow(ref v) => v.inspect_children(size, f),
+            ChildWrapper::Backfilled(ref v) => v.inspect_children(size, f),
+            ChildWrapper::Plain(ref v) => v.inspect_children(size, f),
+        }
+    }
 }
 
 struct Child {
@@ -179,6 +259,192 @@ struct Child {
     // So we want to call `take_focus` right after the first call to `layout`.
     // This flag remembers when we've done that.
     virgin: bool,
+
+    // Set for layers the user can move and resize.
+    window: Option<Window>,
+
+    // If `false`, ignored events go to the layer below.
+    modal: bool,
+
+    // If `false`, `Esc` never closes this layer.
+    closable: bool,
+
+    // If `true`, a click outside this layer closes it.
+    dismiss_on_click_outside: bool,
+
+    // Callbacks active while this is the top layer.
+    callbacks: Callbacks,
+}
+
+/// State of a movable layer.
+#[derive(Default)]
+struct Window {
+    /// Size chosen by the user, replacing the required size.
+    size: Option<Vec2>,
+    drag: Option<Drag>,
+}
+
+/// Mouse drag in progress on a movable layer.
+#[derive(Clone, Copy)]
+enum Drag {
+    /// Moves the layer; stores where it was grabbed, relative to its
+    /// top-left corner.
+    Move(Vec2),
+    /// Resizes the layer along the given axes.
+    Resize(XY<bool>),
+}
+
+impl Child {
+    /// Returns `true` if `event` is a mouse event within this layer.
+    fn is_hit(&self, event: &Event, offset: Vec2) -> bool {
+        match *event {
+            Event::Mouse {
+                offset: origin,
+                position,
+                ..
+            } => {
+                let area = Rect::from_size(offset, self.size);
+                position
+                    .checked_sub(origin)
+                    .is_some_and(|pos| area.contains(pos))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the smallest size the user can give this layer.
+    fn min_size(&self) -> Vec2 {
+        let (before, after) = self.view.decorations();
+        before + after + MIN_WINDOW_SIZE
+    }
+
+    /// Sets the size chosen by the user.
+    fn resize(&mut self, size: Vec2) {
+        let size = size.or_max(self.min_size());
+        if let Some(ref mut window) = self.window {
+            window.size = Some(size);
+        }
+    }
+
+    /// Moves the layer so its top-left corner is at `offset`.
+    fn move_to(&mut self, offset: Vec2) {
+        self.placement = Placement::Floating(Position::absolute(offset));
+    }
+
+    /// Handles mouse events moving or resizing the layer.
+    ///
+    /// `offset` is the current position of the layer. Returns `false` if
+    /// the event should go to the view instead.
+    fn on_window_mouse(&mut self, event: &Event, offset: Vec2) -> bool {
+        let (origin, position, mouse) = match *event {
+            Event::Mouse {
+                offset,
+                position,
+                event,
+            } => (offset, position, event),
+            _ => return false,
+        };
+        let pos = match position.checked_sub(origin) {
+            Some(pos) => pos,
+            None => return false,
+        };
+        let drag = match self.window {
+            Some(ref window) => window.drag,
+            None => return false,
+        };
+
+        match (mouse, drag) {
+            (MouseEvent::Press(MouseButton::Left), _) => {
+                let (before, after) = self.view.decorations();
+                let top_left = offset + before;
+                let bottom_right =
+                    (offset + self.size).saturating_sub(after + (1, 1));
+                if !pos.fits(top_left) || !pos.fits_in(bottom_right) {
+                    return false;
+                }
+
+                let edges =
+                    XY::new(pos.x == bottom_right.x, pos.y == bottom_right.y);
+                let drag = if edges.any() {
+                    Drag::Resize(edges)
+                } else if pos.y == top_left.y {
+                    Drag::Move(pos - offset)
+                } else {
+                    return false;
+                };
+                if let Some(ref mut window) = self.window {
+                    window.drag = Some(drag);
+                }
+            }
+            (MouseEvent::Hold(MouseButton::Left), Some(Drag::Move(grab))) => {
+                self.move_to(pos.saturating_sub(grab));
+            }
+            (
+                MouseEvent::Hold(MouseButton::Left),
+                Some(Drag::Resize(axes)),
+            ) => {
+                // The cell under the mouse becomes the last one of the view.
+                let (_, after) = self.view.decorations();
+                let target = pos.saturating_sub(offset) + (1, 1) + after;
+                let size = axes.select_or(target, self.size);
+                self.resize(size);
+            }
+            (MouseEvent::Release(_), Some(_)) => {
+                if let Some(ref mut window) = self.window {
+                    window.drag = None;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handles keys moving or resizing the layer.
+    ///
+    /// `offset` is the current position of the layer. Returns `false` if
+    /// the event is not such a key.
+    fn on_window_key(&mut self, event: &Event, offset: Vec2) -> bool {
+        if self.window.is_none() {
+            return false;
+        }
+
+        match *event {
+            Event::Ctrl(key) => match arrow_offset(key) {
+                Some(delta) => self.move_to(offset.saturating_add(delta)),
+                None => return false,
+            },
+            Event::CtrlShift(key) => match arrow_offset(key) {
+                Some(delta) => {
+                    let size = self.size.saturating_add(delta);
+                    self.resize(size);
+                }
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+}
+
+fn add_callback<F>(callbacks: &mut Callbacks, event: Event, cb: F)
+where
+    F: FnMut(&mut Cursive) + 'static,
+{
+    callbacks
+        .entry(event)
+        .or_default()
+        .push(Callback::from_fn_mut(cb));
+}
+
+/// Returns the direction of an arrow key.
+fn arrow_offset(key: Key) -> Option<XY<isize>> {
+    match key {
+        Key::Left => Some(XY::new(-1, 0)),
+        Key::Right => Some(XY::new(1, 0)),
+        Key::Up => Some(XY::new(0, -1)),
+        Key::Down => Some(XY::new(0, 1)),
+        _ => None,
+    }
 }
 
 new_default!(StackView);
@@ -190,6 +456,7 @@ impl StackView {
             layers: Vec::new(),
             last_size: Vec2::zero(),
             bg_dirty: cell::Cell::new(true),
+            callbacks: HashMap::new(),
         }
     }
 
@@ -206,6 +473,11 @@ impl StackView {
             size: Vec2::zero(),
             placement: Placement::Fullscreen,
             virgin: true,
+            window: None,
+            modal: true,
+            closable: true,
+            dismiss_on_click_outside: false,
+            callbacks: HashMap::new(),
         });
     }
 
@@ -229,15 +501,12 @@ impl StackView {
 
     /// Returns a reference to the layer at the given position.
     pub fn get(&self, pos: LayerPosition) -> Option<&View> {
-        let i = self.get_index(pos);
-        self.layers.get(i).map(|child| &**child.view.get_inner())
+        self.get_child(pos).map(|child| &**child.view.get_inner())
     }
 
     /// Returns a mutable reference to the layer at the given position.
     pub fn get_mut(&mut self, pos: LayerPosition) -> Option<&mut View> {
-        let i = self.get_index(pos);
-        self.layers
-            .get_mut(i)
+        self.get_child_mut(pos)
             .map(|child| &mut **child.view.get_inner_mut())
     }
 
@@ -314,6 +583,11 @@ impl StackView {
             size: Vec2::new(0, 0),
             placement: Placement::Floating(position),
             virgin: true,
+            window: None,
+            modal: true,
+            closable: true,
+            dismiss_on_click_outside: false,
+            callbacks: HashMap::new(),
         });
     }
 
@@ -336,6 +610,11 @@ impl StackView {
             size: Vec2::new(0, 0),
             placement: Placement::Floating(position),
             virgin: true,
+            window: None,
+            modal: true,
+            closable: true,
+            dismiss_on_click_outside: false,
+            callbacks: HashMap::new(),
         });
     }
 
@@ -349,6 +628,217 @@ impl StackView {
         self.with(|s| s.add_layer_at(position, view))
     }
 
+    /// Adds a movable view on top of the stack in the center of the screen.
+    ///
+    /// The layer can be moved by dragging its first row (for instance the
+    /// title of a [`Dialog`]) with the mouse, and resized by dragging its
+    /// right or bottom edge.
+    ///
+    /// With the keyboard, `Ctrl+Arrow` moves the layer and
+    /// `Ctrl+Shift+Arrow` resizes it, when the view ignores these keys.
+    ///
+    /// [`Dialog`]: struct.Dialog.html
+    pub fn add_movable_layer<T>(&mut self, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        self.add_layer(view);
+        self.set_movable(LayerPosition::FromFront(0), true);
+    }
+
+    /// Adds a movable view on top of the stack in the center of the screen.
+    ///
+    /// Chainable variant.
+    pub fn movable_layer<T>(self, view: T) -> Self
+    where
+        T: IntoBoxedView,
+    {
+        self.with(|s| s.add_movable_layer(view))
+    }
+
+    /// Lets the user move and resize the given layer.
+    ///
+    /// See [`add_movable_layer`] for the controls.
+    ///
+    /// Has no effect on fullscreen layers.
+    /// Has no effect if layer is not found.
+    ///
+    /// [`add_movable_layer`]: #method.add_movable_layer
+    pub fn set_movable(&mut self, layer: LayerPosition, movable: bool) {
+        let child = match self.get_child_mut(layer) {
+            Some(child) => child,
+            None => return,
+        };
+        match child.placement {
+            Placement::Floating(_) if movable => {
+                if child.window.is_none() {
+                    child.window = Some(Window::default());
+                }
+            }
+            _ => {
+                child.window = None;
+                self.bg_dirty.set(true);
+            }
+        }
+    }
+
+    /// Returns `true` if the user can move the given layer.
+    pub fn is_movable(&self, layer: LayerPosition) -> bool {
+        self.get_child(layer).is_some_and(|child| child.window.is_some())
+    }
+
+    /// Adds a non-modal view on top of the stack in the center of the
+    /// screen.
+    ///
+    /// See [`set_modal`].
+    ///
+    /// [`set_modal`]: #method.set_modal
+    pub fn add_non_modal_layer<T>(&mut self, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        self.add_layer(view);
+        self.set_modal(LayerPosition::FromFront(0), false);
+    }
+
+    /// Adds a non-modal view on top of the stack in the center of the
+    /// screen.
+    ///
+    /// Chainable variant.
+    pub fn non_modal_layer<T>(self, view: T) -> Self
+    where
+        T: IntoBoxedView,
+    {
+        self.with(|s| s.add_non_modal_layer(view))
+    }
+
+    /// Sets whether the given layer blocks the layers below it.
+    ///
+    /// Events ignored by a non-modal layer are given to the layer below,
+    /// which makes it suitable for side panels or notifications that
+    /// shouldn't block the main view. Mouse events are only given to the
+    /// layer below if they are outside of the non-modal layer and hit the
+    /// one below.
+    ///
+    /// Layers are modal by default.
+    /// Has no effect if layer is not found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{LayerPosition, StackView, TextView};
+    /// let mut stack = StackView::new();
+    /// stack.add_fullscreen_layer(TextView::new("Main view"));
+    /// stack.add_layer(TextView::new("Notification"));
+    /// stack.set_modal(LayerPosition::FromFront(0), false);
+    /// ```
+    pub fn set_modal(&mut self, layer: LayerPosition, modal: bool) {
+        if let Some(child) = self.get_child_mut(layer) {
+            child.modal = modal;
+        }
+    }
+
+    /// Returns `true` if the given layer blocks the layers below it.
+    pub fn is_modal(&self, layer: LayerPosition) -> bool {
+        self.get_child(layer).is_some_and(|child| child.modal)
+    }
+
+    /// Sets whether the given layer can be closed with `Esc`.
+    ///
+    /// This only matters once enabled with
+    /// [`Cursive::set_global_esc_closes_layer`].
+    ///
+    /// Layers are closable by default.
+    /// Has no effect if layer is not found.
+    ///
+    /// [`Cursive::set_global_esc_closes_layer`]: ../struct.Cursive.html#method.set_global_esc_closes_layer
+    pub fn set_closable(&mut self, layer: LayerPosition, closable: bool) {
+        if let Some(child) = self.get_child_mut(layer) {
+            child.closable = closable;
+        }
+    }
+
+    /// Returns `true` if the given layer can be closed with `Esc`.
+    pub fn is_closable(&self, layer: LayerPosition) -> bool {
+        self.get_child(layer).is_some_and(|child| child.closable)
+    }
+
+    /// Sets whether a click outside the given layer closes it.
+    ///
+    /// Only the top layer is closed this way, and the click is not given
+    /// to the layers below. The layer is closed with
+    /// [`Cursive::pop_layer`], so this applies to the screens of a
+    /// `Cursive` root, and runs the layer transition.
+    ///
+    /// Defaults to `false`.
+    /// Has no effect if layer is not found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{Dialog, LayerPosition, StackView};
+    /// let mut stack = StackView::new();
+    /// stack.add_layer(Dialog::info("Click anywhere to close."));
+    /// stack.set_dismiss_on_click_outside(LayerPosition::FromFront(0), true);
+    /// ```
+    ///
+    /// [`Cursive::pop_layer`]: ../struct.Cursive.html#method.pop_layer
+    pub fn set_dismiss_on_click_outside(
+        &mut self, layer: LayerPosition, dismiss: bool,
+    ) {
+        if let Some(child) = self.get_child_mut(layer) {
+            child.dismiss_on_click_outside = dismiss;
+        }
+    }
+
+    /// Returns `true` if a click outside the given layer closes it.
+    pub fn is_dismissed_on_click_outside(&self, layer: LayerPosition) -> bool {
+        self.get_child(layer)
+            .is_some_and(|child| child.dismiss_on_click_outside)
+    }
+
+    /// Adds a callback active while the given layer is on top.
+    ///
+    /// When this stack is a screen of the `Cursive` root, the callback
+    /// replaces global callbacks for the same event. It is dropped along
+    /// with the layer.
+    ///
+    /// Has no effect if layer is not found.
+    pub fn add_layer_callback<F, E>(
+        &mut self, layer: LayerPosition, event: E, cb: F,
+    ) where
+        F: FnMut(&mut Cursive) + 'static,
+        E: Into<Event>,
+    {
+        if let Some(child) = self.get_child_mut(layer) {
+            add_callback(&mut child.callbacks, event.into(), cb);
+        }
+    }
+
+    /// Adds a callback active while this stack is the active screen.
+    ///
+    /// When this stack is a screen of the `Cursive` root, the callback
+    /// replaces global callbacks for the same event, but not callbacks of
+    /// the top layer.
+    pub fn add_callback<F, E>(&mut self, event: E, cb: F)
+    where
+        F: FnMut(&mut Cursive) + 'static,
+        E: Into<Event>,
+    {
+        add_callback(&mut self.callbacks, event.into(), cb);
+    }
+
+    /// Returns the callbacks to run for an ignored event.
+    ///
+    /// Callbacks of the top layer come first, then callbacks of the stack.
+    pub(crate) fn callbacks(&self, event: &Event) -> Option<Vec<Callback>> {
+        self.layers
+            .last()
+            .and_then(|child| child.callbacks.get(event))
+            .or_else(|| self.callbacks.get(event))
+            .cloned()
+    }
+
     /// Remove the top-most layer.
     pub fn pop_layer(&mut self) -> Option<Box<View>> {
         self.bg_dirty.set(true);
@@ -359,6 +849,20 @@ impl StackView {
             .map(ViewBox::unwrap)
     }
 
+    /// Removes a layer from this `StackView`.
+    ///
+    /// # Panics
+    ///
+    /// If the given position is out of bounds.
+    pub fn remove_layer(&mut self, position: LayerPosition) -> Box<View> {
+        let i = self
+            .get_index(position)
+            .expect("layer position out of bounds");
+        self.bg_dirty.set(true);
+        let child = self.layers.remove(i);
+        child.view.unwrap().unwrap()
+    }
+
     /// Computes the offset of the current top view.
     pub fn offset(&self) -> Vec2 {
         let mut previous = Vec2::zero();
@@ -378,21 +882,38 @@ impl StackView {
         self.layers.iter().map(|layer| layer.size).collect()
     }
 
-    fn get_index(&self, pos: LayerPosition) -> usize {
+    /// Returns the index of the given layer, if it exists.
+    fn get_index(&self, pos: LayerPosition) -> Option<usize> {
+        let len = self.layers.len();
         match pos {
-            LayerPosition::FromBack(i) => i,
-            LayerPosition::FromFront(i) => self.layers.len() - i - 1,
+            LayerPosition::FromBack(i) if i < len => Some(i),
+            LayerPosition::FromBack(_) => None,
+            LayerPosition::FromFront(i) => len.checked_sub(i + 1),
         }
     }
 
+    fn get_child(&self, pos: LayerPosition) -> Option<&Child> {
+        let i = self.get_index(pos)?;
+        self.layers.get(i)
+    }
+
+    fn get_child_mut(&mut self, pos: LayerPosition) -> Option<&mut Child> {
+        let i = self.get_index(pos)?;
+        self.layers.get_mut(i)
+    }
+
     /// Moves a layer to a new position in the stack.
     ///
     /// This only affects the elevation of a layer (whether it is drawn over
     /// or under other views).
+    ///
+    /// Has no effect if either position is not found.
     pub fn move_layer(&mut self, from: LayerPosition, to: LayerPosition) {
         // Convert relative positions to indices in the array
-        let from_i = self.get_index(from);
-        let to_i = self.get_index(to);
+        let (from_i, to_i) = match (self.get_index(from), self.get_index(to)) {
+            (Some(from_i), Some(to_i)) => (from_i, to_i),
+            _ => return,
+        };
 
         let removed = self.layers.remove(from_i);
 
@@ -416,9 +937,8 @@ impl StackView {
     pub fn reposition_layer(
         &mut self, layer: LayerPosition, position: Position,
     ) {
-        let i = self.get_index(layer);
-        let child = match self.layers.get_mut(i) {
-            Some(i) => i,
+        let child = match self.get_child_mut(layer) {
+            Some(child) => child,
             None => return,
         };
         match child.placement {
@@ -455,21 +975,70 @@ impl StackView {
     /// ease inserting layers under the stackview but above it's background
     /// you probably just want to call draw()
     pub fn draw_fg(&self, printer: &Printer) {
+        self.draw_fg_transition(printer, Vec2::zero(), None);
+    }
+
+    /// Draws the layers, with the top one shifted and using `top_theme`.
+    ///
+    /// Used to animate a new layer.
+    pub(crate) fn draw_fg_transition(
+        &self, printer: &Printer, top_shift: Vec2, top_theme: Option<&Theme>,
+    ) {
         let last = self.layers.len();
         printer.with_color(ColorStyle::primary(), |printer| {
             for (i, (v, offset)) in
                 StackPositionIterator::new(self.layers.iter(), printer.size)
                     .enumerate()
             {
-                v.view.draw(
-                    &printer
-                        .offset(offset)
-                        .cropped(v.size)
-                        .focused(i + 1 == last),
-                );
+                let printer = printer.focused(i + 1 == last);
+                if i + 1 < last {
+                    v.view.draw(&printer.offset(offset).cropped(v.size));
+                    continue;
+                }
+
+                let mut printer =
+                    printer.offset(offset + top_shift).cropped(v.size);
+                if top_shift != Vec2::zero() {
+                    // The layer may be partly out of the screen.
+                    printer = printer.inner_size(v.size);
+                }
+                match top_theme {
+                    Some(theme) => v.view.draw(&printer.theme(theme)),
+                    None => v.view.draw(&printer),
+                }
             }
         });
     }
+
+    /// Redraws the background on the next frame.
+    pub(crate) fn invalidate_background(&self) {
+        self.bg_dirty.set(true);
+    }
+
+    /// Returns `true` if `event` is a click outside the top layer, which
+    /// should close it.
+    fn clicked_outside_top(&self, event: &Event) -> bool {
+        let position = match *event {
+            Event::Mouse {
+                event: MouseEvent::Press(_),
+                position,
+                offset,
+            } => position.checked_sub(offset),
+            _ => return false,
+        };
+
+        let layers =
+            StackPositionIterator::new(self.layers.iter(), self.last_size);
+        let (layer, offset) = match layers.last() {
+            Some(top) => top,
+            None => return false,
+        };
+
+        let inside = position.is_some_and(|position| {
+            position.fits_in_rect(offset, layer.size)
+        });
+        layer.dismiss_on_click_outside && !inside
+    }
 }
 
 struct StackPositionIterator<R: Deref<Target = Child>, I: Iterator<Item = R>> {
@@ -526,16 +1095,46 @@ impl View for StackView {
         if event == Event::WindowResize {
             self.bg_dirty.set(true);
         }
-        // Use the stack position iterator to get the offset of the top layer.
+        if self.clicked_outside_top(&event) {
+            // Go through `Cursive::pop_layer` to run the layer transition.
+            return EventResult::with_cb(|s| {
+                s.pop_layer();
+            });
+        }
+
+        // Use the stack position iterator to get the offset of the layers.
         // TODO: save it instead when drawing?
-        match StackPositionIterator::new(
-            self.layers.iter_mut(),
-            self.last_size,
-        ).last()
-        {
-            None => EventResult::Ignored,
-            Some((v, offset)) => v.view.on_event(event.relativized(offset)),
+        let layers: Vec<_> =
+            StackPositionIterator::new(self.layers.iter_mut(), self.last_size)
+                .collect();
+
+        // Start from the top layer, and go down through non-modal ones.
+        // Mouse events only reach the layers below if they hit them.
+        let is_mouse = event.mouse_position().is_some();
+        for (i, (layer, offset)) in layers.into_iter().rev().enumerate() {
+            if i > 0 && is_mouse && !layer.is_hit(&event, offset) {
+                break;
+            }
+
+            if layer.on_window_mouse(&event, offset) {
+                self.bg_dirty.set(true);
+                return EventResult::Consumed(None);
+            }
+
+            match layer.view.on_event(event.relativized(offset)) {
+                EventResult::Ignored
+                    if layer.on_window_key(&event, offset) =>
+                {
+                    self.bg_dirty.set(true);
+                    return EventResult::Consumed(None);
+                }
+                EventResult::Ignored
+                    if !layer.modal && !layer.is_hit(&event, offset) => {}
+                result => return result,
+            }
         }
+
+        EventResult::Ignored
     }
 
     fn layout(&mut self, size: Vec2) {
@@ -546,7 +1145,15 @@ impl View for StackView {
 
         for layer in &mut self.layers {
             // Give each guy what he asks for, within the budget constraints.
-            let size = Vec2::min(size, layer.view.required_size(size));
+            let mut size = Vec2::min(size, layer.view.required_size(size));
+            // Unless the user resized the layer.
+            if let Some(Window {
+                size: Some(window_size),
+                ..
+            }) = layer.window
+            {
+                size = Vec2::min(self.last_size, window_size);
+            }
             layer.size = size;
             layer.view.layout(layer.size);
 
@@ -580,28 +1187,62 @@ impl View for StackView {
     fn call_on_any<'a>(
         &mut self, selector: &Selector, mut callback: AnyCb<'a>,
     ) {
-        for layer in &mut self.layers {
-            layer
-                .view
-                .call_on_any(selector, Box::new(|any| callback(any)));
+        if selector.selects(self) {
+            callback(self);
+        }
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            if let Some(selector) = selector.for_child(i) {
+                layer
+                    .view
+                    .call_on_any(&selector, Box::new(|any| callback(any)));
+            }
         }
     }
 
     fn focus_view(&mut self, selector: &Selector) -> Result<(), ()> {
-        for layer in &mut self.layers {
-            if layer.view.focus_view(selector).is_ok() {
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            let selector = match selector.for_child(i) {
+                Some(selector) => selector,
+                None => continue,
+            };
+            if layer.view.focus_view(&selector).is_ok() {
                 return Ok(());
             }
         }
 
         Err(())
     }
+
+    fn important_area(&self, size: Vec2) -> Rect {
+        // Only the top layer matters.
+        match StackPositionIterator::new(self.layers.iter(), size).last() {
+            Some((layer, offset)) => {
+                layer.view.important_area(layer.size) + offset
+            }
+            None => Rect::from_size((0, 0), size),
+        }
+    }
+
+    fn inspect_children(&self, size: Vec2, f: &mut FnMut(ChildInfo)) {
+        let last = self.layers.len();
+        for (i, (layer, offset)) in
+            StackPositionIterator::new(self.layers.iter(), size).enumerate()
+        {
+            f(ChildInfo {
+                view: &layer.view,
+                offset,
+                size: layer.size,
+                focused: i + 1 == last,
+            });
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use views::TextView;
+    use view::{Finder, Identifiable};
+    use views::{Button, EditView, LinearLayout, TextView};
 
     #[test]
     fn pop_add() {
@@ -703,4 +1344,141 @@ mod tests {
                 .is::<TextView>()
         );
     }
+
+    #[test]
+    fn movable_layer() {
+        let mouse = |position, event| Event::Mouse {
+            offset: Vec2::zero(),
+            position,
+            event,
+        };
+
+        let mut stack = StackView::new()
+            .layer(TextView::new("1"))
+            .movable_layer(TextView::new("title\ntext"));
+        assert!(!stack.is_movable(LayerPosition::FromBack(0)));
+        assert!(stack.is_movable(LayerPosition::FromFront(0)));
+
+        stack.layout(Vec2::new(20, 10));
+        assert_eq!(stack.offset(), Vec2::new(6, 3));
+
+        // Drag the first row.
+        let left = MouseButton::Left;
+        stack.on_event(mouse(Vec2::new(8, 4), MouseEvent::Press(left)));
+        stack.on_event(mouse(Vec2::new(4, 2), MouseEvent::Hold(left)));
+        stack.on_event(mouse(Vec2::new(4, 2), MouseEvent::Release(left)));
+        assert_eq!(stack.offset(), Vec2::new(2, 1));
+
+        stack.on_event(Event::Ctrl(Key::Right));
+        assert_eq!(stack.offset(), Vec2::new(3, 1));
+
+        // Drag the bottom-right corner.
+        stack.on_event(mouse(Vec2::new(8, 3), MouseEvent::Press(left)));
+        stack.on_event(mouse(Vec2::new(12, 6), MouseEvent::Hold(left)));
+        stack.on_event(mouse(Vec2::new(12, 6), MouseEvent::Release(left)));
+        stack.layout(Vec2::new(20, 10));
+        assert_eq!(stack.layer_sizes()[1], Vec2::new(11, 7));
+
+        stack.on_event(Event::CtrlShift(Key::Up));
+        stack.layout(Vec2::new(20, 10));
+        assert_eq!(stack.layer_sizes()[1], Vec2::new(11, 6));
+    }
+
+    #[test]
+    fn missing_layer() {
+        let mut stack = StackView::new();
+        let front = LayerPosition::FromFront(1);
+        stack.set_modal(front, false);
+        stack.set_closable(front, false);
+        stack.set_dismiss_on_click_outside(front, true);
+        stack.add_layer_callback(front, 'a', |_| ());
+        assert!(!stack.is_modal(front));
+        assert!(stack.get(front).is_none());
+
+        stack.add_layer(TextView::new("Single layer"));
+        stack.move_layer(LayerPosition::FromBack(1), front);
+        assert!(stack.get(LayerPosition::FromFront(0)).is_some());
+        assert!(stack.get(LayerPosition::FromBack(1)).is_none());
+    }
+
+    #[test]
+    fn non_modal_layer() {
+        let mut stack = StackView::new()
+            .fullscreen_layer(EditView::new().with_id("main"))
+            .non_modal_layer(TextView::new("Notification"));
+        assert!(stack.is_modal(LayerPosition::FromBack(0)));
+        assert!(!stack.is_modal(LayerPosition::FromFront(0)));
+        stack.layout(Vec2::new(20, 10));
+
+        let click = |x, y| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, y),
+            event: MouseEvent::Press(MouseButton::Left),
+        };
+
+        // Clicks around the notification reach the main view.
+        assert!(stack.on_event(click(0, 0)).is_consumed());
+        // But not clicks on the notification.
+        assert!(!stack.on_event(click(5, 4)).is_consumed());
+
+        // The notification ignores keys: they reach the main view.
+        assert!(stack.on_event(Event::Char('a')).is_consumed());
+        let content = stack
+            .find_id("main", |edit: &mut EditView| edit.get_content());
+        assert_eq!(&*content.unwrap(), "a");
+
+        stack.set_modal(LayerPosition::FromFront(0), true);
+        assert!(!stack.on_event(click(0, 0)).is_consumed());
+        assert!(!stack.on_event(Event::Char('b')).is_consumed());
+    }
+
+    #[test]
+    fn important_area() {
+        let mut stack = StackView::new();
+        stack.add_fullscreen_layer(TextView::new("background"));
+        stack.add_transparent_layer_at(
+            Position::absolute((2, 3)),
+            LinearLayout::vertical()
+                .child(TextView::new("label"))
+                .child(Button::new("Ok", |_| ())),
+        );
+        stack.layout(Vec2::new(20, 10));
+
+        // The focused button, in the top layer.
+        let area = stack.important_area(Vec2::new(20, 10));
+        assert_eq!(area, Rect::from_size((2, 4), (4, 1)));
+    }
+
+    #[test]
+    fn click_outside() {
+        let click = |x, y| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, y),
+            event: MouseEvent::Press(MouseButton::Left),
+        };
+
+        let mut siv = Cursive::dummy();
+        siv.add_fullscreen_layer(TextView::new("background"));
+        siv.screen_mut().add_transparent_layer_at(
+            Position::absolute((2, 3)),
+            TextView::new("popup"),
+        );
+        siv.screen_mut().layout(Vec2::new(20, 10));
+
+        // Layers stay open by default.
+        siv.on_event(click(0, 0));
+        assert_eq!(siv.screen().layer_sizes().len(), 2);
+
+        siv.screen_mut()
+            .set_dismiss_on_click_outside(LayerPosition::FromFront(0), true);
+        siv.on_event(click(3, 3));
+        assert_eq!(siv.screen().layer_sizes().len(), 2);
+
+        // The layer is popped by a callback, not by the stack itself.
+        let result = siv.screen_mut().on_event(click(0, 0));
+        assert!(result.is_consumed());
+        assert_eq!(siv.screen().layer_sizes().len(), 2);
+        result.process(&mut siv);
+        assert_eq!(siv.screen().layer_sizes().len(), 1);
+    }
 }
@@ -0,0 +1,429 @@
+use direction::Direction;
+use event::{Event, EventResult, Key};
+use libc;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use theme::{Color, ColorStyle, ColorType, Effect, PaletteColor, Style};
+use vec::Vec2;
+use view::View;
+use vt100;
+use Printer;
+
+/// Runs a command in a pseudo-terminal, and displays its output.
+///
+/// Requires the `terminal-view` feature, and is only available on unix.
+///
+/// Output is parsed as a VT100-compatible terminal would, so interactive
+/// programs like shells or editors can be embedded. Key events received by
+/// this view are forwarded to the command, and the pseudo-terminal is
+/// resized with the view.
+///
+/// Output is read in a background thread: call [`Cursive::set_fps`] to
+/// regularly refresh the screen.
+///
+/// The command is killed when the view is dropped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cursive::Cursive;
+/// # use cursive::views::TerminalView;
+/// # use cursive::view::Boxable;
+/// # use std::process::Command;
+/// let mut siv = Cursive::dummy();
+/// let view = TerminalView::spawn(Command::new("bash")).unwrap();
+/// siv.add_fullscreen_layer(view.full_screen());
+/// siv.set_fps(30);
+/// ```
+///
+/// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+pub struct TerminalView {
+    parser: Arc<Mutex<vt100::Parser>>,
+
+    /// Master side of the pseudo-terminal.
+    master: File,
+
+    child: Child,
+
+    /// Current size of the pseudo-terminal.
+    size: Vec2,
+}
+
+impl TerminalView {
+    /// Spawns `command` in a new 80x24 pseudo-terminal.
+    ///
+    /// The standard input and outputs of the command are replaced with the
+    /// pseudo-terminal, and `TERM` is set to `xterm-256color`.
+    pub fn spawn(mut command: Command) -> io::Result<Self> {
+        let size = Vec2::new(80, 24);
+
+        let (master, slave) = open_pty(size)?;
+        // Cloned descriptors are close-on-exec, except in the child where
+        // they become the standard streams.
+        let stdio = || slave.try_clone().map(Stdio::from);
+
+        command
+            .stdin(stdio()?)
+            .stdout(stdio()?)
+            .stderr(stdio()?)
+            .env("TERM", "xterm-256color");
+        unsafe {
+            command.pre_exec(|| {
+                // Make the pseudo-terminal the controlling terminal of a new
+                // session, so the command receives signals like SIGINT.
+                if libc::setsid() < 0
+                    || libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let child = command.spawn()?;
+        // Only the child should keep the slave side open, so we notice when
+        // it exits.
+        drop(slave);
+
+        let parser = Arc::new(Mutex::new(vt100::Parser::new(
+            size.y as u16,
+            size.x as u16,
+            0,
+        )));
+
+        let mut reader = master.try_clone()?;
+        let output = Arc::clone(&parser);
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            // Reading fails with EIO once the command exited.
+            while let Ok(n) = reader.read(&mut buffer) {
+                if n == 0 {
+                    break;
+                }
+                output.lock().unwrap().process(&buffer[..n]);
+            }
+        });
+
+        Ok(TerminalView {
+            parser,
+            master,
+            child,
+            size,
+        })
+    }
+
+    /// Returns `true` if the command is still running.
+    pub fn is_running(&mut self) -> bool {
+        self.exit_status().is_none()
+    }
+
+    /// Returns the exit status of the command, if it exited.
+    pub fn exit_status(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().and_then(|status| status)
+    }
+
+    /// Returns the text currently shown, without formatting.
+    pub fn contents(&self) -> String {
+        self.parser.lock().unwrap().screen().contents()
+    }
+
+    /// Returns the title set by the command, if any.
+    pub fn title(&self) -> String {
+        self.parser.lock().unwrap().screen().title().to_string()
+    }
+
+    /// Sends raw bytes to the command's input.
+    pub fn send(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.master.write_all(bytes)
+    }
+
+    fn resize(&mut self, size: Vec2) {
+        self.size = size;
+        self.parser
+            .lock()
+            .unwrap()
+            .set_size(size.y as u16, size.x as u16);
+
+        let winsize = winsize(size);
+        unsafe {
+            libc::ioctl(
+                self.master.as_raw_fd(),
+                libc::TIOCSWINSZ as _,
+                &winsize,
+            );
+        }
+    }
+}
+
+impl Drop for TerminalView {
+    fn drop(&mut self) {
+        if self.is_running() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn winsize(size: Vec2) -> libc::winsize {
+    libc::winsize {
+        ws_row: size.y as u16,
+        ws_col: size.x as u16,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+/// Opens a new pseudo-terminal, returning its master and slave sides.
+fn open_pty(size: Vec2) -> io::Result<(File, File)> {
+    let mut master: RawFd = -1;
+    let mut slave: RawFd = -1;
+    let winsize = winsize(size);
+
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            ptr::null_mut(),
+            ptr::null(),
+            &winsize,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let (master, slave) =
+        unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+    // Other commands spawned by the application shouldn't inherit them.
+    set_cloexec(&master)?;
+    set_cloexec(&slave)?;
+    Ok((master, slave))
+}
+
+/// Closes `file` when executing a new program.
+fn set_cloexec(file: &File) -> io::Result<()> {
+    let fd = file.as_raw_fd();
+    let result = unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFD);
+        if flags < 0 {
+            flags
+        } else {
+            libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC)
+        }
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn translate_color(color: vt100::Color, default: PaletteColor) -> ColorType {
+    match color {
+        vt100::Color::Default => ColorType::Palette(default),
+        vt100::Color::Idx(i) => ColorType::Color(Color::from_256colors(i)),
+        vt100::Color::Rgb(r, g, b) => ColorType::Color(Color::Rgb(r, g, b)),
+    }
+}
+
+fn cell_style(cell: &vt100::Cell) -> Style {
+    let mut style = Style::from(ColorStyle::new(
+        translate_color(cell.fgcolor(), PaletteColor::Primary),
+        translate_color(cell.bgcolor(), PaletteColor::View),
+    ));
+    if cell.bold() {
+        style.effects.insert(Effect::Bold);
+    }
+    if cell.italic() {
+        style.effects.insert(Effect::Italic);
+    }
+    if cell.underline() {
+        style.effects.insert(Effect::Underline);
+    }
+    if cell.inverse() {
+        style.effects.insert(Effect::Reverse);
+    }
+    style
+}
+
+/// Returns the bytes a terminal would send for the given event.
+fn event_bytes(event: &Event, app_cursor: bool) -> Option<Vec<u8>> {
+    let cursor = |c: char| {
+        if app_cursor {
+            format!("\x1BO{}", c)
+        } else {
+            format!("\x1B[{}", c)
+        }
+    };
+
+    let text = match *event {
+        Event::Char(c) => c.to_string(),
+        Event::CtrlChar(c) if c.is_ascii_alphabetic() => {
+            return Some(vec![c.to_ascii_lowercase() as u8 & 0x1F]);
+        }
+        Event::AltChar(c) => format!("\x1B{}", c),
+        Event::Paste(ref text) => text.clone(),
+        Event::Shift(Key::Tab) => "\x1B[Z".to_string(),
+        Event::Key(key) => match key {
+            Key::Enter => "\r".to_string(),
+            Key::Tab => "\t".to_string(),
+            Key::Backspace => "\x7F".to_string(),
+            Key::Esc => "\x1B".to_string(),
+            Key::Up => cursor('A'),
+            Key::Down => cursor('B'),
+            Key::Right => cursor('C'),
+            Key::Left => cursor('D'),
+            Key::Home => cursor('H'),
+            Key::End => cursor('F'),
+            Key::Ins => "\x1B[2~".to_string(),
+            Key::Del => "\x1B[3~".to_string(),
+            Key::PageUp => "\x1B[5~".to_string(),
+            Key::PageDown => "\x1B[6~".to_string(),
+            Key::F1 => "\x1BOP".to_string(),
+            Key::F2 => "\x1BOQ".to_string(),
+            Key::F3 => "\x1BOR".to_string(),
+            Key::F4 => "\x1BOS".to_string(),
+            Key::F5 => "\x1B[15~".to_string(),
+            Key::F6 => "\x1B[17~".to_string(),
+            Key::F7 => "\x1B[18~".to_string(),
+            Key::F8 => "\x1B[19~".to_string(),
+            Key::F9 => "\x1B[20~".to_string(),
+            Key::F10 => "\x1B[21~".to_string(),
+            Key::F11 => "\x1B[23~".to_string(),
+            Key::F12 => "\x1B[24~".to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(text.into_bytes())
+}
+
+impl View for TerminalView {
+    fn draw(&self, printer: &Printer) {
+        let parser = self.parser.lock().unwrap();
+        let screen = parser.screen();
+
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let cell = match screen.cell(y as u16, x as u16) {
+                    Some(cell) => cell,
+                    None => continue,
+                };
+                if cell.is_wide_continuation() {
+                    continue;
+                }
+
+                let contents = cell.contents();
+                let text = if contents.is_empty() { " " } else { &contents };
+                printer.with_style(cell_style(cell), |printer| {
+                    printer.print((x, y), text);
+                });
+            }
+        }
+
+        if printer.focused && !screen.hide_cursor() {
+            let (row, col) = screen.cursor_position();
+            printer.show_cursor((col as usize, row as usize));
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        self.size
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        if size != self.size && size.x > 0 && size.y > 0 {
+            self.resize(size);
+        }
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let (app_cursor, bracketed_paste) = {
+            let parser = self.parser.lock().unwrap();
+            let screen = parser.screen();
+            (screen.application_cursor(), screen.bracketed_paste())
+        };
+
+        let mut bytes = match event_bytes(&event, app_cursor) {
+            Some(bytes) => bytes,
+            None => return EventResult::Ignored,
+        };
+        if let Event::Paste(_) = event {
+            if bracketed_paste {
+                bytes.splice(0..0, b"\x1B[200~".iter().cloned());
+                bytes.extend_from_slice(b"\x1B[201~");
+            }
+        }
+
+        // The command may have exited already, there's nothing to do then.
+        let _ = self.send(&bytes);
+        EventResult::Consumed(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn key_translation() {
+        let bytes = |event| event_bytes(&event, false).unwrap();
+
+        assert_eq!(bytes(Event::Char('é')), "é".as_bytes());
+        assert_eq!(bytes(Event::CtrlChar('c')), b"\x03");
+        assert_eq!(bytes(Event::Key(Key::Up)), b"\x1B[A");
+        assert_eq!(
+            event_bytes(&Event::Key(Key::Up), true).unwrap(),
+            b"\x1BOA"
+        );
+        assert_eq!(event_bytes(&Event::Refresh, false), None);
+    }
+
+    #[test]
+    fn pty_cloexec() {
+        let (master, slave) = open_pty(Vec2::new(10, 2)).unwrap();
+        for file in &[master, slave] {
+            let fd = file.as_raw_fd();
+            let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+            assert!(flags & libc::FD_CLOEXEC != 0);
+        }
+    }
+
+    #[test]
+    fn spawn_command() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "read line; echo \"got $line\""]);
+        let mut view = TerminalView::spawn(command).unwrap();
+
+        for c in "hello".chars() {
+            view.on_event(Event::Char(c));
+        }
+        view.on_event(Event::Key(Key::Enter));
+
+        for _ in 0..100 {
+            if view.contents().contains("got hello") {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(view.contents().contains("got hello"));
+
+        for _ in 0..100 {
+            if !view.is_running() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(view.exit_status().unwrap().success());
+    }
+}
@@ -1,13 +1,14 @@
+use clipboard::{self, ClipboardKeys};
 use direction::Direction;
 use event::{Event, EventResult, Key, MouseButton, MouseEvent};
 use rect::Rect;
 use std::cmp::min;
 use theme::{ColorStyle, Effect};
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 use utils::lines::simple::{prefix, simple_prefix, LinesIterator, Row};
+use utils::width::StrWidth;
 use vec::Vec2;
-use view::{ScrollBase, SizeCache, View};
+use view::{PageKeys, Paging, ScrollBase, SizeCache, View, ViewState};
 use {Printer, With, XY};
 
 /// Multi-lines text editor.
@@ -36,6 +37,12 @@ pub struct TextArea {
 
     /// Byte offset of the currently selected grapheme.
     cursor: usize,
+
+    /// Keys moving the cursor by half a page.
+    page_keys: PageKeys,
+
+    /// Keys copying and pasting the content.
+    clipboard_keys: ClipboardKeys,
 }
 
 fn make_rows(text: &str, width: usize) -> Vec<Row> {
@@ -57,9 +64,37 @@ impl TextArea {
             size_cache: None,
             last_size: Vec2::zero(),
             cursor: 0,
+            page_keys: PageKeys::default(),
+            clipboard_keys: ClipboardKeys::default(),
         }
     }
 
+    /// Sets the keys moving the cursor by half a page.
+    ///
+    /// `PageUp` and `PageDown` always move by a full page.
+    pub fn set_page_keys(&mut self, page_keys: PageKeys) {
+        self.page_keys = page_keys;
+    }
+
+    /// Sets the keys moving the cursor by half a page.
+    ///
+    /// Chainable variant.
+    pub fn page_keys(self, page_keys: PageKeys) -> Self {
+        self.with(|s| s.set_page_keys(page_keys))
+    }
+
+    /// Sets the keys copying and pasting the content.
+    pub fn set_clipboard_keys(&mut self, clipboard_keys: ClipboardKeys) {
+        self.clipboard_keys = clipboard_keys;
+    }
+
+    /// Sets the keys copying and pasting the content.
+    ///
+    /// Chainable variant.
+    pub fn clipboard_keys(self, clipboard_keys: ClipboardKeys) -> Self {
+        self.with(|s| s.set_clipboard_keys(clipboard_keys))
+    }
+
     /// Retrieves the content of the view.
     pub fn get_content(&self) -> &str {
         &self.content
@@ -113,6 +148,17 @@ impl TextArea {
         self.with(|s| s.set_content(content))
     }
 
+    /// Inserts `text` at the current cursor position.
+    pub fn insert_str(&mut self, text: &str) {
+        self.content.insert_str(self.cursor, text);
+        self.cursor += text.len();
+
+        if let Some(size) = self.size_cache.map(|s| s.map(|s| s.value)) {
+            self.invalidate();
+            self.compute_rows(size);
+        }
+    }
+
     /// Disables this view.
     ///
     /// A disabled view cannot be selected.
@@ -177,15 +223,14 @@ impl TextArea {
         self.col_at(self.cursor)
     }
 
-    fn page_up(&mut self) {
-        for _ in 0..5 {
-            self.move_up();
-        }
-    }
-
-    fn page_down(&mut self) {
-        for _ in 0..5 {
-            self.move_down();
+    /// Moves the cursor by a page or half a page.
+    fn page(&mut self, paging: Paging) {
+        for _ in 0..paging.amount(self.scrollbase.view_height) {
+            if paging.is_up() {
+                self.move_up();
+            } else {
+                self.move_down();
+            }
         }
     }
 
@@ -501,15 +546,34 @@ impl View for TextArea {
                     };
                     let offset = text[..cursor_offset].width();
                     printer.print((offset, 0), c);
+                    printer.show_cursor((offset, 0));
                 }
             });
         });
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        if self.clipboard_keys.is_copy(&event) {
+            let content = self.content.clone();
+            return EventResult::with_cb(move |s| {
+                s.set_clipboard(content.as_str())
+            });
+        }
+
+        if let Some(paging) = self.page_keys.paging(&event) {
+            self.page(paging);
+            let focus = self.selected_row();
+            self.scrollbase.scroll_to(focus);
+            return EventResult::Consumed(None);
+        }
+
         let mut fix_scroll = true;
         match event {
+            ref event if self.clipboard_keys.is_paste(event) => {
+                self.insert_str(&clipboard::get())
+            }
             Event::Char(ch) => self.insert(ch),
+            Event::Paste(ref text) => self.insert_str(text),
             Event::Key(Key::Enter) => self.insert('\n'),
             Event::Key(Key::Backspace) if self.cursor > 0 => self.backspace(),
             Event::Key(Key::Del) if self.cursor < self.content.len() => {
@@ -536,8 +600,6 @@ impl View for TextArea {
             {
                 self.move_down()
             }
-            Event::Key(Key::PageUp) => self.page_up(),
-            Event::Key(Key::PageDown) => self.page_down(),
             Event::Key(Key::Left) if self.cursor > 0 => self.move_left(),
             Event::Key(Key::Right) if self.cursor < self.content.len() => {
                 self.move_right()
@@ -616,6 +678,16 @@ impl View for TextArea {
         self.compute_rows(size);
     }
 
+    fn state(&self) -> Option<ViewState> {
+        Some(ViewState::Text(self.content.clone()))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Text(ref content) = *state {
+            self.set_content(content.clone());
+        }
+    }
+
     fn important_area(&self, _: Vec2) -> Rect {
         // The important area is a single character
         let char_width = if self.cursor >= self.content.len() {
@@ -636,3 +708,50 @@ impl View for TextArea {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(view: &mut TextArea, text: &str) {
+        for ch in text.chars() {
+            view.on_event(Event::Char(ch));
+        }
+    }
+
+    #[test]
+    fn combining_characters() {
+        let mut view = TextArea::new();
+        view.layout(Vec2::new(10, 3));
+
+        // `e` followed by a combining acute accent: a single grapheme.
+        type_str(&mut view, "ae\u{301}b");
+        assert_eq!(view.cursor(), 5);
+
+        view.on_event(Event::Key(Key::Left));
+        view.on_event(Event::Key(Key::Left));
+        assert_eq!(view.cursor(), 1);
+
+        view.on_event(Event::Key(Key::Del));
+        assert_eq!(view.get_content(), "ab");
+    }
+
+    #[test]
+    fn wide_characters() {
+        let size = Vec2::new(10, 3);
+        let mut view = TextArea::new();
+        view.layout(size);
+
+        type_str(&mut view, "漢字\na");
+        assert_eq!(view.important_area(size).top_left(), Vec2::new(1, 1));
+
+        view.on_event(Event::Ctrl(Key::Home));
+        view.on_event(Event::Key(Key::Right));
+        assert_eq!(view.cursor(), 3);
+        assert_eq!(view.important_area(size), Rect::from_size((2, 0), (2, 1)));
+
+        view.on_event(Event::Key(Key::Backspace));
+        assert_eq!(view.get_content(), "字\na");
+        assert_eq!(view.cursor(), 0);
+    }
+}
@@ -1,14 +1,19 @@
+use std::cmp::max;
+use std::mem;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::sync::{Mutex, MutexGuard};
 
 use owning_ref::{ArcRef, OwningHandle};
-use unicode_width::UnicodeWidthStr;
 
 use align::*;
-use theme::Effect;
+use theme::{Effect, Style};
+#[cfg(feature = "bidi")]
+use utils::bidi;
 use utils::lines::spans::{LinesIterator, Row};
 use utils::markup::StyledString;
+use utils::span::Span;
+use utils::width::StrWidth;
 use view::{SizeCache, View};
 use {Printer, Vec2, With, XY};
 
@@ -48,6 +53,8 @@ impl TextContent {
             content: Arc::new(Mutex::new(TextContentInner {
                 content,
                 size_cache: None,
+                generation: 0,
+                rewritten: 0,
             })),
         }
     }
@@ -89,7 +96,7 @@ impl TextContent {
     where
         S: Into<StyledString>,
     {
-        self.with_content(|c| c.append(content))
+        self.content.lock().unwrap().append(content.into());
     }
 
     /// Returns a reference to the content.
@@ -107,8 +114,7 @@ impl TextContent {
         let mut lock = self.content.lock().unwrap();
 
         let out = f(&mut lock.content);
-
-        lock.size_cache = None;
+        lock.rewrite();
 
         out
     }
@@ -125,6 +131,13 @@ struct TextContentInner {
 
     // We keep the cache here so it can be busted when we change the content.
     size_cache: Option<XY<SizeCache>>,
+
+    // Incremented on each change, to know if rows are up to date.
+    generation: usize,
+
+    // Generation of the last change that was not an append.
+    // Rows computed since then only need their last row computed again.
+    rewritten: usize,
 }
 
 impl TextContentInner {
@@ -140,6 +153,19 @@ impl TextContentInner {
         }
     }
 
+    fn append(&mut self, content: StyledString) {
+        self.content.append(content);
+        self.generation += 1;
+        self.size_cache = None;
+    }
+
+    /// Records a change other than an append.
+    fn rewrite(&mut self) {
+        self.generation += 1;
+        self.rewritten = self.generation;
+        self.size_cache = None;
+    }
+
     fn is_cache_valid(&self, size: Vec2) -> bool {
         match self.size_cache {
             None => false,
@@ -148,8 +174,52 @@ impl TextContentInner {
     }
 }
 
+/// Rows of the content, wrapped at a given width.
+#[derive(Default)]
+struct WrappedRows {
+    // Width the rows were wrapped at.
+    width: usize,
+
+    // Generation of the content the rows were computed from, if any.
+    generation: Option<usize>,
+
+    rows: Vec<Row>,
+
+    // Width of the widest row, not counting the last one.
+    // The last row is the only one changed by appends.
+    head_width: usize,
+}
+
+impl WrappedRows {
+    fn new(width: usize) -> Self {
+        WrappedRows {
+            width,
+            ..WrappedRows::default()
+        }
+    }
+
+    /// Adds rows at the end.
+    fn extend<I: IntoIterator<Item = Row>>(&mut self, rows: I) {
+        for row in rows {
+            if let Some(last) = self.rows.last() {
+                self.head_width = max(self.head_width, last.width);
+            }
+            self.rows.push(row);
+        }
+    }
+
+    /// Returns the width of the widest row, if any.
+    fn max_width(&self) -> Option<usize> {
+        self.rows.last().map(|last| max(last.width, self.head_width))
+    }
+}
+
 /// A simple view showing a fixed text.
 ///
+/// Wrapped rows are kept for the last two widths, and appending text only
+/// wraps the end of the content again. Other changes, or a new width, wrap
+/// the entire content.
+///
 /// # Examples
 ///
 /// ```rust
@@ -162,7 +232,11 @@ impl TextContentInner {
 pub struct TextView {
     // content: String,
     content: Arc<Mutex<TextContentInner>>,
-    rows: Vec<Row>,
+    rows: WrappedRows,
+
+    // Rows for the previous width: layouts often alternate between two
+    // widths, for instance with or without a scrollbar.
+    spare_rows: WrappedRows,
 
     align: Align,
     effect: Effect,
@@ -204,7 +278,8 @@ impl TextView {
         TextView {
             content: content.content,
             effect: Effect::Simple,
-            rows: Vec::new(),
+            rows: WrappedRows::default(),
+            spare_rows: WrappedRows::default(),
             wrap: true,
             align: Align::top_left(),
             last_size: Vec2::zero(),
@@ -285,8 +360,9 @@ impl TextView {
     where
         S: Into<StyledString>,
     {
-        self.content.lock().unwrap().content = content.into();
-        self.invalidate();
+        let mut inner = self.content.lock().unwrap();
+        inner.content = content.into();
+        inner.rewrite();
     }
 
     /// Append `content` to the end of a `TextView`.
@@ -294,8 +370,7 @@ impl TextView {
     where
         S: Into<StyledString>,
     {
-        self.content.lock().unwrap().content.append(content.into());
-        self.invalidate();
+        self.content.lock().unwrap().append(content.into());
     }
 
     /// Returns the current text in this view.
@@ -332,39 +407,107 @@ impl TextView {
             return;
         }
 
-        self.rows = LinesIterator::new(&content.content, size.x).collect();
+        if self.rows.width != size.x {
+            mem::swap(&mut self.rows, &mut self.spare_rows);
+            if self.rows.width != size.x {
+                self.rows = WrappedRows::new(size.x);
+            }
+        }
+
+        let rows = &mut self.rows;
+        if rows.generation != Some(content.generation) {
+            // If text was only appended, the rows before the last one are
+            // still valid: only wrap the end of the content.
+            let appended =
+                rows.generation.is_some_and(|gen| content.rewritten <= gen);
+            let resumed = if appended {
+                rows.rows.last().and_then(|last| {
+                    LinesIterator::new(&content.content, size.x)
+                        .starting_at(last)
+                })
+            } else {
+                None
+            };
+
+            match resumed {
+                Some(lines) => {
+                    rows.rows.pop();
+                    rows.extend(lines);
+                }
+                None => {
+                    *rows = WrappedRows::new(size.x);
+                    rows.extend(LinesIterator::new(&content.content, size.x));
+                }
+            }
+            rows.generation = Some(content.generation);
+        }
 
         // Desired width
-        self.width = self.rows.iter().map(|row| row.width).max();
+        self.width = rows.max_width();
     }
 
-    // Invalidates the cache, so next call will recompute everything.
-    fn invalidate(&mut self) {
-        let mut content = self.content.lock().unwrap();
-        content.size_cache = None;
+    #[cfg(not(feature = "bidi"))]
+    fn draw_row(
+        &self, printer: &Printer, pos: Vec2, _width: usize,
+        spans: &[Span<Style>],
+    ) {
+        let (mut x, y) = pos.pair();
+
+        for span in spans {
+            printer.with_style(span.attr.clone(), |printer| {
+                printer.print((x, y), span.content);
+                x += span.content.width();
+            });
+        }
+    }
+
+    #[cfg(feature = "bidi")]
+    fn draw_row(
+        &self, printer: &Printer, pos: Vec2, width: usize,
+        spans: &[Span<Style>],
+    ) {
+        let row = bidi::reorder(spans);
+        let y = pos.y;
+
+        // Right-to-left rows start on the right.
+        let mut x = match self.align.h {
+            HAlign::Left if row.rtl => {
+                HAlign::Right.get_offset(width, printer.size.x)
+            }
+            _ => pos.x,
+        };
+
+        for span in &row.spans {
+            printer.with_style(span.attr.clone(), |printer| {
+                printer.print((x, y), &span.content);
+                x += span.content.width();
+            });
+        }
     }
 }
 
 impl View for TextView {
     fn draw(&self, printer: &Printer) {
-        let h = self.rows.len();
-        // If the content is smaller than the view, align it somewhere.
-        let offset = self.align.v.get_offset(h, printer.size.y);
-        let printer = &printer.offset((0, offset));
+        let h = self.rows.rows.len();
 
         let content = self.content.lock().unwrap();
 
         printer.with_effect(self.effect, |printer| {
-            for (y, row) in self.rows.iter().enumerate() {
-                let l = row.width;
-                let mut x = self.align.h.get_offset(l, printer.size.x);
-
-                for span in row.resolve(&content.content) {
-                    printer.with_style(*span.attr, |printer| {
-                        printer.print((x, y), span.content);
-                        x += span.content.width();
-                    });
-                }
+            // Only the visible rows are drawn.
+            for (y, row) in self
+                .rows
+                .rows
+                .iter()
+                .enumerate()
+                .skip(printer.content_offset.y)
+                .take(printer.output_size.y)
+            {
+                let spans = row.resolve(&content.content);
+                // If the content is smaller than the view, align it
+                // somewhere.
+                let offset =
+                    self.align.compute_offset((row.width, h), printer.size);
+                self.draw_row(printer, offset + (0, y), row.width, &spans);
             }
         });
     }
@@ -377,7 +520,7 @@ impl View for TextView {
     fn required_size(&mut self, size: Vec2) -> Vec2 {
         self.compute_rows(size);
 
-        Vec2::new(self.width.unwrap_or(0), self.rows.len())
+        Vec2::new(self.width.unwrap_or(0), self.rows.rows.len())
     }
 
     fn layout(&mut self, size: Vec2) {
@@ -386,10 +529,66 @@ impl View for TextView {
         self.compute_rows(size);
 
         // The entire "virtual" size (includes all rows)
-        let my_size = Vec2::new(self.width.unwrap_or(0), self.rows.len());
+        let my_size = Vec2::new(self.width.unwrap_or(0), self.rows.rows.len());
 
         // Build a fresh cache.
         let mut content = self.content.lock().unwrap();
         content.size_cache = Some(SizeCache::build(my_size, size));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::Effect;
+
+    #[test]
+    fn append_rows() {
+        let parts = [
+            StyledString::plain("Some text that "),
+            StyledString::styled("wraps", Effect::Bold),
+            StyledString::plain("around\nand around, "),
+            StyledString::plain("with_a_very_long_word_to_split"),
+            StyledString::plain("\n"),
+            StyledString::plain("\nmore"),
+        ];
+        let size = Vec2::new(12, 100);
+
+        let mut view = TextView::empty();
+        let mut content = view.get_shared_content();
+        let mut expected = StyledString::plain("");
+        view.layout(size);
+
+        for part in parts.iter() {
+            content.append(part.clone());
+            expected.append(part.clone());
+            assert!(view.needs_relayout());
+            view.layout(size);
+
+            let mut fresh = TextView::new(expected.clone());
+            fresh.layout(size);
+            assert_eq!(view.rows.rows, fresh.rows.rows);
+            assert_eq!(view.width, fresh.width);
+        }
+    }
+
+    #[test]
+    fn two_widths() {
+        let mut view = TextView::new("Some text that wraps around");
+        view.layout(Vec2::new(12, 10));
+        let wide = view.rows.rows.clone();
+        view.layout(Vec2::new(8, 10));
+        assert_eq!(view.spare_rows.rows, wide);
+
+        // Going back to the previous width reuses its rows.
+        view.layout(Vec2::new(12, 10));
+        assert_eq!(view.rows.rows, wide);
+        assert_eq!(view.spare_rows.width, 8);
+
+        // Unless the content changed meanwhile.
+        view.set_content("Short");
+        view.layout(Vec2::new(8, 10));
+        assert_eq!(view.rows.rows.len(), 1);
+        assert_eq!(view.width, Some(5));
+    }
+}
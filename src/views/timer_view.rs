@@ -0,0 +1,450 @@
+use crossbeam_channel::{self, Sender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use vec::Vec2;
+use view::View;
+use {CbFunc, Cursive, Printer, With};
+
+/// Measures time, and can be paused.
+#[derive(Default)]
+struct Clock {
+    // Time measured before the last start.
+    elapsed: Duration,
+    // Set while running.
+    started: Option<Instant>,
+}
+
+impl Clock {
+    fn elapsed(&self) -> Duration {
+        match self.started {
+            Some(started) => self.elapsed + started.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    fn pause(&mut self) {
+        self.elapsed = self.elapsed();
+        self.started = None;
+    }
+
+    fn reset(&mut self) {
+        self.elapsed = Duration::from_secs(0);
+        if self.started.is_some() {
+            self.started = Some(Instant::now());
+        }
+    }
+}
+
+/// Formats a duration as `MM:SS`, or `H:MM:SS` past an hour.
+///
+/// With `tenths`, a tenth of a second is added: `MM:SS.t`.
+fn format_duration(duration: Duration, tenths: bool) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    let mut text = if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    };
+    if tenths {
+        text.push_str(&format!(".{}", duration.subsec_millis() / 100));
+    }
+    text
+}
+
+/// View showing the time elapsed since it was started.
+///
+/// The time is read when the view is drawn: to see it tick, set a frame
+/// rate with [`Cursive::set_fps`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::StopwatchView;
+/// let mut stopwatch = StopwatchView::new();
+/// stopwatch.start();
+/// ```
+///
+/// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+pub struct StopwatchView {
+    clock: Clock,
+    tenths: bool,
+}
+
+new_default!(StopwatchView);
+
+impl StopwatchView {
+    /// Creates a new stopwatch, showing tenths of seconds.
+    ///
+    /// It doesn't run until started.
+    pub fn new() -> Self {
+        StopwatchView {
+            clock: Clock::default(),
+            tenths: true,
+        }
+    }
+
+    /// Starts measuring time, or resumes after a pause.
+    pub fn start(&mut self) {
+        self.clock.start();
+    }
+
+    /// Starts measuring time.
+    ///
+    /// Chainable variant.
+    pub fn started(self) -> Self {
+        self.with(Self::start)
+    }
+
+    /// Stops measuring time, keeping the time elapsed so far.
+    pub fn pause(&mut self) {
+        self.clock.pause();
+    }
+
+    /// Goes back to zero, without stopping.
+    pub fn reset(&mut self) {
+        self.clock.reset();
+    }
+
+    /// Returns `true` if the stopwatch is measuring time.
+    pub fn is_running(&self) -> bool {
+        self.clock.is_running()
+    }
+
+    /// Returns the time measured so far.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Sets whether tenths of seconds are shown.
+    pub fn set_tenths(&mut self, tenths: bool) {
+        self.tenths = tenths;
+    }
+
+    /// Sets whether tenths of seconds are shown.
+    ///
+    /// Chainable variant.
+    pub fn tenths(self, tenths: bool) -> Self {
+        self.with(|s| s.set_tenths(tenths))
+    }
+
+    fn text(&self) -> String {
+        format_duration(self.elapsed(), self.tenths)
+    }
+}
+
+impl View for StopwatchView {
+    fn draw(&self, printer: &Printer) {
+        printer.print((0, 0), &self.text());
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(self.text().len(), 1)
+    }
+}
+
+type OnFinish = Arc<Fn(&mut Cursive) + Send + Sync>;
+
+/// Callback waiting in the timer thread.
+struct Alarm {
+    deadline: Instant,
+    // The alarm is cancelled if this changes from `expected`.
+    generation: Arc<AtomicUsize>,
+    expected: usize,
+    cb_sink: Sender<Box<CbFunc>>,
+    cb: OnFinish,
+}
+
+impl Alarm {
+    fn is_cancelled(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) != self.expected
+    }
+}
+
+lazy_static! {
+    /// Sends alarms to the thread shared by every `TimerView`.
+    static ref ALARMS: Sender<Alarm> = spawn_timer_thread();
+}
+
+/// Starts the thread running alarms when they are due.
+fn spawn_timer_thread() -> Sender<Alarm> {
+    let (sender, receiver) = crossbeam_channel::unbounded::<Alarm>();
+    thread::spawn(move || {
+        let mut alarms: Vec<Alarm> = Vec::new();
+        loop {
+            // Wait for the next alarm to be due, or for a new one.
+            let next = alarms.iter().map(|alarm| alarm.deadline).min();
+            let received = match next {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    let timeout = deadline
+                        .checked_duration_since(now)
+                        .unwrap_or_default();
+                    receiver.recv_timeout(timeout).ok()
+                }
+                None => match receiver.recv() {
+                    Ok(alarm) => Some(alarm),
+                    Err(_) => return,
+                },
+            };
+            alarms.extend(received);
+
+            let now = Instant::now();
+            alarms.retain(|alarm| {
+                if alarm.is_cancelled() {
+                    return false;
+                }
+                if alarm.deadline > now {
+                    return true;
+                }
+                let cb = Arc::clone(&alarm.cb);
+                // Cursive may be gone already.
+                let _ = alarm
+                    .cb_sink
+                    .send(Box::new(move |s: &mut Cursive| cb(s)));
+                false
+            });
+        }
+    });
+    sender
+}
+
+/// View counting down to zero.
+///
+/// A callback can be run when the countdown reaches zero. It is sent
+/// through [`Cursive::cb_sink`], so it runs even while nothing else
+/// happens. A single thread, shared by every timer, waits for them.
+///
+/// Like [`StopwatchView`], the time is read when the view is drawn: to
+/// see it tick, set a frame rate with [`Cursive::set_fps`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::views::{Dialog, TimerView};
+/// # use std::time::Duration;
+/// # let mut siv = Cursive::dummy();
+/// let timer = TimerView::new(Duration::from_secs(25 * 60))
+///     .on_finish(siv.cb_sink().clone(), |s| {
+///         s.add_layer(Dialog::info("Time for a break!"));
+///     })
+///     .started();
+/// siv.add_layer(timer);
+/// ```
+///
+/// [`Cursive::cb_sink`]: ../struct.Cursive.html#method.cb_sink
+/// [`StopwatchView`]: struct.StopwatchView.html
+/// [`Cursive::set_fps`]: ../struct.Cursive.html#method.set_fps
+pub struct TimerView {
+    duration: Duration,
+    clock: Clock,
+    on_finish: Option<(Sender<Box<CbFunc>>, OnFinish)>,
+    // Incremented to cancel the scheduled callback.
+    generation: Arc<AtomicUsize>,
+}
+
+impl TimerView {
+    /// Creates a new timer counting down from `duration`.
+    ///
+    /// It doesn't run until started.
+    pub fn new(duration: Duration) -> Self {
+        TimerView {
+            duration,
+            clock: Clock::default(),
+            on_finish: None,
+            generation: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Sets the callback to run when the countdown reaches zero.
+    ///
+    /// `cb_sink` should come from [`Cursive::cb_sink`].
+    ///
+    /// [`Cursive::cb_sink`]: ../struct.Cursive.html#method.cb_sink
+    pub fn set_on_finish<F>(&mut self, cb_sink: Sender<Box<CbFunc>>, cb: F)
+    where
+        F: 'static + Fn(&mut Cursive) + Send + Sync,
+    {
+        self.on_finish = Some((cb_sink, Arc::new(cb)));
+        self.schedule();
+    }
+
+    /// Sets the callback to run when the countdown reaches zero.
+    ///
+    /// Chainable variant.
+    pub fn on_finish<F>(self, cb_sink: Sender<Box<CbFunc>>, cb: F) -> Self
+    where
+        F: 'static + Fn(&mut Cursive) + Send + Sync,
+    {
+        self.with(|s| s.set_on_finish(cb_sink, cb))
+    }
+
+    /// Starts counting down, or resumes after a pause.
+    pub fn start(&mut self) {
+        if !self.clock.is_running() {
+            self.clock.start();
+            self.schedule();
+        }
+    }
+
+    /// Starts counting down.
+    ///
+    /// Chainable variant.
+    pub fn started(self) -> Self {
+        self.with(Self::start)
+    }
+
+    /// Stops counting down, keeping the remaining time.
+    pub fn pause(&mut self) {
+        self.clock.pause();
+        self.cancel();
+    }
+
+    /// Goes back to the full duration, without stopping.
+    pub fn reset(&mut self) {
+        self.clock.reset();
+        self.schedule();
+    }
+
+    /// Sets the duration to count down from.
+    ///
+    /// The time elapsed so far is kept.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+        self.schedule();
+    }
+
+    /// Returns `true` if the timer is counting down.
+    pub fn is_running(&self) -> bool {
+        self.clock.is_running()
+    }
+
+    /// Returns the time left before zero.
+    pub fn remaining(&self) -> Duration {
+        self.duration
+            .checked_sub(self.clock.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the countdown reached zero.
+    pub fn is_finished(&self) -> bool {
+        self.remaining() == Duration::from_secs(0)
+    }
+
+    /// Cancels the scheduled callback, if any.
+    fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Schedules the callback for when the countdown reaches zero.
+    fn schedule(&mut self) {
+        self.cancel();
+        if !self.clock.is_running() || self.is_finished() {
+            return;
+        }
+        let (cb_sink, cb) = match self.on_finish {
+            Some((ref cb_sink, ref cb)) => (cb_sink.clone(), Arc::clone(cb)),
+            None => return,
+        };
+
+        let alarm = Alarm {
+            deadline: Instant::now() + self.remaining(),
+            generation: Arc::clone(&self.generation),
+            expected: self.generation.load(Ordering::SeqCst),
+            cb_sink,
+            cb,
+        };
+        // The timer thread never stops, so this can't fail.
+        let _ = ALARMS.send(alarm);
+    }
+
+    fn text(&self) -> String {
+        // Round up, so zero is only shown once finished.
+        let remaining = self.remaining();
+        let secs =
+            remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        format_duration(Duration::from_secs(secs), false)
+    }
+}
+
+impl Drop for TimerView {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl View for TimerView {
+    fn draw(&self, printer: &Printer) {
+        printer.print((0, 0), &self.text());
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(self.text().len(), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel;
+
+    #[test]
+    fn format() {
+        let duration = Duration::from_millis(83_450);
+        assert_eq!(format_duration(duration, false), "01:23");
+        assert_eq!(format_duration(duration, true), "01:23.4");
+        let duration = Duration::from_secs(3 * 3600 + 5);
+        assert_eq!(format_duration(duration, false), "3:00:05");
+    }
+
+    #[test]
+    fn stopwatch() {
+        let mut stopwatch = StopwatchView::new();
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.text(), "00:00.0");
+
+        stopwatch.start();
+        thread::sleep(Duration::from_millis(5));
+        stopwatch.pause();
+        let elapsed = stopwatch.elapsed();
+        assert!(elapsed >= Duration::from_millis(5));
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(stopwatch.elapsed(), elapsed);
+
+        stopwatch.reset();
+        assert_eq!(stopwatch.elapsed(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn timer() {
+        let (sink, source) = crossbeam_channel::unbounded();
+        let mut timer = TimerView::new(Duration::from_millis(90))
+            .on_finish(sink.clone(), |_| ());
+        assert_eq!(timer.text(), "00:01");
+
+        // Pausing cancels the callback.
+        timer.start();
+        timer.pause();
+        assert!(source.recv_timeout(Duration::from_millis(200)).is_err());
+
+        timer.set_duration(Duration::from_millis(10));
+        timer.start();
+        assert!(source.recv_timeout(Duration::from_secs(5)).is_ok());
+        assert!(timer.is_finished());
+        assert_eq!(timer.text(), "00:00");
+    }
+}
@@ -1,15 +1,35 @@
 use std::cell::Cell;
 use vec::Vec2;
-use view::{View, ViewWrapper};
+use view::{Position, View, ViewWrapper};
 use views::IdView;
 use Printer;
 
 /// Wrapper around a view that remembers its position.
+///
+/// This can be used to place popups, like context menus or completions, next
+/// to the view:
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::views::{EditView, TextView, TrackedView};
+/// # let mut siv = Cursive::dummy();
+/// siv.add_layer(TrackedView::new(EditView::new()).with_id("name"));
+///
+/// // Later, from a callback:
+/// let position = siv
+///     .call_on_id("name", |view: &mut TrackedView<EditView>| {
+///         view.position_below()
+///     })
+///     .unwrap();
+/// siv.add_layer_at(position, TextView::new("Suggestions..."));
+/// ```
 pub struct TrackedView<T: View> {
     /// Wrapped view.
     pub view: T,
     /// Last position the view was located.
     offset: Cell<Vec2>,
+    /// Last size the view was drawn with.
+    size: Cell<Vec2>,
 }
 
 impl<T: View> TrackedView<T> {
@@ -18,11 +38,31 @@ impl<T: View> TrackedView<T> {
         self.offset.get()
     }
 
+    /// Returns the last size the view was drawn with.
+    pub fn size(&self) -> Vec2 {
+        self.size.get()
+    }
+
+    /// Returns a position just under the view.
+    ///
+    /// Can be given to `StackView::add_layer_at`.
+    pub fn position_below(&self) -> Position {
+        Position::absolute(self.offset() + (0, self.size().y))
+    }
+
+    /// Returns a position just right of the view.
+    ///
+    /// Can be given to `StackView::add_layer_at`.
+    pub fn position_right(&self) -> Position {
+        Position::absolute(self.offset() + (self.size().x, 0))
+    }
+
     /// Creates a new `TrackedView` around `view`.
     pub fn new(view: T) -> Self {
         TrackedView {
             view,
             offset: Cell::new(Vec2::zero()),
+            size: Cell::new(Vec2::zero()),
         }
     }
 
@@ -39,6 +79,26 @@ impl<T: View> ViewWrapper for TrackedView<T> {
 
     fn wrap_draw(&self, printer: &Printer) {
         self.offset.set(printer.offset);
+        self.size.set(printer.size);
         self.view.draw(printer);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use views::DummyView;
+
+    #[test]
+    fn positions() {
+        let view = TrackedView::new(DummyView);
+        view.offset.set(Vec2::new(3, 2));
+        view.size.set(Vec2::new(10, 1));
+
+        let offset = |position: Position| {
+            position.compute_offset((1, 1), (80, 24), (0, 0))
+        };
+        assert_eq!(offset(view.position_below()), Vec2::new(3, 3));
+        assert_eq!(offset(view.position_right()), Vec2::new(13, 2));
+    }
+}
@@ -3,6 +3,7 @@ use std::iter;
 
 /// A generic structure with a value for each axis.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XY<T> {
     /// X-axis value
     pub x: T,
@@ -534,6 +535,20 @@ impl<T> From<(T, T)> for XY<T> {
     }
 }
 
+impl<T: Copy> From<[T; 2]> for XY<T> {
+    /// An array is assumed to be `[x, y]`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::vec::Vec2;
+    /// assert_eq!(Vec2::from([1, 2]), Vec2::new(1, 2));
+    /// ```
+    fn from(array: [T; 2]) -> Self {
+        XY::new(array[0], array[1])
+    }
+}
+
 impl<T, U> From<(XY<T>, XY<U>)> for XY<(T, U)> {
     /// Easily zip a pair of XY into a XY of pair
     fn from((t, u): (XY<T>, XY<U>)) -> Self {